@@ -7,7 +7,8 @@ use std::vec;
 use indexmap::IndexMap;
 
 use executor::Variables;
-use parser::Spanning;
+use parser::{SourcePosition, Spanning};
+use types::upload::Upload;
 
 /// A type literal in the syntax tree
 ///
@@ -35,11 +36,14 @@ pub enum Type<'a> {
 ///
 /// Lists and objects variants are _spanned_, i.e. they contain a reference to
 /// their position in the source file, if available.
+///
+/// Uploads never appear in a query. They are only put into variables by
+/// `http::multipart`, in place of the `null` sent by the client.
 #[derive(Clone, PartialEq, Debug)]
 #[allow(missing_docs)]
 pub enum InputValue {
     Null,
-    Int(i32),
+    Int(i64),
     Float(f64),
     String(String),
     Boolean(bool),
@@ -47,25 +51,35 @@ pub enum InputValue {
     Variable(String),
     List(Vec<Spanning<InputValue>>),
     Object(Vec<(Spanning<String>, Spanning<InputValue>)>),
+    Upload(Upload),
 }
 
+/// Definition of a variable of an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct VariableDefinition<'a> {
     pub var_type: Spanning<Type<'a>>,
     pub default_value: Option<Spanning<InputValue>>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
 }
 
+/// Arguments passed to a field or directive
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Arguments<'a> {
     pub items: Vec<(Spanning<&'a str>, Spanning<InputValue>)>,
 }
 
+/// Variables defined by an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct VariableDefinitions<'a> {
     pub items: Vec<(Spanning<&'a str>, VariableDefinition<'a>)>,
 }
 
+/// Field selected in a selection set
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Field<'a> {
     pub alias: Option<Spanning<&'a str>>,
     pub name: Spanning<&'a str>,
@@ -74,13 +88,17 @@ pub struct Field<'a> {
     pub selection_set: Option<Vec<Selection<'a>>>,
 }
 
+/// Spread of a named fragment, e.g. `...userFields`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct FragmentSpread<'a> {
     pub name: Spanning<&'a str>,
     pub directives: Option<Vec<Spanning<Directive<'a>>>>,
 }
 
+/// Inline fragment, e.g. `... on User { name }`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct InlineFragment<'a> {
     pub type_condition: Option<Spanning<&'a str>>,
     pub directives: Option<Vec<Spanning<Directive<'a>>>>,
@@ -110,19 +128,26 @@ pub enum Selection<'a> {
     InlineFragment(Spanning<InlineFragment<'a>>),
 }
 
+/// Directive applied to a node of a query, e.g. `@skip(if: true)`
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Directive<'a> {
     pub name: Spanning<&'a str>,
     pub arguments: Option<Spanning<Arguments<'a>>>,
 }
 
+/// Kind of an operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub enum OperationType {
     Query,
     Mutation,
+    Subscription,
 }
 
+/// Query, mutation or subscription operation
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Operation<'a> {
     pub operation_type: OperationType,
     pub name: Option<Spanning<&'a str>>,
@@ -131,7 +156,9 @@ pub struct Operation<'a> {
     pub selection_set: Vec<Selection<'a>>,
 }
 
+/// Definition of a named fragment
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub struct Fragment<'a> {
     pub name: Spanning<&'a str>,
     pub type_condition: Spanning<&'a str>,
@@ -139,12 +166,155 @@ pub struct Fragment<'a> {
     pub selection_set: Vec<Selection<'a>>,
 }
 
+/// Definition of an argument or an input object field in the schema language
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct InputValueDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub value_type: Spanning<Type<'a>>,
+    pub default_value: Option<Spanning<InputValue>>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+}
+
+/// Definition of an object or interface field in the schema language
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct FieldDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub arguments: Option<Spanning<Vec<Spanning<InputValueDefinition<'a>>>>>,
+    pub field_type: Spanning<Type<'a>>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+}
+
+/// Definition of an enum value in the schema language
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct EnumValueDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct ScalarTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct ObjectTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub interfaces: Vec<Spanning<&'a str>>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub fields: Vec<Spanning<FieldDefinition<'a>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct InterfaceTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub fields: Vec<Spanning<FieldDefinition<'a>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct UnionTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub types: Vec<Spanning<&'a str>>,
+}
+
 #[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct EnumTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub values: Vec<Spanning<EnumValueDefinition<'a>>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct InputObjectTypeDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub fields: Vec<Spanning<InputValueDefinition<'a>>>,
+}
+
+/// Definition of a named type in the schema language
+///
+/// Type extensions use the same representation, but never carry a
+/// description and might leave out the fields, values or member types.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum TypeDefinition<'a> {
+    Scalar(ScalarTypeDefinition<'a>),
+    Object(ObjectTypeDefinition<'a>),
+    Interface(InterfaceTypeDefinition<'a>),
+    Union(UnionTypeDefinition<'a>),
+    Enum(EnumTypeDefinition<'a>),
+    InputObject(InputObjectTypeDefinition<'a>),
+}
+
+/// Definition of the root operation types of a schema
+///
+/// Schema extensions use the same representation, but might leave out the
+/// operation types.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct SchemaDefinition<'a> {
+    pub directives: Option<Vec<Spanning<Directive<'a>>>>,
+    pub operation_types: Vec<(Spanning<OperationType>, Spanning<&'a str>)>,
+}
+
+/// Definition of a directive in the schema language
+///
+/// The locations are checked against the locations of the specification
+/// while parsing.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct DirectiveDefinition<'a> {
+    pub description: Option<Spanning<String>>,
+    pub name: Spanning<&'a str>,
+    pub arguments: Option<Spanning<Vec<Spanning<InputValueDefinition<'a>>>>>,
+    pub repeatable: bool,
+    pub locations: Vec<Spanning<&'a str>>,
+}
+
+/// Type system definition or extension in a document
+///
+/// These make up documents written in the schema language. They can not be
+/// executed, and are rejected when validating a query.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum TypeSystemDefinition<'a> {
+    Schema(Spanning<SchemaDefinition<'a>>),
+    Type(Spanning<TypeDefinition<'a>>),
+    Directive(Spanning<DirectiveDefinition<'a>>),
+    SchemaExtension(Spanning<SchemaDefinition<'a>>),
+    TypeExtension(Spanning<TypeDefinition<'a>>),
+}
+
+/// Top level definition in a document
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
 pub enum Definition<'a> {
     Operation(Spanning<Operation<'a>>),
     Fragment(Spanning<Fragment<'a>>),
+    TypeSystem(TypeSystemDefinition<'a>),
 }
 
+/// Parsed GraphQL document
 pub type Document<'a> = Vec<Definition<'a>>;
 
 /// Parse an unstructured input value into a Rust data type.
@@ -194,6 +364,49 @@ impl<'a> Type<'a> {
     }
 }
 
+impl<'a> TypeDefinition<'a> {
+    /// The name of the defined type
+    pub fn name(&self) -> &Spanning<&'a str> {
+        match *self {
+            TypeDefinition::Scalar(ref d) => &d.name,
+            TypeDefinition::Object(ref d) => &d.name,
+            TypeDefinition::Interface(ref d) => &d.name,
+            TypeDefinition::Union(ref d) => &d.name,
+            TypeDefinition::Enum(ref d) => &d.name,
+            TypeDefinition::InputObject(ref d) => &d.name,
+        }
+    }
+
+    /// The description of the defined type, if any
+    pub fn description(&self) -> Option<&str> {
+        let description = match *self {
+            TypeDefinition::Scalar(ref d) => &d.description,
+            TypeDefinition::Object(ref d) => &d.description,
+            TypeDefinition::Interface(ref d) => &d.description,
+            TypeDefinition::Union(ref d) => &d.description,
+            TypeDefinition::Enum(ref d) => &d.description,
+            TypeDefinition::InputObject(ref d) => &d.description,
+        };
+
+        description.as_ref().map(|d| d.item.as_str())
+    }
+}
+
+impl<'a> TypeSystemDefinition<'a> {
+    /// The position where the definition starts
+    pub fn start(&self) -> &SourcePosition {
+        match *self {
+            TypeSystemDefinition::Schema(ref d) | TypeSystemDefinition::SchemaExtension(ref d) => {
+                &d.start
+            }
+            TypeSystemDefinition::Type(ref d) | TypeSystemDefinition::TypeExtension(ref d) => {
+                &d.start
+            }
+            TypeSystemDefinition::Directive(ref d) => &d.start,
+        }
+    }
+}
+
 impl<'a> fmt::Display for Type<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -212,7 +425,7 @@ impl InputValue {
     }
 
     /// Construct an integer value.
-    pub fn int(i: i32) -> InputValue {
+    pub fn int(i: i64) -> InputValue {
         InputValue::Int(i)
     }
 
@@ -331,7 +544,7 @@ impl InputValue {
     }
 
     /// View the underlying int value, if present.
-    pub fn as_int_value(&self) -> Option<i32> {
+    pub fn as_int_value(&self) -> Option<i64> {
         match *self {
             InputValue::Int(i) => Some(i),
             _ => None,
@@ -421,10 +634,16 @@ impl InputValue {
     }
 }
 
+impl FromInputValue for InputValue {
+    fn from_input_value(v: &InputValue) -> Option<InputValue> {
+        Some(v.clone())
+    }
+}
+
 impl fmt::Display for InputValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            InputValue::Null => write!(f, "null"),
+            InputValue::Null | InputValue::Upload(_) => write!(f, "null"),
             InputValue::Int(v) => write!(f, "{}", v),
             InputValue::Float(v) => write!(f, "{}", v),
             InputValue::String(ref v) => write!(f, "\"{}\"", v),
@@ -461,22 +680,32 @@ impl fmt::Display for InputValue {
 }
 
 impl<'a> Arguments<'a> {
+    /// Consume the arguments, iterating over names and values
     pub fn into_iter(self) -> vec::IntoIter<(Spanning<&'a str>, Spanning<InputValue>)> {
         self.items.into_iter()
     }
 
+    /// Iterate over the names and values of the arguments
     pub fn iter(&self) -> slice::Iter<(Spanning<&'a str>, Spanning<InputValue>)> {
         self.items.iter()
     }
 
+    /// Iterate mutably over the names and values of the arguments
     pub fn iter_mut(&mut self) -> slice::IterMut<(Spanning<&'a str>, Spanning<InputValue>)> {
         self.items.iter_mut()
     }
 
+    /// The number of arguments
     pub fn len(&self) -> usize {
         self.items.len()
     }
 
+    /// Check if there are no arguments
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Find the value of an argument by name
     pub fn get(&self, key: &str) -> Option<&Spanning<InputValue>> {
         self.items
             .iter()
@@ -487,6 +716,7 @@ impl<'a> Arguments<'a> {
 }
 
 impl<'a> VariableDefinitions<'a> {
+    /// Iterate over the names and definitions of the variables
     pub fn iter(&self) -> slice::Iter<(Spanning<&'a str>, VariableDefinition)> {
         self.items.iter()
     }
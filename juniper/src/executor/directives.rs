@@ -0,0 +1,239 @@
+use fnv::FnvHashMap;
+use indexmap::IndexMap;
+
+use ast::{Directive, InputValue};
+use executor::{FieldResult, Variables};
+use parser::Spanning;
+use schema::meta::{AppliedDirective, Field, MetaType, ObjectMeta};
+use schema::model::SchemaType;
+use types::base::Arguments;
+use value::Value;
+
+/// Execution hook of a custom directive
+///
+/// Hooks are registered with `RootNode::directive_hook`, and run whenever a
+/// field the directive applies to is resolved. A directive applies to a field
+/// if it is used on the field in the query (the `FIELD` location), on the
+/// definition of the field (`FIELD_DEFINITION`) or on the object type the
+/// field belongs to (`OBJECT`).
+///
+/// Hooks receive the context the query is executed with, even for fields of
+/// types that use a different context.
+///
+/// ```rust
+/// # use juniper::{Arguments, DirectiveHook, FieldResult, Value};
+/// struct Uppercase;
+///
+/// impl<CtxT> DirectiveHook<CtxT> for Uppercase {
+///     fn after_field(&self, _: &Arguments, value: Value, _: &CtxT) -> FieldResult<Value> {
+///         Ok(match value.as_string_value() {
+///             Some(s) => Value::string(s.to_uppercase()),
+///             None => value,
+///         })
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub trait DirectiveHook<CtxT>: Send + Sync {
+    /// Run before the field is resolved
+    ///
+    /// Returning an error rejects the field without resolving it, as if the
+    /// resolver of the field had failed.
+    #[allow(unused_variables)]
+    fn before_field(&self, arguments: &Arguments, context: &CtxT) -> FieldResult<()> {
+        Ok(())
+    }
+
+    /// Transform the resolved value of the field
+    #[allow(unused_variables)]
+    fn after_field(&self, arguments: &Arguments, value: Value, context: &CtxT) -> FieldResult<Value> {
+        Ok(value)
+    }
+}
+
+pub(crate) type DirectiveHooks<CtxT> = FnvHashMap<String, Box<DirectiveHook<CtxT>>>;
+
+/// Directive hooks bound to the context of a query
+///
+/// Executors refer to the hooks through this trait, so they stay available
+/// when the context of an executor is replaced with one of another type.
+pub(crate) trait BoundDirectiveHooks {
+    fn has_hook(&self, name: &str) -> bool;
+
+    fn before_field(&self, name: &str, arguments: &Arguments) -> FieldResult<()>;
+
+    fn after_field(&self, name: &str, arguments: &Arguments, value: Value) -> FieldResult<Value>;
+}
+
+/// Directive hooks of an executor
+///
+/// Executors of asynchronous queries refer to hooks that are `Sync`, which
+/// lets the futures of the query be sent to other threads. That requires the
+/// context the hooks are bound to to be `Sync` as well, which synchronous
+/// execution doesn't.
+#[derive(Clone, Copy)]
+pub(crate) enum ExecutorHooks<'a> {
+    Local(&'a (BoundDirectiveHooks + 'a)),
+    Shared(&'a (BoundDirectiveHooks + Sync + 'a)),
+}
+
+impl<'a> ExecutorHooks<'a> {
+    pub(crate) fn get(self) -> &'a (BoundDirectiveHooks + 'a) {
+        match self {
+            ExecutorHooks::Local(hooks) => hooks,
+            ExecutorHooks::Shared(hooks) => hooks,
+        }
+    }
+
+    pub(crate) fn shared(self) -> &'a (BoundDirectiveHooks + Sync + 'a) {
+        match self {
+            ExecutorHooks::Shared(hooks) => hooks,
+            ExecutorHooks::Local(_) => {
+                panic!("Fields can only be resolved asynchronously by execute_async")
+            }
+        }
+    }
+}
+
+pub(crate) struct ContextHooks<'a, CtxT: 'a> {
+    hooks: &'a DirectiveHooks<CtxT>,
+    context: &'a CtxT,
+}
+
+impl<'a, CtxT> ContextHooks<'a, CtxT> {
+    pub(crate) fn new(hooks: &'a DirectiveHooks<CtxT>, context: &'a CtxT) -> ContextHooks<'a, CtxT> {
+        ContextHooks {
+            hooks: hooks,
+            context: context,
+        }
+    }
+}
+
+impl<'a, CtxT> BoundDirectiveHooks for ContextHooks<'a, CtxT> {
+    fn has_hook(&self, name: &str) -> bool {
+        self.hooks.contains_key(name)
+    }
+
+    fn before_field(&self, name: &str, arguments: &Arguments) -> FieldResult<()> {
+        match self.hooks.get(name) {
+            Some(hook) => hook.before_field(arguments, self.context),
+            None => Ok(()),
+        }
+    }
+
+    fn after_field(&self, name: &str, arguments: &Arguments, value: Value) -> FieldResult<Value> {
+        match self.hooks.get(name) {
+            Some(hook) => hook.after_field(arguments, value, self.context),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Hooks of the directives applying to a single field
+///
+/// Directives of the object type come first, followed by the directives of
+/// the field definition and of the query. Both `before_field` and
+/// `after_field` hooks run in that order, so directives in the query see the
+/// value as transformed by the schema.
+pub(crate) struct FieldHooks<'h, H: ?Sized + 'h = BoundDirectiveHooks + 'h> {
+    bound_hooks: &'h H,
+    directives: Vec<(&'h str, Arguments<'h>)>,
+}
+
+impl<'h, H: BoundDirectiveHooks + ?Sized> FieldHooks<'h, H> {
+    pub(crate) fn new(
+        schema: &'h SchemaType,
+        bound_hooks: &'h H,
+        meta_type: &'h MetaType,
+        meta_field: &'h Field,
+        directives: &'h Option<Vec<Spanning<Directive>>>,
+        variables: &Variables,
+    ) -> FieldHooks<'h, H> {
+        let mut field_hooks = FieldHooks {
+            bound_hooks: bound_hooks,
+            directives: Vec::new(),
+        };
+
+        if let MetaType::Object(ObjectMeta {
+            directives: ref type_directives,
+            ..
+        }) = *meta_type
+        {
+            for directive in type_directives {
+                field_hooks.push_applied(schema, directive);
+            }
+        }
+
+        for directive in &meta_field.directives {
+            field_hooks.push_applied(schema, directive);
+        }
+
+        if let Some(ref directives) = *directives {
+            for &Spanning {
+                item: ref directive,
+                ..
+            } in directives
+            {
+                let name = directive.name.item;
+
+                if bound_hooks.has_hook(name) {
+                    let args = directive.arguments.as_ref().map(|m| {
+                        m.item
+                            .iter()
+                            .map(|&(ref k, ref v)| (k.item, v.item.clone().into_const(variables)))
+                            .collect()
+                    });
+
+                    let args = directive_arguments(schema, name, args);
+                    field_hooks.directives.push((name, args));
+                }
+            }
+        }
+
+        field_hooks
+    }
+
+    fn push_applied(&mut self, schema: &'h SchemaType, directive: &'h AppliedDirective) {
+        if self.bound_hooks.has_hook(&directive.name) {
+            let args = directive
+                .arguments
+                .iter()
+                .map(|&(ref k, ref v)| (k.as_str(), v.clone()))
+                .collect();
+
+            let args = directive_arguments(schema, &directive.name, Some(args));
+            self.directives.push((&directive.name, args));
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    pub(crate) fn before(&self) -> FieldResult<()> {
+        for &(name, ref args) in &self.directives {
+            self.bound_hooks.before_field(name, args)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn after(&self, value: Value) -> FieldResult<Value> {
+        self.directives
+            .iter()
+            .fold(Ok(value), |value, &(name, ref args)| {
+                self.bound_hooks.after_field(name, args, value?)
+            })
+    }
+}
+
+fn directive_arguments<'h>(
+    schema: &'h SchemaType,
+    name: &str,
+    args: Option<IndexMap<&'h str, InputValue>>,
+) -> Arguments<'h> {
+    match schema.directive_by_name(name) {
+        Some(directive) => Arguments::with_defaults(args, &directive.arguments),
+        None => Arguments::with_defaults(args, &[]),
+    }
+}
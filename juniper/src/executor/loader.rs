@@ -0,0 +1,268 @@
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::mem;
+use std::sync::Mutex;
+
+use futures::future::{self, JoinAll};
+use futures::task::{self, Task};
+use futures::{Async, Future, IntoFuture, Poll};
+
+use super::{Context, FieldError};
+
+type BatchFuture<K, V> = Box<Future<Item = HashMap<K, V>, Error = FieldError> + Send>;
+
+/// Batching and caching loader for values of type `V` identified by keys of
+/// type `K`
+///
+/// Every call to `load` queues the key and returns a future. The first time
+/// one of the queued futures is polled, all keys queued so far are passed to
+/// the batch function in a single call. Since the executor creates the
+/// futures of all sibling fields and list items before polling any of them,
+/// this results in one batch call per level of the query instead of one call
+/// per loaded value. Loaded values are cached for the lifetime of the loader,
+/// which usually is a single request.
+///
+/// The batch function returns a map containing the values of the keys it
+/// found. Keys missing from the map resolve to `None`.
+///
+/// Loads are futures, so loaders can only be used from `async field`s, which
+/// are only resolved when the query is executed with `execute_async`.
+///
+/// Loaders are `Send` and `Sync`, so the futures of a query can be driven
+/// from any thread.
+pub struct Loader<K, V> {
+    batch_fn: Box<Fn(Vec<K>) -> BatchFuture<K, V> + Send + Sync>,
+    state: Mutex<LoaderState<K, V>>,
+}
+
+struct LoaderState<K, V> {
+    cache: HashMap<K, Result<Option<V>, FieldError>>,
+    requested: HashSet<K>,
+    queue: Vec<K>,
+    batches: Vec<(Vec<K>, BatchFuture<K, V>)>,
+    // Tasks waiting for batches that are being polled by another task
+    waiting: Vec<Task>,
+}
+
+/// Future returned by `Loader::load`
+pub struct LoadFuture<'a, K, V>
+where
+    K: 'a,
+    V: 'a,
+{
+    loader: &'a Loader<K, V>,
+    key: K,
+}
+
+/// Per-request collection of loaders, keyed by their key and value types
+///
+/// Store the loaders of a request in its context object and implement
+/// `FromContext` for `Loaders` to access them through `Executor::loader`:
+///
+/// ```rust
+/// # use juniper::{Context, FromContext, Loader, Loaders};
+/// # use std::collections::HashMap;
+/// struct Database {
+///     loaders: Loaders,
+/// }
+///
+/// impl Context for Database {}
+///
+/// impl FromContext<Database> for Loaders {
+///     fn from(db: &Database) -> &Loaders {
+///         &db.loaders
+///     }
+/// }
+///
+/// let mut loaders = Loaders::new();
+/// loaders.register(Loader::new(|ids: Vec<i32>| {
+///     Ok(ids.into_iter().map(|id| (id, format!("User {}", id))).collect::<HashMap<_, _>>())
+/// }));
+///
+/// let db = Database { loaders: loaders };
+/// ```
+#[derive(Default)]
+pub struct Loaders {
+    loaders: HashMap<TypeId, Box<Any + Send + Sync>>,
+}
+
+impl<K, V> Loader<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Construct a new loader from a batch function
+    pub fn new<F, R>(batch_fn: F) -> Loader<K, V>
+    where
+        F: Fn(Vec<K>) -> R + Send + Sync + 'static,
+        R: IntoFuture<Item = HashMap<K, V>, Error = FieldError>,
+        R::Future: Send + 'static,
+    {
+        Loader {
+            batch_fn: Box::new(move |keys| Box::new(batch_fn(keys).into_future())),
+            state: Mutex::new(LoaderState {
+                cache: HashMap::new(),
+                requested: HashSet::new(),
+                queue: Vec::new(),
+                batches: Vec::new(),
+                waiting: Vec::new(),
+            }),
+        }
+    }
+
+    /// Load the value identified by a key
+    pub fn load(&self, key: K) -> LoadFuture<K, V> {
+        {
+            let mut state = self.state.lock().unwrap();
+
+            if !state.cache.contains_key(&key) && state.requested.insert(key.clone()) {
+                state.queue.push(key.clone());
+            }
+        }
+
+        LoadFuture {
+            loader: self,
+            key: key,
+        }
+    }
+
+    /// Load the values identified by a number of keys, preserving their order
+    pub fn load_many<I>(&self, keys: I) -> JoinAll<Vec<LoadFuture<K, V>>>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        future::join_all(keys.into_iter().map(|key| self.load(key)).collect())
+    }
+
+    /// Store a value in the cache, unless the key is already cached
+    pub fn prime(&self, key: K, value: V) {
+        self.state
+            .lock()
+            .unwrap()
+            .cache
+            .entry(key)
+            .or_insert(Ok(Some(value)));
+    }
+
+    /// Remove a key from the cache, causing the next load to fetch it again
+    pub fn clear(&self, key: &K) {
+        self.state.lock().unwrap().cache.remove(key);
+    }
+
+    fn poll_key(&self, key: &K) -> Poll<Option<V>, FieldError> {
+        if let Some(result) = self.cached(key) {
+            return result.map(Async::Ready);
+        }
+
+        let queue = mem::replace(&mut self.state.lock().unwrap().queue, Vec::new());
+        let mut batches = mem::replace(&mut self.state.lock().unwrap().batches, Vec::new());
+
+        if !queue.is_empty() {
+            let batch = (self.batch_fn)(queue.clone());
+            batches.push((queue, batch));
+        }
+
+        // The state is not locked while the batch functions and futures run,
+        // so they are free to use the loader themselves.
+        let mut pending = Vec::with_capacity(batches.len());
+        let mut finished = Vec::new();
+
+        for (keys, mut batch) in batches {
+            match batch.poll() {
+                Ok(Async::NotReady) => pending.push((keys, batch)),
+                Ok(Async::Ready(values)) => finished.push((keys, Ok(values))),
+                Err(e) => finished.push((keys, Err(e))),
+            }
+        }
+
+        {
+            let mut state = self.state.lock().unwrap();
+            pending.extend(state.batches.drain(..));
+            state.batches = pending;
+
+            if !finished.is_empty() {
+                for task in state.waiting.drain(..) {
+                    task.notify();
+                }
+            }
+
+            for (keys, result) in finished {
+                match result {
+                    Ok(mut values) => {
+                        for key in keys {
+                            state.requested.remove(&key);
+                            let value = values.remove(&key);
+                            state.cache.insert(key, Ok(value));
+                        }
+                    }
+                    Err(e) => {
+                        for key in keys {
+                            state.requested.remove(&key);
+                            state.cache.insert(key, Err(e.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        match state.cache.get(key).cloned() {
+            Some(result) => result.map(Async::Ready),
+            None => {
+                // The batch of the key may be polled by another task, which
+                // has to wake this one up when it completes
+                state.waiting.push(task::current());
+                Ok(Async::NotReady)
+            }
+        }
+    }
+
+    fn cached(&self, key: &K) -> Option<Result<Option<V>, FieldError>> {
+        self.state.lock().unwrap().cache.get(key).cloned()
+    }
+}
+
+impl<'a, K, V> Future for LoadFuture<'a, K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    type Item = Option<V>;
+    type Error = FieldError;
+
+    fn poll(&mut self) -> Poll<Option<V>, FieldError> {
+        self.loader.poll_key(&self.key)
+    }
+}
+
+impl Loaders {
+    /// Construct an empty collection of loaders
+    pub fn new() -> Loaders {
+        Loaders::default()
+    }
+
+    /// Add a loader, replacing any loader with the same key and value types
+    pub fn register<K, V>(&mut self, loader: Loader<K, V>)
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        self.loaders
+            .insert(TypeId::of::<Loader<K, V>>(), Box::new(loader));
+    }
+
+    /// Get the loader for the given key and value types
+    pub fn get<K, V>(&self) -> Option<&Loader<K, V>>
+    where
+        K: 'static,
+        V: 'static,
+    {
+        self.loaders
+            .get(&TypeId::of::<Loader<K, V>>())
+            .and_then(|loader| loader.downcast_ref())
+    }
+}
+
+impl Context for Loaders {}
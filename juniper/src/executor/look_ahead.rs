@@ -1,5 +1,6 @@
 use ast::{Directive, Fragment, InputValue, Selection};
 use parser::Spanning;
+use types::upload::Upload;
 
 use std::collections::HashMap;
 
@@ -23,13 +24,14 @@ pub enum Applies<'a> {
 #[allow(missing_docs)]
 pub enum LookAheadValue<'a> {
     Null,
-    Int(i32),
+    Int(i64),
     Float(f64),
     String(&'a str),
     Boolean(bool),
     Enum(&'a str),
     List(Vec<LookAheadValue<'a>>),
     Object(Vec<(&'a str, LookAheadValue<'a>)>),
+    Upload(&'a Upload),
 }
 
 impl<'a> LookAheadValue<'a> {
@@ -41,6 +43,7 @@ impl<'a> LookAheadValue<'a> {
             InputValue::String(ref s) => LookAheadValue::String(s),
             InputValue::Boolean(b) => LookAheadValue::Boolean(b),
             InputValue::Enum(ref e) => LookAheadValue::Enum(e),
+            InputValue::Upload(ref u) => LookAheadValue::Upload(u),
             InputValue::Variable(ref v) => Self::from_input_value(vars.get(v).unwrap(), vars),
             InputValue::List(ref l) => LookAheadValue::List(
                 l.iter()
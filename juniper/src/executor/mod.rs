@@ -2,16 +2,21 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::sync::RwLock;
+use std::mem;
+use std::sync::{Arc, RwLock};
 
 use fnv::FnvHashMap;
+use futures::future;
+use futures::{Async, Future, IntoFuture, Poll, Stream};
 
 use ast::{
-    Definition, Document, Fragment, FromInputValue, InputValue, OperationType, Selection,
-    ToInputValue, Type,
+    Definition, Directive, Document, Field as FieldAst, Fragment, FromInputValue, InputValue,
+    Operation, OperationType, Selection, ToInputValue, Type,
 };
-use parser::SourcePosition;
-use value::Value;
+use parser::{SourcePosition, Spanning};
+use prepared::QueryDocument;
+use value::{Object, Value};
+use extensions::{FieldInfo, RequestInstrumentation};
 use GraphQLError;
 
 use schema::meta::{
@@ -20,15 +25,25 @@ use schema::meta::{
 };
 use schema::model::{RootNode, SchemaType, TypeType};
 
-use types::base::GraphQLType;
+use types::base::{is_excluded, resolve_selection_set_serially, Arguments, GraphQLType};
 use types::name::Name;
+use types::subscriptions::{GraphQLSubscriptionType, SourceEvents};
 
+mod directives;
+mod loader;
 mod look_ahead;
+mod writer;
 
+pub(crate) use self::directives::{
+    BoundDirectiveHooks, ContextHooks, DirectiveHooks, ExecutorHooks, FieldHooks,
+};
+pub use self::directives::DirectiveHook;
+pub use self::loader::{LoadFuture, Loader, Loaders};
 pub use self::look_ahead::{
     Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadMethods,
     LookAheadSelection, LookAheadValue,
 };
+pub(crate) use self::writer::ResponseWriter;
 
 /// A type registry used to build schemas
 ///
@@ -38,12 +53,16 @@ pub use self::look_ahead::{
 pub struct Registry<'r> {
     /// Currently registered types
     pub types: FnvHashMap<Name, MetaType<'r>>,
+    // Object names, interface names and interface field names of
+    // implementations whose object type has not been registered yet
+    pending_implementations: Vec<(String, String, Vec<String>)>,
 }
 
 #[derive(Clone)]
 pub enum FieldPath<'a> {
     Root(SourcePosition),
-    Field(&'a str, SourcePosition, &'a FieldPath<'a>),
+    Field(&'a str, SourcePosition, Arc<FieldPath<'a>>),
+    Index(usize, Arc<FieldPath<'a>>),
 }
 
 /// Query execution engine
@@ -63,6 +82,31 @@ where
     context: &'a CtxT,
     errors: &'a RwLock<Vec<ExecutionError>>,
     field_path: FieldPath<'a>,
+    directive_hooks: ExecutorHooks<'a>,
+    instrumentation: &'a RequestInstrumentation,
+    output: Option<&'a ResponseWriter<'a>>,
+}
+
+/// An executor that can be moved into the futures of asynchronous fields
+///
+/// Unlike `Executor`, it is `Send` whenever the context is `Sync`. Detached
+/// executors never write to the output of `execute_to_writer`, which only
+/// executes synchronously.
+pub(crate) struct DetachedExecutor<'a, CtxT>
+where
+    CtxT: 'a,
+{
+    fragments: &'a HashMap<&'a str, &'a Fragment<'a>>,
+    variables: &'a Variables,
+    current_selection_set: Option<&'a [Selection<'a>]>,
+    parent_selection_set: Option<&'a [Selection<'a>]>,
+    current_type: TypeType<'a>,
+    schema: &'a SchemaType<'a>,
+    context: &'a CtxT,
+    errors: &'a RwLock<Vec<ExecutionError>>,
+    field_path: FieldPath<'a>,
+    directive_hooks: &'a (BoundDirectiveHooks + Sync + 'a),
+    instrumentation: &'a RequestInstrumentation,
 }
 
 /// Error type for errors that occur during query execution
@@ -72,10 +116,22 @@ where
 #[derive(Debug, PartialEq)]
 pub struct ExecutionError {
     location: SourcePosition,
-    path: Vec<String>,
+    path: Vec<PathSegment>,
     error: FieldError,
 }
 
+/// Segment of the path to the field that failed to resolve
+///
+/// Paths contain the response names of fields, and the indices of list items
+/// leading to the field. They are serialized as strings and integers.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// Response name of a field, i.e. its alias or name
+    Field(String),
+    /// Index of an item in a list
+    Index(usize),
+}
+
 impl ExecutionError {
     /// Construct a new execution error occuring at the beginning of the query
     pub fn at_origin(error: FieldError) -> ExecutionError {
@@ -124,7 +180,7 @@ impl Ord for ExecutionError {
 ///     Ok(s)
 /// }
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FieldError {
     message: String,
     extensions: Value,
@@ -197,6 +253,15 @@ pub type FieldResult<T> = Result<T, FieldError>;
 /// The result of resolving an unspecified field
 pub type ExecutionResult = Result<Value, FieldError>;
 
+/// A future resolving to the value of a field of type `T`
+///
+/// Field futures are `Send`, so that the future of a whole query can be
+/// driven on a thread pool.
+pub type FieldFuture<'a, T> = Box<Future<Item = T, Error = FieldError> + Send + 'a>;
+
+/// A future resolving to the value of an unspecified field
+pub type ExecutionFuture<'a> = FieldFuture<'a, Value>;
+
 /// The map of variables used for substitution during query execution
 pub type Variables = HashMap<String, InputValue>;
 
@@ -344,11 +409,120 @@ impl<'a, CtxT> Executor<'a, CtxT> {
         }
     }
 
+    /// Resolve a single arbitrary value asynchronously, mapping the context to
+    /// a new type
+    pub fn resolve_with_ctx_async<NewCtxT, T: GraphQLType<Context = NewCtxT>>(
+        &self,
+        info: &'a T::TypeInfo,
+        value: &T,
+    ) -> ExecutionFuture<'a>
+    where
+        NewCtxT: FromContext<CtxT> + 'a,
+    {
+        self.replaced_context(<NewCtxT as FromContext<CtxT>>::from(self.context))
+            .resolve_async(info, value)
+    }
+
+    /// Resolve a single arbitrary value into a future
+    ///
+    /// The returned future does not borrow `value`: all fields are resolved
+    /// up front, and only the futures returned by asynchronous resolvers are
+    /// awaited.
+    pub fn resolve_async<T: GraphQLType<Context = CtxT>>(
+        &self,
+        info: &'a T::TypeInfo,
+        value: &T,
+    ) -> ExecutionFuture<'a> {
+        value.resolve_async(info, self.current_selection_set, self)
+    }
+
+    /// Resolve a single arbitrary value into a future of a return value
+    ///
+    /// If the field fails to resolve, the future resolves to `null`.
+    pub fn resolve_into_value_async<T: GraphQLType<Context = CtxT>>(
+        &self,
+        info: &'a T::TypeInfo,
+        value: &T,
+    ) -> ExecutionFuture<'a> {
+        let executor = self.detach_without_context();
+
+        Box::new(self.resolve_async(info, value).or_else(move |e| {
+            executor.attach().push_error(e);
+            Ok(Value::null())
+        }))
+    }
+
+    #[doc(hidden)]
+    pub fn resolve_resolvable_async<R, T>(&self, value: R) -> ExecutionFuture<'a>
+    where
+        R: IntoResolvable<'a, T, CtxT>,
+        T: GraphQLType<TypeInfo = ()>,
+        T::Context: 'a,
+    {
+        match value.into(self.context) {
+            Ok(Some((ctx, r))) => self.replaced_context(ctx).resolve_async(&(), &r),
+            Ok(None) => Box::new(future::ok(Value::null())),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn resolve_future<F, R, T>(&self, future: F) -> ExecutionFuture<'a>
+    where
+        CtxT: Sync,
+        F: IntoFuture<Item = R>,
+        F::Future: Send + 'a,
+        F::Error: IntoFieldError + 'a,
+        R: IntoResolvable<'a, T, CtxT> + 'a,
+        T: GraphQLType<TypeInfo = ()> + 'a,
+        T::Context: 'a,
+    {
+        let executor = self.detach();
+
+        Box::new(
+            future
+                .into_future()
+                .map_err(IntoFieldError::into_field_error)
+                .and_then(move |value| executor.attach().resolve_resolvable_async(value)),
+        )
+    }
+
+    /// Detach the executor, so that it can be moved into a future
+    ///
+    /// Panics if the executor doesn't belong to an asynchronous query.
+    pub(crate) fn detach(&self) -> DetachedExecutor<'a, CtxT> {
+        DetachedExecutor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: self.field_path.clone(),
+            directive_hooks: self.directive_hooks.shared(),
+            instrumentation: self.instrumentation,
+        }
+    }
+
+    /// Detach the executor without its context
+    ///
+    /// The detached executor is `Send` regardless of the context, and can be
+    /// used to report errors and instrument fields once they have been
+    /// resolved.
+    pub(crate) fn detach_without_context(&self) -> DetachedExecutor<'a, ()> {
+        self.replaced_context(&NULL_CONTEXT).detach()
+    }
+
     /// Derive a new executor by replacing the context
     ///
     /// This can be used to connect different types, e.g. from different Rust
     /// libraries, that require different context types.
-    pub fn replaced_context<'b, NewCtxT>(&'b self, ctx: &'b NewCtxT) -> Executor<'b, NewCtxT> {
+    pub fn replaced_context<'b, NewCtxT>(&self, ctx: &'b NewCtxT) -> Executor<'b, NewCtxT>
+    where
+        'a: 'b,
+    {
         Executor {
             fragments: self.fragments,
             variables: self.variables,
@@ -359,6 +533,100 @@ impl<'a, CtxT> Executor<'a, CtxT> {
             context: ctx,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            directive_hooks: self.directive_hooks,
+            instrumentation: self.instrumentation,
+            output: self.output,
+        }
+    }
+
+    pub(crate) fn field_hooks<'h>(
+        &self,
+        meta_type: &'h MetaType,
+        meta_field: &'h Field,
+        directives: &'h Option<Vec<Spanning<Directive>>>,
+    ) -> FieldHooks<'h>
+    where
+        'a: 'h,
+    {
+        FieldHooks::new(
+            self.schema,
+            self.directive_hooks.get(),
+            meta_type,
+            meta_field,
+            directives,
+            self.variables,
+        )
+    }
+
+    /// Hooks of a field that can be moved into its future
+    ///
+    /// Panics if the executor doesn't belong to an asynchronous query.
+    pub(crate) fn shared_field_hooks<'h>(
+        &self,
+        meta_type: &'h MetaType,
+        meta_field: &'h Field,
+        directives: &'h Option<Vec<Spanning<Directive>>>,
+    ) -> FieldHooks<'h, BoundDirectiveHooks + Sync + 'h>
+    where
+        'a: 'h,
+    {
+        FieldHooks::new(
+            self.schema,
+            self.directive_hooks.shared(),
+            meta_type,
+            meta_field,
+            directives,
+            self.variables,
+        )
+    }
+
+    /// Notify the instrumentation that the field of this executor starts
+    /// resolving
+    ///
+    /// The returned info has to be passed to `field_finished` once the field
+    /// has been resolved.
+    pub(crate) fn field_started(
+        &self,
+        meta_type: &'a MetaType<'a>,
+        meta_field: &'a Field<'a>,
+    ) -> Option<FieldInfo<'a>> {
+        if self.instrumentation.is_empty() {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        self.field_path.construct_path(&mut path);
+
+        let field = FieldInfo::new(
+            path,
+            meta_type.name().expect("Resolving field of unnamed type"),
+            &meta_field.name,
+            &meta_field.field_type,
+        );
+        self.instrumentation.field_start(&field);
+
+        Some(field)
+    }
+
+    pub(crate) fn field_finished(&self, field: Option<FieldInfo<'a>>) {
+        if let Some(field) = field {
+            self.instrumentation.field_end(&field);
+        }
+    }
+
+    /// Output the value resolved by this executor writes itself into
+    ///
+    /// Only objects and lists write themselves, and only when executing with
+    /// `execute_to_writer`. Sub executors do not inherit the output: whoever
+    /// creates them decides whether the value is written or returned.
+    pub(crate) fn output(&self) -> Option<&'a ResponseWriter<'a>> {
+        self.output
+    }
+
+    pub(crate) fn with_output(&self, output: Option<&'a ResponseWriter<'a>>) -> Executor<'a, CtxT> {
+        Executor {
+            output: output,
+            ..self.clone()
         }
     }
 
@@ -368,8 +636,8 @@ impl<'a, CtxT> Executor<'a, CtxT> {
         field_alias: &'a str,
         field_name: &'a str,
         location: SourcePosition,
-        selection_set: Option<&'a [Selection]>,
-    ) -> Executor<CtxT> {
+        selection_set: Option<&'a [Selection<'a>]>,
+    ) -> Executor<'a, CtxT> {
         Executor {
             fragments: self.fragments,
             variables: self.variables,
@@ -385,7 +653,28 @@ impl<'a, CtxT> Executor<'a, CtxT> {
             schema: self.schema,
             context: self.context,
             errors: self.errors,
-            field_path: FieldPath::Field(field_alias, location, &self.field_path),
+            field_path: FieldPath::Field(field_alias, location, Arc::new(self.field_path.clone())),
+            directive_hooks: self.directive_hooks,
+            instrumentation: self.instrumentation,
+            output: None,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn index_sub_executor(&self, index: usize) -> Executor<'a, CtxT> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: FieldPath::Index(index, Arc::new(self.field_path.clone())),
+            directive_hooks: self.directive_hooks,
+            instrumentation: self.instrumentation,
+            output: None,
         }
     }
 
@@ -393,8 +682,8 @@ impl<'a, CtxT> Executor<'a, CtxT> {
     pub fn type_sub_executor(
         &self,
         type_name: Option<&'a str>,
-        selection_set: Option<&'a [Selection]>,
-    ) -> Executor<CtxT> {
+        selection_set: Option<&'a [Selection<'a>]>,
+    ) -> Executor<'a, CtxT> {
         Executor {
             fragments: self.fragments,
             variables: self.variables,
@@ -408,6 +697,9 @@ impl<'a, CtxT> Executor<'a, CtxT> {
             context: self.context,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            directive_hooks: self.directive_hooks,
+            instrumentation: self.instrumentation,
+            output: None,
         }
     }
 
@@ -419,8 +711,24 @@ impl<'a, CtxT> Executor<'a, CtxT> {
         self.context
     }
 
+    /// Access the loader for the given key and value types
+    ///
+    /// The loaders are taken from the context through its `FromContext`
+    /// implementation for `Loaders`. Panics if no loader with these types has
+    /// been registered.
+    pub fn loader<K, V>(&self) -> &'a Loader<K, V>
+    where
+        Loaders: FromContext<CtxT>,
+        K: 'static,
+        V: 'static,
+    {
+        <Loaders as FromContext<CtxT>>::from(self.context)
+            .get()
+            .expect("No loader registered for the requested key and value types")
+    }
+
     /// The currently executing schema
-    pub fn schema(&self) -> &'a SchemaType {
+    pub fn schema(&self) -> &'a SchemaType<'a> {
         self.schema
     }
 
@@ -435,7 +743,7 @@ impl<'a, CtxT> Executor<'a, CtxT> {
     }
 
     #[doc(hidden)]
-    pub fn fragment_by_name(&self, name: &str) -> Option<&'a Fragment> {
+    pub fn fragment_by_name(&self, name: &str) -> Option<&'a Fragment<'a>> {
         self.fragments.get(name).map(|f| *f)
     }
 
@@ -495,13 +803,56 @@ impl<'a, CtxT> Executor<'a, CtxT> {
     }
 }
 
+impl<'a, CtxT> Clone for Executor<'a, CtxT> {
+    fn clone(&self) -> Executor<'a, CtxT> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: self.field_path.clone(),
+            directive_hooks: self.directive_hooks,
+            instrumentation: self.instrumentation,
+            output: self.output,
+        }
+    }
+}
+
+impl<'a, CtxT> DetachedExecutor<'a, CtxT> {
+    /// Turn the detached executor back into an executor
+    pub(crate) fn attach(&self) -> Executor<'a, CtxT> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: self.field_path.clone(),
+            directive_hooks: ExecutorHooks::Shared(self.directive_hooks),
+            instrumentation: self.instrumentation,
+            output: None,
+        }
+    }
+}
+
 impl<'a> FieldPath<'a> {
-    fn construct_path(&self, acc: &mut Vec<String>) {
+    fn construct_path(&self, acc: &mut Vec<PathSegment>) {
         match *self {
             FieldPath::Root(_) => (),
-            FieldPath::Field(name, _, parent) => {
+            FieldPath::Field(name, _, ref parent) => {
+                parent.construct_path(acc);
+                acc.push(PathSegment::Field(name.to_owned()));
+            }
+            FieldPath::Index(index, ref parent) => {
                 parent.construct_path(acc);
-                acc.push(name.to_owned());
+                acc.push(PathSegment::Index(index));
             }
         }
     }
@@ -509,16 +860,20 @@ impl<'a> FieldPath<'a> {
     fn location(&self) -> &SourcePosition {
         match *self {
             FieldPath::Root(ref pos) | FieldPath::Field(_, ref pos, _) => pos,
+            FieldPath::Index(_, ref parent) => parent.location(),
         }
     }
 }
 
 impl ExecutionError {
     #[doc(hidden)]
-    pub fn new(location: SourcePosition, path: &[&str], error: FieldError) -> ExecutionError {
+    pub fn new<P>(location: SourcePosition, path: &[P], error: FieldError) -> ExecutionError
+    where
+        P: Clone + Into<PathSegment>,
+    {
         ExecutionError {
             location: location,
-            path: path.iter().map(|s| (*s).to_owned()).collect(),
+            path: path.iter().cloned().map(Into::into).collect(),
             error: error,
         }
     }
@@ -533,77 +888,60 @@ impl ExecutionError {
         &self.location
     }
 
-    /// The path of fields leading to the field that generated this error
-    pub fn path(&self) -> &[String] {
+    /// The path of fields and list indices leading to the field that
+    /// generated this error
+    pub fn path(&self) -> &[PathSegment] {
         &self.path
     }
 }
 
-pub fn execute_validated_query<'a, QueryT, MutationT, CtxT>(
-    document: Document,
+impl<'a> From<&'a str> for PathSegment {
+    fn from(name: &'a str) -> PathSegment {
+        PathSegment::Field(name.to_owned())
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> PathSegment {
+        PathSegment::Index(index)
+    }
+}
+
+pub fn execute_validated_query<'a, QueryT, MutationT, SubscriptionT, CtxT>(
+    document: &Document,
     operation_name: Option<&str>,
-    root_node: &RootNode<QueryT, MutationT>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
     variables: &Variables,
     context: &CtxT,
+    instrumentation: &RequestInstrumentation,
+    output: Option<&ResponseWriter>,
 ) -> Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>
 where
     QueryT: GraphQLType<Context = CtxT>,
     MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
 {
-    let mut fragments = vec![];
-    let mut operation = None;
-
-    for def in document {
-        match def {
-            Definition::Operation(op) => {
-                if operation_name.is_none() && operation.is_some() {
-                    return Err(GraphQLError::MultipleOperationsProvided);
-                }
-
-                let move_op = operation_name.is_none()
-                    || op.item.name.as_ref().map(|s| s.item.as_ref()) == operation_name;
+    let op = get_operation(document, operation_name)?;
 
-                if move_op {
-                    operation = Some(op);
-                }
-            }
-            Definition::Fragment(f) => fragments.push(f),
-        };
+    if op.item.operation_type == OperationType::Subscription {
+        return Err(GraphQLError::IsSubscription);
     }
 
-    let op = match operation {
-        Some(op) => op,
-        None => return Err(GraphQLError::UnknownOperationName),
-    };
-
-    let default_variable_values = op.item.variable_definitions.map(|defs| {
-        defs.item
-            .items
-            .iter()
-            .filter_map(|&(ref name, ref def)| {
-                def.default_value
-                    .as_ref()
-                    .map(|i| (name.item.to_owned(), i.item.clone()))
-            })
-            .collect::<HashMap<String, InputValue>>()
-    });
+    let fragments = collect_fragments(document);
 
     let errors = RwLock::new(Vec::new());
+    let directive_hooks = ContextHooks::new(&root_node.directive_hooks, context);
     let value;
 
     {
-        let mut all_vars;
-        let mut final_vars = variables;
-
-        if let Some(defaults) = default_variable_values {
-            all_vars = variables.clone();
-
-            for (name, value) in defaults {
-                all_vars.entry(name).or_insert(value);
+        let all_vars;
+        let final_vars = match default_variable_values(&op.item, variables) {
+            Some(vars) => {
+                all_vars = vars;
+                &all_vars
             }
-
-            final_vars = &all_vars;
-        }
+            None => variables,
+        };
 
         let root_type = match op.item.operation_type {
             OperationType::Query => root_node.schema.query_type(),
@@ -611,13 +949,11 @@ where
                 .schema
                 .mutation_type()
                 .expect("No mutation type found"),
+            OperationType::Subscription => unreachable!(),
         };
 
         let executor = Executor {
-            fragments: &fragments
-                .iter()
-                .map(|f| (f.item.name.item, &f.item))
-                .collect(),
+            fragments: &fragments,
             variables: final_vars,
             current_selection_set: Some(&op.item.selection_set[..]),
             parent_selection_set: None,
@@ -625,15 +961,29 @@ where
             schema: &root_node.schema,
             context: context,
             errors: &errors,
-            field_path: FieldPath::Root(op.start),
+            field_path: FieldPath::Root(op.start.clone()),
+            directive_hooks: ExecutorHooks::Local(&directive_hooks),
+            instrumentation: instrumentation,
+            output: output,
         };
 
+        let start = output.map(ResponseWriter::position);
+
+        instrumentation.execution_start();
         value = match op.item.operation_type {
             OperationType::Query => executor.resolve_into_value(&root_node.query_info, &root_node),
             OperationType::Mutation => {
                 executor.resolve_into_value(&root_node.mutation_info, &root_node.mutation_type)
             }
+            OperationType::Subscription => unreachable!(),
         };
+        instrumentation.execution_end();
+
+        if let Some(output) = output {
+            if Some(output.position()) == start {
+                output.write_value(&value);
+            }
+        }
     }
 
     let mut errors = errors.into_inner().unwrap();
@@ -642,10 +992,543 @@ where
     Ok((value, errors))
 }
 
+type Fragments<'a> = HashMap<&'a str, &'a Fragment<'a>>;
+
+self_cell!(
+    struct QueryDocumentCell<'a> {
+        owner: QueryDocument<'a>,
+
+        #[covariant]
+        dependent: Fragments,
+    }
+);
+
+struct QueryState<'a> {
+    document: QueryDocumentCell<'a>,
+    operation_index: usize,
+    variables: Variables,
+    errors: RwLock<Vec<ExecutionError>>,
+    directive_hooks: Box<BoundDirectiveHooks + Send + Sync + 'a>,
+    instrumentation: RequestInstrumentation,
+}
+
+// The resolution future of a query borrows from the state of the query.
+self_cell!(
+    struct QueryCell<'a> {
+        owner: QueryState<'a>,
+
+        #[not_covariant]
+        dependent: ExecutionFuture,
+    }
+);
+
+/// Future returned by asynchronous query execution
+///
+/// Resolves to the same data and errors as `execute_validated_query` once
+/// every asynchronous resolver of the query has completed. The future is
+/// `Send` as long as the context is `Sync`, so it can be spawned on a thread
+/// pool.
+pub struct QueryFuture<'a> {
+    cell: QueryCell<'a>,
+    finished: bool,
+}
+
+impl<'a> Future for QueryFuture<'a> {
+    type Item = (Value, Vec<ExecutionError>);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(Value, Vec<ExecutionError>), ()> {
+        assert!(!self.finished, "QueryFuture polled after completion");
+
+        let value = match self.cell.with_dependent_mut(|_, value| value.poll()) {
+            Ok(Async::Ready(value)) => value,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(e) => {
+                self.push_root_error(e);
+                Value::null()
+            }
+        };
+
+        self.finished = true;
+
+        let state = self.cell.borrow_owner();
+        state.instrumentation.execution_end();
+
+        let mut errors = mem::replace(&mut *state.errors.write().unwrap(), Vec::new());
+        errors.sort();
+
+        Ok(Async::Ready((value, errors)))
+    }
+}
+
+impl<'a> QueryFuture<'a> {
+    /// Also resolve to the results of the instrumentation of the query
+    pub(crate) fn with_extensions(self) -> ExtendedQueryFuture<'a> {
+        ExtendedQueryFuture(self)
+    }
+
+    fn push_root_error(&self, error: FieldError) {
+        self.cell
+            .borrow_owner()
+            .errors
+            .write()
+            .unwrap()
+            .push(ExecutionError::at_origin(error));
+    }
+}
+
+/// Query future that also resolves to the `extensions` of the response
+pub(crate) struct ExtendedQueryFuture<'a>(QueryFuture<'a>);
+
+impl<'a> Future for ExtendedQueryFuture<'a> {
+    type Item = (Value, Vec<ExecutionError>, Object);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(Value, Vec<ExecutionError>, Object), ()> {
+        let (value, errors) = match self.0.poll()? {
+            Async::Ready(result) => result,
+            Async::NotReady => return Ok(Async::NotReady),
+        };
+        let extensions = self.0.cell.borrow_owner().instrumentation.extensions();
+
+        Ok(Async::Ready((value, errors, extensions)))
+    }
+}
+
+pub(crate) fn execute_validated_query_async<'a, QueryT, MutationT, SubscriptionT, CtxT>(
+    document: QueryDocument<'a>,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+    instrumentation: RequestInstrumentation,
+) -> Result<QueryFuture<'a>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT> + Sync,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    CtxT: Sync,
+{
+    let operation_index;
+    let final_vars;
+
+    {
+        let op = get_operation(document.get(), operation_name)?;
+
+        if op.item.operation_type == OperationType::Subscription {
+            return Err(GraphQLError::IsSubscription);
+        }
+
+        operation_index = get_operation_index(document.get(), op);
+        final_vars =
+            default_variable_values(&op.item, variables).unwrap_or_else(|| variables.clone());
+    }
+
+    let state = QueryState {
+        document: QueryDocumentCell::new(document, |document| {
+            collect_fragments(document.get())
+        }),
+        operation_index: operation_index,
+        variables: final_vars,
+        errors: RwLock::new(Vec::new()),
+        directive_hooks: Box::new(ContextHooks::new(&root_node.directive_hooks, context)),
+        instrumentation: instrumentation,
+    };
+
+    Ok(QueryFuture {
+        cell: QueryCell::new(state, |state| resolve_operation(state, root_node, context)),
+        finished: false,
+    })
+}
+
+fn resolve_operation<'q, 'a, QueryT, MutationT, SubscriptionT, CtxT>(
+    state: &'q QueryState<'a>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    context: &'a CtxT,
+) -> ExecutionFuture<'q>
+where
+    'a: 'q,
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT> + Sync,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    CtxT: Sync,
+{
+    let op = match state.document.borrow_owner().get()[state.operation_index] {
+        Definition::Operation(ref op) => op,
+        Definition::Fragment(_) | Definition::TypeSystem(_) => unreachable!(),
+    };
+
+    let root_type = match op.item.operation_type {
+        OperationType::Query => root_node.schema.query_type(),
+        OperationType::Mutation => root_node
+            .schema
+            .mutation_type()
+            .expect("No mutation type found"),
+        OperationType::Subscription => unreachable!(),
+    };
+
+    let executor = Executor {
+        fragments: state.document.borrow_dependent(),
+        variables: &state.variables,
+        current_selection_set: Some(&op.item.selection_set[..]),
+        parent_selection_set: None,
+        current_type: root_type,
+        schema: &root_node.schema,
+        context: context,
+        errors: &state.errors,
+        field_path: FieldPath::Root(op.start.clone()),
+        directive_hooks: ExecutorHooks::Shared(&*state.directive_hooks),
+        instrumentation: &state.instrumentation,
+        output: None,
+    };
+
+    state.instrumentation.execution_start();
+    match op.item.operation_type {
+        OperationType::Query => executor.resolve_into_value_async(&root_node.query_info, root_node),
+        OperationType::Mutation => resolve_selection_set_serially(
+            &root_node.mutation_type,
+            &root_node.mutation_info,
+            &op.item.selection_set[..],
+            &executor,
+        ),
+        OperationType::Subscription => unreachable!(),
+    }
+}
+
+/// Stream of responses produced by a subscription operation
+///
+/// Every event of the source stream of the subscription field is executed
+/// against the selection set as soon as the stream is polled. The stream ends
+/// when the source stream ends, or after reporting an error of the source
+/// stream.
+pub struct SubscriptionStream<'a, CtxT>
+where
+    CtxT: 'a,
+{
+    document: QueryDocument<'a>,
+    operation_index: usize,
+    variables: Variables,
+    schema: &'a SchemaType<'a>,
+    context: &'a CtxT,
+    directive_hooks: &'a DirectiveHooks<CtxT>,
+    instrumentation: RequestInstrumentation,
+    events: Option<SourceEvents<'a, CtxT>>,
+    setup_error: Option<ExecutionError>,
+}
+
+impl<'a, CtxT> Stream for SubscriptionStream<'a, CtxT> {
+    type Item = (Value, Vec<ExecutionError>);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<(Value, Vec<ExecutionError>)>, ()> {
+        if let Some(error) = self.setup_error.take() {
+            return Ok(Async::Ready(Some((Value::null(), vec![error]))));
+        }
+
+        let event = match self.events.as_mut().map(SourceEvents::poll_event) {
+            Some(Ok(Async::Ready(Some(event)))) => Ok(event),
+            Some(Ok(Async::NotReady)) => return Ok(Async::NotReady),
+            Some(Err(e)) => Err(e),
+            Some(Ok(Async::Ready(None))) | None => return Ok(Async::Ready(None)),
+        };
+
+        let document = self.document.get();
+        let op = match document[self.operation_index] {
+            Definition::Operation(ref op) => op,
+            Definition::Fragment(_) | Definition::TypeSystem(_) => unreachable!(),
+        };
+        let fragments = collect_fragments(document);
+
+        let field = match root_subscription_field(
+            &op.item.selection_set,
+            &fragments,
+            &self.variables,
+        ) {
+            Some(field) => field,
+            None => {
+                let value = Value::object(Object::with_capacity(0));
+                return Ok(Async::Ready(Some((value, Vec::new()))));
+            }
+        };
+        let response_name = field.item.alias.as_ref().unwrap_or(&field.item.name).item;
+
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                let error = ExecutionError::new(field.start.clone(), &[response_name], e);
+                self.events = None;
+                return Ok(Async::Ready(Some((Value::null(), vec![error]))));
+            }
+        };
+
+        let meta_field = match subscription_field(self.schema, field.item.name.item) {
+            Ok(meta_field) => meta_field,
+            Err(e) => {
+                let error = ExecutionError::new(field.start.clone(), &[response_name], e);
+                self.events = None;
+                return Ok(Async::Ready(Some((Value::null(), vec![error]))));
+            }
+        };
+
+        let errors = RwLock::new(Vec::new());
+        let directive_hooks = ContextHooks::new(self.directive_hooks, self.context);
+        let value;
+
+        {
+            let root_path = Arc::new(FieldPath::Root(op.start.clone()));
+            let executor = Executor {
+                fragments: &fragments,
+                variables: &self.variables,
+                current_selection_set: field.item.selection_set.as_ref().map(|v| &v[..]),
+                parent_selection_set: Some(&op.item.selection_set[..]),
+                current_type: self.schema.make_type(&meta_field.field_type),
+                schema: self.schema,
+                context: self.context,
+                errors: &errors,
+                field_path: FieldPath::Field(response_name, field.start.clone(), root_path),
+                directive_hooks: ExecutorHooks::Local(&directive_hooks),
+                instrumentation: &self.instrumentation,
+                output: None,
+            };
+
+            self.instrumentation.execution_start();
+            let field_value = match event(&executor) {
+                Ok(v) => v,
+                Err(e) => {
+                    executor.push_error(e);
+                    Value::null()
+                }
+            };
+            self.instrumentation.execution_end();
+
+            value = if field_value.is_null() && meta_field.field_type.is_non_null() {
+                Value::null()
+            } else {
+                let mut result = Object::with_capacity(1);
+                result.add_field(response_name, field_value);
+                Value::Object(result)
+            };
+        }
+
+        let mut errors = errors.into_inner().unwrap();
+        errors.sort();
+
+        Ok(Async::Ready(Some((value, errors))))
+    }
+}
+
+pub fn execute_validated_subscription<'a, QueryT, MutationT, SubscriptionT, CtxT>(
+    document: QueryDocument<'a>,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+    instrumentation: RequestInstrumentation,
+) -> Result<SubscriptionStream<'a, CtxT>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLSubscriptionType<Context = CtxT>,
+{
+    let operation_index;
+    let final_vars;
+    let mut setup_error = None;
+    let mut events = None;
+
+    {
+        let parsed = document.get();
+        let op = get_operation(parsed, operation_name)?;
+
+        if op.item.operation_type != OperationType::Subscription {
+            return Err(GraphQLError::NotSubscription);
+        }
+
+        operation_index = get_operation_index(parsed, op);
+
+        final_vars =
+            default_variable_values(&op.item, variables).unwrap_or_else(|| variables.clone());
+
+        let fragments = collect_fragments(parsed);
+
+        if let Some(field) =
+            root_subscription_field(&op.item.selection_set, &fragments, &final_vars)
+        {
+            let response_name = field.item.alias.as_ref().unwrap_or(&field.item.name).item;
+
+            let result = subscription_field(&root_node.schema, field.item.name.item).and_then(
+                |meta_field| {
+                    let args = Arguments::new(
+                        field.item.arguments.as_ref().map(|m| {
+                            m.item
+                                .iter()
+                                .map(|&(ref k, ref v)| {
+                                    (k.item, v.item.clone().into_const(&final_vars))
+                                })
+                                .collect()
+                        }),
+                        &meta_field.arguments,
+                    );
+
+                    root_node.subscription_type.resolve_field_into_stream(
+                        &root_node.subscription_info,
+                        field.item.name.item,
+                        &args,
+                        context,
+                    )
+                },
+            );
+
+            match result {
+                Ok(e) => events = Some(e),
+                Err(e) => {
+                    setup_error = Some(ExecutionError::new(
+                        field.start.clone(),
+                        &[response_name],
+                        e,
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(SubscriptionStream {
+        document: document,
+        operation_index: operation_index,
+        variables: final_vars,
+        schema: &root_node.schema,
+        context: context,
+        directive_hooks: &root_node.directive_hooks,
+        instrumentation: instrumentation,
+        events: events,
+        setup_error: setup_error,
+    })
+}
+
+/// Look up a field of the subscription type
+///
+/// Introspection fields such as `__typename` do not produce events, and can
+/// not be subscribed to.
+fn subscription_field<'s>(schema: &'s SchemaType, name: &str) -> Result<&'s Field<'s>, FieldError> {
+    let field = if name.starts_with("__") {
+        None
+    } else {
+        schema
+            .concrete_subscription_type()
+            .and_then(|subscription_type| subscription_type.field_by_name(name))
+    };
+
+    field.ok_or_else(|| FieldError::from(format!("Field {} can not be subscribed to", name)))
+}
+
+fn get_operation<'b, 'd, 'e>(
+    document: &'b Document<'d>,
+    operation_name: Option<&str>,
+) -> Result<&'b Spanning<Operation<'d>>, GraphQLError<'e>> {
+    let mut operation = None;
+
+    for def in document {
+        if let Definition::Operation(ref op) = *def {
+            if operation_name.is_none() && operation.is_some() {
+                return Err(GraphQLError::MultipleOperationsProvided);
+            }
+
+            let move_op = operation_name.is_none()
+                || op.item.name.as_ref().map(|s| s.item.as_ref()) == operation_name;
+
+            if move_op {
+                operation = Some(op);
+            }
+        }
+    }
+
+    operation.ok_or(GraphQLError::UnknownOperationName)
+}
+
+fn get_operation_index(document: &Document, operation: &Spanning<Operation>) -> usize {
+    document
+        .iter()
+        .position(|def| match *def {
+            Definition::Operation(ref op) => op as *const _ == operation as *const _,
+            _ => false,
+        })
+        .expect("Operation not found in document")
+}
+
+fn collect_fragments<'b, 'd>(document: &'b Document<'d>) -> HashMap<&'b str, &'b Fragment<'d>> {
+    document
+        .iter()
+        .filter_map(|def| match *def {
+            Definition::Fragment(ref f) => Some((f.item.name.item, &f.item)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn default_variable_values(op: &Operation, variables: &Variables) -> Option<Variables> {
+    op.variable_definitions.as_ref().map(|defs| {
+        let mut all_vars = variables.clone();
+
+        for &(ref name, ref def) in defs.item.iter() {
+            if let Some(ref default_value) = def.default_value {
+                all_vars
+                    .entry(name.item.to_owned())
+                    .or_insert_with(|| default_value.item.clone());
+            }
+        }
+
+        all_vars
+    })
+}
+
+fn root_subscription_field<'b, 'd>(
+    selection_set: &'b [Selection<'d>],
+    fragments: &HashMap<&str, &'b Fragment<'d>>,
+    variables: &Variables,
+) -> Option<&'b Spanning<FieldAst<'d>>> {
+    for selection in selection_set {
+        let field = match *selection {
+            Selection::Field(ref f) => {
+                if is_excluded(&f.item.directives, variables) {
+                    None
+                } else {
+                    Some(f)
+                }
+            }
+            Selection::FragmentSpread(ref spread) => {
+                if is_excluded(&spread.item.directives, variables) {
+                    None
+                } else {
+                    fragments.get(spread.item.name.item).and_then(|f| {
+                        root_subscription_field(&f.selection_set, fragments, variables)
+                    })
+                }
+            }
+            Selection::InlineFragment(ref fragment) => {
+                if is_excluded(&fragment.item.directives, variables) {
+                    None
+                } else {
+                    root_subscription_field(&fragment.item.selection_set, fragments, variables)
+                }
+            }
+        };
+
+        if field.is_some() {
+            return field;
+        }
+    }
+
+    None
+}
+
 impl<'r> Registry<'r> {
     /// Construct a new registry
     pub fn new(types: FnvHashMap<Name, MetaType<'r>>) -> Registry<'r> {
-        Registry { types: types }
+        Registry {
+            types: types,
+            pending_implementations: Vec::new(),
+        }
     }
 
     /// Get the `Type` instance for a given GraphQL type
@@ -665,6 +1548,7 @@ impl<'r> Registry<'r> {
                 );
                 let meta = T::meta(info, self);
                 self.types.insert(validated_name, meta);
+                self.apply_pending_implementations();
             }
             self.types[name].as_type()
         } else {
@@ -672,6 +1556,62 @@ impl<'r> Registry<'r> {
         }
     }
 
+    /// Register the object type `T` as an implementation of an interface
+    ///
+    /// The interface is added to the interfaces of the object type. If the
+    /// object type is still being built, this happens as soon as its metadata
+    /// has been stored.
+    ///
+    /// Panics if the object type does not declare all fields of the
+    /// interface.
+    pub fn add_implementation<T>(
+        &mut self,
+        info: &T::TypeInfo,
+        interface: &InterfaceMeta<'r>,
+    ) -> Type<'r>
+    where
+        T: GraphQLType,
+    {
+        let object_type = self.get_type::<T>(info);
+        let object_name = T::name(info).expect("Interface implementations must be named");
+
+        self.pending_implementations.push((
+            object_name.to_owned(),
+            interface.name.to_string(),
+            interface.fields.iter().map(|f| f.name.clone()).collect(),
+        ));
+        self.apply_pending_implementations();
+
+        object_type
+    }
+
+    fn apply_pending_implementations(&mut self) {
+        let types = &mut self.types;
+
+        self.pending_implementations.retain(
+            |&(ref object_name, ref interface_name, ref field_names)| {
+                match types.get_mut(&object_name[..]) {
+                    Some(&mut MetaType::Object(ref mut meta)) => {
+                        for field_name in field_names {
+                            if !meta.fields.iter().any(|f| f.name == *field_name) {
+                                panic!(
+                                    "Type {} does not declare the field {} of its interface {}",
+                                    object_name, field_name, interface_name
+                                );
+                            }
+                        }
+
+                        if !meta.interface_names.contains(interface_name) {
+                            meta.interface_names.push(interface_name.clone());
+                        }
+                        false
+                    }
+                    _ => true,
+                }
+            },
+        );
+    }
+
     /// Create a field with the provided name
     pub fn field<T>(&mut self, name: &str, info: &T::TypeInfo) -> Field<'r>
     where
@@ -683,6 +1623,9 @@ impl<'r> Registry<'r> {
             arguments: None,
             field_type: self.get_type::<T>(info),
             deprecation_reason: None,
+            complexity: None,
+            complexity_multiplier: None,
+            directives: Vec::new(),
         }
     }
 
@@ -701,6 +1644,9 @@ impl<'r> Registry<'r> {
             arguments: None,
             field_type: self.get_type::<I>(info),
             deprecation_reason: None,
+            complexity: None,
+            complexity_multiplier: None,
+            directives: Vec::new(),
         }
     }
 
@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::mem;
+
+use serde::Serialize;
+use serde_json;
+
+/// Bytes that can no longer change are passed on to the underlying writer in
+/// chunks of at least this size
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// JSON output of a query executed with `execute_to_writer`
+///
+/// Objects and lists write themselves as their fields and items resolve. A
+/// value that still has to be replaced by `null` if one of its non-null
+/// fields or items fails takes a checkpoint before writing anything: the
+/// bytes following the oldest open checkpoint are buffered, everything
+/// before it is passed on to the underlying writer.
+pub(crate) struct ResponseWriter<'a> {
+    writer: &'a (Sink + 'a),
+    state: RefCell<WriterState>,
+}
+
+struct WriterState {
+    buffer: Vec<u8>,
+    flushed: usize,
+    checkpoints: Vec<usize>,
+    error: Option<io::Error>,
+}
+
+/// Start of a value that may have to be discarded
+pub(crate) struct Checkpoint(usize);
+
+trait Sink {
+    fn write_all(&self, bytes: &[u8]) -> io::Result<()>;
+
+    fn flush(&self) -> io::Result<()>;
+}
+
+impl<W: Write> Sink for RefCell<W> {
+    fn write_all(&self, bytes: &[u8]) -> io::Result<()> {
+        self.borrow_mut().write_all(bytes)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.borrow_mut().flush()
+    }
+}
+
+impl<'a> ResponseWriter<'a> {
+    pub(crate) fn new<W: Write + 'a>(writer: &'a RefCell<W>) -> ResponseWriter<'a> {
+        ResponseWriter {
+            writer: writer,
+            state: RefCell::new(WriterState {
+                buffer: Vec::with_capacity(CHUNK_SIZE),
+                flushed: 0,
+                checkpoints: Vec::new(),
+                error: None,
+            }),
+        }
+    }
+
+    /// Number of bytes written so far
+    pub(crate) fn position(&self) -> usize {
+        let state = self.state.borrow();
+        state.flushed + state.buffer.len()
+    }
+
+    pub(crate) fn write_raw(&self, bytes: &[u8]) {
+        self.state.borrow_mut().buffer.extend_from_slice(bytes);
+    }
+
+    /// Write the key of the next entry of an object
+    pub(crate) fn write_key(&self, key: &str, first: &mut bool) {
+        if !mem::replace(first, false) {
+            self.write_raw(b",");
+        }
+
+        self.write_value(key);
+        self.write_raw(b":");
+    }
+
+    pub(crate) fn write_value<T: Serialize + ?Sized>(&self, value: &T) {
+        serde_json::to_writer(&mut self.state.borrow_mut().buffer, value)
+            .expect("Value could not be serialized");
+    }
+
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        let position = self.position();
+        self.state.borrow_mut().checkpoints.push(position);
+        Checkpoint(position)
+    }
+
+    /// Keep everything written since the checkpoint
+    pub(crate) fn commit(&self, checkpoint: Checkpoint) {
+        self.pop_checkpoint(&checkpoint);
+        self.flush_committed();
+    }
+
+    /// Discard everything written since the checkpoint
+    pub(crate) fn rollback(&self, checkpoint: Checkpoint) {
+        self.pop_checkpoint(&checkpoint);
+
+        let mut state = self.state.borrow_mut();
+        let length = checkpoint.0 - state.flushed;
+        state.buffer.truncate(length);
+    }
+
+    /// Pass the bytes before the oldest open checkpoint on to the underlying
+    /// writer, if there are enough of them
+    pub(crate) fn flush_committed(&self) {
+        let mut state = self.state.borrow_mut();
+
+        let committed = match state.checkpoints.first() {
+            Some(&position) => position - state.flushed,
+            None => state.buffer.len(),
+        };
+
+        if committed >= CHUNK_SIZE {
+            self.write_through(&mut state, committed);
+        }
+    }
+
+    /// Write the remaining bytes and flush the underlying writer
+    pub(crate) fn finish(self) -> io::Result<()> {
+        {
+            let mut state = self.state.borrow_mut();
+            let length = state.buffer.len();
+            self.write_through(&mut state, length);
+        }
+
+        match self.state.into_inner().error {
+            Some(e) => Err(e),
+            None => self.writer.flush(),
+        }
+    }
+
+    fn pop_checkpoint(&self, checkpoint: &Checkpoint) {
+        let position = self.state.borrow_mut().checkpoints.pop();
+        debug_assert_eq!(position, Some(checkpoint.0), "Checkpoints closed out of order");
+    }
+
+    /// Once writing has failed, the remaining output is discarded
+    fn write_through(&self, state: &mut WriterState, length: usize) {
+        if state.error.is_none() {
+            if let Err(e) = self.writer.write_all(&state.buffer[..length]) {
+                state.error = Some(e);
+            }
+        }
+
+        state.buffer.drain(..length);
+        state.flushed += length;
+    }
+}
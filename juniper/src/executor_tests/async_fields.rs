@@ -0,0 +1,408 @@
+use std::sync::Mutex;
+
+use futures::future::{self, Future};
+use futures::sync::oneshot;
+use futures::{task, Async, Poll};
+use tokio_threadpool::ThreadPool;
+
+use ast::InputValue;
+use executor::{Context, ExecutionError, FieldError, PathSegment, Variables};
+use parser::SourcePosition;
+use schema::model::RootNode;
+use value::{Object, Value};
+
+/// Future that is not ready for the given number of polls
+struct Delay<T> {
+    polls: i32,
+    value: Option<T>,
+}
+
+fn delay<T>(polls: i32, value: T) -> Delay<T> {
+    Delay {
+        polls: polls,
+        value: Some(value),
+    }
+}
+
+impl<T> Future for Delay<T> {
+    type Item = T;
+    type Error = FieldError;
+
+    fn poll(&mut self) -> Poll<T, FieldError> {
+        if self.polls > 0 {
+            self.polls -= 1;
+            task::current().notify();
+            Ok(Async::NotReady)
+        } else {
+            Ok(Async::Ready(
+                self.value.take().expect("Delay polled after completion"),
+            ))
+        }
+    }
+}
+
+#[derive(Default)]
+struct Database {
+    log: Mutex<Vec<String>>,
+    sender: Mutex<Option<oneshot::Sender<i32>>>,
+    receiver: Mutex<Option<oneshot::Receiver<i32>>>,
+}
+
+impl Context for Database {}
+
+trait Entity {
+    fn id(&self) -> i32;
+    fn as_user(&self) -> Option<&User>;
+}
+
+struct User {
+    id: i32,
+}
+
+impl Entity for User {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn as_user(&self) -> Option<&User> {
+        Some(self)
+    }
+}
+
+graphql_interface!(<'a> &'a Entity: Database as "Entity" |&self| {
+    field id() -> i32 { self.id() }
+
+    instance_resolvers: |&_| {
+        &User => self.as_user(),
+    }
+});
+
+graphql_object!(User: Database |&self| {
+    field id() -> i32 {
+        self.id
+    }
+
+    async field name(&executor) -> String {
+        let id = self.id;
+        let db = executor.context();
+
+        delay(1, ()).map(move |()| {
+            db.log.lock().unwrap().push(format!("name {}", id));
+            format!("User {}", id)
+        })
+    }
+
+    async field best_friend() -> Option<User> {
+        let id = self.id;
+
+        delay(1, if id < 3 { Some(User { id: id + 1 }) } else { None })
+    }
+
+    async field broken() -> i32 {
+        delay(1, ()).and_then(|()| Err::<i32, _>(FieldError::from("Broken")))
+    }
+
+    interfaces: [&Entity]
+});
+
+struct Query {
+    entities: Vec<User>,
+}
+
+graphql_object!(Query: Database |&self| {
+    async field user(id: i32) -> User {
+        future::ok::<_, FieldError>(User { id: id })
+    }
+
+    async field maybe_user(id: i32) -> Option<User> {
+        delay(2, Some(User { id: id }))
+    }
+
+    async field users(ids: Vec<i32>) -> Vec<User> {
+        delay(2, ids.into_iter().map(|id| User { id: id }).collect())
+    }
+
+    field entities() -> Vec<&Entity> {
+        self.entities.iter().map(|u| u as &Entity).collect()
+    }
+
+    async field waiting(&executor) -> i32 {
+        executor
+            .context()
+            .receiver
+            .lock().unwrap()
+            .take()
+            .expect("Receiver already taken")
+            .map_err(|_| FieldError::from("Sender dropped"))
+    }
+
+    async field sending(&executor) -> i32 {
+        let sender = executor
+            .context()
+            .sender
+            .lock().unwrap()
+            .take()
+            .expect("Sender already taken");
+
+        future::lazy(move || {
+            sender
+                .send(42)
+                .map(|()| 0)
+                .map_err(|_| FieldError::from("Receiver dropped"))
+        })
+    }
+});
+
+struct Mutation;
+
+graphql_object!(Mutation: Database |&self| {
+    async field append(&executor, value: i32, polls: i32) -> i32 {
+        let db = executor.context();
+
+        delay(polls, ()).map(move |()| {
+            db.log.lock().unwrap().push(format!("append {}", value));
+            value
+        })
+    }
+});
+
+fn schema<'a>() -> RootNode<'a, Query, Mutation> {
+    RootNode::new(
+        Query {
+            entities: vec![User { id: 1 }, User { id: 2 }],
+        },
+        Mutation,
+    )
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::object(fields.into_iter().collect::<Object>())
+}
+
+fn run_async(doc: &str, db: &Database, vars: &Variables) -> (Value, Vec<ExecutionError>) {
+    let schema = schema();
+
+    ::execute_async(doc, None, &schema, vars, db)
+        .expect("Execution failed")
+        .wait()
+        .expect("Query future failed")
+}
+
+#[test]
+fn resolves_async_fields() {
+    let db = Database::default();
+    let (result, errs) = run_async(
+        "{ user(id: 1) { id name bestFriend { id name } } }",
+        &db,
+        &Variables::new(),
+    );
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        object(vec![(
+            "user",
+            object(vec![
+                ("id", Value::int(1)),
+                ("name", Value::string("User 1")),
+                (
+                    "bestFriend",
+                    object(vec![
+                        ("id", Value::int(2)),
+                        ("name", Value::string("User 2"))
+                    ]),
+                ),
+            ]),
+        )])
+    );
+}
+
+#[test]
+fn synchronous_execution_reports_errors_for_async_fields() {
+    let schema = schema();
+    let db = Database::default();
+
+    let result = ::execute(
+        "{ entities { id ... on User { name } } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &db,
+    ).expect("Execution failed");
+
+    assert_eq!(
+        result,
+        (
+            object(vec![(
+                "entities",
+                Value::list(vec![
+                    object(vec![("id", Value::int(1))]),
+                    object(vec![("id", Value::int(2))]),
+                ]),
+            )]),
+            vec![
+                ExecutionError::new(
+                    SourcePosition::new(30, 0, 30),
+                    &[
+                        PathSegment::from("entities"),
+                        PathSegment::Index(0),
+                        PathSegment::from("name"),
+                    ],
+                    FieldError::from("Asynchronous fields can only be resolved by execute_async"),
+                ),
+                ExecutionError::new(
+                    SourcePosition::new(30, 0, 30),
+                    &[
+                        PathSegment::from("entities"),
+                        PathSegment::Index(1),
+                        PathSegment::from("name"),
+                    ],
+                    FieldError::from("Asynchronous fields can only be resolved by execute_async"),
+                ),
+            ],
+        )
+    );
+    assert_eq!(*db.log.lock().unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn query_futures_are_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let schema = schema();
+    let db = Database::default();
+    let future = ::execute_async("{ user(id: 1) { name } }", None, &schema, &Variables::new(), &db)
+        .expect("Execution failed");
+
+    assert_send(&future);
+}
+
+#[test]
+fn resolves_queries_on_a_thread_pool() {
+    let schema: &'static RootNode<Query, Mutation> = Box::leak(Box::new(schema()));
+    let db: &'static Database = Box::leak(Box::new(Database::default()));
+    let pool = ThreadPool::new();
+
+    let doc = "{ users(ids: [1, 3]) { id name bestFriend { id } } }";
+    let future = ::execute_async(doc, None, schema, &Variables::new(), db)
+        .expect("Execution failed");
+    let (result, errs) = pool.spawn_handle(future).wait().expect("Query future failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        object(vec![(
+            "users",
+            Value::list(vec![
+                object(vec![
+                    ("id", Value::int(1)),
+                    ("name", Value::string("User 1")),
+                    ("bestFriend", object(vec![("id", Value::int(2))])),
+                ]),
+                object(vec![
+                    ("id", Value::int(3)),
+                    ("name", Value::string("User 3")),
+                    ("bestFriend", Value::null()),
+                ]),
+            ]),
+        )])
+    );
+}
+
+#[test]
+fn resolves_sibling_fields_concurrently() {
+    let (sender, receiver) = oneshot::channel();
+    let db = Database {
+        sender: Mutex::new(Some(sender)),
+        receiver: Mutex::new(Some(receiver)),
+        ..Database::default()
+    };
+
+    // `waiting` only completes once `sending` has been polled
+    let (result, errs) = run_async("{ waiting sending }", &db, &Variables::new());
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        object(vec![
+            ("waiting", Value::int(42)),
+            ("sending", Value::int(0))
+        ])
+    );
+}
+
+#[test]
+fn resolves_mutation_root_fields_serially() {
+    let db = Database::default();
+    let (result, errs) = run_async(
+        r"mutation {
+            first: append(value: 1, polls: 3)
+            ... on Mutation { second: append(value: 2, polls: 0) }
+            third: append(value: 3, polls: 1)
+        }",
+        &db,
+        &Variables::new(),
+    );
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        object(vec![
+            ("first", Value::int(1)),
+            ("second", Value::int(2)),
+            ("third", Value::int(3)),
+        ])
+    );
+    assert_eq!(*db.log.lock().unwrap(), vec!["append 1", "append 2", "append 3"]);
+}
+
+#[test]
+fn propagates_errors_to_the_nearest_nullable_field() {
+    let db = Database::default();
+    let vars = vec![("id".to_owned(), InputValue::int(1))]
+        .into_iter()
+        .collect();
+    let (result, errs) = run_async(
+        "query Q($id: Int!) { maybeUser(id: $id) { id broken } }",
+        &db,
+        &vars,
+    );
+
+    assert_eq!(result, object(vec![("maybeUser", Value::null())]));
+    assert_eq!(
+        errs,
+        vec![ExecutionError::new(
+            SourcePosition::new(45, 0, 45),
+            &["maybeUser", "broken"],
+            FieldError::from("Broken"),
+        )]
+    );
+}
+
+#[test]
+fn resolves_async_fields_through_interfaces() {
+    let db = Database::default();
+    let (result, errs) = run_async(
+        "{ entities { id ... on User { name } } }",
+        &db,
+        &Variables::new(),
+    );
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        object(vec![(
+            "entities",
+            Value::list(vec![
+                object(vec![
+                    ("id", Value::int(1)),
+                    ("name", Value::string("User 1"))
+                ]),
+                object(vec![
+                    ("id", Value::int(2)),
+                    ("name", Value::string("User 2"))
+                ]),
+            ]),
+        )])
+    );
+}
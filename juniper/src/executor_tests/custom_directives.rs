@@ -0,0 +1,337 @@
+use futures::Future;
+
+use ast::{InputValue, Type};
+use executor::{Context, ExecutionError, FieldError, FieldResult, Variables};
+use parser::SourcePosition;
+use schema::dynamic::DynamicSchema;
+use schema::meta::Argument;
+use schema::model::{DirectiveLocation, DirectiveType, RootNode};
+use types::base::Arguments;
+use types::scalars::EmptyMutation;
+use validation::RuleError;
+use value::Value;
+use DirectiveHook;
+use GraphQLError::ValidationError;
+
+struct User {
+    role: String,
+}
+
+impl Context for User {}
+
+struct Query;
+
+struct Planet;
+
+graphql_object!(Query: User |&self| {
+    field name() -> &str {
+        "Luke"
+    }
+
+    field home_planet() -> Planet {
+        Planet
+    }
+});
+
+graphql_object!(Planet: User |&self| {
+    field name() -> &str {
+        "Tatooine"
+    }
+});
+
+struct Uppercase;
+
+impl<CtxT> DirectiveHook<CtxT> for Uppercase {
+    fn after_field(&self, _: &Arguments, value: Value, _: &CtxT) -> FieldResult<Value> {
+        Ok(match value.as_string_value() {
+            Some(s) => Value::string(s.to_uppercase()),
+            None => value,
+        })
+    }
+}
+
+struct Suffix;
+
+impl<CtxT> DirectiveHook<CtxT> for Suffix {
+    fn after_field(&self, args: &Arguments, value: Value, _: &CtxT) -> FieldResult<Value> {
+        let suffix = args.get::<String>("suffix").expect("Argument missing");
+
+        Ok(match value.as_string_value() {
+            Some(s) => Value::string(format!("{}{}", s, suffix)),
+            None => value,
+        })
+    }
+}
+
+struct Auth;
+
+impl DirectiveHook<User> for Auth {
+    fn before_field(&self, args: &Arguments, user: &User) -> FieldResult<()> {
+        let role = args.get::<String>("role").expect("Argument missing");
+
+        if user.role == role {
+            Ok(())
+        } else {
+            Err(FieldError::new(
+                format!("Requires the role {}", role),
+                Value::null(),
+            ))
+        }
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<User>> {
+    RootNode::new(Query, EmptyMutation::<User>::new())
+        .directive(DirectiveType::new(
+            "uppercase",
+            &[DirectiveLocation::Field],
+            &[],
+        ))
+        .directive_hook("uppercase", Uppercase)
+        .directive(DirectiveType::new(
+            "suffix",
+            &[DirectiveLocation::Field],
+            &[Argument::new("suffix", Type::Named("String".into()))
+                .default_value(InputValue::string("!"))],
+        ))
+        .directive_hook("suffix", Suffix)
+        .directive(
+            DirectiveType::new(
+                "tag",
+                &[DirectiveLocation::Field, DirectiveLocation::InlineFragment],
+                &[],
+            ).repeatable(),
+        )
+}
+
+fn user(role: &str) -> User {
+    User {
+        role: role.to_owned(),
+    }
+}
+
+#[test]
+fn transforms_resolved_values() {
+    let schema = schema();
+
+    assert_eq!(
+        ::execute(
+            r#"{
+                name @uppercase
+                homePlanet { name @suffix(suffix: "?") planet: name @suffix }
+                plain: name @tag @tag
+            }"#,
+            None,
+            &schema,
+            &Variables::new(),
+            &user("USER"),
+        ),
+        Ok((
+            graphql_value!({
+                "name": "LUKE",
+                "homePlanet": { "name": "Tatooine?", "planet": "Tatooine!" },
+                "plain": "Luke",
+            }),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn runs_hooks_in_order() {
+    let schema = schema();
+
+    assert_eq!(
+        ::execute(
+            r#"{ a: name @uppercase @suffix(suffix: "x") b: name @suffix(suffix: "x") @uppercase }"#,
+            None,
+            &schema,
+            &Variables::new(),
+            &user("USER"),
+        ),
+        Ok((graphql_value!({ "a": "LUKEx", "b": "LUKEX" }), vec![]))
+    );
+}
+
+#[test]
+fn runs_hooks_asynchronously() {
+    let schema = schema();
+    let context = user("USER");
+    let query = r#"{ name @uppercase homePlanet { name @suffix } }"#;
+
+    assert_eq!(
+        ::execute_async(query, None, &schema, &Variables::new(), &context)
+            .expect("Execution failed")
+            .wait(),
+        Ok(::execute(query, None, &schema, &Variables::new(), &context)
+            .expect("Execution failed"))
+    );
+}
+
+#[test]
+fn validates_custom_directives() {
+    let schema = schema();
+
+    assert_eq!(
+        ::execute(
+            "{ name @unknown }",
+            None,
+            &schema,
+            &Variables::new(),
+            &user("USER"),
+        ),
+        Err(ValidationError(vec![RuleError::new(
+            r#"Unknown directive "unknown""#,
+            &[SourcePosition::new(7, 0, 7)],
+        )]))
+    );
+
+    assert_eq!(
+        ::execute(
+            "query @uppercase { name }",
+            None,
+            &schema,
+            &Variables::new(),
+            &user("USER"),
+        ),
+        Err(ValidationError(vec![RuleError::new(
+            r#"Directive "uppercase" may not be used on query"#,
+            &[SourcePosition::new(6, 0, 6)],
+        )]))
+    );
+}
+
+#[test]
+fn introspects_custom_directives() {
+    let schema = schema();
+
+    let (result, errs) = ::execute(
+        "{ __schema { directives { name locations isRepeatable args { name defaultValue } } } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &user("USER"),
+    ).expect("Execution failed");
+    assert_eq!(errs, []);
+
+    let directives = result
+        .as_object_value()
+        .and_then(|r| r.get_field_value("__schema"))
+        .and_then(|s| s.as_object_value())
+        .and_then(|s| s.get_field_value("directives"))
+        .and_then(|d| d.as_list_value())
+        .expect("directives missing");
+
+    assert!(directives.contains(&graphql_value!({
+        "name": "suffix",
+        "locations": ["FIELD"],
+        "isRepeatable": false,
+        "args": [{ "name": "suffix", "defaultValue": "\"!\"" }],
+    })));
+    assert!(directives.contains(&graphql_value!({
+        "name": "uppercase",
+        "locations": ["FIELD"],
+        "isRepeatable": false,
+        "args": [],
+    })));
+    assert!(directives.contains(&graphql_value!({
+        "name": "tag",
+        "locations": ["FIELD", "INLINE_FRAGMENT"],
+        "isRepeatable": true,
+        "args": [],
+    })));
+}
+
+static AUTH_SCHEMA: &str = r#"
+    directive @auth(role: String!) on FIELD_DEFINITION | OBJECT
+    directive @uppercase on FIELD_DEFINITION | FIELD
+
+    type Query {
+        name: String @uppercase
+        secret: String @auth(role: "ADMIN")
+        admin: Admin
+    }
+
+    type Admin @auth(role: "ADMIN") {
+        name: String!
+    }
+"#;
+
+fn run_auth_query(query: &str, role: &str) -> (Value, Vec<ExecutionError>) {
+    let schema = DynamicSchema::<User>::from_schema_language(AUTH_SCHEMA)
+        .expect("Invalid schema")
+        .root_value(graphql_value!({
+            "name": "Luke",
+            "secret": "Dagobah",
+            "admin": { "name": "Yoda" },
+        }))
+        .into_root_node()
+        .expect("Invalid schema")
+        .directive_hook("auth", Auth)
+        .directive_hook("uppercase", Uppercase);
+
+    ::execute(query, None, &schema, &Variables::new(), &user(role)).expect("Execution failed")
+}
+
+#[test]
+fn runs_hooks_of_schema_directives() {
+    assert_eq!(
+        run_auth_query("{ name secret admin { name } }", "ADMIN"),
+        (
+            graphql_value!({
+                "name": "LUKE",
+                "secret": "Dagobah",
+                "admin": { "name": "Yoda" },
+            }),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn rejects_fields_before_they_resolve() {
+    assert_eq!(
+        run_auth_query("{ name secret admin { name } }", "USER"),
+        (
+            graphql_value!({ "name": "LUKE", "secret": None, "admin": None }),
+            vec![
+                ExecutionError::new(
+                    SourcePosition::new(7, 0, 7),
+                    &["secret"],
+                    FieldError::new("Requires the role ADMIN", Value::null()),
+                ),
+                ExecutionError::new(
+                    SourcePosition::new(22, 0, 22),
+                    &["admin", "name"],
+                    FieldError::new("Requires the role ADMIN", Value::null()),
+                ),
+            ]
+        )
+    );
+}
+
+#[test]
+fn prints_schema_directives() {
+    let schema = DynamicSchema::<User>::from_schema_language(AUTH_SCHEMA)
+        .expect("Invalid schema")
+        .into_root_node()
+        .expect("Invalid schema");
+
+    assert_eq!(
+        schema.as_schema_language(),
+        r#"directive @auth(role: String!) on FIELD_DEFINITION | OBJECT
+
+directive @uppercase on FIELD_DEFINITION | FIELD
+
+type Admin @auth(role: "ADMIN") {
+  name: String!
+}
+
+type Query {
+  name: String @uppercase
+  secret: String @auth(role: "ADMIN")
+  admin: Admin
+}
+"#
+    );
+}
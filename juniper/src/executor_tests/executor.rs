@@ -676,7 +676,7 @@ mod dynamic_context_switching {
 }
 
 mod propagates_errors_to_nullable_fields {
-    use executor::{ExecutionError, FieldError, FieldResult, IntoFieldError};
+    use executor::{ExecutionError, FieldError, FieldResult, IntoFieldError, PathSegment};
     use parser::SourcePosition;
     use schema::model::RootNode;
     use types::scalars::EmptyMutation;
@@ -880,7 +880,11 @@ mod propagates_errors_to_nullable_fields {
             errs,
             vec![ExecutionError::new(
                 SourcePosition::new(11, 0, 11),
-                &["inners", "nonNullableErrorField"],
+                &[
+                    PathSegment::from("inners"),
+                    PathSegment::Index(0),
+                    PathSegment::from("nonNullableErrorField"),
+                ],
                 FieldError::new("Error for nonNullableErrorField", Value::null()),
             )]
         );
@@ -904,33 +908,17 @@ mod propagates_errors_to_nullable_fields {
 
         assert_eq!(
             errs,
-            vec![
-                ExecutionError::new(
+            (0..5)
+                .map(|i| ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        PathSegment::Index(i),
+                        PathSegment::from("nonNullableErrorField"),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-                ExecutionError::new(
-                    SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
-                    FieldError::new("Error for nonNullableErrorField", Value::null()),
-                ),
-            ]
+                ))
+                .collect::<Vec<_>>()
         );
     }
 }
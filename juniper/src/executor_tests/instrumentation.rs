@@ -0,0 +1,297 @@
+use std::sync::{Arc, Mutex};
+
+use futures::Future;
+use serde_json::{self, Value as Json};
+
+use executor::{PathSegment, Variables};
+use extensions::{ApolloTracing, Extension, FieldInfo, Instrumentation};
+use http::GraphQLRequest;
+use schema::model::RootNode;
+use types::scalars::EmptyMutation;
+use value::Value;
+
+struct Query;
+
+struct Droid {
+    name: &'static str,
+}
+
+graphql_object!(Query: () |&self| {
+    field hero() -> Droid {
+        Droid { name: "R2-D2" }
+    }
+
+    field droids() -> Vec<Droid> {
+        vec![Droid { name: "R2-D2" }, Droid { name: "C-3PO" }]
+    }
+});
+
+graphql_object!(Droid: () |&self| {
+    field name() -> &str {
+        self.name
+    }
+});
+
+#[derive(Clone, Default)]
+pub(super) struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    pub(super) fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl Extension for Recorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn instrument(&self) -> Box<Instrumentation> {
+        Box::new(self.clone())
+    }
+}
+
+fn describe(field: &FieldInfo) -> String {
+    let path = field
+        .path()
+        .iter()
+        .map(|s| match *s {
+            PathSegment::Field(ref name) => name.clone(),
+            PathSegment::Index(index) => index.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+
+    format!(
+        "{} {}.{}: {}",
+        path,
+        field.parent_type_name(),
+        field.field_name(),
+        field.field_type()
+    )
+}
+
+impl Instrumentation for Recorder {
+    fn parse_start(&self) {
+        self.record("parse start".to_owned());
+    }
+
+    fn parse_end(&self) {
+        self.record("parse end".to_owned());
+    }
+
+    fn validation_start(&self) {
+        self.record("validation start".to_owned());
+    }
+
+    fn validation_end(&self) {
+        self.record("validation end".to_owned());
+    }
+
+    fn execution_start(&self) {
+        self.record("execution start".to_owned());
+    }
+
+    fn execution_end(&self) {
+        self.record("execution end".to_owned());
+    }
+
+    fn field_start(&self, field: &FieldInfo) {
+        self.record(format!("start {}", describe(field)));
+    }
+
+    fn field_end(&self, field: &FieldInfo) {
+        self.record(format!("end {}", describe(field)));
+    }
+
+    fn result(&self) -> Option<Value> {
+        Some(Value::int(self.events.lock().unwrap().len() as i64))
+    }
+}
+
+fn recorded_schema<'a>(recorder: &Recorder) -> RootNode<'a, Query, EmptyMutation<()>> {
+    RootNode::new(Query, EmptyMutation::<()>::new()).add_extension(recorder.clone())
+}
+
+#[test]
+fn notifies_instrumentation_of_each_stage_and_field() {
+    let recorder = Recorder::default();
+    let schema = recorded_schema(&recorder);
+
+    ::execute(
+        "{ hero { name } droids { droidName: name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    ).expect("Execution failed");
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse start",
+            "parse end",
+            "validation start",
+            "validation end",
+            "execution start",
+            "start hero Query.hero: Droid!",
+            "start hero.name Droid.name: String!",
+            "end hero.name Droid.name: String!",
+            "end hero Query.hero: Droid!",
+            "start droids Query.droids: [Droid!]!",
+            "start droids.0.droidName Droid.name: String!",
+            "end droids.0.droidName Droid.name: String!",
+            "start droids.1.droidName Droid.name: String!",
+            "end droids.1.droidName Droid.name: String!",
+            "end droids Query.droids: [Droid!]!",
+            "execution end",
+        ]
+    );
+}
+
+#[test]
+fn notifies_instrumentation_of_asynchronous_fields() {
+    let recorder = Recorder::default();
+    let schema = recorded_schema(&recorder);
+
+    ::execute_async(
+        "{ hero { name } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    ).expect("Execution failed")
+        .wait()
+        .expect("Execution failed");
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse start",
+            "parse end",
+            "validation start",
+            "validation end",
+            "execution start",
+            "start hero Query.hero: Droid!",
+            "start hero.name Droid.name: String!",
+            "end hero.name Droid.name: String!",
+            "end hero Query.hero: Droid!",
+            "execution end",
+        ]
+    );
+}
+
+#[test]
+fn stops_notifying_after_failed_validation() {
+    let recorder = Recorder::default();
+    let schema = recorded_schema(&recorder);
+
+    assert!(::execute("{ villain }", None, &schema, &Variables::new(), &()).is_err());
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse start",
+            "parse end",
+            "validation start",
+            "validation end",
+        ]
+    );
+}
+
+fn json(source: &str) -> Json {
+    serde_json::from_str(source).expect("Invalid JSON")
+}
+
+fn execute_request<'a>(
+    query: &str,
+    schema: &RootNode<'a, Query, EmptyMutation<()>>,
+    asynchronous: bool,
+) -> Json {
+    let request = GraphQLRequest::new(query.to_owned(), None, None);
+
+    let response = if asynchronous {
+        request.execute_async(schema, &()).wait().expect("Execution failed")
+    } else {
+        request.execute(schema, &())
+    };
+
+    serde_json::to_value(&response).expect("Invalid response")
+}
+
+#[test]
+fn serializes_extension_results() {
+    let recorder = Recorder::default();
+    let schema = recorded_schema(&recorder);
+
+    for &asynchronous in &[false, true] {
+        let response = execute_request("{ hero { name } }", &schema, asynchronous);
+
+        assert_eq!(response["data"], json(r#"{ "hero": { "name": "R2-D2" } }"#));
+        assert!(response["extensions"]["recorder"].is_number());
+    }
+}
+
+#[test]
+fn omits_extensions_without_results() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+
+    assert_eq!(
+        execute_request("{ hero { name } }", &schema, false),
+        json(r#"{ "data": { "hero": { "name": "R2-D2" } } }"#)
+    );
+}
+
+#[test]
+fn reports_apollo_tracing_timings() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new()).add_extension(ApolloTracing);
+
+    for &asynchronous in &[false, true] {
+        let response = execute_request("{ droids { name } }", &schema, asynchronous);
+        let tracing = &response["extensions"]["tracing"];
+
+        assert_eq!(tracing["version"], Json::from(1));
+        assert!(tracing["startTime"].as_str().unwrap().ends_with('Z'));
+        assert!(tracing["endTime"].as_str().unwrap() >= tracing["startTime"].as_str().unwrap());
+
+        let duration = tracing["duration"].as_i64().unwrap();
+
+        for stage in &["parsing", "validation"] {
+            let start_offset = tracing[stage]["startOffset"].as_i64().unwrap();
+            assert!(start_offset + tracing[stage]["duration"].as_i64().unwrap() <= duration);
+        }
+
+        let resolvers = tracing["execution"]["resolvers"].as_array().unwrap();
+        let fields = resolvers
+            .iter()
+            .map(|r| {
+                (
+                    r["path"].clone(),
+                    r["parentType"].as_str().unwrap(),
+                    r["fieldName"].as_str().unwrap(),
+                    r["returnType"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fields,
+            vec![
+                (json(r#"["droids"]"#), "Query", "droids", "[Droid!]!"),
+                (json(r#"["droids", 0, "name"]"#), "Droid", "name", "String!"),
+                (json(r#"["droids", 1, "name"]"#), "Droid", "name", "String!"),
+            ]
+        );
+
+        for resolver in resolvers {
+            let start_offset = resolver["startOffset"].as_i64().unwrap();
+            assert!(start_offset + resolver["duration"].as_i64().unwrap() <= duration);
+        }
+    }
+}
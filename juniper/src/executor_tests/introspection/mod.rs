@@ -25,11 +25,11 @@ struct Root {}
 
 graphql_scalar!(Scalar as "SampleScalar" {
     resolve(&self) -> Value {
-        Value::int(self.0)
+        Value::int(i64::from(self.0))
     }
 
     from_input_value(v: &InputValue) -> Option<Scalar> {
-        v.as_int_value().map(|i| Scalar(i))
+        v.as_int_value().map(|i| Scalar(i as i32))
     }
 });
 
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use futures::Future;
+
+use executor::{
+    Context, ExecutionError, FieldError, FromContext, Loader, Loaders, PathSegment, Variables,
+};
+use parser::SourcePosition;
+use schema::model::RootNode;
+use types::scalars::EmptyMutation;
+use value::{Object, Value};
+
+#[derive(Clone)]
+struct Human {
+    id: i32,
+    friend_ids: Vec<i32>,
+}
+
+struct Database {
+    loaders: Loaders,
+}
+
+impl Context for Database {}
+
+impl FromContext<Database> for Loaders {
+    fn from(db: &Database) -> &Loaders {
+        &db.loaders
+    }
+}
+
+graphql_object!(Human: Database |&self| {
+    field id() -> i32 {
+        self.id
+    }
+
+    async field friends(&executor) -> Vec<Option<Human>> {
+        executor
+            .loader::<i32, Human>()
+            .load_many(self.friend_ids.clone())
+    }
+
+    async field name(&executor) -> Option<String> {
+        executor.loader::<i32, String>().load(self.id)
+    }
+});
+
+struct Query;
+
+graphql_object!(Query: Database |&self| {
+    async field humans(&executor, ids: Vec<i32>) -> Vec<Option<Human>> {
+        executor.loader::<i32, Human>().load_many(ids)
+    }
+});
+
+/// Build a database whose loaders record the keys of every batch call
+fn database(batches: &Arc<Mutex<Vec<Vec<i32>>>>) -> Database {
+    let human_batches = batches.clone();
+    let humans = Loader::new(move |mut ids: Vec<i32>| {
+        ids.sort();
+        human_batches.lock().unwrap().push(ids.clone());
+
+        Ok(ids
+            .into_iter()
+            .filter(|&id| id <= 4)
+            .map(|id| {
+                let human = Human {
+                    id: id,
+                    friend_ids: vec![id % 4 + 1, (id + 1) % 4 + 1],
+                };
+                (id, human)
+            })
+            .collect::<HashMap<_, _>>())
+    });
+
+    let names = Loader::new(|_: Vec<i32>| -> Result<HashMap<i32, String>, _> {
+        Err(FieldError::from("Names are unavailable"))
+    });
+
+    let mut loaders = Loaders::new();
+    loaders.register(humans);
+    loaders.register(names);
+
+    Database { loaders: loaders }
+}
+
+fn ids(values: &Value) -> Vec<Option<i64>> {
+    values
+        .as_list_value()
+        .expect("Not a list")
+        .iter()
+        .map(|human| {
+            human
+                .as_object_value()
+                .and_then(|human| human.get_field_value("id"))
+                .and_then(|id| match *id {
+                    Value::Int(id) => Some(id),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn batches_loads_per_level() {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let db = database(&batches);
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    let doc = "{ humans(ids: [1, 2, 5]) { id friends { id friends { id } } } }";
+
+    let (result, errs) = ::execute_async(doc, None, &schema, &Variables::new(), &db)
+        .expect("Execution failed")
+        .wait()
+        .expect("Query future failed");
+
+    assert_eq!(errs, []);
+
+    let humans = result
+        .as_object_value()
+        .and_then(|result| result.get_field_value("humans"))
+        .expect("humans field missing");
+    assert_eq!(ids(humans), vec![Some(1), Some(2), None]);
+
+    let friends = humans.as_list_value().expect("Not a list")[0]
+        .as_object_value()
+        .and_then(|human| human.get_field_value("friends"))
+        .expect("friends field missing");
+    assert_eq!(ids(friends), vec![Some(2), Some(3)]);
+
+    // Humans 1 and 2 are cached after the first batch, only 3 and 4 are
+    // loaded by the second one, and all friends of friends are cached.
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2, 5], vec![3, 4]]);
+}
+
+#[test]
+fn reports_batch_errors_for_every_key() {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let db = database(&batches);
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    let doc = "{ humans(ids: [1, 2]) { name } }";
+
+    let (result, errs) = ::execute_async(doc, None, &schema, &Variables::new(), &db)
+        .expect("Execution failed")
+        .wait()
+        .expect("Query future failed");
+
+    let null_name: Object = vec![("name", Value::null())].into_iter().collect();
+    assert_eq!(
+        result,
+        Value::object(
+            vec![(
+                "humans",
+                Value::list(vec![
+                    Value::object(null_name.clone()),
+                    Value::object(null_name),
+                ]),
+            )].into_iter()
+                .collect()
+        )
+    );
+    assert_eq!(
+        errs,
+        vec![
+            ExecutionError::new(
+                SourcePosition::new(24, 0, 24),
+                &[
+                    PathSegment::from("humans"),
+                    PathSegment::Index(0),
+                    PathSegment::from("name"),
+                ],
+                FieldError::from("Names are unavailable"),
+            ),
+            ExecutionError::new(
+                SourcePosition::new(24, 0, 24),
+                &[
+                    PathSegment::from("humans"),
+                    PathSegment::Index(1),
+                    PathSegment::from("name"),
+                ],
+                FieldError::from("Names are unavailable"),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn synchronous_execution_does_not_load() {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let db = database(&batches);
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    let doc = "{ humans(ids: [3, 4]) { friends { id } } }";
+
+    let (result, errs) =
+        ::execute(doc, None, &schema, &Variables::new(), &db).expect("Execution failed");
+
+    assert_eq!(result, Value::null());
+    assert_eq!(
+        errs,
+        vec![ExecutionError::new(
+            SourcePosition::new(2, 0, 2),
+            &["humans"],
+            FieldError::from("Asynchronous fields can only be resolved by execute_async"),
+        )]
+    );
+    assert_eq!(*batches.lock().unwrap(), Vec::<Vec<i32>>::new());
+}
+
+#[test]
+fn primed_values_are_not_loaded() {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let db = database(&batches);
+    let loader = db.loaders.get::<i32, Human>().expect("Loader missing");
+
+    loader.prime(
+        7,
+        Human {
+            id: 7,
+            friend_ids: vec![],
+        },
+    );
+
+    let humans = loader.load_many(vec![7, 1]).wait().expect("Load failed");
+
+    assert_eq!(
+        humans
+            .iter()
+            .map(|h| h.as_ref().map(|h| h.id))
+            .collect::<Vec<_>>(),
+        vec![Some(7), Some(1)]
+    );
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1]]);
+
+    loader.clear(&1);
+    loader.load(1).wait().expect("Load failed");
+
+    assert_eq!(*batches.lock().unwrap(), vec![vec![1], vec![1]]);
+}
+
+#[test]
+fn loaders_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Loader<i32, Human>>();
+    assert_send_sync::<Loaders>();
+}
+
+#[test]
+fn loads_from_other_threads() {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let db = Arc::new(database(&batches));
+
+    let handles = (1..3)
+        .map(|id| {
+            let db = db.clone();
+            thread::spawn(move || {
+                let loader = db.loaders.get::<i32, Human>().expect("Loader missing");
+                loader.load(id).wait().expect("Load failed").map(|h| h.id)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let ids = handles
+        .into_iter()
+        .map(|handle| handle.join().expect("Loading thread panicked"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(ids, vec![Some(1), Some(2)]);
+}
@@ -1,6 +1,14 @@
+mod async_fields;
+mod custom_directives;
 mod directives;
 mod enums;
 mod executor;
+mod instrumentation;
 mod interfaces_unions;
 mod introspection;
+mod loaders;
+mod prepared;
+mod subscriptions;
+mod validation_rules;
 mod variables;
+mod writer;
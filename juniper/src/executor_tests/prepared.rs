@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use futures::Future;
+
+use ast::InputValue;
+use executor::Variables;
+use extensions::{Extension, Instrumentation};
+use http::GraphQLRequest;
+use schema::model::RootNode;
+use types::scalars::EmptyMutation;
+use value::Value;
+use {GraphQLError, PreparedQuery};
+
+struct Query;
+
+graphql_object!(Query: () |&self| {
+    field echo(text: String) -> String {
+        text
+    }
+
+    field nested() -> Query {
+        Query
+    }
+});
+
+struct OtherQuery;
+
+graphql_object!(OtherQuery: () as "Query" |&self| {
+    field echo(text: String) -> String {
+        text.to_uppercase()
+    }
+});
+
+/// Counts how often queries are parsed
+#[derive(Clone, Default)]
+struct ParseCounter {
+    parses: Arc<AtomicUsize>,
+}
+
+impl ParseCounter {
+    fn parses(&self) -> usize {
+        self.parses.load(Ordering::SeqCst)
+    }
+}
+
+impl Extension for ParseCounter {
+    fn name(&self) -> &str {
+        "parseCounter"
+    }
+
+    fn instrument(&self) -> Box<Instrumentation> {
+        Box::new(self.clone())
+    }
+}
+
+impl Instrumentation for ParseCounter {
+    fn parse_start(&self) {
+        self.parses.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn text_variables(text: &str) -> Variables {
+    vec![("text".to_owned(), InputValue::string(text))]
+        .into_iter()
+        .collect()
+}
+
+fn echo(text: &str) -> Value {
+    Value::object(vec![("echo", Value::string(text))].into_iter().collect())
+}
+
+#[test]
+fn executes_prepared_queries_with_different_variables() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+    let prepared = ::prepare("query Q($text: String!) { echo(text: $text) }", &schema)
+        .expect("Preparing failed");
+
+    for text in &["a", "b", "c"] {
+        let result = prepared.execute(None, &schema, &text_variables(text), &());
+
+        assert_eq!(result, Ok((echo(text), vec![])));
+    }
+
+    let result = prepared
+        .execute_async(None, &schema, &text_variables("d"), &())
+        .expect("Execution failed")
+        .wait();
+
+    assert_eq!(result, Ok((echo("d"), vec![])));
+
+    let mut written = Vec::new();
+    prepared
+        .execute_to_writer(None, &schema, &text_variables("e"), &(), &mut written)
+        .expect("Execution failed")
+        .expect("Writing failed");
+
+    assert_eq!(written, br#"{"echo":"e"}"#.to_vec());
+}
+
+#[test]
+fn rejects_invalid_queries_when_preparing() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+
+    match ::prepare("{ echo(text: ", &schema) {
+        Err(GraphQLError::ParseError(_)) => (),
+        _ => panic!("Expected a parse error"),
+    }
+
+    match ::prepare("{ unknown }", &schema) {
+        Err(GraphQLError::ValidationError(_)) => (),
+        _ => panic!("Expected a validation error"),
+    }
+}
+
+#[test]
+fn checks_variables_and_limits_on_execution() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new()).max_depth(2);
+
+    let prepared = ::prepare("query Q($text: String!) { echo(text: $text) }", &schema)
+        .expect("Preparing failed");
+
+    match prepared.execute(None, &schema, &Variables::new(), &()) {
+        Err(GraphQLError::ValidationError(_)) => (),
+        _ => panic!("Expected a validation error"),
+    }
+
+    let prepared = ::prepare("{ nested { nested { echo(text: \"a\") } } }", &schema)
+        .expect("Preparing failed");
+
+    match prepared.execute(None, &schema, &Variables::new(), &()) {
+        Err(GraphQLError::ValidationError(_)) => (),
+        _ => panic!("Expected a validation error"),
+    }
+}
+
+#[test]
+fn validates_queries_prepared_for_another_root_node() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+    let other_schema = RootNode::new(OtherQuery, EmptyMutation::<()>::new());
+
+    let prepared = ::prepare("{ nested { echo(text: \"a\") } }", &schema)
+        .expect("Preparing failed");
+
+    match prepared.execute(None, &other_schema, &Variables::new(), &()) {
+        Err(GraphQLError::ValidationError(_)) => (),
+        _ => panic!("Expected a validation error"),
+    }
+
+    let prepared =
+        ::prepare("{ echo(text: \"a\") }", &schema).expect("Preparing failed");
+
+    assert_eq!(
+        prepared.execute(None, &other_schema, &Variables::new(), &()),
+        Ok((echo("A"), vec![]))
+    );
+}
+
+#[test]
+fn shares_prepared_queries_between_threads() {
+    let schema = Arc::new(RootNode::new(Query, EmptyMutation::<()>::new()));
+    let prepared = ::prepare("query Q($text: String!) { echo(text: $text) }", &schema)
+        .expect("Preparing failed");
+
+    let threads = (0..4)
+        .map(|i| {
+            let schema = schema.clone();
+            let prepared: PreparedQuery = prepared.clone();
+
+            thread::spawn(move || {
+                let text = i.to_string();
+                let result = prepared.execute(None, &schema, &text_variables(&text), &());
+
+                assert_eq!(result, Ok((echo(&text), vec![])));
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        thread.join().expect("Execution failed");
+    }
+}
+
+#[test]
+fn parses_cached_queries_once() {
+    let counter = ParseCounter::default();
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new())
+        .add_extension(counter.clone())
+        .query_cache(10);
+    let query = "query Q($text: String!) { echo(text: $text) }";
+
+    for text in &["a", "b", "c"] {
+        let result = ::execute(query, None, &schema, &text_variables(text), &());
+
+        assert_eq!(result, Ok((echo(text), vec![])));
+    }
+
+    assert_eq!(counter.parses(), 1);
+
+    let variables =
+        InputValue::object(vec![("text", InputValue::string("d"))].into_iter().collect());
+    let request = GraphQLRequest::new(query.to_owned(), None, Some(variables));
+    assert!(request.execute(&schema, &()).is_ok());
+
+    assert_eq!(counter.parses(), 1);
+}
+
+#[test]
+fn checks_variables_of_cached_queries() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new()).query_cache(10);
+    let query = "query Q($text: String!) { echo(text: $text) }";
+
+    for _ in 0..2 {
+        match ::execute(query, None, &schema, &Variables::new(), &()) {
+            Err(GraphQLError::ValidationError(_)) => (),
+            _ => panic!("Expected a validation error"),
+        }
+
+        assert!(::execute(query, None, &schema, &text_variables("a"), &()).is_ok());
+    }
+}
+
+#[test]
+fn does_not_cache_invalid_queries() {
+    let counter = ParseCounter::default();
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new())
+        .add_extension(counter.clone())
+        .query_cache(10);
+
+    for _ in 0..2 {
+        match ::execute("{ unknown }", None, &schema, &Variables::new(), &()) {
+            Err(GraphQLError::ValidationError(_)) => (),
+            _ => panic!("Expected a validation error"),
+        }
+    }
+
+    assert_eq!(counter.parses(), 2);
+}
+
+#[test]
+fn evicts_the_least_recently_used_query() {
+    let counter = ParseCounter::default();
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new())
+        .add_extension(counter.clone())
+        .query_cache(2);
+    let vars = Variables::new();
+
+    for query in &[
+        "{ a: echo(text: \"a\") }",
+        "{ b: echo(text: \"b\") }",
+        "{ a: echo(text: \"a\") }",
+        "{ c: echo(text: \"c\") }",
+        "{ a: echo(text: \"a\") }",
+    ] {
+        ::execute(query, None, &schema, &vars, &()).expect("Execution failed");
+    }
+
+    assert_eq!(counter.parses(), 3);
+
+    ::execute("{ b: echo(text: \"b\") }", None, &schema, &vars, &()).expect("Execution failed");
+
+    assert_eq!(counter.parses(), 4);
+}
@@ -0,0 +1,513 @@
+use std::sync::Arc;
+use std::thread;
+
+use futures::stream::{self, Stream};
+use futures::{task, Async, Poll};
+use serde_json::{self, Value as Json};
+
+use ast::InputValue;
+use executor::{ExecutionError, FieldError, FieldResult, Registry, Variables};
+use parser::SourcePosition;
+use schema::meta::MetaType;
+use schema::model::RootNode;
+use types::base::{Arguments, GraphQLType};
+use types::scalars::EmptyMutation;
+use types::subscriptions::{GraphQLSubscriptionType, SourceEvents};
+use value::{Object, Value};
+use http::{GraphQLRequest, GraphQLResponse};
+use executor_tests::instrumentation::Recorder;
+use validation::RuleError;
+use GraphQLError;
+
+struct Query;
+
+graphql_object!(Query: () |&self| {
+    field ping() -> &str {
+        "pong"
+    }
+});
+
+struct Message {
+    id: i32,
+    text: String,
+}
+
+graphql_object!(Message: () |&self| {
+    field id() -> i32 {
+        self.id
+    }
+
+    field text() -> &str {
+        &self.text
+    }
+});
+
+struct Subscription;
+
+impl GraphQLType for Subscription {
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("Subscription")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r> {
+        let fields = &[
+            registry
+                .field::<Message>("messages", &())
+                .argument(registry.arg::<i32>("count", &())),
+            registry.field::<Option<i32>>("broken", &()),
+            registry.field::<i32>("ticks", &()),
+            registry.field::<i32>("flaky", &()),
+        ];
+
+        registry
+            .build_object_type::<Subscription>(&(), fields)
+            .into_meta()
+    }
+}
+
+impl GraphQLSubscriptionType for Subscription {
+    fn resolve_field_into_stream<'a>(
+        &'a self,
+        info: &'a (),
+        field_name: &str,
+        args: &Arguments,
+        _: &'a (),
+    ) -> FieldResult<SourceEvents<'a, ()>> {
+        match field_name {
+            "messages" => {
+                let count: i32 = args.get("count").unwrap();
+                Ok(SourceEvents::new(
+                    info,
+                    (1..count + 1).map(|id| Message {
+                        id: id,
+                        text: format!("Message {}", id),
+                    }),
+                ))
+            }
+            "broken" => Err(FieldError::from("Subscription failed")),
+            "ticks" => Ok(SourceEvents::from_stream(info, Ticks { next: 1, ready: false })),
+            "flaky" => Ok(SourceEvents::from_stream(
+                info,
+                stream::iter_result(vec![
+                    Ok(1),
+                    Err(FieldError::from("Source failed")),
+                    Ok(3),
+                ]),
+            )),
+            _ => panic!("Field {} not found on type Subscription", field_name),
+        }
+    }
+}
+
+/// Source that is only ready every other time it is polled, and ends after
+/// its third event
+struct Ticks {
+    next: i32,
+    ready: bool,
+}
+
+impl Stream for Ticks {
+    type Item = i32;
+    type Error = FieldError;
+
+    fn poll(&mut self) -> Poll<Option<i32>, FieldError> {
+        if self.next > 3 {
+            return Ok(Async::Ready(None));
+        }
+
+        if !self.ready {
+            self.ready = true;
+            task::current().notify();
+            return Ok(Async::NotReady);
+        }
+
+        self.ready = false;
+        self.next += 1;
+        Ok(Async::Ready(Some(self.next - 1)))
+    }
+}
+
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<()>, Subscription> {
+    RootNode::new_with_subscription(Query, EmptyMutation::new(), Subscription)
+}
+
+fn message(response_name: &str, id: i32, text: &str) -> Value {
+    Value::object(
+        vec![(
+            response_name,
+            Value::object(
+                vec![("id", Value::int(i64::from(id))), ("text", Value::string(text))]
+                    .into_iter()
+                    .collect(),
+            ),
+        )].into_iter()
+            .collect(),
+    )
+}
+
+#[test]
+fn resolves_each_event_against_the_selection_set() {
+    let schema = schema();
+    let doc = "subscription { messages(count: 3) { id text } }";
+
+    let stream = ::execute_subscription(doc, None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![
+            (message("messages", 1, "Message 1"), vec![]),
+            (message("messages", 2, "Message 2"), vec![]),
+            (message("messages", 3, "Message 3"), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn uses_aliases_fragments_and_variables() {
+    let schema = schema();
+    let doc = r"
+        subscription Updates($count: Int!) {
+            ...Frag
+        }
+
+        fragment Frag on Subscription {
+            latest: messages(count: $count) { id text }
+        }";
+
+    let vars = vec![("count".to_owned(), InputValue::int(1))]
+        .into_iter()
+        .collect();
+
+    let stream = ::execute_subscription(doc, Some("Updates"), &schema, &vars, &())
+        .expect("Execution failed");
+
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(message("latest", 1, "Message 1"), vec![])]
+    );
+}
+
+#[test]
+fn reports_errors_when_creating_the_source_stream() {
+    let schema = schema();
+    let doc = "subscription { broken }";
+
+    let stream = ::execute_subscription(doc, None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(15, 0, 15),
+                &["broken"],
+                FieldError::from("Subscription failed"),
+            )],
+        )]
+    );
+}
+
+#[test]
+fn waits_for_events_of_source_streams() {
+    let schema = schema();
+    let doc = "subscription { ticks }";
+
+    let stream = ::execute_subscription(doc, None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    let tick = |i| Value::object(vec![("ticks", Value::int(i))].into_iter().collect());
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(tick(1), vec![]), (tick(2), vec![]), (tick(3), vec![])]
+    );
+}
+
+#[test]
+fn subscription_streams_are_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let schema = schema();
+    let stream = ::execute_subscription("subscription { ticks }", None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_send(&stream);
+}
+
+#[test]
+fn ends_after_reporting_errors_of_the_source_stream() {
+    let schema = schema();
+    let doc = "subscription { flaky }";
+
+    let stream = ::execute_subscription(doc, None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![
+            (
+                Value::object(vec![("flaky", Value::int(1))].into_iter().collect()),
+                vec![],
+            ),
+            (
+                Value::null(),
+                vec![ExecutionError::new(
+                    SourcePosition::new(15, 0, 15),
+                    &["flaky"],
+                    FieldError::from("Source failed"),
+                )],
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rejects_introspection_root_fields() {
+    let schema = schema();
+    let doc = "subscription { __typename }";
+
+    assert!(
+        match ::execute_subscription(doc, None, &schema, &Variables::new(), &()) {
+            Err(GraphQLError::ValidationError(errors)) => {
+                errors == vec![RuleError::new(
+                    "Anonymous subscription must not select an introspection top level field",
+                    &[SourcePosition::new(15, 0, 15)],
+                )]
+            }
+            _ => false,
+        }
+    );
+}
+
+#[test]
+fn reports_fields_that_can_not_be_subscribed_to() {
+    let schema = schema();
+    let document = ::parser::parse_document_source("subscription { __typename }")
+        .expect("Parsing failed");
+
+    let stream = ::execute_validated_subscription(
+        ::prepared::QueryDocument::Parsed(document),
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+        Default::default(),
+    ).expect("Execution failed");
+
+    assert_eq!(
+        stream.wait().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(15, 0, 15),
+                &["__typename"],
+                FieldError::from("Field __typename can not be subscribed to"),
+            )],
+        )]
+    );
+}
+
+#[test]
+fn notifies_instrumentation_of_each_event() {
+    let recorder = Recorder::default();
+    let schema = schema().add_extension(recorder.clone());
+    let doc = "subscription { messages(count: 2) { id } }";
+
+    ::execute_subscription(doc, None, &schema, &Variables::new(), &())
+        .expect("Execution failed")
+        .wait()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "parse start",
+            "parse end",
+            "validation start",
+            "validation end",
+            "execution start",
+            "start messages.id Message.id: Int!",
+            "end messages.id Message.id: Int!",
+            "execution end",
+            "execution start",
+            "start messages.id Message.id: Int!",
+            "end messages.id Message.id: Int!",
+            "execution end",
+        ]
+    );
+}
+
+#[test]
+fn queries_still_execute() {
+    let schema = schema();
+
+    let (result, errs) = ::execute("{ ping }", None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(vec![("ping", Value::string("pong"))].into_iter().collect())
+    );
+}
+
+#[test]
+fn rejects_mismatched_operation_types() {
+    let schema = schema();
+
+    assert_eq!(
+        ::execute(
+            "subscription { messages(count: 1) { id } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &(),
+        ),
+        Err(GraphQLError::IsSubscription)
+    );
+
+    assert!(match ::execute_subscription("{ ping }", None, &schema, &Variables::new(), &()) {
+        Err(GraphQLError::NotSubscription) => true,
+        _ => false,
+    });
+}
+
+#[test]
+fn introspects_subscription_type() {
+    let schema = schema();
+    let doc = "{ __schema { subscriptionType { name } } }";
+
+    let (result, errs) =
+        ::execute(doc, None, &schema, &Variables::new(), &()).expect("Execution failed");
+
+    assert_eq!(errs, []);
+
+    let subscription_type: Object = vec![("name", Value::string("Subscription"))]
+        .into_iter()
+        .collect();
+    let schema_type: Object = vec![("subscriptionType", Value::object(subscription_type))]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        result,
+        Value::object(
+            vec![("__schema", Value::object(schema_type))]
+                .into_iter()
+                .collect()
+        )
+    );
+}
+
+#[test]
+fn schema_without_subscriptions_has_no_subscription_type() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+    let doc = "{ __schema { subscriptionType { name } } }";
+
+    let (result, errs) =
+        ::execute(doc, None, &schema, &Variables::new(), &()).expect("Execution failed");
+
+    assert_eq!(errs, []);
+
+    let schema_type: Object = vec![("subscriptionType", Value::null())]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        result,
+        Value::object(
+            vec![("__schema", Value::object(schema_type))]
+                .into_iter()
+                .collect()
+        )
+    );
+}
+
+fn to_json(response: &GraphQLResponse) -> Json {
+    serde_json::to_value(response).expect("Invalid response")
+}
+
+#[test]
+fn subscribes_to_http_requests() {
+    let schema = schema();
+    let request = GraphQLRequest::new(
+        "subscription { messages(count: 2) { id } }".to_owned(),
+        None,
+        None,
+    );
+
+    let responses = request
+        .subscribe(&schema, &())
+        .unwrap_or_else(|_| panic!("Subscribing failed"))
+        .wait()
+        .map(|response| to_json(&response.unwrap()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        responses,
+        vec![
+            serde_json::from_str::<Json>(r#"{ "data": { "messages": { "id": 1 } } }"#).unwrap(),
+            serde_json::from_str::<Json>(r#"{ "data": { "messages": { "id": 2 } } }"#).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn executes_queries_sent_as_subscription_requests() {
+    let schema = schema();
+    let request = GraphQLRequest::new("{ ping }".to_owned(), None, None);
+
+    let responses = request
+        .subscribe(&schema, &())
+        .unwrap_or_else(|_| panic!("Subscribing failed"))
+        .wait()
+        .map(|response| to_json(&response.unwrap()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        responses,
+        vec![serde_json::from_str::<Json>(r#"{ "data": { "ping": "pong" } }"#).unwrap()]
+    );
+}
+
+#[test]
+fn returns_errors_of_invalid_subscription_requests() {
+    let schema = schema();
+    let request = GraphQLRequest::new("subscription { unknown }".to_owned(), None, None);
+
+    let result = request.subscribe(&schema, &());
+
+    match result {
+        Err(response) => {
+            assert!(!response.is_ok());
+            assert!(to_json(&response)["errors"].is_array());
+        }
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn subscribes_with_shared_root_nodes() {
+    let schema = Arc::new(schema());
+    let context = Arc::new(());
+
+    let responses = thread::spawn(move || {
+        let request = GraphQLRequest::new(
+            "subscription { messages(count: 1) { id } }".to_owned(),
+            None,
+            None,
+        );
+
+        request
+            .subscribe(&schema, &context)
+            .unwrap_or_else(|_| panic!("Subscribing failed"))
+            .wait()
+            .count()
+    }).join()
+        .expect("Subscribing failed");
+
+    assert_eq!(responses, 1);
+}
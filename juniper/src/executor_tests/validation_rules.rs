@@ -0,0 +1,90 @@
+use ast::Operation;
+use executor::Variables;
+use parser::{SourcePosition, Spanning};
+use schema::model::RootNode;
+use types::scalars::EmptyMutation;
+use validation::{BuiltInRule, RuleError, ValidatorContext, Visitor};
+use GraphQLError::ValidationError;
+
+struct TestType;
+
+graphql_object!(TestType: () |&self| {
+    field a() -> &str {
+        "a"
+    }
+});
+
+struct RequireOperationNames;
+
+impl<'a> Visitor<'a> for RequireOperationNames {
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        op: &'a Spanning<Operation>,
+    ) {
+        if op.item.name.is_none() {
+            ctx.report_error("Operations must be named", &[op.start.clone()]);
+        }
+    }
+}
+
+#[test]
+fn custom_rule_rejects_query() {
+    let schema =
+        RootNode::new(TestType, EmptyMutation::<()>::new()).add_validation_rule(|| {
+            RequireOperationNames
+        });
+
+    assert_eq!(
+        ::execute("{ a }", None, &schema, &Variables::new(), &()),
+        Err(ValidationError(vec![RuleError::new(
+            "Operations must be named",
+            &[SourcePosition::new(0, 0, 0)],
+        )]))
+    );
+
+    assert_eq!(
+        ::execute("query Named { a }", None, &schema, &Variables::new(), &()),
+        Ok((graphql_value!({ "a": "a" }), vec![]))
+    );
+}
+
+#[test]
+fn custom_rule_reports_with_built_in_rules() {
+    let schema =
+        RootNode::new(TestType, EmptyMutation::<()>::new()).add_validation_rule(|| {
+            RequireOperationNames
+        });
+
+    assert_eq!(
+        ::execute("{ a, b }", None, &schema, &Variables::new(), &()),
+        Err(ValidationError(vec![
+            RuleError::new("Operations must be named", &[SourcePosition::new(0, 0, 0)]),
+            RuleError::new(
+                r#"Unknown field "b" on type "TestType""#,
+                &[SourcePosition::new(5, 0, 5)],
+            ),
+        ]))
+    );
+}
+
+#[test]
+fn disabled_built_in_rule() {
+    let query = "query Q { a } fragment unused on TestType { a }";
+
+    let schema = RootNode::new(TestType, EmptyMutation::<()>::new());
+    assert_eq!(
+        ::execute(query, None, &schema, &Variables::new(), &()),
+        Err(ValidationError(vec![RuleError::new(
+            r#"Fragment "unused" is never used"#,
+            &[SourcePosition::new(14, 0, 14)],
+        )]))
+    );
+
+    let schema = RootNode::new(TestType, EmptyMutation::<()>::new())
+        .disable_validation_rule(BuiltInRule::NoUnusedFragments);
+    assert_eq!(
+        ::execute(query, None, &schema, &Variables::new(), &()),
+        Ok((graphql_value!({ "a": "a" }), vec![]))
+    );
+}
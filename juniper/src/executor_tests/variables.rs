@@ -152,6 +152,18 @@ graphql_object!(TestType: () |&self| {
         format!("value: {}", value)
     }
 
+    field long_input(value: i64) -> String {
+        format!("value: {}", value)
+    }
+
+    field unsigned_long_input(value: u64) -> String {
+        format!("value: {}", value)
+    }
+
+    field unsigned_longs() -> Vec<u64> {
+        vec![9_223_372_036_854_775_807, u64::max_value()]
+    }
+
     field float_input(value: f64) -> String {
         format!("value: {}", value)
     }
@@ -1179,6 +1191,150 @@ mod integers {
             )])
         );
     }
+
+    #[test]
+    fn does_not_accept_values_outside_32_bits() {
+        let schema = RootNode::new(TestType, EmptyMutation::<()>::new());
+
+        let query = r#"query q($var: Int!) { integerInput(value: $var) }"#;
+        let vars = vec![("var".to_owned(), InputValue::int(2_147_483_648))]
+            .into_iter()
+            .collect();
+
+        let error = ::execute(query, None, &schema, &vars, &()).unwrap_err();
+
+        assert_eq!(
+            error,
+            ValidationError(vec![RuleError::new(
+                r#"Variable "$var" got invalid value. Expected "Int"."#,
+                &[SourcePosition::new(8, 0, 8)],
+            )])
+        );
+    }
+}
+
+mod longs {
+    use super::*;
+
+    #[test]
+    fn values_outside_32_bits_should_work() {
+        run_variable_query(
+            r#"query q($var: Long!) { longInput(value: $var) }"#,
+            vec![("var".to_owned(), InputValue::int(-9_007_199_254_740_993))]
+                .into_iter()
+                .collect(),
+            |result| {
+                assert_eq!(
+                    result.get_field_value("longInput"),
+                    Some(&Value::string(r#"value: -9007199254740993"#))
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn literal_values_outside_32_bits_should_work() {
+        run_query(r#"{ longInput(value: 9007199254740993) }"#, |result| {
+            assert_eq!(
+                result.get_field_value("longInput"),
+                Some(&Value::string(r#"value: 9007199254740993"#))
+            );
+        });
+    }
+}
+
+mod unsigned_longs {
+    use super::*;
+
+    #[test]
+    fn values_outside_32_bits_should_work() {
+        run_variable_query(
+            r#"query q($var: UnsignedLong!) { unsignedLongInput(value: $var) }"#,
+            vec![("var".to_owned(), InputValue::int(9_007_199_254_740_993))]
+                .into_iter()
+                .collect(),
+            |result| {
+                assert_eq!(
+                    result.get_field_value("unsignedLongInput"),
+                    Some(&Value::string(r#"value: 9007199254740993"#))
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn values_outside_signed_64_bits_should_work() {
+        run_variable_query(
+            r#"query q($var: UnsignedLong!) { unsignedLongInput(value: $var) }"#,
+            vec![(
+                "var".to_owned(),
+                InputValue::float(9_223_372_036_854_775_808.0),
+            )].into_iter()
+                .collect(),
+            |result| {
+                assert_eq!(
+                    result.get_field_value("unsignedLongInput"),
+                    Some(&Value::string(r#"value: 9223372036854775808"#))
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn does_not_accept_negative_values() {
+        let schema = RootNode::new(TestType, EmptyMutation::<()>::new());
+
+        let query = r#"query q($var: UnsignedLong!) { unsignedLongInput(value: $var) }"#;
+        let vars = vec![("var".to_owned(), InputValue::int(-1))]
+            .into_iter()
+            .collect();
+
+        let error = ::execute(query, None, &schema, &vars, &()).unwrap_err();
+
+        assert_eq!(
+            error,
+            ValidationError(vec![RuleError::new(
+                r#"Variable "$var" got invalid value. Expected "UnsignedLong"."#,
+                &[SourcePosition::new(8, 0, 8)],
+            )])
+        );
+    }
+
+    #[test]
+    fn does_not_accept_fractional_or_too_large_values() {
+        let schema = RootNode::new(TestType, EmptyMutation::<()>::new());
+
+        let query = r#"query q($var: UnsignedLong!) { unsignedLongInput(value: $var) }"#;
+
+        for value in &[10.5, 18_446_744_073_709_551_616.0] {
+            let vars = vec![("var".to_owned(), InputValue::float(*value))]
+                .into_iter()
+                .collect();
+
+            let error = ::execute(query, None, &schema, &vars, &()).unwrap_err();
+
+            assert_eq!(
+                error,
+                ValidationError(vec![RuleError::new(
+                    r#"Variable "$var" got invalid value. Expected "UnsignedLong"."#,
+                    &[SourcePosition::new(8, 0, 8)],
+                )])
+            );
+        }
+    }
+
+    #[test]
+    fn values_outside_signed_64_bits_resolve_as_floats() {
+        run_query(r#"{ unsignedLongs }"#, |result| {
+            assert_eq!(
+                result.get_field_value("unsignedLongs"),
+                Some(&Value::list(vec![
+                    Value::int(9_223_372_036_854_775_807),
+                    Value::float(18_446_744_073_709_551_616.0),
+                ]))
+            );
+        });
+    }
 }
 
 mod floats {
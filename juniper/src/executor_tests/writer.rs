@@ -0,0 +1,259 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{self, Value as Json};
+
+use executor::{Context, FieldResult, Variables};
+use http::GraphQLRequest;
+use schema::model::RootNode;
+use tests::model::Database;
+use types::scalars::EmptyMutation;
+use GraphQLError;
+
+fn execute_both<QueryT>(
+    query: &str,
+    schema: &RootNode<QueryT, EmptyMutation<QueryT::Context>>,
+    context: &QueryT::Context,
+) -> (Json, Json)
+where
+    QueryT: ::GraphQLType,
+{
+    let vars = Variables::new();
+
+    let (value, errors) = ::execute(query, None, schema, &vars, context).expect("Execution failed");
+
+    let mut written = Vec::new();
+    let written_errors = ::execute_to_writer(query, None, schema, &vars, context, &mut written)
+        .expect("Execution failed")
+        .expect("Writing failed");
+
+    assert_eq!(written_errors, errors);
+
+    (
+        serde_json::from_slice(&written).expect("Invalid JSON written"),
+        serde_json::to_value(&value).expect("Invalid value"),
+    )
+}
+
+#[test]
+fn writes_the_data_of_execute() {
+    let database = Database::new();
+    let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+
+    for query in &[
+        "{ hero { name friends { name appearsIn } } }",
+        "{ hero { __typename id ...on Droid { primaryFunction } } }",
+        "{ luke: human(id: \"1000\") { ...Names } leia: human(id: \"1003\") { ...Names } }
+         fragment Names on Human { name friends { name } }",
+        "{ hero { name } hero { id friends { name } } }",
+        "{ hero { name @skip(if: true) id @include(if: false) friends @include(if: true) { name } } }",
+        "{ hero { ... { name } ... @include(if: true) { id } } }",
+        "{ human(id: \"unknown\") { name } }",
+        "{ __schema { types { name fields { name type { name ofType { name } } } } } }",
+    ] {
+        let (written, value) = execute_both(query, &schema, &database);
+        assert_eq!(written, value, "Different data for {}", query);
+    }
+}
+
+mod null_propagation {
+    use super::execute_both;
+
+    use executor::FieldResult;
+    use schema::model::RootNode;
+    use types::scalars::EmptyMutation;
+
+    struct Schema;
+    struct Inner;
+
+    graphql_object!(Schema: () |&self| {
+        field inner() -> Inner { Inner }
+        field nullable_inner() -> Option<Inner> { Some(Inner) }
+        field inners() -> Vec<Inner> { (0..3).map(|_| Inner).collect() }
+        field nullable_inners() -> Vec<Option<Inner>> { (0..3).map(|_| Some(Inner)).collect() }
+    });
+
+    graphql_object!(Inner: () |&self| {
+        field value() -> i32 { 1 }
+        field nullable_field() -> Option<Inner> { Some(Inner) }
+        field non_nullable_field() -> Inner { Inner }
+        field nullable_error_field() -> FieldResult<Option<&str>> { Err("Error for nullableErrorField")? }
+        field non_nullable_error_field() -> FieldResult<&str> { Err("Error for nonNullableErrorField")? }
+    });
+
+    #[test]
+    fn replaces_objects_with_null() {
+        let schema = RootNode::new(Schema, EmptyMutation::<()>::new());
+
+        for query in &[
+            "{ inner { value nullableErrorField } }",
+            "{ inner { value nonNullableErrorField } }",
+            "{ nullableInner { value nonNullableErrorField } }",
+            "{ nullableInner { value nonNullableField { value nonNullableErrorField } } }",
+            "{ inner { value nullableField { value nonNullableField { nonNullableErrorField } } } }",
+            "{ inners { value nonNullableErrorField } }",
+            "{ nullableInners { value nonNullableErrorField } }",
+            "{ nullableInner { value } inners { value nonNullableErrorField } }",
+        ] {
+            let (written, value) = execute_both(query, &schema, &());
+            assert_eq!(written, value, "Different data for {}", query);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct SharedWriter {
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Context for SharedWriter {}
+
+struct Query;
+
+struct Item;
+
+graphql_object!(Query: SharedWriter |&self| {
+    field items() -> Option<Vec<Option<Item>>> {
+        Some((0..1000).map(|_| Some(Item)).collect())
+    }
+
+    field written_bytes(&executor) -> Option<i32> {
+        Some(executor.context().bytes.lock().unwrap().len() as i32)
+    }
+
+    field error() -> FieldResult<Option<i32>> {
+        Err("Not available")?
+    }
+});
+
+graphql_object!(Item: SharedWriter |&self| {
+    field text() -> Option<&str> {
+        Some("All work and no play makes Jack a dull boy")
+    }
+});
+
+#[test]
+fn writes_data_while_executing() {
+    let schema = RootNode::new(Query, EmptyMutation::<SharedWriter>::new());
+    let writer = SharedWriter::default();
+
+    let errors = ::execute_to_writer(
+        "{ items { text } writtenBytes }",
+        None,
+        &schema,
+        &Variables::new(),
+        &writer,
+        writer.clone(),
+    ).expect("Execution failed")
+        .expect("Writing failed");
+    assert_eq!(errors, []);
+
+    let data: Json = serde_json::from_slice(&writer.bytes.lock().unwrap()).expect("Invalid JSON");
+
+    assert_eq!(data["items"].as_array().map(Vec::len), Some(1000));
+    assert!(data["writtenBytes"].as_i64().unwrap() > 0);
+}
+
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "Connection closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn returns_write_errors() {
+    let schema = RootNode::new(Query, EmptyMutation::<SharedWriter>::new());
+
+    let result = ::execute_to_writer(
+        "{ items { text } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &SharedWriter::default(),
+        FailingWriter,
+    ).expect("Execution failed");
+
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn writes_nothing_if_execution_fails() {
+    let schema = RootNode::new(Query, EmptyMutation::<SharedWriter>::new());
+    let mut written = Vec::new();
+
+    let result = ::execute_to_writer(
+        "{ unknown }",
+        None,
+        &schema,
+        &Variables::new(),
+        &SharedWriter::default(),
+        &mut written,
+    );
+
+    match result {
+        Err(GraphQLError::ValidationError(_)) => (),
+        _ => panic!("Expected a validation error"),
+    }
+    assert!(written.is_empty());
+}
+
+#[test]
+fn writes_http_responses() {
+    let database = Database::new();
+    let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+
+    for query in &[
+        "{ hero { name } }",
+        "{ villain }",
+        "query A { hero { name } } query B { hero { id } }",
+    ] {
+        let request = GraphQLRequest::new(query.to_string(), None, None);
+        let response = request.execute(&schema, &database);
+
+        let mut written = Vec::new();
+        let is_ok = request
+            .execute_to_writer(&schema, &database, &mut written)
+            .expect("Writing failed");
+
+        assert_eq!(is_ok, response.is_ok());
+        assert_eq!(
+            serde_json::from_slice::<Json>(&written).expect("Invalid JSON written"),
+            serde_json::to_value(&response).expect("Invalid response"),
+        );
+    }
+}
+
+#[test]
+fn writes_errors_and_extensions_of_http_responses() {
+    let schema = RootNode::new(Query, EmptyMutation::<SharedWriter>::new())
+        .add_extension(::extensions::ApolloTracing);
+    let request = GraphQLRequest::new("{ error }".to_owned(), None, None);
+
+    let mut written = Vec::new();
+    let is_ok = request
+        .execute_to_writer(&schema, &SharedWriter::default(), &mut written)
+        .expect("Writing failed");
+    assert!(is_ok);
+
+    let response: Json = serde_json::from_slice(&written).expect("Invalid JSON written");
+
+    assert_eq!(response["data"], serde_json::from_str::<Json>(r#"{ "error": null }"#).unwrap());
+    assert_eq!(response["errors"][0]["message"], Json::from("Not available"));
+    assert_eq!(response["extensions"]["tracing"]["version"], Json::from(1));
+}
@@ -0,0 +1,211 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use executor::PathSegment;
+use extensions::{Extension, FieldInfo, Instrumentation};
+use value::{Object, Value};
+
+/// Extension reporting timings in the Apollo tracing format
+///
+/// The timings are added to the `tracing` key of the `extensions` of every
+/// response, following version 1 of the [Apollo tracing
+/// format](https://github.com/apollographql/apollo-tracing). Durations and
+/// offsets are measured in nanoseconds. The duration of a field includes
+/// resolving its selections.
+///
+/// ```rust
+/// # use juniper::{EmptyMutation, RootNode};
+/// use juniper::extensions::ApolloTracing;
+///
+/// struct Query;
+///
+/// juniper::graphql_object!(Query: () |&self| {
+///     field hello() -> &str { "world" }
+/// });
+///
+/// let schema = RootNode::new(Query, EmptyMutation::<()>::new())
+///     .add_extension(ApolloTracing);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ApolloTracing;
+
+impl Extension for ApolloTracing {
+    fn name(&self) -> &str {
+        "tracing"
+    }
+
+    fn instrument(&self) -> Box<Instrumentation> {
+        Box::new(Tracing {
+            start_time: Utc::now(),
+            start: Instant::now(),
+            trace: Mutex::new(Trace::default()),
+        })
+    }
+}
+
+struct Tracing {
+    start_time: DateTime<Utc>,
+    start: Instant,
+    trace: Mutex<Trace>,
+}
+
+#[derive(Default)]
+struct Trace {
+    parsing: Span,
+    validation: Span,
+    resolvers: Vec<Resolver>,
+}
+
+#[derive(Default)]
+struct Span {
+    start_offset: Option<Duration>,
+    duration: Option<Duration>,
+}
+
+struct Resolver {
+    path: Vec<PathSegment>,
+    parent_type: String,
+    field_name: String,
+    return_type: String,
+    span: Span,
+}
+
+impl Tracing {
+    fn start_span<F>(&self, span: F)
+    where
+        F: FnOnce(&mut Trace) -> &mut Span,
+    {
+        let offset = self.start.elapsed();
+        span(&mut self.trace.lock().unwrap()).start_offset = Some(offset);
+    }
+
+    fn end_span<F>(&self, span: F)
+    where
+        F: FnOnce(&mut Trace) -> &mut Span,
+    {
+        let offset = self.start.elapsed();
+        span(&mut self.trace.lock().unwrap()).end(offset);
+    }
+}
+
+impl Instrumentation for Tracing {
+    fn parse_start(&self) {
+        self.start_span(|t| &mut t.parsing);
+    }
+
+    fn parse_end(&self) {
+        self.end_span(|t| &mut t.parsing);
+    }
+
+    fn validation_start(&self) {
+        self.start_span(|t| &mut t.validation);
+    }
+
+    fn validation_end(&self) {
+        self.end_span(|t| &mut t.validation);
+    }
+
+    fn field_start(&self, field: &FieldInfo) {
+        let offset = self.start.elapsed();
+
+        self.trace.lock().unwrap().resolvers.push(Resolver {
+            path: field.path().to_vec(),
+            parent_type: field.parent_type_name().to_owned(),
+            field_name: field.field_name().to_owned(),
+            return_type: field.field_type().to_string(),
+            span: Span {
+                start_offset: Some(offset),
+                duration: None,
+            },
+        });
+    }
+
+    fn field_end(&self, field: &FieldInfo) {
+        let offset = self.start.elapsed();
+        let mut trace = self.trace.lock().unwrap();
+
+        if let Some(resolver) = trace
+            .resolvers
+            .iter_mut()
+            .rev()
+            .find(|r| r.path == field.path())
+        {
+            resolver.span.end(offset);
+        }
+    }
+
+    fn result(&self) -> Option<Value> {
+        let duration = self.start.elapsed();
+        let trace = self.trace.lock().unwrap();
+
+        let end_time = self.start_time
+            + ::chrono::Duration::from_std(duration).unwrap_or_else(|_| ::chrono::Duration::zero());
+
+        let resolvers = trace
+            .resolvers
+            .iter()
+            .map(|r| {
+                let mut resolver = Object::with_capacity(6);
+                resolver.add_field("path", Value::list(r.path.iter().map(path_value).collect()));
+                resolver.add_field("parentType", Value::string(&r.parent_type));
+                resolver.add_field("fieldName", Value::string(&r.field_name));
+                resolver.add_field("returnType", Value::string(&r.return_type));
+                r.span.add_fields(&mut resolver);
+                Value::object(resolver)
+            })
+            .collect();
+
+        let mut execution = Object::with_capacity(1);
+        execution.add_field("resolvers", Value::list(resolvers));
+
+        let mut result = Object::with_capacity(7);
+        result.add_field("version", Value::int(1));
+        result.add_field("startTime", Value::string(rfc3339(&self.start_time)));
+        result.add_field("endTime", Value::string(rfc3339(&end_time)));
+        result.add_field("duration", nanoseconds(duration));
+        result.add_field("parsing", trace.parsing.to_value());
+        result.add_field("validation", trace.validation.to_value());
+        result.add_field("execution", Value::object(execution));
+
+        Some(Value::object(result))
+    }
+}
+
+impl Span {
+    fn end(&mut self, offset: Duration) {
+        if let Some(start_offset) = self.start_offset {
+            self.duration = Some(offset - start_offset);
+        }
+    }
+
+    fn add_fields(&self, object: &mut Object) {
+        object.add_field(
+            "startOffset",
+            self.start_offset.map_or_else(Value::null, nanoseconds),
+        );
+        object.add_field("duration", self.duration.map_or_else(Value::null, nanoseconds));
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = Object::with_capacity(2);
+        self.add_fields(&mut object);
+        Value::object(object)
+    }
+}
+
+fn path_value(segment: &PathSegment) -> Value {
+    match *segment {
+        PathSegment::Field(ref name) => Value::string(name),
+        PathSegment::Index(index) => Value::int(index as i64),
+    }
+}
+
+fn nanoseconds(duration: Duration) -> Value {
+    Value::int(duration.as_secs() as i64 * 1_000_000_000 + i64::from(duration.subsec_nanos()))
+}
+
+fn rfc3339(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
@@ -0,0 +1,229 @@
+//! Instrumentation of query execution
+//!
+//! Extensions are registered with `RootNode::add_extension`, and create an
+//! `Instrumentation` for every executed request. The instrumentation is
+//! notified when the request is parsed, validated and executed, and when each
+//! field starts and finishes resolving:
+//!
+//! ```rust
+//! # use std::collections::HashMap;
+//! # use std::sync::Mutex;
+//! # use std::time::{Duration, Instant};
+//! use juniper::PathSegment;
+//! use juniper::extensions::{Extension, FieldInfo, Instrumentation};
+//!
+//! struct SlowFields;
+//!
+//! impl Extension for SlowFields {
+//!     fn name(&self) -> &str {
+//!         "slowFields"
+//!     }
+//!
+//!     fn instrument(&self) -> Box<Instrumentation> {
+//!         Box::new(SlowFieldLogger { started: Mutex::new(HashMap::new()) })
+//!     }
+//! }
+//!
+//! struct SlowFieldLogger {
+//!     started: Mutex<HashMap<Vec<PathSegment>, Instant>>,
+//! }
+//!
+//! impl Instrumentation for SlowFieldLogger {
+//!     fn field_start(&self, field: &FieldInfo) {
+//!         let mut started = self.started.lock().unwrap();
+//!         started.insert(field.path().to_vec(), Instant::now());
+//!     }
+//!
+//!     fn field_end(&self, field: &FieldInfo) {
+//!         let started = self.started.lock().unwrap().remove(field.path()).unwrap();
+//!
+//!         if started.elapsed() > Duration::from_millis(100) {
+//!             println!("{}.{} is slow", field.parent_type_name(), field.field_name());
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Data returned by `Instrumentation::result` is added to the `extensions` of
+//! the `http::GraphQLResponse` under the name of the extension. The
+//! `ApolloTracing` extension reports the timings of a request in the [Apollo
+//! tracing](https://github.com/apollographql/apollo-tracing) format.
+//!
+//! The instrumentation of a subscription is notified of the execution of
+//! every event. Its result is not reported, as the events are sent as
+//! separate responses.
+
+#[cfg(feature = "chrono")]
+mod apollo_tracing;
+
+#[cfg(feature = "chrono")]
+pub use self::apollo_tracing::ApolloTracing;
+
+use ast::Type;
+use executor::PathSegment;
+use value::{Object, Value};
+
+/// Extension of the executor
+///
+/// Extensions are registered with `RootNode::add_extension`.
+pub trait Extension: Send + Sync {
+    /// Name of the extension
+    ///
+    /// The result of the instrumentation is added to the `extensions` of the
+    /// response under this name.
+    fn name(&self) -> &str;
+
+    /// Create the instrumentation of a single request
+    fn instrument(&self) -> Box<Instrumentation>;
+}
+
+/// Callbacks of an extension for a single request
+///
+/// All callbacks do nothing by default. Fields resolved asynchronously may
+/// start before sibling fields have finished.
+#[allow(unused_variables)]
+pub trait Instrumentation: Send + Sync {
+    /// The request is about to be parsed
+    fn parse_start(&self) {}
+
+    /// The request has been parsed, successfully or not
+    fn parse_end(&self) {}
+
+    /// The document is about to be validated
+    fn validation_start(&self) {}
+
+    /// The document has been validated, successfully or not
+    fn validation_end(&self) {}
+
+    /// The operation is about to be executed
+    fn execution_start(&self) {}
+
+    /// Every field of the operation has been resolved
+    fn execution_end(&self) {}
+
+    /// A field is about to be resolved
+    fn field_start(&self, field: &FieldInfo) {}
+
+    /// A field has been resolved, successfully or not
+    ///
+    /// Fields of the resolved value are resolved before the field ends.
+    fn field_end(&self, field: &FieldInfo) {}
+
+    /// Data to add to the `extensions` of the response
+    fn result(&self) -> Option<Value> {
+        None
+    }
+}
+
+/// Field being resolved
+#[derive(Debug)]
+pub struct FieldInfo<'a> {
+    path: Vec<PathSegment>,
+    parent_type_name: &'a str,
+    field_name: &'a str,
+    field_type: &'a Type<'a>,
+}
+
+impl<'a> FieldInfo<'a> {
+    pub(crate) fn new(
+        path: Vec<PathSegment>,
+        parent_type_name: &'a str,
+        field_name: &'a str,
+        field_type: &'a Type<'a>,
+    ) -> FieldInfo<'a> {
+        FieldInfo {
+            path: path,
+            parent_type_name: parent_type_name,
+            field_name: field_name,
+            field_type: field_type,
+        }
+    }
+
+    /// Path of response names and list indices leading to the field
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Name of the object type the field belongs to
+    pub fn parent_type_name(&self) -> &'a str {
+        self.parent_type_name
+    }
+
+    /// Name of the field in the schema
+    pub fn field_name(&self) -> &'a str {
+        self.field_name
+    }
+
+    /// Type of the field
+    pub fn field_type(&self) -> &'a Type<'a> {
+        self.field_type
+    }
+}
+
+/// Instrumentation of every extension of a schema for a single request
+#[derive(Default)]
+pub(crate) struct RequestInstrumentation {
+    instrumentations: Vec<(String, Box<Instrumentation>)>,
+}
+
+impl RequestInstrumentation {
+    pub(crate) fn new(extensions: &[Box<Extension>]) -> RequestInstrumentation {
+        RequestInstrumentation {
+            instrumentations: extensions
+                .iter()
+                .map(|e| (e.name().to_owned(), e.instrument()))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.instrumentations.is_empty()
+    }
+
+    pub(crate) fn parse_start(&self) {
+        self.each(|i| i.parse_start());
+    }
+
+    pub(crate) fn parse_end(&self) {
+        self.each(|i| i.parse_end());
+    }
+
+    pub(crate) fn validation_start(&self) {
+        self.each(|i| i.validation_start());
+    }
+
+    pub(crate) fn validation_end(&self) {
+        self.each(|i| i.validation_end());
+    }
+
+    pub(crate) fn execution_start(&self) {
+        self.each(|i| i.execution_start());
+    }
+
+    pub(crate) fn execution_end(&self) {
+        self.each(|i| i.execution_end());
+    }
+
+    pub(crate) fn field_start(&self, field: &FieldInfo) {
+        self.each(|i| i.field_start(field));
+    }
+
+    pub(crate) fn field_end(&self, field: &FieldInfo) {
+        self.each(|i| i.field_end(field));
+    }
+
+    /// Collect the results of the instrumentations, keyed by extension name
+    pub(crate) fn extensions(&self) -> Object {
+        self.instrumentations
+            .iter()
+            .filter_map(|&(ref name, ref i)| i.result().map(|r| (name.as_str(), r)))
+            .collect()
+    }
+
+    fn each<F: Fn(&Instrumentation)>(&self, f: F) {
+        for &(_, ref instrumentation) in &self.instrumentations {
+            f(&**instrumentation);
+        }
+    }
+}
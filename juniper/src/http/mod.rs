@@ -1,27 +1,60 @@
 //! Utilities for building HTTP endpoints in a library-agnostic manner
 
 pub mod graphiql;
+pub mod multipart;
+pub mod persisted;
 
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use futures::future::{self, Either, Future, Loop};
+use futures::{Async, Poll, Stream};
 use serde::ser;
 use serde::ser::SerializeMap;
+use serde_json;
 
 use ast::InputValue;
 use executor::ExecutionError;
-use {FieldError, GraphQLError, GraphQLType, RootNode, Value, Variables};
+use extensions::RequestInstrumentation;
+use self::persisted::{PersistedQueryExtension, Registration};
+use {
+    FieldError, GraphQLError, GraphQLSubscriptionType, GraphQLType, Object, Query, RootNode,
+    SubscriptionStream, Value, Variables,
+};
 
 /// The expected structure of the decoded JSON document for either POST or GET requests.
 ///
 /// For POST, you can use Serde to deserialize the incoming JSON data directly
 /// into this struct - it derives Deserialize for exactly this reason.
 ///
-/// For GET, you will need to parse the query string and extract "query",
-/// "operationName", and "variables" manually.
+/// For GET, decode the parameters of the query string and pass them to
+/// `GraphQLRequest::from_get_parameters`.
+///
+/// Instead of the query, a request may contain the hash of a persisted query,
+/// see the `persisted` module.
 #[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
 pub struct GraphQLRequest {
-    query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
     variables: Option<InputValue>,
+    #[serde(default, skip_serializing_if = "RequestExtensions::is_empty")]
+    extensions: RequestExtensions,
+}
+
+/// The `extensions` of a request understood by the executor
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug, Default)]
+struct RequestExtensions {
+    #[serde(rename = "persistedQuery", skip_serializing_if = "Option::is_none")]
+    persisted_query: Option<PersistedQueryExtension>,
+}
+
+impl RequestExtensions {
+    fn is_empty(&self) -> bool {
+        self.persisted_query.is_none()
+    }
 }
 
 impl GraphQLRequest {
@@ -49,32 +82,390 @@ impl GraphQLRequest {
         variables: Option<InputValue>,
     ) -> GraphQLRequest {
         GraphQLRequest {
-            query: query,
+            query: Some(query),
+            operation_name: operation_name,
+            variables: variables,
+            extensions: RequestExtensions::default(),
+        }
+    }
+
+    /// Construct a new GraphQL request for the persisted query with the given
+    /// SHA-256 hash
+    pub fn new_persisted(
+        sha256_hash: String,
+        operation_name: Option<String>,
+        variables: Option<InputValue>,
+    ) -> GraphQLRequest {
+        GraphQLRequest {
+            query: None,
             operation_name: operation_name,
             variables: variables,
+            extensions: RequestExtensions {
+                persisted_query: Some(PersistedQueryExtension::new(sha256_hash)),
+            },
         }
     }
 
+    /// Construct a GraphQL request from the decoded query string parameters
+    /// of a GET request
+    ///
+    /// The `query` and `operationName` parameters are taken as they are, while
+    /// `variables` and `extensions` hold JSON. Instead of the query, the
+    /// `extensions` may contain the hash of a persisted query. Other
+    /// parameters are ignored.
+    pub fn from_get_parameters<I>(parameters: I) -> Result<GraphQLRequest, GraphQLRequestError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut query = None;
+        let mut operation_name = None;
+        let mut variables = None;
+        let mut extensions = None;
+
+        for (key, value) in parameters {
+            let (name, is_set) = match key.as_ref() {
+                "query" => ("query", query.is_some()),
+                "operationName" => ("operationName", operation_name.is_some()),
+                "variables" => ("variables", variables.is_some()),
+                "extensions" => ("extensions", extensions.is_some()),
+                _ => continue,
+            };
+
+            if is_set {
+                return Err(GraphQLRequestError::DuplicateParameter(name));
+            }
+
+            match name {
+                "query" => query = Some(value),
+                "operationName" => operation_name = Some(value),
+                "variables" => {
+                    variables = Some(
+                        serde_json::from_str::<InputValue>(&value)
+                            .map_err(|err| GraphQLRequestError::InvalidJson(name, err))?,
+                    )
+                }
+                _ => {
+                    extensions = Some(
+                        serde_json::from_str::<RequestExtensions>(&value)
+                            .map_err(|err| GraphQLRequestError::InvalidJson(name, err))?,
+                    )
+                }
+            }
+        }
+
+        let extensions = extensions.unwrap_or_default();
+
+        if query.is_none() && extensions.persisted_query.is_none() {
+            return Err(GraphQLRequestError::MissingQuery);
+        }
+
+        Ok(GraphQLRequest {
+            query: query,
+            operation_name: operation_name,
+            variables: variables,
+            extensions: extensions,
+        })
+    }
+
+    /// Send the SHA-256 hash of the query along, to persist the query
+    pub fn with_persisted_query_hash(mut self, sha256_hash: String) -> GraphQLRequest {
+        self.extensions.persisted_query = Some(PersistedQueryExtension::new(sha256_hash));
+        self
+    }
+
+    /// Find the query to execute, looking up persisted queries in the store of
+    /// the root node
+    fn resolve_query<QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    ) -> Result<(Query, Option<Registration>), GraphQLError>
+    where
+        QueryT: GraphQLType,
+        MutationT: GraphQLType,
+        SubscriptionT: GraphQLType,
+    {
+        persisted::resolve(
+            root_node.persisted_queries.as_ref(),
+            self.query.as_ref().map(|query| &**query),
+            self.extensions.persisted_query.as_ref(),
+        )
+    }
+
     /// Execute a GraphQL request using the specified schema and context
     ///
     /// This is a simple wrapper around the `execute` function exposed at the
-    /// top level of this crate.
-    pub fn execute<'a, CtxT, QueryT, MutationT>(
+    /// top level of this crate. The response also contains the `extensions`
+    /// of the schema.
+    pub fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
         &'a self,
-        root_node: &RootNode<QueryT, MutationT>,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
         context: &CtxT,
     ) -> GraphQLResponse<'a>
     where
         QueryT: GraphQLType<Context = CtxT>,
         MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLType<Context = CtxT>,
     {
-        GraphQLResponse(::execute(
-            &self.query,
+        let (query, registration) = match self.resolve_query(root_node) {
+            Ok(resolved) => resolved,
+            Err(err) => return GraphQLResponse::from_result(Err(err)),
+        };
+
+        let result = ::execute_with_extensions(
+            query,
             self.operation_name(),
             root_node,
             &self.variables(),
             context,
-        ))
+        );
+
+        if result.is_ok() {
+            register(root_node, registration);
+        }
+
+        GraphQLResponse::from_result(result)
+    }
+
+    /// Execute a GraphQL request asynchronously using the specified schema
+    /// and context
+    ///
+    /// This is a simple wrapper around the `execute_async` function exposed at
+    /// the top level of this crate. The response also contains the
+    /// `extensions` of the schema.
+    pub fn execute_async<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+        context: &'a CtxT,
+    ) -> impl Future<Item = GraphQLResponse<'a>, Error = ()> + 'a
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT> + Sync,
+        MutationT::TypeInfo: Sync,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+        CtxT: Sync,
+    {
+        let result = self.resolve_query(root_node).and_then(|(query, registration)| {
+            let result = ::execute_instrumented_async(
+                query,
+                self.operation_name(),
+                root_node,
+                &self.variables(),
+                context,
+                RequestInstrumentation::new(&root_node.extensions),
+            );
+
+            if result.is_ok() {
+                register(root_node, registration);
+            }
+
+            result
+        });
+
+        match result {
+            Ok(query) => Either::A(
+                query
+                    .with_extensions()
+                    .map(|res| GraphQLResponse::from_result(Ok(res))),
+            ),
+            Err(err) => Either::B(future::ok(GraphQLResponse::from_result(Err(err)))),
+        }
+    }
+
+    /// Execute a GraphQL request using the specified schema and context,
+    /// writing the JSON response into `writer`
+    ///
+    /// The data is written as the fields resolve, see the `execute_to_writer`
+    /// function exposed at the top level of this crate. The response has the
+    /// same content as the serialization of the `GraphQLResponse` returned by
+    /// `execute`. Returns whether the request could be executed, like
+    /// `GraphQLResponse::is_ok`.
+    pub fn execute_to_writer<CtxT, QueryT, MutationT, SubscriptionT, W>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+        context: &CtxT,
+        writer: W,
+    ) -> io::Result<bool>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+        W: Write,
+    {
+        // The key of the data is only written once execution has started
+        let mut writer = PrefixedWriter {
+            prefix: Some(b"{\"data\":"),
+            writer: writer,
+        };
+
+        let result = self.resolve_query(root_node).and_then(|(query, registration)| {
+            let result = ::execute_to_writer_with_extensions(
+                query,
+                self.operation_name(),
+                root_node,
+                &self.variables(),
+                context,
+                &mut writer,
+            );
+
+            if result.is_ok() {
+                register(root_node, registration);
+            }
+
+            result
+        });
+
+        match result {
+            Ok(result) => {
+                let (errors, extensions) = result?;
+
+                if !errors.is_empty() {
+                    writer.write_all(b",\"errors\":")?;
+                    serde_json::to_writer(&mut writer, &errors)?;
+                }
+
+                if extensions.field_count() > 0 {
+                    writer.write_all(b",\"extensions\":")?;
+                    serde_json::to_writer(&mut writer, &extensions)?;
+                }
+
+                writer.write_all(b"}")?;
+                writer.flush()?;
+
+                Ok(true)
+            }
+            Err(err) => {
+                serde_json::to_writer(&mut writer.writer, &GraphQLResponse::from_result(Err(err)))?;
+                writer.flush()?;
+
+                Ok(false)
+            }
+        }
+    }
+
+    /// Execute a GraphQL request that may be a subscription, using the
+    /// specified schema and context
+    ///
+    /// Subscriptions yield a response for every event of their source
+    /// stream, see the `execute_subscription` function exposed at the top
+    /// level of this crate. Queries and mutations are executed like with
+    /// `execute`, and yield a single response. Requests that cannot be
+    /// executed at all return the response with the error instead.
+    pub fn subscribe<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+        context: &'a CtxT,
+    ) -> Result<GraphQLResponseStream<'a, CtxT>, GraphQLResponse<'a>>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLSubscriptionType<Context = CtxT>,
+    {
+        let (query, registration) = match self.resolve_query(root_node) {
+            Ok(resolved) => resolved,
+            Err(err) => return Err(GraphQLResponse::from_result(Err(err))),
+        };
+
+        let result = ::subscribe(
+            query,
+            self.operation_name(),
+            root_node,
+            &self.variables(),
+            context,
+            RequestInstrumentation::new(&root_node.extensions),
+        );
+
+        match result {
+            Ok(stream) => {
+                register(root_node, registration);
+                Ok(GraphQLResponseStream::Subscription(stream))
+            }
+            Err(GraphQLError::NotSubscription) => {
+                let response = self.execute(root_node, context);
+
+                if response.is_ok() {
+                    Ok(GraphQLResponseStream::Single(Some(response)))
+                } else {
+                    Err(response)
+                }
+            }
+            Err(err) => Err(GraphQLResponse::from_result(Err(err))),
+        }
+    }
+}
+
+/// Store a query that has passed validation as persisted query
+fn register<QueryT, MutationT, SubscriptionT>(
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    registration: Option<Registration>,
+) where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    if let (Some(persisted), Some(registration)) = (&root_node.persisted_queries, registration) {
+        persisted.register(registration);
+    }
+}
+
+/// Writer emitting a prefix before the first bytes written into it
+struct PrefixedWriter<W> {
+    prefix: Option<&'static [u8]>,
+    writer: W,
+}
+
+impl<W: Write> Write for PrefixedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(prefix) = self.prefix.take() {
+            self.writer.write_all(prefix)?;
+        }
+
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// An error in the parameters of a GET request
+#[derive(Debug)]
+pub enum GraphQLRequestError {
+    /// A parameter is specified more than once
+    DuplicateParameter(&'static str),
+    /// Neither a query nor a persisted query is given
+    MissingQuery,
+    /// The `variables` or `extensions` parameter is not valid JSON
+    InvalidJson(&'static str, serde_json::Error),
+}
+
+impl fmt::Display for GraphQLRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphQLRequestError::DuplicateParameter(name) => {
+                write!(f, "'{}' parameter is specified multiple times", name)
+            }
+            GraphQLRequestError::MissingQuery => write!(f, "'query' parameter is missing"),
+            GraphQLRequestError::InvalidJson(name, ref err) => {
+                write!(f, "Invalid '{}' parameter: {}", name, err)
+            }
+        }
+    }
+}
+
+impl Error for GraphQLRequestError {
+    fn description(&self) -> &str {
+        match *self {
+            GraphQLRequestError::DuplicateParameter(_) => "duplicate parameter",
+            GraphQLRequestError::MissingQuery => "missing query",
+            GraphQLRequestError::InvalidJson(_, _) => "invalid JSON",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            GraphQLRequestError::InvalidJson(_, ref err) => Some(err),
+            _ => None,
+        }
     }
 }
 
@@ -83,12 +474,28 @@ impl GraphQLRequest {
 /// This struct implements Serialize, so you can simply serialize this
 /// to JSON and send it over the wire. Use the `is_ok` method to determine
 /// whether to send a 200 or 400 HTTP status code.
-pub struct GraphQLResponse<'a>(Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>);
+///
+/// Results of the extensions of the schema are serialized under the top-level
+/// `extensions` key of executed requests.
+pub struct GraphQLResponse<'a>(
+    Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>,
+    Object,
+);
 
 impl<'a> GraphQLResponse<'a> {
+    fn from_result(result: Result<(Value, Vec<ExecutionError>, Object), GraphQLError<'a>>) -> Self {
+        match result {
+            Ok((value, errors, extensions)) => GraphQLResponse(Ok((value, errors)), extensions),
+            Err(err) => GraphQLResponse(Err(err), Object::with_capacity(0)),
+        }
+    }
+
     /// Constructs an error response outside of the normal execution flow
     pub fn error(error: FieldError) -> Self {
-        GraphQLResponse(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+        GraphQLResponse(
+            Ok((Value::null(), vec![ExecutionError::at_origin(error)])),
+            Object::with_capacity(0),
+        )
     }
 
     /// Was the request successful or not?
@@ -100,6 +507,32 @@ impl<'a> GraphQLResponse<'a> {
     }
 }
 
+/// Responses to a request executed with `GraphQLRequest::subscribe`
+pub enum GraphQLResponseStream<'a, CtxT: 'a> {
+    /// A subscription, yielding a response per event
+    Subscription(SubscriptionStream<'a, CtxT>),
+    /// A query or mutation, yielding its only response
+    Single(Option<GraphQLResponse<'a>>),
+}
+
+impl<'a, CtxT> Stream for GraphQLResponseStream<'a, CtxT> {
+    type Item = GraphQLResponse<'a>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<GraphQLResponse<'a>>, ()> {
+        match *self {
+            GraphQLResponseStream::Subscription(ref mut stream) => {
+                Ok(stream.poll()?.map(|response| {
+                    response.map(|(value, errors)| {
+                        GraphQLResponse::from_result(Ok((value, errors, Object::with_capacity(0))))
+                    })
+                }))
+            }
+            GraphQLResponseStream::Single(ref mut response) => Ok(Async::Ready(response.take())),
+        }
+    }
+}
+
 impl<'a> ser::Serialize for GraphQLResponse<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -117,6 +550,11 @@ impl<'a> ser::Serialize for GraphQLResponse<'a> {
                     map.serialize_value(err)?;
                 }
 
+                if self.1.field_count() > 0 {
+                    map.serialize_key("extensions")?;
+                    map.serialize_value(&self.1)?;
+                }
+
                 map.end()
             }
             Err(ref err) => {
@@ -129,12 +567,203 @@ impl<'a> ser::Serialize for GraphQLResponse<'a> {
     }
 }
 
+/// A single GraphQL request, or a batch of them
+///
+/// Batches are sent as a JSON array of requests, and get an array of the
+/// responses of their requests in the same order. Like `GraphQLRequest`,
+/// this can be deserialized directly from the body of POST requests.
+///
+/// The number of requests in a batch can be limited with
+/// `RootNode::max_batch_size`.
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum GraphQLBatchRequest {
+    /// A single request, sent as a JSON object
+    Single(GraphQLRequest),
+    /// A batch of requests, sent as a JSON array
+    Batch(Vec<GraphQLRequest>),
+}
+
+impl GraphQLBatchRequest {
+    /// Execute the request or batch of requests using the specified schema
+    /// and context
+    ///
+    /// The requests of a batch are executed one after another.
+    pub fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+        context: &CtxT,
+    ) -> GraphQLBatchResponse<'a>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+    {
+        match *self {
+            GraphQLBatchRequest::Single(ref request) => {
+                GraphQLBatchResponse::Single(request.execute(root_node, context))
+            }
+            GraphQLBatchRequest::Batch(ref requests) => {
+                if let Err(response) = check_batch_size(requests, root_node) {
+                    return GraphQLBatchResponse::Single(response);
+                }
+
+                GraphQLBatchResponse::Batch(
+                    requests
+                        .iter()
+                        .map(|request| request.execute(root_node, context))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Execute the request or batch of requests asynchronously using the
+    /// specified schema and context
+    ///
+    /// The requests of a batch are executed one after another, or all at
+    /// once if the root node was built with `RootNode::parallel_batches`.
+    pub fn execute_async<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+        context: &'a CtxT,
+    ) -> Box<Future<Item = GraphQLBatchResponse<'a>, Error = ()> + 'a>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT> + Sync,
+        MutationT::TypeInfo: Sync,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+        CtxT: Sync,
+    {
+        let requests = match *self {
+            GraphQLBatchRequest::Single(ref request) => {
+                return Box::new(
+                    request
+                        .execute_async(root_node, context)
+                        .map(GraphQLBatchResponse::Single),
+                )
+            }
+            GraphQLBatchRequest::Batch(ref requests) => requests,
+        };
+
+        if let Err(response) = check_batch_size(requests, root_node) {
+            return Box::new(future::ok(GraphQLBatchResponse::Single(response)));
+        }
+
+        if root_node.parallel_batches {
+            Box::new(
+                future::join_all(
+                    requests
+                        .iter()
+                        .map(move |request| request.execute_async(root_node, context)),
+                ).map(GraphQLBatchResponse::Batch),
+            )
+        } else {
+            Box::new(
+                future::loop_fn(
+                    (requests.iter(), Vec::with_capacity(requests.len())),
+                    move |(mut remaining, mut responses)| match remaining.next() {
+                        Some(request) => Either::A(request.execute_async(root_node, context).map(
+                            move |response| {
+                                responses.push(response);
+                                Loop::Continue((remaining, responses))
+                            },
+                        )),
+                        None => Either::B(future::ok(Loop::Break(responses))),
+                    },
+                ).map(GraphQLBatchResponse::Batch),
+            )
+        }
+    }
+
+    /// Check the size of a batch against `RootNode::max_batch_size`
+    ///
+    /// For integrations executing the requests of a batch by themselves. The
+    /// error is the response the `execute` methods give for a batch that is
+    /// too large.
+    pub fn check_batch_size<'a, QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    ) -> Result<(), GraphQLResponse<'a>>
+    where
+        QueryT: GraphQLType,
+        MutationT: GraphQLType,
+        SubscriptionT: GraphQLType,
+    {
+        match *self {
+            GraphQLBatchRequest::Single(_) => Ok(()),
+            GraphQLBatchRequest::Batch(ref requests) => check_batch_size(requests, root_node),
+        }
+    }
+
+    /// Whether this is a batch of requests rather than a single request
+    pub fn is_batch(&self) -> bool {
+        match *self {
+            GraphQLBatchRequest::Single(_) => false,
+            GraphQLBatchRequest::Batch(_) => true,
+        }
+    }
+}
+
+/// Reject batches containing more requests than the root node allows
+fn check_batch_size<'a, QueryT, MutationT, SubscriptionT>(
+    requests: &[GraphQLRequest],
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+) -> Result<(), GraphQLResponse<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    match root_node.max_batch_size {
+        Some(max_size) if requests.len() > max_size => Err(GraphQLResponse::from_result(Err(
+            GraphQLError::BatchSizeExceeded,
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Result of executing a `GraphQLBatchRequest`
+///
+/// Serializes to the response of a single request, or an array of the
+/// responses of a batch. Batches that could not be executed at all, such as
+/// batches exceeding the maximum batch size, result in a single error
+/// response.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum GraphQLBatchResponse<'a> {
+    /// Response to a single request, or error of a whole batch
+    Single(GraphQLResponse<'a>),
+    /// Responses to the requests of a batch
+    Batch(Vec<GraphQLResponse<'a>>),
+}
+
+impl<'a> GraphQLBatchResponse<'a> {
+    /// Were all requests successful?
+    ///
+    /// Like `GraphQLResponse::is_ok`, this is meant to decide between a 200
+    /// and a 400 HTTP status code.
+    pub fn is_ok(&self) -> bool {
+        match *self {
+            GraphQLBatchResponse::Single(ref response) => response.is_ok(),
+            GraphQLBatchResponse::Batch(ref responses) => {
+                responses.iter().all(|response| response.is_ok())
+            }
+        }
+    }
+}
+
 #[cfg(any(test, feature = "expose-test-schema"))]
 #[allow(missing_docs)]
 pub mod tests {
     use serde_json;
     use serde_json::Value as Json;
 
+    use executor::FieldResult;
+    use schema::model::RootNode;
+    use tests::model::Database;
+    use types::upload::Upload;
+
     /// Normalized response content we expect to get back from
     /// the http framework integration we are testing.
     #[derive(Debug)]
@@ -170,6 +799,9 @@ pub mod tests {
         println!("  - test_batched_post");
         test_batched_post(integration);
 
+        println!("  - test_batched_post_with_error");
+        test_batched_post_with_error(integration);
+
         println!("  - test_invalid_json");
         test_invalid_json(integration);
 
@@ -180,6 +812,106 @@ pub mod tests {
         test_duplicate_keys(integration);
     }
 
+    /// Normalized way to make multipart requests to the http framework
+    /// integration we are testing, which serves the schema built by
+    /// `upload_schema`.
+    pub trait HTTPUploadIntegration {
+        fn post_multipart(&self, url: &str, content_type: &str, body: &[u8]) -> TestResponse;
+    }
+
+    pub struct UploadQuery;
+
+    pub struct UploadMutation;
+
+    struct UploadedFile(Upload);
+
+    graphql_object!(UploadQuery: Database |&self| {
+        field hello() -> &str {
+            "world"
+        }
+    });
+
+    graphql_object!(UploadMutation: Database |&self| {
+        field upload(file: Upload) -> UploadedFile {
+            UploadedFile(file)
+        }
+
+        field upload_many(files: Vec<Upload>) -> Vec<UploadedFile> {
+            files.into_iter().map(UploadedFile).collect()
+        }
+    });
+
+    graphql_object!(UploadedFile: Database |&self| {
+        field name() -> Option<&str> {
+            self.0.filename()
+        }
+
+        field content_type() -> Option<&str> {
+            self.0.content_type()
+        }
+
+        field text() -> FieldResult<String> {
+            Ok(String::from_utf8_lossy(&self.0.bytes()?).into_owned())
+        }
+    });
+
+    /// Schema the upload test suite expects integrations to serve
+    pub fn upload_schema() -> RootNode<'static, UploadQuery, UploadMutation> {
+        RootNode::new(UploadQuery, UploadMutation)
+    }
+
+    /// Content type and body of a multipart request made of the given parts
+    ///
+    /// Each part has a name, an optional file name, and contents. Parts with
+    /// a file name are sent as `text/plain`.
+    pub fn multipart_body(parts: &[(&str, Option<&str>, &str)]) -> (String, Vec<u8>) {
+        let boundary = "------------------------juniper";
+        let mut body = String::new();
+
+        for &(name, filename, contents) in parts {
+            body.push_str(&format!("--{}\r\n", boundary));
+            match filename {
+                Some(filename) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: text/plain\r\n\r\n",
+                    name, filename
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                    name
+                )),
+            }
+            body.push_str(contents);
+            body.push_str("\r\n");
+        }
+        body.push_str(&format!("--{}--\r\n", boundary));
+
+        (
+            format!("multipart/form-data; boundary={}", boundary),
+            body.into_bytes(),
+        )
+    }
+
+    #[allow(missing_docs)]
+    pub fn run_http_upload_test_suite<T: HTTPUploadIntegration>(integration: &T) {
+        println!("Running HTTP upload test suite for integration");
+
+        println!("  - test_single_upload");
+        test_single_upload(integration);
+
+        println!("  - test_multiple_uploads");
+        test_multiple_uploads(integration);
+
+        println!("  - test_batched_uploads");
+        test_batched_uploads(integration);
+
+        println!("  - test_upload_with_missing_file");
+        test_upload_with_missing_file(integration);
+
+        println!("  - test_upload_with_invalid_path");
+        test_upload_with_invalid_path(integration);
+    }
+
     fn unwrap_json_response(response: &TestResponse) -> Json {
         serde_json::from_str::<Json>(
             response
@@ -292,6 +1024,24 @@ pub mod tests {
         );
     }
 
+    fn test_batched_post_with_error<T: HTTPIntegration>(integration: &T) {
+        let response = integration.post(
+            "/",
+            r#"[{"query": "{hero{name}}"}, {"query": "{hero{blah}}"}]"#,
+        );
+
+        assert_eq!(response.status_code, 400);
+        assert_eq!(response.content_type, "application/json");
+
+        let json = unwrap_json_response(&response);
+        assert_eq!(
+            json[0],
+            serde_json::from_str::<Json>(r#"{"data": {"hero": {"name": "R2-D2"}}}"#)
+                .expect("Invalid JSON constant in test")
+        );
+        assert!(json[1]["errors"].is_array());
+    }
+
     fn test_invalid_json<T: HTTPIntegration>(integration: &T) {
         let response = integration.get("/?query=blah");
         assert_eq!(response.status_code, 400);
@@ -307,6 +1057,121 @@ pub mod tests {
         assert_eq!(response.status_code, 400);
     }
 
+    fn post_multipart<T: HTTPUploadIntegration>(
+        integration: &T,
+        parts: &[(&str, Option<&str>, &str)],
+    ) -> TestResponse {
+        let (content_type, body) = multipart_body(parts);
+        integration.post_multipart("/", &content_type, &body)
+    }
+
+    fn test_single_upload<T: HTTPUploadIntegration>(integration: &T) {
+        let response = post_multipart(
+            integration,
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation ($file: Upload!) { upload(file: $file) { name contentType text } }", "variables": {"file": null}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+        );
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.content_type, "application/json");
+
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"data": {"upload": {"name": "a.txt", "contentType": "text/plain", "text": "Hello"}}}"#
+            ).expect("Invalid JSON constant in test")
+        );
+    }
+
+    fn test_multiple_uploads<T: HTTPUploadIntegration>(integration: &T) {
+        let response = post_multipart(
+            integration,
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation ($files: [Upload!]!) { uploadMany(files: $files) { name text } }", "variables": {"files": [null, null]}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.files.0"], "1": ["variables.files.1"]}"#),
+                ("0", Some("a.txt"), "Hello"),
+                ("1", Some("b.txt"), "World"),
+            ],
+        );
+
+        assert_eq!(response.status_code, 200);
+
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"data": {"uploadMany": [{"name": "a.txt", "text": "Hello"}, {"name": "b.txt", "text": "World"}]}}"#
+            ).expect("Invalid JSON constant in test")
+        );
+    }
+
+    fn test_batched_uploads<T: HTTPUploadIntegration>(integration: &T) {
+        let response = post_multipart(
+            integration,
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"[{"query": "mutation ($file: Upload!) { upload(file: $file) { text } }", "variables": {"file": null}}, {"query": "mutation ($file: Upload!) { upload(file: $file) { name } }", "variables": {"file": null}}]"#,
+                ),
+                ("map", None, r#"{"0": ["0.variables.file", "1.variables.file"]}"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+        );
+
+        assert_eq!(response.status_code, 200);
+
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"[{"data": {"upload": {"text": "Hello"}}}, {"data": {"upload": {"name": "a.txt"}}}]"#
+            ).expect("Invalid JSON constant in test")
+        );
+    }
+
+    fn test_upload_with_missing_file<T: HTTPUploadIntegration>(integration: &T) {
+        let response = post_multipart(
+            integration,
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation ($file: Upload!) { upload(file: $file) { name } }", "variables": {"file": null}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+            ],
+        );
+
+        assert_eq!(response.status_code, 400);
+    }
+
+    fn test_upload_with_invalid_path<T: HTTPUploadIntegration>(integration: &T) {
+        let response = post_multipart(
+            integration,
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation ($file: Upload!) { upload(file: $file) { name } }", "variables": {"file": null}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.other"]}"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+        );
+
+        assert_eq!(response.status_code, 400);
+    }
+
     fn test_duplicate_keys<T: HTTPIntegration>(integration: &T) {
         // {hero{name}}
         let response = integration.get("/?query=%7B%22query%22%3A%20%22%7Bhero%7Bname%7D%7D%22%2C%20%22query%22%3A%20%22%7Bhero%7Bname%7D%7D%22%7D");
@@ -317,3 +1182,166 @@ pub mod tests {
         assert_eq!(response.status_code, 400);
     }
 }
+
+#[cfg(test)]
+mod batch_tests {
+    use futures::Future;
+    use serde_json::{self, Value as Json};
+
+    use super::{GraphQLBatchRequest, GraphQLBatchResponse, GraphQLRequest};
+    use schema::model::RootNode;
+    use tests::model::Database;
+    use types::scalars::EmptyMutation;
+
+    fn to_json(response: &GraphQLBatchResponse) -> Json {
+        serde_json::to_value(response).expect("Invalid response")
+    }
+
+    fn batch(size: usize) -> GraphQLBatchRequest {
+        GraphQLBatchRequest::Batch(
+            (0..size)
+                .map(|_| GraphQLRequest::new("{ hero { name } }".to_owned(), None, None))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn deserializes_single_requests_and_batches() {
+        let single: GraphQLBatchRequest =
+            serde_json::from_str(r#"{ "query": "{ hero { name } }" }"#).unwrap();
+        assert!(!single.is_batch());
+
+        let batch: GraphQLBatchRequest =
+            serde_json::from_str(r#"[{ "query": "{ hero { name } }" }]"#).unwrap();
+        assert!(batch.is_batch());
+    }
+
+    #[test]
+    fn executes_batches() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+        let request = GraphQLBatchRequest::Batch(vec![
+            GraphQLRequest::new("{ hero { name } }".to_owned(), None, None),
+            GraphQLRequest::new("{ hero { id } }".to_owned(), None, None),
+        ]);
+        let expected = serde_json::from_str::<Json>(
+            r#"[{ "data": { "hero": { "name": "R2-D2" } } }, { "data": { "hero": { "id": "2001" } } }]"#,
+        ).unwrap();
+
+        let response = request.execute(&schema, &database);
+        assert!(response.is_ok());
+        assert_eq!(to_json(&response), expected);
+
+        let response = request.execute_async(&schema, &database).wait().unwrap();
+        assert!(response.is_ok());
+        assert_eq!(to_json(&response), expected);
+    }
+
+    #[test]
+    fn executes_batches_in_parallel() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new()).parallel_batches();
+        let request = batch(3);
+
+        let response = request.execute_async(&schema, &database).wait().unwrap();
+
+        assert!(response.is_ok());
+        assert_eq!(to_json(&response), to_json(&request.execute(&schema, &database)));
+    }
+
+    #[test]
+    fn fails_if_any_request_of_a_batch_fails() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+        let request = GraphQLBatchRequest::Batch(vec![
+            GraphQLRequest::new("{ hero { name } }".to_owned(), None, None),
+            GraphQLRequest::new("{ hero { unknown } }".to_owned(), None, None),
+        ]);
+
+        let response = request.execute(&schema, &database);
+
+        assert!(!response.is_ok());
+        assert_eq!(to_json(&response).as_array().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn rejects_batches_exceeding_the_max_batch_size() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new()).max_batch_size(2);
+        let expected = serde_json::from_str::<Json>(
+            r#"{ "errors": [{ "message": "Batch contains more requests than allowed" }] }"#,
+        ).unwrap();
+
+        assert!(batch(2).execute(&schema, &database).is_ok());
+
+        let request = batch(3);
+
+        let response = request.execute(&schema, &database);
+        assert!(!response.is_ok());
+        assert_eq!(to_json(&response), expected);
+
+        let response = request.execute_async(&schema, &database).wait().unwrap();
+        assert!(!response.is_ok());
+        assert_eq!(to_json(&response), expected);
+    }
+}
+
+#[cfg(test)]
+mod get_tests {
+    use super::{GraphQLRequest, GraphQLRequestError};
+    use ast::InputValue;
+
+    fn parameters(parameters: &[(&str, &str)]) -> Vec<(String, String)> {
+        parameters
+            .iter()
+            .map(|&(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_get_parameters() {
+        let request = GraphQLRequest::from_get_parameters(parameters(&[
+            ("query", "query Q($id: String!) { human(id: $id) { name } }"),
+            ("operationName", "Q"),
+            ("variables", r#"{ "id": "1000" }"#),
+            ("other", "ignored"),
+        ])).expect("Invalid request");
+
+        assert_eq!(
+            request,
+            GraphQLRequest::new(
+                "query Q($id: String!) { human(id: $id) { name } }".to_owned(),
+                Some("Q".to_owned()),
+                Some(InputValue::object(
+                    vec![("id", InputValue::string("1000"))]
+                        .into_iter()
+                        .collect()
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_get_parameters() {
+        match GraphQLRequest::from_get_parameters(parameters(&[("operationName", "Q")])) {
+            Err(GraphQLRequestError::MissingQuery) => (),
+            _ => panic!("Expected a missing query"),
+        }
+
+        match GraphQLRequest::from_get_parameters(parameters(&[
+            ("query", "{ hero { name } }"),
+            ("query", "{ hero { id } }"),
+        ])) {
+            Err(GraphQLRequestError::DuplicateParameter("query")) => (),
+            _ => panic!("Expected a duplicate parameter"),
+        }
+
+        match GraphQLRequest::from_get_parameters(parameters(&[
+            ("query", "{ hero { name } }"),
+            ("extensions", "{"),
+        ])) {
+            Err(GraphQLRequestError::InvalidJson("extensions", _)) => (),
+            _ => panic!("Expected invalid JSON"),
+        }
+    }
+}
@@ -0,0 +1,777 @@
+//! Multipart requests with file uploads
+//!
+//! Implements the [GraphQL multipart request spec][spec]. A
+//! `multipart/form-data` request carries the request, or batch of requests,
+//! as JSON in its `operations` field, with `null` in place of each file. The
+//! `map` field maps the names of the file parts to the paths of these
+//! placeholders:
+//!
+//! ```text
+//! operations: { "query": "mutation ($file: Upload!) { ... }", "variables": { "file": null } }
+//! map: { "0": ["variables.file"] }
+//! 0: <contents of the file>
+//! ```
+//!
+//! `parse_multipart_request` replaces the placeholders with `Upload` values,
+//! which resolvers take as arguments of the `Upload` scalar type.
+//!
+//! [spec]: https://github.com/jaydenseric/graphql-multipart-request-spec
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use ast::InputValue;
+use super::{GraphQLBatchRequest, GraphQLRequest};
+use types::upload::Upload;
+
+/// The default limit on the size of a multipart request body, 16 MiB
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 16 << 20;
+
+/// The default limit on the size of a single file, 8 MiB
+pub const DEFAULT_MAX_FILE_SIZE: usize = 8 << 20;
+
+/// The default limit on the number of files of a request
+pub const DEFAULT_MAX_FILES: usize = 16;
+
+/// The default size up to which files are kept in memory, 1 MiB
+pub const DEFAULT_MEMORY_LIMIT: usize = 1 << 20;
+
+/// Limits and storage of uploaded files
+#[derive(Clone, Debug)]
+pub struct MultipartOptions {
+    max_request_size: usize,
+    max_file_size: usize,
+    max_files: usize,
+    memory_limit: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+/// An error in a multipart request
+#[derive(Debug)]
+pub enum MultipartError {
+    /// The content type is not `multipart/form-data` with a boundary
+    InvalidContentType,
+    /// The body is larger than `MultipartOptions::max_request_size`
+    RequestTooLarge,
+    /// The body could not be split into parts
+    InvalidBody,
+    /// The `operations` or `map` field is missing
+    MissingField(&'static str),
+    /// The `operations` or `map` field is not valid JSON
+    InvalidJson(&'static str, serde_json::Error),
+    /// The map refers to a file part that is not in the body
+    MissingFile(String),
+    /// A path of the map does not point at a value in the operations
+    InvalidPath(String),
+    /// A file is larger than `MultipartOptions::max_file_size`
+    FileTooLarge(String),
+    /// The request has more files than `MultipartOptions::max_files`
+    TooManyFiles,
+    /// A file could not be written to the temporary directory
+    Io(io::Error),
+}
+
+/// The body of a multipart request, collected from the chunks it arrives in
+///
+/// Integrations reading the body as a stream push every chunk into it, which
+/// fails as soon as the body grows past `MultipartOptions::max_request_size`
+/// so that the rest of the request is never buffered.
+#[derive(Debug)]
+pub struct MultipartBody {
+    bytes: Vec<u8>,
+    max_request_size: usize,
+}
+
+struct Part<'a> {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    body: &'a [u8],
+}
+
+impl MultipartOptions {
+    /// Construct the default options
+    ///
+    /// Request bodies are limited to `DEFAULT_MAX_REQUEST_SIZE` bytes, with
+    /// at most `DEFAULT_MAX_FILES` files of up to `DEFAULT_MAX_FILE_SIZE`
+    /// bytes each. Files of up to `DEFAULT_MEMORY_LIMIT` bytes are kept in
+    /// memory, larger ones are written to the temporary directory of the
+    /// system.
+    pub fn new() -> MultipartOptions {
+        MultipartOptions::default()
+    }
+
+    /// Reject request bodies larger than the given number of bytes
+    ///
+    /// The limit includes the `operations` and `map` fields as well as the
+    /// headers of the parts.
+    pub fn max_request_size(mut self, max_request_size: usize) -> MultipartOptions {
+        self.max_request_size = max_request_size;
+        self
+    }
+
+    /// Reject files larger than the given number of bytes
+    pub fn max_file_size(mut self, max_file_size: usize) -> MultipartOptions {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Reject requests with more than the given number of files
+    pub fn max_files(mut self, max_files: usize) -> MultipartOptions {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Keep files of up to the given number of bytes in memory
+    ///
+    /// Larger files are written to a temporary file when the body is parsed.
+    /// Integrations buffer the whole body, up to `max_request_size`, before
+    /// parsing it, so this does not lower the memory needed to receive a
+    /// request. It only limits the memory held by the files of a request
+    /// once the body has been parsed, while the request executes.
+    pub fn memory_limit(mut self, memory_limit: usize) -> MultipartOptions {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Write files that are not kept in memory to the given directory
+    pub fn temp_dir<P: Into<PathBuf>>(mut self, temp_dir: P) -> MultipartOptions {
+        self.temp_dir = Some(temp_dir.into());
+        self
+    }
+}
+
+impl Default for MultipartOptions {
+    fn default() -> MultipartOptions {
+        MultipartOptions {
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            temp_dir: None,
+        }
+    }
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MultipartError::InvalidContentType => {
+                write!(f, "Expected a multipart/form-data content type with a boundary")
+            }
+            MultipartError::RequestTooLarge => write!(f, "Request body is too large"),
+            MultipartError::InvalidBody => write!(f, "Invalid multipart body"),
+            MultipartError::MissingField(name) => write!(f, "Missing '{}' field", name),
+            MultipartError::InvalidJson(name, ref err) => {
+                write!(f, "Invalid '{}' field: {}", name, err)
+            }
+            MultipartError::MissingFile(ref name) => write!(f, "Missing file '{}'", name),
+            MultipartError::InvalidPath(ref path) => write!(f, "Invalid path '{}'", path),
+            MultipartError::FileTooLarge(ref name) => write!(f, "File '{}' is too large", name),
+            MultipartError::TooManyFiles => write!(f, "Too many files"),
+            MultipartError::Io(ref err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for MultipartError {
+    fn description(&self) -> &str {
+        match *self {
+            MultipartError::InvalidContentType => "invalid content type",
+            MultipartError::RequestTooLarge => "request too large",
+            MultipartError::InvalidBody => "invalid multipart body",
+            MultipartError::MissingField(_) => "missing field",
+            MultipartError::InvalidJson(_, _) => "invalid JSON",
+            MultipartError::MissingFile(_) => "missing file",
+            MultipartError::InvalidPath(_) => "invalid path",
+            MultipartError::FileTooLarge(_) => "file too large",
+            MultipartError::TooManyFiles => "too many files",
+            MultipartError::Io(_) => "I/O error",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            MultipartError::InvalidJson(_, ref err) => Some(err),
+            MultipartError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MultipartError {
+    fn from(err: io::Error) -> MultipartError {
+        MultipartError::Io(err)
+    }
+}
+
+impl MultipartBody {
+    /// Construct an empty body limited according to `options`
+    pub fn new(options: &MultipartOptions) -> MultipartBody {
+        MultipartBody {
+            bytes: Vec::new(),
+            max_request_size: options.max_request_size,
+        }
+    }
+
+    /// Append the next chunk of the body
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), MultipartError> {
+        let len = self.bytes.len() + chunk.len();
+        if len > self.max_request_size {
+            return Err(MultipartError::RequestTooLarge);
+        }
+
+        self.bytes.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    /// The bytes of the body received so far
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Take the bytes of the body received so far
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Is the content type that of a multipart request?
+pub fn is_multipart_request(content_type: &str) -> bool {
+    content_type
+        .trim()
+        .to_ascii_lowercase()
+        .starts_with("multipart/form-data")
+}
+
+/// Parse the body of a multipart request into the request or batch of
+/// requests it carries
+///
+/// `content_type` is the value of the `Content-Type` header of the request,
+/// which contains the boundary between the parts of the body.
+pub fn parse_multipart_request(
+    content_type: &str,
+    body: &[u8],
+    options: &MultipartOptions,
+) -> Result<GraphQLBatchRequest, MultipartError> {
+    if !is_multipart_request(content_type) {
+        return Err(MultipartError::InvalidContentType);
+    }
+    if body.len() > options.max_request_size {
+        return Err(MultipartError::RequestTooLarge);
+    }
+    let boundary =
+        parameter(content_type, "boundary").ok_or(MultipartError::InvalidContentType)?;
+    let parts = split_parts(body, &boundary)?;
+
+    let mut request = parse_field::<GraphQLBatchRequest>(&parts, "operations")?;
+    let map = parse_field::<IndexMap<String, Vec<String>>>(&parts, "map")?;
+
+    if map.len() > options.max_files {
+        return Err(MultipartError::TooManyFiles);
+    }
+
+    for (name, paths) in map {
+        let part = parts
+            .iter()
+            .find(|part| part.name == name)
+            .ok_or_else(|| MultipartError::MissingFile(name.clone()))?;
+
+        if part.body.len() > options.max_file_size {
+            return Err(MultipartError::FileTooLarge(name));
+        }
+
+        let upload = if part.body.len() > options.memory_limit {
+            let temp_dir = options.temp_dir.clone().unwrap_or_else(env::temp_dir);
+            Upload::write_to_temp_file(
+                part.filename.clone(),
+                part.content_type.clone(),
+                part.body,
+                &temp_dir,
+            )?
+        } else {
+            Upload::new(
+                part.filename.clone(),
+                part.content_type.clone(),
+                part.body.to_vec(),
+            )
+        };
+
+        for path in paths {
+            if !insert_into_batch(&mut request, &path, &upload) {
+                return Err(MultipartError::InvalidPath(path));
+            }
+        }
+    }
+
+    Ok(request)
+}
+
+fn parse_field<T>(parts: &[Part], name: &'static str) -> Result<T, MultipartError>
+where
+    T: DeserializeOwned,
+{
+    let part = parts
+        .iter()
+        .find(|part| part.name == name)
+        .ok_or(MultipartError::MissingField(name))?;
+
+    serde_json::from_slice(part.body).map_err(|err| MultipartError::InvalidJson(name, err))
+}
+
+fn insert_into_batch(request: &mut GraphQLBatchRequest, path: &str, upload: &Upload) -> bool {
+    let segments = path.split('.').collect::<Vec<_>>();
+
+    match *request {
+        GraphQLBatchRequest::Single(ref mut request) => {
+            insert_into_request(request, &segments, upload)
+        }
+        GraphQLBatchRequest::Batch(ref mut requests) => segments
+            .split_first()
+            .and_then(|(index, rest)| {
+                index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| requests.get_mut(index))
+                    .map(|request| insert_into_request(request, rest, upload))
+            })
+            .unwrap_or(false),
+    }
+}
+
+fn insert_into_request(request: &mut GraphQLRequest, segments: &[&str], upload: &Upload) -> bool {
+    match (segments.split_first(), request.variables.as_mut()) {
+        (Some((&"variables", rest)), Some(variables)) if !rest.is_empty() => {
+            insert_into_value(variables, rest, upload)
+        }
+        _ => false,
+    }
+}
+
+fn insert_into_value(value: &mut InputValue, segments: &[&str], upload: &Upload) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            *value = InputValue::Upload(upload.clone());
+            return true;
+        }
+    };
+
+    match *value {
+        InputValue::Object(ref mut fields) => fields
+            .iter_mut()
+            .find(|&&mut (ref key, _)| key.item == *segment)
+            .map_or(false, |&mut (_, ref mut value)| {
+                insert_into_value(&mut value.item, rest, upload)
+            }),
+        InputValue::List(ref mut items) => segment
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| items.get_mut(index))
+            .map_or(false, |item| insert_into_value(&mut item.item, rest, upload)),
+        _ => false,
+    }
+}
+
+fn split_parts<'a>(body: &'a [u8], boundary: &str) -> Result<Vec<Part<'a>>, MultipartError> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut position = find(body, &delimiter, 0).ok_or(MultipartError::InvalidBody)?;
+    let mut parts = Vec::new();
+
+    loop {
+        position += delimiter.len();
+
+        if body[position..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        if !body[position..].starts_with(b"\r\n") {
+            return Err(MultipartError::InvalidBody);
+        }
+        position += 2;
+
+        let end = find_delimiter(body, &delimiter, position).ok_or(MultipartError::InvalidBody)?;
+        parts.push(parse_part(&body[position..end])?);
+        position = end + 2;
+    }
+}
+
+fn parse_part(part: &[u8]) -> Result<Part, MultipartError> {
+    let (headers, body) = if part.starts_with(b"\r\n") {
+        (&[][..], &part[2..])
+    } else {
+        let end = find(part, b"\r\n\r\n", 0).ok_or(MultipartError::InvalidBody)?;
+        (&part[..end], &part[end + 4..])
+    };
+    let headers = str::from_utf8(headers).map_err(|_| MultipartError::InvalidBody)?;
+
+    let mut name = None;
+    let mut filename = None;
+    let mut content_type = None;
+
+    for line in headers.split("\r\n") {
+        let colon = line.find(':').ok_or(MultipartError::InvalidBody)?;
+        let (header, value) = (line[..colon].trim(), line[colon + 1..].trim());
+
+        if header.eq_ignore_ascii_case("content-disposition") {
+            name = parameter(value, "name");
+            filename = parameter(value, "filename");
+        } else if header.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_owned());
+        }
+    }
+
+    Ok(Part {
+        name: name.ok_or(MultipartError::InvalidBody)?,
+        filename: filename,
+        content_type: content_type,
+        body: body,
+    })
+}
+
+/// The value of a `key=value` parameter of a header, which may be quoted
+fn parameter(header: &str, key: &str) -> Option<String> {
+    let mut rest = header;
+
+    while let Some(semicolon) = rest.find(';') {
+        rest = rest[semicolon + 1..].trim();
+
+        let equals = rest.find('=')?;
+        let value = &rest[equals + 1..];
+
+        let (parsed, remaining) = if value.starts_with('"') {
+            let mut parsed = String::new();
+            let mut chars = value[1..].char_indices();
+            let mut end = None;
+
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => parsed.extend(chars.next().map(|(_, c)| c)),
+                    '"' => {
+                        end = Some(i + 2);
+                        break;
+                    }
+                    c => parsed.push(c),
+                }
+            }
+
+            (parsed, &value[end?..])
+        } else {
+            let end = value.find(';').unwrap_or(value.len());
+            (value[..end].trim().to_owned(), &value[end..])
+        };
+
+        if rest[..equals].trim().eq_ignore_ascii_case(key) {
+            return Some(parsed);
+        }
+        rest = remaining;
+    }
+
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+/// Position of the line break before the next delimiter
+fn find_delimiter(body: &[u8], delimiter: &[u8], from: usize) -> Option<usize> {
+    let mut position = from;
+
+    loop {
+        let found = find(body, b"\r\n", position)?;
+        if body[found + 2..].starts_with(delimiter) {
+            return Some(found);
+        }
+        position = found + 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_multipart_request, parameter, parse_multipart_request, MultipartBody, MultipartError,
+        MultipartOptions, DEFAULT_MAX_FILES, DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_REQUEST_SIZE,
+    };
+    use ast::InputValue;
+    use http::tests::{
+        multipart_body, run_http_upload_test_suite, upload_schema, HTTPUploadIntegration,
+        TestResponse,
+    };
+    use http::GraphQLBatchRequest;
+    use serde_json;
+    use tests::model::Database;
+    use types::upload::Upload;
+
+    const OPERATIONS: &str =
+        r#"{ "query": "mutation ($file: Upload!) { upload(file: $file) { name } }", "variables": { "file": null } }"#;
+
+    fn parse(
+        parts: &[(&str, Option<&str>, &str)],
+        options: &MultipartOptions,
+    ) -> Result<GraphQLBatchRequest, MultipartError> {
+        let (content_type, body) = multipart_body(parts);
+        parse_multipart_request(&content_type, &body, options)
+    }
+
+    fn file_variable(request: &GraphQLBatchRequest) -> Upload {
+        match *request {
+            GraphQLBatchRequest::Single(ref request) => request.variables()["file"]
+                .convert::<Upload>()
+                .expect("Not an upload"),
+            GraphQLBatchRequest::Batch(_) => panic!("Expected a single request"),
+        }
+    }
+
+    struct TestUploadIntegration;
+
+    impl HTTPUploadIntegration for TestUploadIntegration {
+        fn post_multipart(&self, _: &str, content_type: &str, body: &[u8]) -> TestResponse {
+            let schema = upload_schema();
+            let database = Database::new();
+
+            let (status_code, body) =
+                match parse_multipart_request(content_type, body, &MultipartOptions::new()) {
+                    Ok(request) => {
+                        let response = request.execute(&schema, &database);
+                        let status_code = if response.is_ok() { 200 } else { 400 };
+                        (status_code, serde_json::to_string(&response).unwrap())
+                    }
+                    Err(err) => (400, err.to_string()),
+                };
+
+            TestResponse {
+                status_code: status_code,
+                body: Some(body),
+                content_type: "application/json".to_owned(),
+            }
+        }
+    }
+
+    #[test]
+    fn runs_the_upload_test_suite() {
+        run_http_upload_test_suite(&TestUploadIntegration);
+    }
+
+    #[test]
+    fn detects_multipart_requests() {
+        assert!(is_multipart_request("multipart/form-data; boundary=abc"));
+        assert!(is_multipart_request("Multipart/Form-Data"));
+        assert!(!is_multipart_request("application/json"));
+    }
+
+    #[test]
+    fn parses_header_parameters() {
+        let header = r#"form-data; name="file"; filename="a \"b\".txt""#;
+
+        assert_eq!(parameter(header, "name"), Some("file".to_owned()));
+        assert_eq!(parameter(header, "filename"), Some(r#"a "b".txt"#.to_owned()));
+        assert_eq!(
+            parameter("multipart/form-data; boundary=abc", "boundary"),
+            Some("abc".to_owned())
+        );
+        assert_eq!(parameter(header, "size"), None);
+    }
+
+    #[test]
+    fn maps_files_into_variables() {
+        let request = parse(
+            &[
+                ("operations", None, OPERATIONS),
+                ("map", None, r#"{ "0": ["variables.file"] }"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+            &MultipartOptions::new(),
+        ).expect("Parsing failed");
+
+        let upload = file_variable(&request);
+        assert_eq!(upload.filename(), Some("a.txt"));
+        assert_eq!(upload.content_type(), Some("text/plain"));
+        assert_eq!(&*upload.bytes().unwrap(), b"Hello");
+        assert_eq!(upload.path(), None);
+    }
+
+    #[test]
+    fn writes_large_files_to_temp_files() {
+        let request = parse(
+            &[
+                ("operations", None, OPERATIONS),
+                ("map", None, r#"{ "0": ["variables.file"] }"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+            &MultipartOptions::new().memory_limit(4),
+        ).expect("Parsing failed");
+
+        let upload = file_variable(&request);
+        let path = upload.path().expect("No temp file").to_owned();
+        assert!(path.exists());
+        assert_eq!(upload.len(), 5);
+        assert_eq!(&*upload.bytes().unwrap(), b"Hello");
+
+        drop(request);
+        drop(upload);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restricts_the_permissions_of_temp_files() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let request = parse(
+            &[
+                ("operations", None, OPERATIONS),
+                ("map", None, r#"{ "0": ["variables.file"] }"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+            &MultipartOptions::new().memory_limit(4),
+        ).expect("Parsing failed");
+
+        let upload = file_variable(&request);
+        let metadata = fs::metadata(upload.path().expect("No temp file")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let parts = [
+            ("operations", None, OPERATIONS),
+            ("map", None, r#"{ "0": ["variables.file"], "1": ["variables.file"] }"#),
+            ("0", Some("a.txt"), "Hello"),
+            ("1", Some("b.txt"), "Hi"),
+        ];
+
+        match parse(&parts, &MultipartOptions::new().max_file_size(4)) {
+            Err(MultipartError::FileTooLarge(ref name)) if name == "0" => (),
+            other => panic!("Expected FileTooLarge, got {:?}", other),
+        }
+
+        match parse(&parts, &MultipartOptions::new().max_files(1)) {
+            Err(MultipartError::TooManyFiles) => (),
+            other => panic!("Expected TooManyFiles, got {:?}", other),
+        }
+
+        match parse(&parts, &MultipartOptions::new().max_request_size(100)) {
+            Err(MultipartError::RequestTooLarge) => (),
+            other => panic!("Expected RequestTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn limits_requests_by_default() {
+        let map = (0..DEFAULT_MAX_FILES + 1)
+            .map(|i| format!(r#""{}": ["variables.file"]"#, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let parts = [
+            ("operations", None, OPERATIONS),
+            ("map", None, &format!("{{ {} }}", map)[..]),
+        ];
+        match parse(&parts, &MultipartOptions::new()) {
+            Err(MultipartError::TooManyFiles) => (),
+            other => panic!("Expected TooManyFiles, got {:?}", other),
+        }
+
+        let file = "a".repeat(DEFAULT_MAX_FILE_SIZE + 1);
+        let parts = [
+            ("operations", None, OPERATIONS),
+            ("map", None, r#"{ "0": ["variables.file"] }"#),
+            ("0", Some("a.txt"), &file[..]),
+        ];
+        match parse(&parts, &MultipartOptions::new()) {
+            Err(MultipartError::FileTooLarge(ref name)) if name == "0" => (),
+            other => panic!("Expected FileTooLarge, got {:?}", other),
+        }
+
+        let mut body = MultipartBody::new(&MultipartOptions::new());
+        body.push(&vec![0; DEFAULT_MAX_REQUEST_SIZE])
+            .expect("Body too large");
+        match body.push(b"\r\n") {
+            Err(MultipartError::RequestTooLarge) => (),
+            other => panic!("Expected RequestTooLarge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn limits_the_size_of_streamed_bodies() {
+        let (content_type, body) = multipart_body(&[
+            ("operations", None, OPERATIONS),
+            ("map", None, r#"{ "0": ["variables.file"] }"#),
+            ("0", Some("a.txt"), "Hello"),
+        ]);
+        let options = MultipartOptions::new().max_request_size(body.len());
+
+        let mut streamed = MultipartBody::new(&options);
+        for chunk in body.chunks(16) {
+            streamed.push(chunk).expect("Body too large");
+        }
+        assert_eq!(streamed.bytes(), &body[..]);
+        parse_multipart_request(&content_type, &streamed.into_bytes(), &options)
+            .expect("Parsing failed");
+
+        let mut streamed = MultipartBody::new(&options);
+        streamed.push(&body).expect("Body too large");
+        match streamed.push(b"\r\n") {
+            Err(MultipartError::RequestTooLarge) => (),
+            other => panic!("Expected RequestTooLarge, got {:?}", other),
+        }
+        assert_eq!(streamed.bytes().len(), body.len());
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let options = MultipartOptions::new();
+
+        match parse_multipart_request("multipart/form-data", b"", &options) {
+            Err(MultipartError::InvalidContentType) => (),
+            other => panic!("Expected InvalidContentType, got {:?}", other),
+        }
+
+        match parse_multipart_request("multipart/form-data; boundary=x", b"--x\r\nbroken", &options)
+        {
+            Err(MultipartError::InvalidBody) => (),
+            other => panic!("Expected InvalidBody, got {:?}", other),
+        }
+
+        match parse(&[("operations", None, OPERATIONS)], &options) {
+            Err(MultipartError::MissingField("map")) => (),
+            other => panic!("Expected MissingField, got {:?}", other),
+        }
+
+        match parse(&[("operations", None, "{"), ("map", None, "{}")], &options) {
+            Err(MultipartError::InvalidJson("operations", _)) => (),
+            other => panic!("Expected InvalidJson, got {:?}", other),
+        }
+
+        match parse(
+            &[
+                ("operations", None, OPERATIONS),
+                ("map", None, r#"{ "0": ["query"] }"#),
+                ("0", Some("a.txt"), "Hello"),
+            ],
+            &options,
+        ) {
+            Err(MultipartError::InvalidPath(ref path)) if path == "query" => (),
+            other => panic!("Expected InvalidPath, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn serializes_uploads_as_null() {
+        let upload = InputValue::Upload(Upload::new(None, None, b"Hello".to_vec()));
+
+        assert_eq!(serde_json::to_string(&upload).unwrap(), "null");
+        assert_eq!(upload.to_string(), "null");
+    }
+}
@@ -0,0 +1,502 @@
+//! Automatic persisted queries
+//!
+//! Instead of the text of a query, clients may send its SHA-256 hash in the
+//! `extensions` of a request, in the format used by Apollo:
+//!
+//! ```json
+//! { "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "..." } } }
+//! ```
+//!
+//! Once a store of persisted queries has been added to the root node with
+//! `RootNode::persisted_queries`, the hash is looked up in the store. Unknown
+//! hashes fail with a `PersistedQueryNotFound` error, upon which clients send
+//! the hash again together with the text of the query. The query is stored
+//! under its hash once it has passed validation.
+//!
+//! With `RootNode::strict_persisted_queries`, the store is never added to:
+//! only queries that have been registered in it up front can be executed.
+
+use std::sync::Mutex;
+
+use linked_hash_map::LinkedHashMap;
+use sha2::{Digest, Sha256};
+
+use {GraphQLError, Query};
+
+/// Storage of persisted queries, looked up by the SHA-256 hash of their text
+///
+/// Hashes are lowercase hexadecimal strings.
+pub trait PersistedQueryStore: Send + Sync {
+    /// Text of the query with the given hash, if it has been stored
+    fn get(&self, sha256_hash: &str) -> Option<String>;
+
+    /// Store the text of a query under its hash
+    fn insert(&self, sha256_hash: &str, query: &str);
+}
+
+/// Number of queries kept by `MemoryStore::new`
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// Store keeping persisted queries in memory
+///
+/// The store keeps a bounded number of queries. Once it is full, storing
+/// another query removes the query used least recently. Queries registered up
+/// front for `RootNode::strict_persisted_queries` should fit in the store.
+pub struct MemoryStore {
+    capacity: usize,
+    // Ordered from the least to the most recently used query
+    queries: Mutex<LinkedHashMap<String, String>>,
+}
+
+impl MemoryStore {
+    /// Construct an empty store keeping up to `DEFAULT_CAPACITY` queries
+    pub fn new() -> MemoryStore {
+        MemoryStore::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Construct an empty store keeping up to the given number of queries
+    pub fn with_capacity(capacity: usize) -> MemoryStore {
+        MemoryStore {
+            capacity: capacity,
+            queries: Mutex::new(LinkedHashMap::new()),
+        }
+    }
+
+    /// Store a query, returning its hash
+    pub fn register(&self, query: &str) -> String {
+        let hash = sha256_hash(query);
+        self.insert(&hash, query);
+        hash
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> MemoryStore {
+        MemoryStore::new()
+    }
+}
+
+impl PersistedQueryStore for MemoryStore {
+    fn get(&self, sha256_hash: &str) -> Option<String> {
+        self.queries
+            .lock()
+            .unwrap()
+            .get_refresh(sha256_hash)
+            .map(|query| query.clone())
+    }
+
+    fn insert(&self, sha256_hash: &str, query: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut queries = self.queries.lock().unwrap();
+
+        if queries.get_refresh(sha256_hash).is_none() && queries.len() >= self.capacity {
+            queries.pop_front();
+        }
+
+        queries.insert(sha256_hash.to_owned(), query.to_owned());
+    }
+}
+
+/// The SHA-256 hash of a query, as expected in requests
+pub fn sha256_hash(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Persisted queries of a root node
+pub(crate) struct PersistedQueries {
+    store: Box<PersistedQueryStore>,
+    strict: bool,
+}
+
+/// The `persistedQuery` entry of the `extensions` of a request
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+pub(crate) struct PersistedQueryExtension {
+    version: i32,
+    #[serde(rename = "sha256Hash")]
+    sha256_hash: String,
+}
+
+/// Query text to store once it has passed validation
+pub(crate) struct Registration<'a> {
+    sha256_hash: String,
+    query: &'a str,
+}
+
+impl PersistedQueries {
+    pub(crate) fn new<S>(store: S, strict: bool) -> PersistedQueries
+    where
+        S: PersistedQueryStore + 'static,
+    {
+        PersistedQueries {
+            store: Box::new(store),
+            strict: strict,
+        }
+    }
+
+    pub(crate) fn register(&self, registration: Registration) {
+        self.store
+            .insert(&registration.sha256_hash, registration.query);
+    }
+}
+
+impl PersistedQueryExtension {
+    pub(crate) fn new(sha256_hash: String) -> PersistedQueryExtension {
+        PersistedQueryExtension {
+            version: 1,
+            sha256_hash: sha256_hash,
+        }
+    }
+}
+
+/// Find the query a request asks for
+///
+/// Returns the query to execute, and the query text to store if execution
+/// gets past validation.
+pub(crate) fn resolve<'a>(
+    persisted: Option<&PersistedQueries>,
+    query: Option<&'a str>,
+    extension: Option<&PersistedQueryExtension>,
+) -> Result<(Query<'a>, Option<Registration<'a>>), GraphQLError<'a>> {
+    let strict = persisted.map_or(false, |p| p.strict);
+
+    let extension = match (extension, query) {
+        (Some(extension), _) => extension,
+        (None, Some(_)) if strict => return Err(GraphQLError::PersistedQueryRequired),
+        (None, Some(query)) => return Ok((Query::Source(query), None)),
+        (None, None) => return Err(GraphQLError::NoOperationProvided),
+    };
+
+    let persisted = match persisted {
+        Some(persisted) if extension.version == 1 => persisted,
+        _ => return Err(GraphQLError::PersistedQueryNotSupported),
+    };
+
+    // Stores only ever see lowercase hashes
+    let requested_hash = extension.sha256_hash.to_ascii_lowercase();
+
+    let query = match query {
+        Some(query) => query,
+        None => {
+            return persisted
+                .store
+                .get(&requested_hash)
+                .map(|query| (Query::Owned(query), None))
+                .ok_or(GraphQLError::PersistedQueryNotFound)
+        }
+    };
+
+    let hash = sha256_hash(query);
+    if hash != requested_hash {
+        return Err(GraphQLError::PersistedQueryHashMismatch);
+    }
+
+    if !strict {
+        let registration = Registration {
+            sha256_hash: hash,
+            query: query,
+        };
+
+        Ok((Query::Source(query), Some(registration)))
+    } else if persisted.store.get(&hash).is_some() {
+        Ok((Query::Source(query), None))
+    } else {
+        Err(GraphQLError::PersistedQueryNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::Future;
+    use serde_json::{self, Value as Json};
+
+    use super::{sha256_hash, MemoryStore, PersistedQueryStore};
+    use http::{GraphQLRequest, GraphQLResponse};
+    use schema::model::RootNode;
+    use tests::model::Database;
+    use types::scalars::EmptyMutation;
+
+    const QUERY: &str = "{ hero { name } }";
+
+    fn to_json(response: &GraphQLResponse) -> Json {
+        serde_json::to_value(response).expect("Invalid response")
+    }
+
+    fn error_message(response: &GraphQLResponse) -> Json {
+        to_json(response)["errors"][0]["message"].clone()
+    }
+
+    fn hero_data() -> Json {
+        serde_json::from_str(r#"{ "hero": { "name": "R2-D2" } }"#).unwrap()
+    }
+
+    #[test]
+    fn hashes_queries() {
+        assert_eq!(
+            sha256_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn deserializes_persisted_query_requests() {
+        let request: GraphQLRequest = serde_json::from_str(
+            r#"{
+                "operationName": "Hero",
+                "variables": {},
+                "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "abc" } }
+            }"#,
+        ).expect("Invalid request");
+
+        assert_eq!(
+            request,
+            GraphQLRequest::new_persisted(
+                "abc".to_owned(),
+                Some("Hero".to_owned()),
+                Some(serde_json::from_str("{}").unwrap()),
+            )
+        );
+    }
+
+    #[test]
+    fn executes_stored_queries() {
+        let database = Database::new();
+        let store = MemoryStore::new();
+        let hash = store.register(QUERY);
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(store);
+        let request = GraphQLRequest::new_persisted(hash, None, None);
+
+        let response = request.execute(&schema, &database);
+        assert!(response.is_ok());
+        assert_eq!(to_json(&response)["data"], hero_data());
+
+        let response = request.execute_async(&schema, &database).wait().unwrap();
+        assert_eq!(to_json(&response)["data"], hero_data());
+
+        let mut written = Vec::new();
+        request
+            .execute_to_writer(&schema, &database, &mut written)
+            .expect("Writing failed");
+        let written: Json = serde_json::from_slice(&written).expect("Invalid JSON written");
+        assert_eq!(written["data"], hero_data());
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_queries() {
+        let store = MemoryStore::with_capacity(2);
+        let first = store.register("{ a }");
+        let second = store.register("{ b }");
+
+        assert_eq!(store.get(&first), Some("{ a }".to_owned()));
+
+        let third = store.register("{ c }");
+
+        assert_eq!(store.get(&first), Some("{ a }".to_owned()));
+        assert_eq!(store.get(&second), None);
+        assert_eq!(store.get(&third), Some("{ c }".to_owned()));
+    }
+
+    #[test]
+    fn bounds_the_queries_registered_by_requests() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(MemoryStore::with_capacity(1));
+
+        for query in &["{ hero { name } }", "{ hero { id } }"] {
+            let request = GraphQLRequest::new((*query).to_owned(), None, None)
+                .with_persisted_query_hash(sha256_hash(query));
+            assert!(request.execute(&schema, &database).is_ok());
+        }
+
+        let evicted = GraphQLRequest::new_persisted(sha256_hash(QUERY), None, None);
+        assert_eq!(
+            error_message(&evicted.execute(&schema, &database)),
+            Json::from("PersistedQueryNotFound")
+        );
+
+        let kept = GraphQLRequest::new_persisted(sha256_hash("{ hero { id } }"), None, None);
+        assert!(kept.execute(&schema, &database).is_ok());
+    }
+
+    #[test]
+    fn registers_queries() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(MemoryStore::new());
+        let hash = sha256_hash(QUERY);
+        let persisted = GraphQLRequest::new_persisted(hash.clone(), None, None);
+
+        let response = persisted.execute(&schema, &database);
+        assert!(!response.is_ok());
+        assert_eq!(
+            to_json(&response),
+            serde_json::from_str::<Json>(
+                r#"{ "errors": [{
+                    "message": "PersistedQueryNotFound",
+                    "extensions": { "code": "PERSISTED_QUERY_NOT_FOUND" }
+                }] }"#
+            ).unwrap()
+        );
+
+        let registration = GraphQLRequest::new(QUERY.to_owned(), None, None)
+            .with_persisted_query_hash(hash);
+        assert_eq!(to_json(&registration.execute(&schema, &database))["data"], hero_data());
+
+        assert_eq!(to_json(&persisted.execute(&schema, &database))["data"], hero_data());
+    }
+
+    #[test]
+    fn looks_up_uppercase_hashes() {
+        let database = Database::new();
+        let store = MemoryStore::new();
+        let hash = store.register(QUERY).to_uppercase();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(store);
+
+        let request = GraphQLRequest::new_persisted(hash, None, None);
+        assert_eq!(to_json(&request.execute(&schema, &database))["data"], hero_data());
+    }
+
+    #[test]
+    fn registers_queries_with_uppercase_hashes() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(MemoryStore::new());
+        let hash = sha256_hash(QUERY).to_uppercase();
+
+        let registration = GraphQLRequest::new(QUERY.to_owned(), None, None)
+            .with_persisted_query_hash(hash);
+        assert_eq!(to_json(&registration.execute(&schema, &database))["data"], hero_data());
+
+        let persisted = GraphQLRequest::new_persisted(sha256_hash(QUERY), None, None);
+        assert_eq!(to_json(&persisted.execute(&schema, &database))["data"], hero_data());
+    }
+
+    #[test]
+    fn executes_stored_queries_from_get_parameters() {
+        let database = Database::new();
+        let store = MemoryStore::new();
+        let hash = store.register(QUERY);
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(store);
+        let extensions = format!(
+            r#"{{ "persistedQuery": {{ "version": 1, "sha256Hash": "{}" }} }}"#,
+            hash
+        );
+
+        let request = GraphQLRequest::from_get_parameters(vec![(
+            "extensions".to_owned(),
+            extensions,
+        )]).expect("Invalid request");
+
+        assert_eq!(request, GraphQLRequest::new_persisted(hash, None, None));
+        assert_eq!(to_json(&request.execute(&schema, &database))["data"], hero_data());
+    }
+
+    #[test]
+    fn does_not_register_invalid_queries() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(MemoryStore::new());
+        let query = "{ unknown }";
+        let hash = sha256_hash(query);
+
+        let registration = GraphQLRequest::new(query.to_owned(), None, None)
+            .with_persisted_query_hash(hash.clone());
+        assert!(!registration.execute(&schema, &database).is_ok());
+
+        let request = GraphQLRequest::new_persisted(hash, None, None);
+        let response = request.execute(&schema, &database);
+        assert_eq!(error_message(&response), Json::from("PersistedQueryNotFound"));
+    }
+
+    #[test]
+    fn rejects_mismatching_hashes() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(MemoryStore::new());
+
+        let request = GraphQLRequest::new(QUERY.to_owned(), None, None)
+            .with_persisted_query_hash(sha256_hash("{ hero { id } }"));
+        let response = request.execute(&schema, &database);
+
+        assert!(!response.is_ok());
+        assert_eq!(
+            error_message(&response),
+            Json::from("Provided sha256Hash does not match query")
+        );
+    }
+
+    #[test]
+    fn rejects_hashes_without_store() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+
+        let request = GraphQLRequest::new_persisted(sha256_hash(QUERY), None, None);
+        let response = request.execute(&schema, &database);
+
+        assert_eq!(error_message(&response), Json::from("PersistedQueryNotSupported"));
+    }
+
+    #[test]
+    fn only_executes_registered_queries_in_strict_mode() {
+        let database = Database::new();
+        let store = MemoryStore::new();
+        let hash = store.register(QUERY);
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .strict_persisted_queries(store);
+
+        let request = GraphQLRequest::new_persisted(hash, None, None);
+        let response = request.execute(&schema, &database);
+        assert_eq!(to_json(&response)["data"], hero_data());
+
+        let request = GraphQLRequest::new("{ hero { id } }".to_owned(), None, None);
+        let response = request.execute(&schema, &database);
+        assert_eq!(
+            error_message(&response),
+            Json::from("Only persisted queries are allowed")
+        );
+
+        let query = "{ hero { id } }";
+        let registration = GraphQLRequest::new(query.to_owned(), None, None)
+            .with_persisted_query_hash(sha256_hash(query));
+        for _ in 0..2 {
+            let response = registration.execute(&schema, &database);
+            assert_eq!(error_message(&response), Json::from("PersistedQueryNotFound"));
+        }
+    }
+
+    struct BrokenStore;
+
+    impl PersistedQueryStore for BrokenStore {
+        fn get(&self, _: &str) -> Option<String> {
+            Some("{ hero { name }".to_owned())
+        }
+
+        fn insert(&self, _: &str, _: &str) {}
+    }
+
+    #[test]
+    fn reports_parse_errors_of_stored_queries() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new())
+            .persisted_queries(BrokenStore);
+
+        let request = GraphQLRequest::new_persisted(sha256_hash(QUERY), None, None);
+        let response = request.execute(&schema, &database);
+
+        assert!(!response.is_ok());
+        assert_eq!(
+            to_json(&response),
+            serde_json::from_str::<Json>(
+                r#"{ "errors": [{
+                    "message": "Unexpected end of input",
+                    "locations": [{ "line": 1, "column": 16 }]
+                }] }"#
+            ).unwrap()
+        );
+    }
+}
@@ -5,7 +5,7 @@ use serde::{de, ser};
 use std::fmt;
 
 use ast::InputValue;
-use executor::ExecutionError;
+use executor::{ExecutionError, PathSegment};
 use parser::{ParseError, SourcePosition, Spanning};
 use validation::RuleError;
 use {GraphQLError, Object, Value};
@@ -15,6 +15,17 @@ struct SerializeHelper {
     message: &'static str,
 }
 
+#[derive(Serialize)]
+struct SerializeCodeHelper {
+    message: &'static str,
+    extensions: ErrorCode,
+}
+
+#[derive(Serialize)]
+struct ErrorCode {
+    code: &'static str,
+}
+
 impl ser::Serialize for ExecutionError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -41,6 +52,18 @@ impl ser::Serialize for ExecutionError {
     }
 }
 
+impl ser::Serialize for PathSegment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            PathSegment::Field(ref name) => serializer.serialize_str(name),
+            PathSegment::Index(index) => serializer.serialize_u64(index as u64),
+        }
+    }
+}
+
 impl<'a> ser::Serialize for GraphQLError<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -59,6 +82,33 @@ impl<'a> ser::Serialize for GraphQLError<'a> {
             GraphQLError::UnknownOperationName => [SerializeHelper {
                 message: "Unknown operation",
             }].serialize(serializer),
+            GraphQLError::IsSubscription => [SerializeHelper {
+                message: "Expected query or mutation, got subscription",
+            }].serialize(serializer),
+            GraphQLError::NotSubscription => [SerializeHelper {
+                message: "Expected subscription, got query or mutation",
+            }].serialize(serializer),
+            GraphQLError::PersistedQueryNotSupported => [SerializeCodeHelper {
+                message: "PersistedQueryNotSupported",
+                extensions: ErrorCode {
+                    code: "PERSISTED_QUERY_NOT_SUPPORTED",
+                },
+            }].serialize(serializer),
+            GraphQLError::PersistedQueryNotFound => [SerializeCodeHelper {
+                message: "PersistedQueryNotFound",
+                extensions: ErrorCode {
+                    code: "PERSISTED_QUERY_NOT_FOUND",
+                },
+            }].serialize(serializer),
+            GraphQLError::PersistedQueryHashMismatch => [SerializeHelper {
+                message: "Provided sha256Hash does not match query",
+            }].serialize(serializer),
+            GraphQLError::PersistedQueryRequired => [SerializeHelper {
+                message: "Only persisted queries are allowed",
+            }].serialize(serializer),
+            GraphQLError::BatchSizeExceeded => [SerializeHelper {
+                message: "Batch contains more requests than allowed",
+            }].serialize(serializer),
         }
     }
 }
@@ -85,23 +135,14 @@ impl<'de> de::Deserialize<'de> for InputValue {
             where
                 E: de::Error,
             {
-                if value >= i64::from(i32::min_value()) && value <= i64::from(i32::max_value()) {
-                    Ok(InputValue::int(value as i32))
-                } else {
-                    // Browser's JSON.stringify serialize all numbers having no
-                    // fractional part as integers (no decimal point), so we
-                    // must parse large integers as floating point otherwise
-                    // we would error on transferring large floating point
-                    // numbers.
-                    Ok(InputValue::float(value as f64))
-                }
+                Ok(InputValue::int(value))
             }
 
             fn visit_u64<E>(self, value: u64) -> Result<InputValue, E>
             where
                 E: de::Error,
             {
-                if value <= i32::max_value() as u64 {
+                if value <= i64::max_value() as u64 {
                     self.visit_i64(value as i64)
                 } else {
                     // Browser's JSON.stringify serialize all numbers having no
@@ -173,8 +214,10 @@ impl ser::Serialize for InputValue {
         S: ser::Serializer,
     {
         match *self {
-            InputValue::Null | InputValue::Variable(_) => serializer.serialize_unit(),
-            InputValue::Int(v) => serializer.serialize_i64(i64::from(v)),
+            InputValue::Null | InputValue::Variable(_) | InputValue::Upload(_) => {
+                serializer.serialize_unit()
+            }
+            InputValue::Int(v) => serializer.serialize_i64(v),
             InputValue::Float(v) => serializer.serialize_f64(v),
             InputValue::String(ref v) | InputValue::Enum(ref v) => serializer.serialize_str(v),
             InputValue::Boolean(v) => serializer.serialize_bool(v),
@@ -273,7 +316,7 @@ impl ser::Serialize for Value {
     {
         match *self {
             Value::Null => serializer.serialize_unit(),
-            Value::Int(v) => serializer.serialize_i64(i64::from(v)),
+            Value::Int(v) => serializer.serialize_i64(v),
             Value::Float(v) => serializer.serialize_f64(v),
             Value::String(ref v) => serializer.serialize_str(v),
             Value::Boolean(v) => serializer.serialize_bool(v),
@@ -285,8 +328,9 @@ impl ser::Serialize for Value {
 
 #[cfg(test)]
 mod tests {
-    use super::{ExecutionError, GraphQLError};
+    use super::{ExecutionError, GraphQLError, PathSegment};
     use ast::InputValue;
+    use parser::SourcePosition;
     use serde_json::from_str;
     use serde_json::to_string;
     use {FieldError, Value};
@@ -298,6 +342,10 @@ mod tests {
             from_str::<InputValue>("1235").unwrap(),
             InputValue::int(1235)
         );
+        assert_eq!(
+            from_str::<InputValue>("123567890123").unwrap(),
+            InputValue::int(123567890123)
+        );
     }
 
     #[test]
@@ -306,10 +354,11 @@ mod tests {
             from_str::<InputValue>("2.0").unwrap(),
             InputValue::float(2.0)
         );
-        // large value without a decimal part is also float
+        // value too large for a 64-bit integer without a decimal part is
+        // also float
         assert_eq!(
-            from_str::<InputValue>("123567890123").unwrap(),
-            InputValue::float(123567890123.0)
+            from_str::<InputValue>("12356789012345678901").unwrap(),
+            InputValue::float(12356789012345678901.0)
         );
     }
 
@@ -319,6 +368,10 @@ mod tests {
             to_string(&GraphQLError::UnknownOperationName).unwrap(),
             r#"[{"message":"Unknown operation"}]"#
         );
+        assert_eq!(
+            to_string(&GraphQLError::PersistedQueryNotFound).unwrap(),
+            r#"[{"message":"PersistedQueryNotFound","extensions":{"code":"PERSISTED_QUERY_NOT_FOUND"}}]"#
+        );
     }
 
     #[test]
@@ -333,4 +386,21 @@ mod tests {
             r#"{"message":"foo error","locations":[{"line":1,"column":1}],"path":[],"extensions":{"foo":"bar"}}"#
         );
     }
+
+    #[test]
+    fn error_paths() {
+        assert_eq!(
+            to_string(&ExecutionError::new(
+                SourcePosition::new(0, 0, 0),
+                &[
+                    PathSegment::from("hero"),
+                    PathSegment::from("friends"),
+                    PathSegment::Index(1),
+                    PathSegment::from("name"),
+                ],
+                FieldError::new("foo error", Value::null()),
+            )).unwrap(),
+            r#"{"message":"foo error","locations":[{"line":1,"column":1}],"path":["hero","friends",1,"name"]}"#
+        );
+    }
 }
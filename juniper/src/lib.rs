@@ -41,7 +41,9 @@ see the [rocket][rocket_examples] and [iron][iron_examples] examples folders.
 Juniper supports the full GraphQL query language according to the
 [specification][graphql_spec], including interfaces, unions, schema
 introspection, and validations.
-It does not, however, support the schema language.
+Schemas can be printed in the schema language with
+`RootNode::as_schema_language`, and schemas defined at runtime can be read
+from it with the `dynamic` module.
 
 As an exception to other GraphQL libraries for other languages, Juniper builds
 non-null types by default. A field of type `Vec<Episode>` will be converted into
@@ -94,12 +96,17 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 
-#[cfg(any(test, feature = "expose-test-schema"))]
+#[macro_use]
+extern crate self_cell;
 extern crate serde_json;
+extern crate sha2;
 
 extern crate fnv;
+#[doc(hidden)]
+pub extern crate futures;
 
 extern crate indexmap;
+extern crate linked_hash_map;
 
 #[cfg(any(test, feature = "chrono"))]
 extern crate chrono;
@@ -110,6 +117,9 @@ extern crate url;
 #[cfg(any(test, feature = "uuid"))]
 extern crate uuid;
 
+#[cfg(test)]
+extern crate tokio_threadpool;
+
 // Depend on juniper_codegen and re-export everything in it.
 // This allows users to just depend on juniper and get the derive functionality automatically.
 #[allow(unused_imports)]
@@ -124,11 +134,13 @@ mod value;
 mod macros;
 mod ast;
 mod executor;
+pub mod extensions;
 pub mod parser;
+mod prepared;
 mod schema;
 mod types;
 mod util;
-mod validation;
+pub mod validation;
 // This needs to be public until docs have support for private modules:
 // https://github.com/rust-lang/cargo/issues/1520
 pub mod http;
@@ -147,25 +159,38 @@ mod executor_tests;
 // Needs to be public because macros use it.
 pub use util::to_camel_case;
 
-use executor::execute_validated_query;
+use std::cell::RefCell;
+use std::io;
+
+use executor::{
+    execute_validated_query, execute_validated_query_async, execute_validated_subscription,
+    ResponseWriter,
+};
+use ast::Document;
+use extensions::RequestInstrumentation;
 use parser::{parse_document_source, ParseError, Spanning};
-use validation::{validate_input_values, visit_all_rules, ValidatorContext};
+use prepared::QueryDocument;
+use validation::{validate_input_values, visit_limit_rules, visit_rules, ValidatorContext};
 
 pub use ast::{FromInputValue, InputValue, Selection, ToInputValue, Type};
 pub use executor::{
     Applies, LookAheadArgument, LookAheadMethods, LookAheadSelection, LookAheadValue,
 };
 pub use executor::{
-    Context, ExecutionError, ExecutionResult, Executor, FieldError, FieldResult, FromContext,
-    IntoFieldError, IntoResolvable, Registry, Variables,
+    Context, DirectiveHook, ExecutionError, ExecutionFuture, ExecutionResult, Executor, FieldError,
+    FieldFuture, FieldResult, FromContext, IntoFieldError, IntoResolvable, LoadFuture, Loader,
+    Loaders, PathSegment, QueryFuture, Registry, SubscriptionStream, Variables,
 };
-pub use schema::model::RootNode;
+pub use prepared::PreparedQuery;
+pub use schema::model::{DirectiveLocation, DirectiveType, RootNode};
 pub use types::base::{Arguments, GraphQLType, TypeKind};
-pub use types::scalars::{EmptyMutation, ID};
+pub use types::scalars::{EmptyMutation, EmptySubscription, ID};
+pub use types::subscriptions::{GraphQLSubscriptionType, SourceEvents};
+pub use types::upload::Upload;
 pub use validation::RuleError;
 pub use value::{Value, Object};
 
-pub use schema::meta;
+pub use schema::{diff, dynamic, meta};
 
 /// An error that prevented query execution
 #[derive(Debug, PartialEq)]
@@ -176,41 +201,589 @@ pub enum GraphQLError<'a> {
     NoOperationProvided,
     MultipleOperationsProvided,
     UnknownOperationName,
+    IsSubscription,
+    NotSubscription,
+    PersistedQueryNotSupported,
+    PersistedQueryNotFound,
+    PersistedQueryHashMismatch,
+    PersistedQueryRequired,
+    BatchSizeExceeded,
 }
 
 /// Execute a query in a provided schema
-pub fn execute<'a, CtxT, QueryT, MutationT>(
+///
+/// Subscription operations can not be executed by this function, use
+/// `execute_subscription` instead.
+pub fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
     document_source: &'a str,
     operation_name: Option<&str>,
-    root_node: &RootNode<QueryT, MutationT>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &CtxT,
+) -> Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+{
+    let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+    execute_instrumented(
+        Query::Source(document_source),
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &instrumentation,
+        None,
+    )
+}
+
+fn execute_instrumented<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
     variables: &Variables,
     context: &CtxT,
+    instrumentation: &RequestInstrumentation,
+    output: Option<&ResponseWriter>,
 ) -> Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>
 where
     QueryT: GraphQLType<Context = CtxT>,
     MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+{
+    let document = query.validate(root_node, operation_name, variables, instrumentation)?;
+
+    execute_validated_query(
+        document.get(),
+        operation_name,
+        root_node,
+        variables,
+        context,
+        instrumentation,
+        output,
+    )
+}
+
+/// Execute a query like `execute`, also returning the `extensions` of the
+/// response
+pub(crate) fn execute_with_extensions<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &CtxT,
+) -> Result<(Value, Vec<ExecutionError>, Object), GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+{
+    let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+    let (value, errors) = execute_instrumented(
+        query,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &instrumentation,
+        None,
+    )?;
+
+    Ok((value, errors, instrumentation.extensions()))
+}
+
+/// Execute a query in a provided schema, writing the resulting data into
+/// `writer` as JSON
+///
+/// Unlike `execute`, this does not build a `Value` of the whole result:
+/// objects and lists are written as their fields resolve. An object or list
+/// that has to become `null` if one of its non-null fields or items fails is
+/// buffered until it is complete. Objects selecting the same field more than
+/// once or containing inline fragments on other types, and fields with
+/// directive hooks, are resolved into a `Value` before being written.
+///
+/// Errors preventing the execution are returned before anything is written.
+/// Otherwise, the execution errors are returned once all data has been
+/// written, unless writing failed.
+pub fn execute_to_writer<'a, CtxT, QueryT, MutationT, SubscriptionT, W>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &CtxT,
+    writer: W,
+) -> Result<io::Result<Vec<ExecutionError>>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    W: io::Write,
 {
-    let document = parse_document_source(document_source)?;
+    let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+    execute_instrumented_to_writer(
+        Query::Source(document_source),
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &instrumentation,
+        writer,
+    )
+}
+
+/// Execute a query like `execute_to_writer`, also returning the `extensions`
+/// of the response
+pub(crate) fn execute_to_writer_with_extensions<'a, CtxT, QueryT, MutationT, SubscriptionT, W>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &CtxT,
+    writer: W,
+) -> Result<io::Result<(Vec<ExecutionError>, Object)>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    W: io::Write,
+{
+    let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+    let result = execute_instrumented_to_writer(
+        query,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &instrumentation,
+        writer,
+    )?;
+
+    Ok(result.map(|errors| (errors, instrumentation.extensions())))
+}
 
+fn execute_instrumented_to_writer<'a, CtxT, QueryT, MutationT, SubscriptionT, W>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &CtxT,
+    instrumentation: &RequestInstrumentation,
+    writer: W,
+) -> Result<io::Result<Vec<ExecutionError>>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    W: io::Write,
+{
+    let writer = RefCell::new(writer);
+    let output = ResponseWriter::new(&writer);
+
+    let (_, errors) = execute_instrumented(
+        query,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        instrumentation,
+        Some(&output),
+    )?;
+
+    Ok(output.finish().map(|()| errors))
+}
+
+/// Execute a query in a provided schema, resolving fields asynchronously
+///
+/// The returned future resolves to the same data and errors as `execute`.
+/// Sibling fields are resolved concurrently, while the root fields of a
+/// mutation are resolved one after another.
+pub fn execute_async<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+) -> Result<QueryFuture<'a>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT> + Sync,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    CtxT: Sync,
+{
+    execute_instrumented_async(
+        Query::Source(document_source),
+        operation_name,
+        root_node,
+        variables,
+        context,
+        RequestInstrumentation::new(&root_node.extensions),
+    )
+}
+
+pub(crate) fn execute_instrumented_async<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+    instrumentation: RequestInstrumentation,
+) -> Result<QueryFuture<'a>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT> + Sync,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<Context = CtxT>,
+    CtxT: Sync,
+{
+    let document = query.validate(root_node, operation_name, variables, &instrumentation)?;
+
+    execute_validated_query_async(
+        document,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        instrumentation,
+    )
+}
+
+/// Parse and validate a query once, to execute it any number of times
+///
+/// The variables of the executions are not known yet, so they are checked
+/// whenever the returned `PreparedQuery` is executed, together with the depth
+/// and complexity limits of the root node.
+pub fn prepare<'a, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+) -> Result<PreparedQuery, GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    let prepared = PreparedQuery::parse(document_source, root_node.id)?;
+    validate_rules(prepared.document(), root_node)?;
+
+    Ok(prepared)
+}
+
+/// Execute a subscription in a provided schema
+///
+/// The returned stream yields one response for every event produced by the
+/// subscription field. Only subscription operations can be executed by this
+/// function.
+pub fn execute_subscription<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+) -> Result<SubscriptionStream<'a, CtxT>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLSubscriptionType<Context = CtxT>,
+{
+    subscribe(
+        Query::Source(document_source),
+        operation_name,
+        root_node,
+        variables,
+        context,
+        RequestInstrumentation::new(&root_node.extensions),
+    )
+}
+
+pub(crate) fn subscribe<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+    query: Query<'a>,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+    context: &'a CtxT,
+    instrumentation: RequestInstrumentation,
+) -> Result<SubscriptionStream<'a, CtxT>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType<Context = CtxT>,
+    MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLSubscriptionType<Context = CtxT>,
+{
+    let document = query.validate(root_node, operation_name, variables, &instrumentation)?;
+
+    execute_validated_subscription(
+        document,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        instrumentation,
+    )
+}
+
+fn parse_and_validate<'a, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    operation_name: Option<&str>,
+    variables: &Variables,
+    instrumentation: &RequestInstrumentation,
+) -> Result<Document<'a>, GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    instrumentation.parse_start();
+    let document = parse_document_source(document_source);
+    instrumentation.parse_end();
+    let document = document?;
+
+    instrumentation.validation_start();
+    let result = validate(&document, root_node, operation_name, variables);
+    instrumentation.validation_end();
+    result?;
+
+    Ok(document)
+}
+
+/// A query to execute, either as text or already prepared
+pub(crate) enum Query<'a> {
+    Source(&'a str),
+    /// Text that does not outlive the execution, such as a query looked up
+    /// by the HTTP layer
+    Owned(String),
+    Prepared(PreparedQuery),
+}
+
+impl<'a> Query<'a> {
+    /// Get the document of the query, once it has passed validation
+    ///
+    /// If the root node has a query cache, query text is only parsed and
+    /// validated if it isn't cached yet. Queries prepared for the root node
+    /// only need their variables and limits checked.
+    fn validate<QueryT, MutationT, SubscriptionT>(
+        self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+        operation_name: Option<&str>,
+        variables: &Variables,
+        instrumentation: &RequestInstrumentation,
+    ) -> Result<QueryDocument<'a>, GraphQLError<'a>>
+    where
+        QueryT: GraphQLType,
+        MutationT: GraphQLType,
+        SubscriptionT: GraphQLType,
     {
-        let errors = validate_input_values(variables, &document, &root_node.schema);
+        let prepared = match self {
+            Query::Source(document_source) => match root_node.query_cache {
+                Some(ref cache) => match cache.get(document_source) {
+                    Some(prepared) => prepared,
+                    None => {
+                        return prepare_instrumented(
+                            document_source,
+                            root_node,
+                            operation_name,
+                            variables,
+                            instrumentation,
+                        ).map(QueryDocument::Prepared)
+                    }
+                },
+                None => {
+                    let document = parse_and_validate(
+                        document_source,
+                        root_node,
+                        operation_name,
+                        variables,
+                        instrumentation,
+                    )?;
+                    return Ok(QueryDocument::Parsed(document));
+                }
+            },
+            Query::Owned(document_source) => {
+                let cached = root_node
+                    .query_cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(&document_source));
+
+                match cached {
+                    Some(prepared) => prepared,
+                    None => {
+                        return prepare_instrumented(
+                            &document_source,
+                            root_node,
+                            operation_name,
+                            variables,
+                            instrumentation,
+                        ).map(QueryDocument::Prepared)
+                            .map_err(into_static_error)
+                    }
+                }
+            }
+            Query::Prepared(prepared) => prepared,
+        };
+
+        // Queries prepared for another root node may not be valid for this
+        // one, so they are validated from scratch
+        instrumentation.validation_start();
+        let result = if prepared.is_prepared_for(root_node) {
+            validate_variables(prepared.document(), root_node, variables).and_then(|()| {
+                validate_limits(prepared.document(), root_node, operation_name, variables)
+            })
+        } else {
+            validate(prepared.document(), root_node, operation_name, variables)
+        };
+        instrumentation.validation_end();
+        result?;
+
+        Ok(QueryDocument::Prepared(prepared))
+    }
+}
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
+/// Parse and validate query text into a prepared query, adding it to the
+/// query cache of the root node if it has one
+fn prepare_instrumented<'a, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    operation_name: Option<&str>,
+    variables: &Variables,
+    instrumentation: &RequestInstrumentation,
+) -> Result<PreparedQuery, GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    instrumentation.parse_start();
+    let prepared = PreparedQuery::parse(document_source, root_node.id);
+    instrumentation.parse_end();
+    let prepared = prepared?;
+
+    instrumentation.validation_start();
+    let result = validate(prepared.document(), root_node, operation_name, variables);
+    instrumentation.validation_end();
+    result?;
+
+    if let Some(ref cache) = root_node.query_cache {
+        cache.insert(prepared.clone());
     }
 
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+    Ok(prepared)
+}
+
+/// Detach an error from the query text it was reported for
+///
+/// Parse errors are turned into validation errors with the same message and
+/// location, which are serialized the same way.
+fn into_static_error(error: GraphQLError) -> GraphQLError<'static> {
+    match error {
+        GraphQLError::ParseError(error) => GraphQLError::ValidationError(vec![RuleError::new(
+            &error.item.to_string(),
+            &[error.start],
+        )]),
+        GraphQLError::ValidationError(errors) => GraphQLError::ValidationError(errors),
+        GraphQLError::NoOperationProvided => GraphQLError::NoOperationProvided,
+        GraphQLError::MultipleOperationsProvided => GraphQLError::MultipleOperationsProvided,
+        GraphQLError::UnknownOperationName => GraphQLError::UnknownOperationName,
+        GraphQLError::IsSubscription => GraphQLError::IsSubscription,
+        GraphQLError::NotSubscription => GraphQLError::NotSubscription,
+        GraphQLError::PersistedQueryNotSupported => GraphQLError::PersistedQueryNotSupported,
+        GraphQLError::PersistedQueryNotFound => GraphQLError::PersistedQueryNotFound,
+        GraphQLError::PersistedQueryHashMismatch => GraphQLError::PersistedQueryHashMismatch,
+        GraphQLError::PersistedQueryRequired => GraphQLError::PersistedQueryRequired,
+        GraphQLError::BatchSizeExceeded => GraphQLError::BatchSizeExceeded,
+    }
+}
+
+fn validate<'a, QueryT, MutationT, SubscriptionT>(
+    document: &Document,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    operation_name: Option<&str>,
+    variables: &Variables,
+) -> Result<(), GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    validate_variables(document, root_node, variables)?;
+    validate_rules(document, root_node)?;
+    validate_limits(document, root_node, operation_name, variables)
+}
+
+fn validate_variables<'a, QueryT, MutationT, SubscriptionT>(
+    document: &Document,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    variables: &Variables,
+) -> Result<(), GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    let errors = validate_input_values(variables, document, &root_node.schema);
+
+    if !errors.is_empty() {
+        return Err(GraphQLError::ValidationError(errors));
+    }
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
+    Ok(())
+}
+
+fn validate_rules<'a, QueryT, MutationT, SubscriptionT>(
+    document: &Document,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+) -> Result<(), GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    let mut ctx = ValidatorContext::new(&root_node.schema, document);
+    visit_rules(
+        &mut ctx,
+        document,
+        &root_node.disabled_rules,
+        &root_node.validation_rules,
+    );
+
+    let errors = ctx.into_errors();
+    if !errors.is_empty() {
+        return Err(GraphQLError::ValidationError(errors));
+    }
+
+    Ok(())
+}
+
+fn validate_limits<'a, QueryT, MutationT, SubscriptionT>(
+    document: &Document,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    operation_name: Option<&str>,
+    variables: &Variables,
+) -> Result<(), GraphQLError<'a>>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    let mut ctx = ValidatorContext::new(&root_node.schema, document);
+    visit_limit_rules(
+        &mut ctx,
+        document,
+        root_node.max_depth,
+        root_node.max_complexity,
+        operation_name,
+        variables,
+    );
+
+    let errors = ctx.into_errors();
+    if !errors.is_empty() {
+        return Err(GraphQLError::ValidationError(errors));
     }
 
-    execute_validated_query(document, operation_name, root_node, variables, context)
+    Ok(())
 }
 
 impl<'a> From<Spanning<ParseError<'a>>> for GraphQLError<'a> {
@@ -14,7 +14,7 @@ macro_rules! __graphql__build_field_matches {
     ) => {
         __graphql__build_field_matches!(
             $resolveargs,
-            (($name; $args; $t; $body) $( $acc )*),
+            ((field $name; $args; $t; $body) $( $acc )*),
             $( $rest )*);
     };
 
@@ -26,7 +26,7 @@ macro_rules! __graphql__build_field_matches {
     ) => {
         __graphql__build_field_matches!(
             $resolveargs,
-            (($name; $args; $t; $body) $( $acc )*),
+            ((field $name; $args; $t; $body) $( $acc )*),
             $( $rest )*);
     };
 
@@ -42,7 +42,7 @@ macro_rules! __graphql__build_field_matches {
     ) => {
         __graphql__build_field_matches!(
             $resolveargs,
-            (($name; $args; $t; $body) $( $acc )*),
+            ((field $name; $args; $t; $body) $( $acc )*),
             $( $rest )*);
     };
 
@@ -53,10 +53,94 @@ macro_rules! __graphql__build_field_matches {
     ) => {
         __graphql__build_field_matches!(
             $resolveargs,
-            (($name; $args; $t; $body) $( $acc )*),
+            ((field $name; $args; $t; $body) $( $acc )*),
             $( $rest )*);
     };
 
+    // async field deprecated <reason> <name>(...) -> <type> as <description> { ... }
+    (
+        $resolveargs:tt,
+        ( $( $acc:tt )* ),
+        async field deprecated $_reason:tt
+            $name:ident
+            $args:tt -> $t:ty
+            as $desc:tt
+            $body:block
+            $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!(
+            $resolveargs,
+            ((async $name; $args; $t; $body) $( $acc )*),
+            $( $rest )*);
+    };
+
+    // async field deprecated <reason> <name>(...) -> <type> { ... }
+    (
+        $resolveargs:tt,
+        ( $( $acc:tt )* ),
+        async field deprecated $_reason:tt $name:ident $args:tt -> $t:ty $body:block $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!(
+            $resolveargs,
+            ((async $name; $args; $t; $body) $( $acc )*),
+            $( $rest )*);
+    };
+
+    // async field <name>(...) -> <type> as <description> { ... }
+    (
+        $resolveargs:tt,
+        ( $( $acc:tt )* ),
+        async field $name:ident
+        $args:tt -> $t:ty
+        as $desc:tt
+        $body:block
+        $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!(
+            $resolveargs,
+            ((async $name; $args; $t; $body) $( $acc )*),
+            $( $rest )*);
+    };
+
+    // async field <name>(...) -> <type> { ... }
+    (
+        $resolveargs:tt,
+        ( $( $acc:tt )* ), async field $name:ident $args:tt -> $t:ty $body:block $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!(
+            $resolveargs,
+            ((async $name; $args; $t; $body) $( $acc )*),
+            $( $rest )*);
+    };
+
+    // field complexity <cost> * <argument> ...
+    (
+        $resolveargs:tt,
+        $acc:tt,
+        field complexity $_cost:tt * $_multiplier:ident $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!($resolveargs, $acc, field $( $rest )*);
+    };
+
+    // field complexity <cost> ...
+    ( $resolveargs:tt, $acc:tt, field complexity $_cost:tt $( $rest:tt )*) => {
+        __graphql__build_field_matches!($resolveargs, $acc, field $( $rest )*);
+    };
+
+    // async field complexity <cost> * <argument> ...
+    (
+        $resolveargs:tt,
+        $acc:tt,
+        async field complexity $_cost:tt * $_multiplier:ident $( $rest:tt )*
+    ) => {
+        __graphql__build_field_matches!($resolveargs, $acc, async field $( $rest )*);
+    };
+
+    // async field complexity <cost> ...
+    ( $resolveargs:tt, $acc:tt, async field complexity $_cost:tt $( $rest:tt )*) => {
+        __graphql__build_field_matches!($resolveargs, $acc, async field $( $rest )*);
+    };
+
     ( $resolveargs:tt, $acc:tt, description : $value:tt $( $rest:tt )*) => {
         __graphql__build_field_matches!($resolveargs, $acc, $( $rest )*);
     };
@@ -77,27 +161,81 @@ macro_rules! __graphql__build_field_matches {
     };
 
     (
-        ($outname:tt, $selfvar:ident, $fieldvar:ident, $argsvar:ident, $executorvar:ident),
-        ( $( ( $name:ident; ( $($args:tt)* ); $t:ty; $body:block ) )* ),
+        ($mode:ident, $outname:tt, $selfvar:ident, $fieldvar:ident, $argsvar:ident, $executorvar:ident),
+        ( $( ( $kind:ident $name:ident; $args:tt; $t:ty; $body:block ) )* ),
     ) => {
         $(
             if $fieldvar == &$crate::to_camel_case(__graphql__stringify!($name)) {
-                let result: $t = (||{
-                    __graphql__args!(
-                        @assign_arg_vars,
-                        $argsvar, $executorvar, $($args)*
-                    );
-                    $body
-                })();
-
-                return ($crate::IntoResolvable::into(result, $executorvar.context())).and_then(
-                    |res| match res {
-                        Some((ctx, r)) =>
-                            $executorvar.replaced_context(ctx).resolve_with_ctx(&(), &r),
-                        None => Ok($crate::Value::null()),
-                    })
+                __graphql__build_field_matches!(
+                    @resolve, $mode, $kind, $argsvar, $executorvar, $args, $t, $body);
             }
         )*
         __graphql__panic!("Field {} not found on type {}", $fieldvar, $outname);
     };
+
+    // Synchronous field resolved by `resolve_field`
+    (
+        @resolve, sync, field, $argsvar:ident, $executorvar:ident,
+        ( $($args:tt)* ), $t:ty, $body:block
+    ) => {
+        let result: $t = (||{
+            __graphql__args!(
+                @assign_arg_vars,
+                $argsvar, $executorvar, $($args)*
+            );
+            $body
+        })();
+
+        return ($crate::IntoResolvable::into(result, $executorvar.context())).and_then(
+            |res| match res {
+                Some((ctx, r)) =>
+                    $executorvar.replaced_context(ctx).resolve_with_ctx(&(), &r),
+                None => Ok($crate::Value::null()),
+            })
+    };
+
+    // Asynchronous field resolved by `resolve_field`, which can't wait for
+    // the future without blocking the thread
+    (
+        @resolve, sync, async, $argsvar:ident, $executorvar:ident,
+        ( $($args:tt)* ), $t:ty, $body:block
+    ) => {
+        let _ = ($argsvar, $executorvar);
+
+        return Err($crate::FieldError::from(
+            "Asynchronous fields can only be resolved by execute_async"
+        ));
+    };
+
+    // Synchronous field resolved by `resolve_field_async`
+    (
+        @resolve, async, field, $argsvar:ident, $executorvar:ident,
+        ( $($args:tt)* ), $t:ty, $body:block
+    ) => {
+        let result: $t = (||{
+            __graphql__args!(
+                @assign_arg_vars,
+                $argsvar, $executorvar, $($args)*
+            );
+            $body
+        })();
+
+        return $executorvar.resolve_resolvable_async(result);
+    };
+
+    // Asynchronous field resolved by `resolve_field_async`
+    (
+        @resolve, async, async, $argsvar:ident, $executorvar:ident,
+        ( $($args:tt)* ), $t:ty, $body:block
+    ) => {
+        let future = (||{
+            __graphql__args!(
+                @assign_arg_vars,
+                $argsvar, $executorvar, $($args)*
+            );
+            $body
+        })();
+
+        return $executorvar.resolve_future::<_, $t, _>(future);
+    };
 }
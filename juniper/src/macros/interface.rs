@@ -166,6 +166,43 @@ macro_rules! graphql_interface {
         graphql_interface!(@ gather_meta, ($reg, $acc, $info, $descr), $( $rest )*);
     };
 
+    // field complexity <cost> * <argument> ...
+    (
+        @ gather_meta,
+        ($reg:expr, $acc:expr, $info:expr, $descr:expr),
+        field complexity $cost:tt * $multiplier:ident $( $rest:tt )*
+    ) => {
+        let index = $acc.len();
+        graphql_interface!(@ gather_meta, ($reg, $acc, $info, $descr), field $( $rest )*);
+
+        let field = $acc.remove(index);
+        $acc.insert(index, field
+            .complexity($cost)
+            .complexity_multiplier(&$crate::to_camel_case(__graphql__stringify!($multiplier))));
+    };
+
+    // field complexity <cost> ...
+    (
+        @ gather_meta,
+        ($reg:expr, $acc:expr, $info:expr, $descr:expr),
+        field complexity $cost:tt $( $rest:tt )*
+    ) => {
+        let index = $acc.len();
+        graphql_interface!(@ gather_meta, ($reg, $acc, $info, $descr), field $( $rest )*);
+
+        let field = $acc.remove(index);
+        $acc.insert(index, field.complexity($cost));
+    };
+
+    // async field ...
+    (
+        @ gather_meta,
+        ($reg:expr, $acc:expr, $info:expr, $descr:expr),
+        async field $( $rest:tt )*
+    ) => {
+        graphql_interface!(@ gather_meta, ($reg, $acc, $info, $descr), field $( $rest )*);
+    };
+
     // description: <description>
     (
         @ gather_meta,
@@ -227,6 +264,24 @@ macro_rules! graphql_interface {
             __graphql__panic!("Concrete type not handled by instance resolvers on {}", $outname);
     };
 
+    // instance_resolvers: | <ctxtvar> |
+    (
+        @ resolve_into_type_async,
+        ($outname:tt, $typenamearg:ident, $execarg:ident, $ctxttype:ty),
+        instance_resolvers : | $ctxtvar:pat
+                             | { $( $srctype:ty => $resolver:expr ),* $(,)* } $( $rest:tt )*
+    ) => {
+        let $ctxtvar = &$execarg.context();
+
+        $(
+            if $typenamearg == (<$srctype as $crate::GraphQLType>::name(&())).unwrap() {
+                return $execarg.resolve_async(&(), &$resolver);
+            }
+        )*
+
+            __graphql__panic!("Concrete type not handled by instance resolvers on {}", $outname);
+    };
+
     ( @ $mfn:ident, $args:tt, $first:tt $($rest:tt)* ) => {
         graphql_interface!(@ $mfn, $args, $($rest)*);
     };
@@ -276,7 +331,22 @@ macro_rules! graphql_interface {
                 mut executor: &$crate::Executor<Self::Context>
             ) -> $crate::ExecutionResult {
                 __graphql__build_field_matches!(
-                    ($outname, $mainself, field, args, executor),
+                    (sync, $outname, $mainself, field, args, executor),
+                    (),
+                    $($items)*);
+            }
+
+            #[allow(unused_variables)]
+            #[allow(unused_mut)]
+            fn resolve_field_async<'r>(
+                &$mainself,
+                info: &'r (),
+                field: &str,
+                args: &$crate::Arguments,
+                mut executor: &$crate::Executor<'r, Self::Context>
+            ) -> $crate::ExecutionFuture<'r> {
+                __graphql__build_field_matches!(
+                    (async, $outname, $mainself, field, args, executor),
                     (),
                     $($items)*);
             }
@@ -302,6 +372,21 @@ macro_rules! graphql_interface {
                     ($outname, type_name, executor, $ctxt),
                     $($items)*);
             }
+
+            fn resolve_into_type_async<'r>(
+                &$mainself,
+                _: &'r (),
+                type_name: &str,
+                _: Option<&'r [$crate::Selection<'r>]>,
+                executor: &$crate::Executor<'r, Self::Context>,
+            )
+                -> $crate::ExecutionFuture<'r>
+            {
+                graphql_interface!(
+                    @ resolve_into_type_async,
+                    ($outname, type_name, executor, $ctxt),
+                    $($items)*);
+            }
         });
     };
 
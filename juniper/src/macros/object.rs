@@ -149,6 +149,50 @@ graphql_object!(User: () |&self| {
 # fn main() { }
 ```
 
+## Asynchronous fields
+
+Fields prefixed with `async` return anything that implements `IntoFuture`
+instead of the value itself. The item of the future is the declared field
+type, and its error must be convertible into a `FieldError`. When executing a
+query with `juniper::execute_async`, sibling fields are resolved concurrently
+and the root fields of a mutation one after another. The context has to be
+`Sync` for that, as the future of the query may be polled on any thread. The
+synchronous `juniper::execute` never waits for futures, and reports an error
+for every asynchronous field instead.
+
+The future is created while resolving the field, but polled later on. It can
+therefore not borrow `self` or the arguments, only the context:
+
+```
+# #[macro_use] extern crate juniper;
+# extern crate futures;
+# use juniper::FieldError;
+use futures::future::{self, Future};
+
+struct Database;
+impl juniper::Context for Database {}
+
+impl Database {
+    fn load_name(&self, id: i32) -> future::FutureResult<String, FieldError> {
+        future::ok(format!("User {}", id))
+    }
+}
+
+struct User { id: i32 }
+
+graphql_object!(User: Database |&self| {
+    field id() -> i32 {
+        self.id
+    }
+
+    async field name(&executor) -> String {
+        executor.context().load_name(self.id).map(|name| name.to_uppercase())
+    }
+});
+
+# fn main() { }
+```
+
 # Syntax
 
 The top-most syntax of this macro defines which type to expose, the context
@@ -197,6 +241,22 @@ Defines a field on the object. The name is converted to camel case, e.g.
 `user_name` is exposed as `userName`. The `as "Field description"` adds the
 string as documentation on the field.
 
+All of the forms above can be prefixed with `async` to declare an
+asynchronous field, whose body returns a future resolving to `Type`.
+
+### Field complexity
+
+```text
+field complexity 5 name(args...) -> Type { }
+field complexity 5 * arg_name name(args...) -> Type { }
+```
+
+Sets the cost of a field used by `RootNode::max_complexity`, which is 1 by
+default. With `* arg_name`, the complexity of the field and its selections is
+multiplied by the value of the integer argument `arg_name`, e.g. the number of
+items returned by a `first` argument. `complexity` goes before `deprecated`
+and can be combined with all forms above.
+
 ### Field arguments
 
 ```text
@@ -320,6 +380,46 @@ macro_rules! graphql_object {
         graphql_object!(@gather_object_meta, $reg, $acc, $info, $descr, $ifaces, $( $rest )*);
     };
 
+    // field complexity <cost> * <argument> ...
+    (
+        @gather_object_meta,
+        $reg:expr, $acc:expr, $info:expr, $descr:expr, $ifaces:expr,
+        field complexity $cost:tt * $multiplier:ident $( $rest:tt )*
+    ) => {
+        let index = $acc.len();
+        graphql_object!(
+            @gather_object_meta, $reg, $acc, $info, $descr, $ifaces, field $( $rest )*);
+
+        let field = $acc.remove(index);
+        $acc.insert(index, field
+            .complexity($cost)
+            .complexity_multiplier(&$crate::to_camel_case(__graphql__stringify!($multiplier))));
+    };
+
+    // field complexity <cost> ...
+    (
+        @gather_object_meta,
+        $reg:expr, $acc:expr, $info:expr, $descr:expr, $ifaces:expr,
+        field complexity $cost:tt $( $rest:tt )*
+    ) => {
+        let index = $acc.len();
+        graphql_object!(
+            @gather_object_meta, $reg, $acc, $info, $descr, $ifaces, field $( $rest )*);
+
+        let field = $acc.remove(index);
+        $acc.insert(index, field.complexity($cost));
+    };
+
+    // async field ...
+    (
+        @gather_object_meta,
+        $reg:expr, $acc:expr, $info:expr, $descr:expr, $ifaces:expr,
+        async field $( $rest:tt )*
+    ) => {
+        graphql_object!(
+            @gather_object_meta, $reg, $acc, $info, $descr, $ifaces, field $( $rest )*);
+    };
+
     // description: <description>
     (
         @gather_object_meta,
@@ -369,7 +469,7 @@ macro_rules! graphql_object {
     };
 
     (
-        ( $($lifetime:tt)* );
+        $mode:ident; ( $($lifetime:tt)* );
         $name:ty; $ctxt:ty; $outname:expr; $mainself:ident; $($items:tt)*
     ) => {
         graphql_object!(@as_item, impl<$($lifetime)*> $crate::GraphQLType for $name {
@@ -422,20 +522,53 @@ macro_rules! graphql_object {
                 -> $crate::ExecutionResult
             {
                 __graphql__build_field_matches!(
-                    ($outname, $mainself, field, args, executor),
+                    (sync, $outname, $mainself, field, args, executor),
                     (),
                     $($items)*);
             }
+
+            graphql_object!(@resolve_field_async, $mode, $outname, $mainself, $($items)*);
         });
     };
 
+    ( @resolve_field_async, async, $outname:expr, $mainself:ident, $($items:tt)* ) => {
+        #[allow(unused_variables)]
+        #[allow(unused_mut)]
+        fn resolve_field_async<'r>(
+            &$mainself,
+            info: &'r (),
+            field: &str,
+            args: &$crate::Arguments,
+            executor: &$crate::Executor<'r, Self::Context>
+        )
+            -> $crate::ExecutionFuture<'r>
+        {
+            __graphql__build_field_matches!(
+                (async, $outname, $mainself, field, args, executor),
+                (),
+                $($items)*);
+        }
+    };
+
+    // Objects whose fields can't outlive a borrow of the object, such as the
+    // introspection types, are only resolved synchronously
+    ( @resolve_field_async, sync, $outname:expr, $mainself:ident, $($items:tt)* ) => {};
+
+    (
+        @sync_only <$( $lifetime:tt ),*> $name:ty : $ctxt:ty as $outname:tt
+        | &$mainself:ident | { $( $items:tt )* }
+    ) => {
+        graphql_object!(
+            sync; ( $($lifetime),* ); $name; $ctxt; $outname; $mainself; $( $items )*);
+    };
+
     (
         <$( $lifetime:tt ),*> $name:ty : $ctxt:ty as $outname:tt | &$mainself:ident | {
             $( $items:tt )*
         }
     ) => {
         graphql_object!(
-            ( $($lifetime),* ); $name; $ctxt; $outname; $mainself; $( $items )*);
+            async; ( $($lifetime),* ); $name; $ctxt; $outname; $mainself; $( $items )*);
     };
 
     (
@@ -444,7 +577,7 @@ macro_rules! graphql_object {
         }
     ) => {
         graphql_object!(
-            ( ); $name; $ctxt; $outname; $mainself; $( $items )*);
+            async; ( ); $name; $ctxt; $outname; $mainself; $( $items )*);
     };
 
     (
@@ -453,6 +586,6 @@ macro_rules! graphql_object {
         }
     ) => {
         graphql_object!(
-            ( ); $name; $ctxt; (__graphql__stringify!($name)); $mainself; $( $items )*);
+            async; ( ); $name; $ctxt; (__graphql__stringify!($name)); $mainself; $( $items )*);
     };
 }
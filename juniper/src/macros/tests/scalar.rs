@@ -3,10 +3,10 @@ use schema::model::RootNode;
 use types::scalars::EmptyMutation;
 use value::{Value, Object};
 
-struct DefaultName(i32);
-struct OtherOrder(i32);
-struct Named(i32);
-struct ScalarDescription(i32);
+struct DefaultName(i64);
+struct OtherOrder(i64);
+struct Named(i64);
+struct ScalarDescription(i64);
 
 struct Root;
 
@@ -94,7 +94,7 @@ where
 
 #[test]
 fn path_in_resolve_return_type() {
-    struct ResolvePath(i32);
+    struct ResolvePath(i64);
     graphql_scalar!(ResolvePath {
         resolve(&self) -> self::Value {
             Value::int(self.0)
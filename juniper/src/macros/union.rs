@@ -14,6 +14,27 @@ items, no fields or interfaces can be declared.
 See the documentation for [`graphql_interface!`][2] on the syntax for interface
 resolvers.
 
+Enums whose variants each wrap a single object type can derive the union
+instead:
+
+```rust
+# #[macro_use] extern crate juniper;
+#[derive(GraphQLObject)]
+struct Human { id: String }
+
+#[derive(GraphQLObject)]
+struct Droid { id: String }
+
+#[derive(GraphQLUnion)]
+#[graphql(description = "A search result")]
+enum SearchResult {
+    Human(Human),
+    Droid(Droid),
+}
+
+# fn main() { }
+```
+
 [1]: macro.graphql_object!.html
 [2]: macro.graphql_interface!.html
 */
@@ -90,6 +111,25 @@ macro_rules! graphql_union {
            __graphql__panic!("Concrete type not handled by instance resolvers on {}", $outname);
     };
 
+    // To generate the asynchronous "resolve into type" resolver, syntax case:
+    // instance_resolvers: | <ctxtvar> | [...]
+    (
+        @ resolve_into_type_async,
+        ($outname:tt, $typenamearg:ident, $execarg:ident, $ctxttype:ty),
+        instance_resolvers: | $ctxtvar:pat
+                            | { $( $srctype:ty => $resolver:expr ),* $(,)* } $( $rest:tt )*
+    ) => {
+        let $ctxtvar = &$execarg.context();
+
+        $(
+            if $typenamearg == (<$srctype as $crate::GraphQLType>::name(&())).unwrap().to_owned() {
+                return $execarg.resolve_async(&(), &$resolver);
+            }
+        )*
+
+           __graphql__panic!("Concrete type not handled by instance resolvers on {}", $outname);
+    };
+
     // eat commas
     ( @ $mfn:ident, $args:tt, , $($rest:tt)* ) => {
         graphql_union!(@ $mfn, $args, $($rest)*);
@@ -152,6 +192,21 @@ macro_rules! graphql_union {
                     ($outname, type_name, executor, $ctxt),
                     $($items)*);
             }
+
+            fn resolve_into_type_async<'r>(
+                &$mainself,
+                _: &'r (),
+                type_name: &str,
+                _: Option<&'r [$crate::Selection<'r>]>,
+                executor: &$crate::Executor<'r, Self::Context>,
+            )
+                -> $crate::ExecutionFuture<'r>
+            {
+                graphql_union!(
+                    @ resolve_into_type_async,
+                    ($outname, type_name, executor, $ctxt),
+                    $($items)*);
+            }
         });
     };
 
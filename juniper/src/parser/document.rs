@@ -1,17 +1,22 @@
 use std::borrow::Cow;
 
 use ast::{
-    Arguments, Definition, Directive, Document, Field, Fragment, FragmentSpread, InlineFragment,
-    InputValue, Operation, OperationType, Selection, Type, VariableDefinition, VariableDefinitions,
+    Arguments, Definition, Directive, DirectiveDefinition, Document, EnumTypeDefinition,
+    EnumValueDefinition, Field, FieldDefinition, Fragment, FragmentSpread, InlineFragment,
+    InputObjectTypeDefinition, InputValue, InputValueDefinition, InterfaceTypeDefinition,
+    ObjectTypeDefinition, Operation, OperationType, ScalarTypeDefinition, SchemaDefinition,
+    Selection, Type, TypeDefinition, TypeSystemDefinition, UnionTypeDefinition,
+    VariableDefinition, VariableDefinitions,
 };
 
 use parser::value::parse_value_literal;
 use parser::{
-    Lexer, OptionParseResult, ParseError, ParseResult, Parser, Spanning, Token,
+    Lexer, OptionParseResult, ParseError, ParseResult, Parser, SourcePosition, Spanning, Token,
     UnlocatedParseResult,
 };
 
-#[doc(hidden)]
+/// Parse a document containing executable definitions, type system
+/// definitions, or both
 pub fn parse_document_source(s: &str) -> UnlocatedParseResult<Document> {
     let mut lexer = Lexer::new(s);
     let mut parser = Parser::new(&mut lexer).map_err(|s| s.map(ParseError::LexerError))?;
@@ -32,10 +37,25 @@ fn parse_document<'a>(parser: &mut Parser<'a>) -> UnlocatedParseResult<'a, Docum
 
 fn parse_definition<'a>(parser: &mut Parser<'a>) -> UnlocatedParseResult<'a, Definition<'a>> {
     match parser.peek().item {
-        Token::CurlyOpen | Token::Name("query") | Token::Name("mutation") => {
+        Token::CurlyOpen
+        | Token::Name("query")
+        | Token::Name("mutation")
+        | Token::Name("subscription") => {
             Ok(Definition::Operation(parse_operation_definition(parser)?))
         }
         Token::Name("fragment") => Ok(Definition::Fragment(parse_fragment_definition(parser)?)),
+        Token::String(_)
+        | Token::Name("schema")
+        | Token::Name("scalar")
+        | Token::Name("type")
+        | Token::Name("interface")
+        | Token::Name("union")
+        | Token::Name("enum")
+        | Token::Name("input")
+        | Token::Name("directive")
+        | Token::Name("extend") => Ok(Definition::TypeSystem(parse_type_system_definition(
+            parser,
+        )?)),
         _ => Err(parser.next()?.map(ParseError::UnexpectedToken)),
     }
 }
@@ -265,6 +285,7 @@ fn parse_operation_type<'a>(parser: &mut Parser<'a>) -> ParseResult<'a, Operatio
     match parser.peek().item {
         Token::Name("query") => Ok(parser.next()?.map(|_| OperationType::Query)),
         Token::Name("mutation") => Ok(parser.next()?.map(|_| OperationType::Mutation)),
+        Token::Name("subscription") => Ok(parser.next()?.map(|_| OperationType::Subscription)),
         _ => Err(parser.next()?.map(ParseError::UnexpectedToken)),
     }
 }
@@ -305,17 +326,24 @@ fn parse_variable_definition<'a>(
         None
     };
 
+    let directives = parse_directives(parser)?;
+
+    let end_pos = directives
+        .as_ref()
+        .map(|s| &s.end)
+        .or_else(|| default_value.as_ref().map(|s| &s.end))
+        .unwrap_or(&var_type.end)
+        .clone();
+
     Ok(Spanning::start_end(
         &start_pos,
-        &default_value
-            .as_ref()
-            .map_or(&var_type.end, |s| &s.end)
-            .clone(),
+        &end_pos,
         (
             Spanning::start_end(&start_pos, &var_name.end, var_name.item),
             VariableDefinition {
                 var_type: var_type,
                 default_value: default_value,
+                directives: directives.map(|s| s.item),
             },
         ),
     ))
@@ -388,3 +416,491 @@ fn wrap_non_null<'a>(
 
     Ok(Spanning::start_end(&inner.start, &end_pos, wrapped))
 }
+
+fn parse_type_system_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, TypeSystemDefinition<'a>> {
+    if parser.peek().item == Token::Name("extend") {
+        return parse_type_system_extension(parser);
+    }
+
+    let description = parse_description(parser)?;
+
+    match parser.peek().item {
+        Token::Name("schema") if description.is_none() => {
+            let Spanning {
+                start: start_pos, ..
+            } = parser.next()?;
+            Ok(TypeSystemDefinition::Schema(parse_schema_definition(
+                parser, start_pos, false,
+            )?))
+        }
+        Token::Name("directive") => Ok(TypeSystemDefinition::Directive(
+            parse_directive_definition(parser, description)?,
+        )),
+        Token::Name("scalar")
+        | Token::Name("type")
+        | Token::Name("interface")
+        | Token::Name("union")
+        | Token::Name("enum")
+        | Token::Name("input") => {
+            let start_pos = description
+                .as_ref()
+                .map_or(&parser.peek().start, |d| &d.start)
+                .clone();
+            Ok(TypeSystemDefinition::Type(parse_type_definition(
+                parser,
+                start_pos,
+                description,
+                false,
+            )?))
+        }
+        _ => Err(parser.next()?.map(ParseError::UnexpectedToken)),
+    }
+}
+
+fn parse_type_system_extension<'a>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<'a, TypeSystemDefinition<'a>> {
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::Name("extend"))?;
+
+    match parser.peek().item {
+        Token::Name("schema") => {
+            parser.next()?;
+            Ok(TypeSystemDefinition::SchemaExtension(
+                parse_schema_definition(parser, start_pos, true)?,
+            ))
+        }
+        Token::Name("scalar")
+        | Token::Name("type")
+        | Token::Name("interface")
+        | Token::Name("union")
+        | Token::Name("enum")
+        | Token::Name("input") => Ok(TypeSystemDefinition::TypeExtension(
+            parse_type_definition(parser, start_pos, None, true)?,
+        )),
+        _ => Err(parser.next()?.map(ParseError::UnexpectedToken)),
+    }
+}
+
+fn parse_description<'a>(parser: &mut Parser<'a>) -> OptionParseResult<'a, String> {
+    match parser.peek().item {
+        Token::String(_) => Ok(Some(parser.next()?.map(|t| {
+            if let Token::String(s) = t {
+                s
+            } else {
+                panic!("Internal parser error");
+            }
+        }))),
+        _ => Ok(None),
+    }
+}
+
+/// Parse the body of a schema definition or extension, after the `schema`
+/// keyword
+///
+/// Extensions need either directives or operation types, definitions need
+/// operation types.
+fn parse_schema_definition<'a>(
+    parser: &mut Parser<'a>,
+    start_pos: SourcePosition,
+    is_extension: bool,
+) -> ParseResult<'a, SchemaDefinition<'a>> {
+    let directives = parse_directives(parser)?;
+
+    let operation_types = if !is_extension || parser.peek().item == Token::CurlyOpen {
+        Some(parser.delimited_nonempty_list(
+            &Token::CurlyOpen,
+            parse_operation_type_definition,
+            &Token::CurlyClose,
+        )?)
+    } else if directives.is_none() {
+        return Err(parser.next()?.map(ParseError::UnexpectedToken));
+    } else {
+        None
+    };
+
+    let end_pos = operation_types
+        .as_ref()
+        .map(|s| &s.end)
+        .or_else(|| directives.as_ref().map(|s| &s.end))
+        .expect("Schema definition without directives and operation types")
+        .clone();
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &end_pos,
+        SchemaDefinition {
+            directives: directives.map(|s| s.item),
+            operation_types: operation_types
+                .map(|s| s.item.into_iter().map(|s| s.item).collect())
+                .unwrap_or_default(),
+        },
+    ))
+}
+
+fn parse_operation_type_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<'a, (Spanning<OperationType>, Spanning<&'a str>)> {
+    let operation_type = parse_operation_type(parser)?;
+    parser.expect(&Token::Colon)?;
+    let type_name = parser.expect_name()?;
+
+    Ok(Spanning::start_end(
+        &operation_type.start.clone(),
+        &type_name.end.clone(),
+        (operation_type, type_name),
+    ))
+}
+
+/// Parse a type definition or extension, starting at the type keyword
+///
+/// The body of an extension may be left out if it has directives.
+fn parse_type_definition<'a>(
+    parser: &mut Parser<'a>,
+    start_pos: SourcePosition,
+    description: Option<Spanning<String>>,
+    is_extension: bool,
+) -> ParseResult<'a, TypeDefinition<'a>> {
+    let Spanning { item: keyword, .. } = parser.next()?;
+    let name = parser.expect_name()?;
+
+    let interfaces = if keyword == Token::Name("type") {
+        parse_implements_interfaces(parser)?
+    } else {
+        None
+    };
+
+    let directives = parse_directives(parser)?;
+    let mut end_pos = interfaces
+        .as_ref()
+        .map(|s| &s.end)
+        .or_else(|| directives.as_ref().map(|s| &s.end))
+        .unwrap_or(&name.end)
+        .clone();
+
+    // Extensions need to add something to the extended type
+    let body_required = !is_extension || (interfaces.is_none() && directives.is_none());
+
+    let definition = match keyword {
+        Token::Name("scalar") => {
+            if is_extension && directives.is_none() {
+                return Err(parser.next()?.map(ParseError::UnexpectedToken));
+            }
+
+            TypeDefinition::Scalar(ScalarTypeDefinition {
+                description: description,
+                name: name,
+                directives: directives.map(|s| s.item),
+            })
+        }
+        Token::Name("type") | Token::Name("interface") => {
+            let fields = parse_definition_body(
+                parser,
+                body_required,
+                &Token::CurlyOpen,
+                parse_field_definition,
+                &Token::CurlyClose,
+            )?;
+            if let Some(ref fields) = fields {
+                end_pos = fields.end.clone();
+            }
+            let fields = fields.map(|s| s.item).unwrap_or_default();
+
+            if keyword == Token::Name("type") {
+                TypeDefinition::Object(ObjectTypeDefinition {
+                    description: description,
+                    name: name,
+                    interfaces: interfaces.map(|s| s.item).unwrap_or_default(),
+                    directives: directives.map(|s| s.item),
+                    fields: fields,
+                })
+            } else {
+                TypeDefinition::Interface(InterfaceTypeDefinition {
+                    description: description,
+                    name: name,
+                    directives: directives.map(|s| s.item),
+                    fields: fields,
+                })
+            }
+        }
+        Token::Name("union") => {
+            let types = if body_required || parser.peek().item == Token::Equals {
+                let types = parse_union_member_types(parser)?;
+                end_pos = types.end.clone();
+                types.item
+            } else {
+                Vec::new()
+            };
+
+            TypeDefinition::Union(UnionTypeDefinition {
+                description: description,
+                name: name,
+                directives: directives.map(|s| s.item),
+                types: types,
+            })
+        }
+        Token::Name("enum") => {
+            let values = parse_definition_body(
+                parser,
+                body_required,
+                &Token::CurlyOpen,
+                parse_enum_value_definition,
+                &Token::CurlyClose,
+            )?;
+            if let Some(ref values) = values {
+                end_pos = values.end.clone();
+            }
+
+            TypeDefinition::Enum(EnumTypeDefinition {
+                description: description,
+                name: name,
+                directives: directives.map(|s| s.item),
+                values: values.map(|s| s.item).unwrap_or_default(),
+            })
+        }
+        Token::Name("input") => {
+            let fields = parse_definition_body(
+                parser,
+                body_required,
+                &Token::CurlyOpen,
+                parse_input_value_definition,
+                &Token::CurlyClose,
+            )?;
+            if let Some(ref fields) = fields {
+                end_pos = fields.end.clone();
+            }
+
+            TypeDefinition::InputObject(InputObjectTypeDefinition {
+                description: description,
+                name: name,
+                directives: directives.map(|s| s.item),
+                fields: fields.map(|s| s.item).unwrap_or_default(),
+            })
+        }
+        _ => panic!("Internal parser error"),
+    };
+
+    Ok(Spanning::start_end(&start_pos, &end_pos, definition))
+}
+
+fn parse_definition_body<'a, T, F>(
+    parser: &mut Parser<'a>,
+    required: bool,
+    opening: &Token,
+    item_parser: F,
+    closing: &Token,
+) -> OptionParseResult<'a, Vec<Spanning<T>>>
+where
+    T: ::std::fmt::Debug,
+    F: Fn(&mut Parser<'a>) -> ParseResult<'a, T>,
+{
+    if required || &parser.peek().item == opening {
+        Ok(Some(parser.delimited_nonempty_list(
+            opening,
+            item_parser,
+            closing,
+        )?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn parse_implements_interfaces<'a>(
+    parser: &mut Parser<'a>,
+) -> OptionParseResult<'a, Vec<Spanning<&'a str>>> {
+    let Spanning {
+        start: start_pos, ..
+    } = match parser.peek().item {
+        Token::Name("implements") => parser.next()?,
+        _ => return Ok(None),
+    };
+
+    parser.skip(&Token::Amp)?;
+    let mut interfaces = vec![parser.expect_name()?];
+    while parser.peek().item == Token::Amp {
+        parser.next()?;
+        interfaces.push(parser.expect_name()?);
+    }
+
+    let end_pos = interfaces[interfaces.len() - 1].end.clone();
+
+    Ok(Some(Spanning::start_end(&start_pos, &end_pos, interfaces)))
+}
+
+fn parse_union_member_types<'a>(parser: &mut Parser<'a>) -> ParseResult<'a, Vec<Spanning<&'a str>>> {
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::Equals)?;
+
+    parser.skip(&Token::Pipe)?;
+    let mut types = vec![parser.expect_name()?];
+    while parser.peek().item == Token::Pipe {
+        parser.next()?;
+        types.push(parser.expect_name()?);
+    }
+
+    let end_pos = types[types.len() - 1].end.clone();
+
+    Ok(Spanning::start_end(&start_pos, &end_pos, types))
+}
+
+fn parse_field_definition<'a>(parser: &mut Parser<'a>) -> ParseResult<'a, FieldDefinition<'a>> {
+    let description = parse_description(parser)?;
+    let name = parser.expect_name()?;
+    let arguments = parse_argument_definitions(parser)?;
+    parser.expect(&Token::Colon)?;
+    let field_type = parse_type(parser)?;
+    let directives = parse_directives(parser)?;
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(&name.start, |d| &d.start).clone(),
+        &directives
+            .as_ref()
+            .map_or(&field_type.end, |s| &s.end)
+            .clone(),
+        FieldDefinition {
+            description: description,
+            name: name,
+            arguments: arguments,
+            field_type: field_type,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_argument_definitions<'a>(
+    parser: &mut Parser<'a>,
+) -> OptionParseResult<'a, Vec<Spanning<InputValueDefinition<'a>>>> {
+    if parser.peek().item != Token::ParenOpen {
+        Ok(None)
+    } else {
+        Ok(Some(parser.delimited_nonempty_list(
+            &Token::ParenOpen,
+            parse_input_value_definition,
+            &Token::ParenClose,
+        )?))
+    }
+}
+
+fn parse_input_value_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<'a, InputValueDefinition<'a>> {
+    let description = parse_description(parser)?;
+    let name = parser.expect_name()?;
+    parser.expect(&Token::Colon)?;
+    let value_type = parse_type(parser)?;
+
+    let default_value = if parser.skip(&Token::Equals)?.is_some() {
+        Some(parse_value_literal(parser, true)?)
+    } else {
+        None
+    };
+
+    let directives = parse_directives(parser)?;
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(&name.start, |d| &d.start).clone(),
+        &directives
+            .as_ref()
+            .map(|s| &s.end)
+            .or_else(|| default_value.as_ref().map(|s| &s.end))
+            .unwrap_or(&value_type.end)
+            .clone(),
+        InputValueDefinition {
+            description: description,
+            name: name,
+            value_type: value_type,
+            default_value: default_value,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_enum_value_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<'a, EnumValueDefinition<'a>> {
+    let description = parse_description(parser)?;
+    let name = match parser.peek().item {
+        Token::Name("true") | Token::Name("false") | Token::Name("null") => {
+            return Err(parser.next()?.map(ParseError::UnexpectedToken))
+        }
+        _ => parser.expect_name()?,
+    };
+    let directives = parse_directives(parser)?;
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(&name.start, |d| &d.start).clone(),
+        &directives.as_ref().map_or(&name.end, |s| &s.end).clone(),
+        EnumValueDefinition {
+            description: description,
+            name: name,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_directive_definition<'a>(
+    parser: &mut Parser<'a>,
+    description: Option<Spanning<String>>,
+) -> ParseResult<'a, DirectiveDefinition<'a>> {
+    let Spanning {
+        start: keyword_start,
+        ..
+    } = parser.expect(&Token::Name("directive"))?;
+    parser.expect(&Token::At)?;
+    let name = parser.expect_name()?;
+    let arguments = parse_argument_definitions(parser)?;
+    let repeatable = parser.skip(&Token::Name("repeatable"))?.is_some();
+    parser.expect(&Token::Name("on"))?;
+
+    parser.skip(&Token::Pipe)?;
+    let mut locations = vec![parse_directive_location(parser)?];
+    while parser.peek().item == Token::Pipe {
+        parser.next()?;
+        locations.push(parse_directive_location(parser)?);
+    }
+
+    let end_pos = locations[locations.len() - 1].end.clone();
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(&keyword_start, |d| &d.start).clone(),
+        &end_pos,
+        DirectiveDefinition {
+            description: description,
+            name: name,
+            arguments: arguments,
+            repeatable: repeatable,
+            locations: locations,
+        },
+    ))
+}
+
+fn parse_directive_location<'a>(parser: &mut Parser<'a>) -> ParseResult<'a, &'a str> {
+    let location = parser.expect_name()?;
+
+    match location.item {
+        "QUERY"
+        | "MUTATION"
+        | "SUBSCRIPTION"
+        | "FIELD"
+        | "FRAGMENT_DEFINITION"
+        | "FRAGMENT_SPREAD"
+        | "INLINE_FRAGMENT"
+        | "VARIABLE_DEFINITION"
+        | "SCHEMA"
+        | "SCALAR"
+        | "OBJECT"
+        | "FIELD_DEFINITION"
+        | "ARGUMENT_DEFINITION"
+        | "INTERFACE"
+        | "UNION"
+        | "ENUM"
+        | "ENUM_VALUE"
+        | "INPUT_OBJECT"
+        | "INPUT_FIELD_DEFINITION" => Ok(location),
+        name => Err(location.map(|_| ParseError::UnexpectedToken(Token::Name(name)))),
+    }
+}
@@ -21,7 +21,7 @@ pub struct Lexer<'a> {
 #[allow(missing_docs)]
 pub enum Token<'a> {
     Name(&'a str),
-    Int(i32),
+    Int(i64),
     Float(f64),
     String(String),
     ExclamationMark,
@@ -37,6 +37,7 @@ pub enum Token<'a> {
     Equals,
     At,
     Pipe,
+    Amp,
     EndOfFile,
 }
 
@@ -310,6 +311,55 @@ impl<'a> Lexer<'a> {
         ))
     }
 
+    fn lookahead_is(&mut self, s: &str) -> bool {
+        match self.peek_char() {
+            Some((idx, _)) => self.source[idx..].starts_with(s),
+            None => false,
+        }
+    }
+
+    fn scan_block_string(&mut self) -> LexerResult<'a> {
+        let start_pos = self.position.clone();
+
+        for _ in 0..3 {
+            self.next_char();
+        }
+
+        let mut raw = String::new();
+
+        while let Some((_, ch)) = self.peek_char() {
+            if self.lookahead_is("\"\"\"") {
+                for _ in 0..3 {
+                    self.next_char();
+                }
+
+                return Ok(Spanning::start_end(
+                    &start_pos,
+                    &self.position,
+                    Token::String(block_string_value(&raw)),
+                ));
+            } else if self.lookahead_is("\\\"\"\"") {
+                for _ in 0..4 {
+                    self.next_char();
+                }
+                raw.push_str("\"\"\"");
+            } else if !is_source_char(ch) {
+                return Err(Spanning::zero_width(
+                    &self.position,
+                    LexerError::UnknownCharacterInString(ch),
+                ));
+            } else {
+                self.next_char();
+                raw.push(ch);
+            }
+        }
+
+        Err(Spanning::zero_width(
+            &self.position,
+            LexerError::UnterminatedString,
+        ))
+    }
+
     fn scan_escaped_unicode(
         &mut self,
         start_pos: &SourcePosition,
@@ -390,7 +440,7 @@ impl<'a> Lexer<'a> {
         }
 
         let mantissa = frac_part
-            .map(|f| f64::from(f))
+            .map(|f| f as f64)
             .map(|frac| {
                 if frac > 0f64 {
                     frac / 10f64.powf(frac.log10().floor() + 1f64)
@@ -400,23 +450,23 @@ impl<'a> Lexer<'a> {
             })
             .map(|m| if int_part < 0 { -m } else { m });
 
-        let exp = exp_part.map(|e| f64::from(e)).map(|e| 10f64.powf(e));
+        let exp = exp_part.map(|e| e as f64).map(|e| 10f64.powf(e));
 
         Ok(Spanning::start_end(
             &start_pos,
             &self.position,
             match (mantissa, exp) {
                 (None, None) => Token::Int(int_part),
-                (None, Some(exp)) => Token::Float((f64::from(int_part)) * exp),
-                (Some(mantissa), None) => Token::Float((f64::from(int_part)) + mantissa),
+                (None, Some(exp)) => Token::Float((int_part as f64) * exp),
+                (Some(mantissa), None) => Token::Float((int_part as f64) + mantissa),
                 (Some(mantissa), Some(exp)) => {
-                    Token::Float(((f64::from(int_part)) + mantissa) * exp)
+                    Token::Float(((int_part as f64) + mantissa) * exp)
                 }
             },
         ))
     }
 
-    fn scan_integer_part(&mut self) -> Result<i32, Spanning<LexerError>> {
+    fn scan_integer_part(&mut self) -> Result<i64, Spanning<LexerError>> {
         let is_negative = {
             let (_, init_ch) = self.peek_char().ok_or(Spanning::zero_width(
                 &self.position,
@@ -451,7 +501,7 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn scan_digits(&mut self) -> Result<i32, Spanning<LexerError>> {
+    fn scan_digits(&mut self) -> Result<i64, Spanning<LexerError>> {
         let start_pos = self.position.clone();
         let (start_idx, ch) = self.peek_char().ok_or(Spanning::zero_width(
             &self.position,
@@ -475,7 +525,7 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        i32::from_str_radix(&self.source[start_idx..end_idx + 1], 10)
+        i64::from_str_radix(&self.source[start_idx..end_idx + 1], 10)
             .map_err(|_| Spanning::zero_width(&start_pos, LexerError::InvalidNumber))
     }
 }
@@ -505,8 +555,13 @@ impl<'a> Iterator for Lexer<'a> {
             Some('=') => Ok(self.emit_single_char(Token::Equals)),
             Some('@') => Ok(self.emit_single_char(Token::At)),
             Some('|') => Ok(self.emit_single_char(Token::Pipe)),
+            Some('&') => Ok(self.emit_single_char(Token::Amp)),
             Some('.') => self.scan_ellipsis(),
-            Some('"') => self.scan_string(),
+            Some('"') => if self.lookahead_is("\"\"\"") {
+                self.scan_block_string()
+            } else {
+                self.scan_string()
+            },
             Some(ch) => if is_number_start(ch) {
                 self.scan_number()
             } else if is_name_start(ch) {
@@ -547,11 +602,52 @@ impl<'a> fmt::Display for Token<'a> {
             Token::Equals => write!(f, "="),
             Token::At => write!(f, "@"),
             Token::Pipe => write!(f, "|"),
+            Token::Amp => write!(f, "&"),
             Token::EndOfFile => write!(f, "End of file"),
         }
     }
 }
 
+/// Remove the common indentation and the leading and trailing blank lines of
+/// a block string
+fn block_string_value(raw: &str) -> String {
+    let lines = raw.split("\r\n")
+        .flat_map(|l| l.split(|c| c == '\n' || c == '\r'))
+        .collect::<Vec<_>>();
+
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            let indent = line.chars().take_while(|&c| c == ' ' || c == '\t').count();
+            if indent < line.len() {
+                Some(indent)
+            } else {
+                None
+            }
+        })
+        .min()
+        .unwrap_or(0);
+
+    let lines = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.len() < common_indent {
+                *line
+            } else {
+                &line[common_indent..]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let is_blank = |line: &&str| line.chars().all(|c| c == ' ' || c == '\t');
+    let start = lines.iter().position(|l| !is_blank(l)).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|l| !is_blank(l)).map_or(start, |i| i + 1);
+
+    lines[start..end].join("\n")
+}
+
 fn is_source_char(c: char) -> bool {
     c == '\t' || c == '\n' || c == '\r' || c >= ' '
 }
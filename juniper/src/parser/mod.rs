@@ -10,6 +10,17 @@ mod value;
 mod tests;
 
 pub use self::document::parse_document_source;
+pub(crate) use self::document::parse_type;
+pub(crate) use self::value::parse_value_literal;
+
+pub use ast::{
+    Arguments, Definition, Directive, DirectiveDefinition, Document, EnumTypeDefinition,
+    EnumValueDefinition, Field, FieldDefinition, Fragment, FragmentSpread, InlineFragment,
+    InputObjectTypeDefinition, InputValueDefinition, InterfaceTypeDefinition,
+    ObjectTypeDefinition, Operation, OperationType, ScalarTypeDefinition, SchemaDefinition,
+    TypeDefinition, TypeSystemDefinition, UnionTypeDefinition, VariableDefinition,
+    VariableDefinitions,
+};
 
 pub use self::lexer::{Lexer, LexerError, Token};
 pub use self::parser::{OptionParseResult, ParseError, ParseResult, Parser, UnlocatedParseResult};
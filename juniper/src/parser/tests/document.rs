@@ -1,5 +1,10 @@
+use std::borrow::Cow;
+
 use ast::{
-    Arguments, Definition, Document, Field, InputValue, Operation, OperationType, Selection,
+    Arguments, Definition, Directive, DirectiveDefinition, Document, EnumTypeDefinition,
+    EnumValueDefinition, Field, FieldDefinition, InputValue, InputValueDefinition,
+    ObjectTypeDefinition, Operation, OperationType, SchemaDefinition, Selection, Type,
+    TypeDefinition, TypeSystemDefinition, UnionTypeDefinition, VariableDefinition,
 };
 use parser::document::parse_document_source;
 use parser::{ParseError, SourcePosition, Spanning, Token};
@@ -132,3 +137,411 @@ fn errors() {
         )
     );
 }
+
+#[test]
+fn subscription_operation() {
+    assert_eq!(
+        parse_document("subscription Events { newEvent }"),
+        vec![Definition::Operation(Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(32, 0, 32),
+            Operation {
+                operation_type: OperationType::Subscription,
+                name: Some(Spanning::start_end(
+                    &SourcePosition::new(13, 0, 13),
+                    &SourcePosition::new(19, 0, 19),
+                    "Events",
+                )),
+                variable_definitions: None,
+                directives: None,
+                selection_set: vec![Selection::Field(Spanning::start_end(
+                    &SourcePosition::new(22, 0, 22),
+                    &SourcePosition::new(30, 0, 30),
+                    Field {
+                        alias: None,
+                        name: Spanning::start_end(
+                            &SourcePosition::new(22, 0, 22),
+                            &SourcePosition::new(30, 0, 30),
+                            "newEvent",
+                        ),
+                        arguments: None,
+                        directives: None,
+                        selection_set: None,
+                    },
+                ))],
+            },
+        ))]
+    );
+}
+
+#[test]
+fn object_type_definition() {
+    assert_eq!(
+        parse_document(
+            r#""A dog" type Dog implements Pet & Named @key { name(full: Boolean = true): String! }"#
+        ),
+        vec![Definition::TypeSystem(TypeSystemDefinition::Type(
+            Spanning::start_end(
+                &SourcePosition::new(0, 0, 0),
+                &SourcePosition::new(84, 0, 84),
+                TypeDefinition::Object(ObjectTypeDefinition {
+                    description: Some(Spanning::start_end(
+                        &SourcePosition::new(0, 0, 0),
+                        &SourcePosition::new(7, 0, 7),
+                        "A dog".to_owned(),
+                    )),
+                    name: Spanning::start_end(
+                        &SourcePosition::new(13, 0, 13),
+                        &SourcePosition::new(16, 0, 16),
+                        "Dog",
+                    ),
+                    interfaces: vec![
+                        Spanning::start_end(
+                            &SourcePosition::new(28, 0, 28),
+                            &SourcePosition::new(31, 0, 31),
+                            "Pet",
+                        ),
+                        Spanning::start_end(
+                            &SourcePosition::new(34, 0, 34),
+                            &SourcePosition::new(39, 0, 39),
+                            "Named",
+                        ),
+                    ],
+                    directives: Some(vec![Spanning::start_end(
+                        &SourcePosition::new(40, 0, 40),
+                        &SourcePosition::new(44, 0, 44),
+                        Directive {
+                            name: Spanning::start_end(
+                                &SourcePosition::new(41, 0, 41),
+                                &SourcePosition::new(44, 0, 44),
+                                "key",
+                            ),
+                            arguments: None,
+                        },
+                    )]),
+                    fields: vec![Spanning::start_end(
+                        &SourcePosition::new(47, 0, 47),
+                        &SourcePosition::new(82, 0, 82),
+                        FieldDefinition {
+                            description: None,
+                            name: Spanning::start_end(
+                                &SourcePosition::new(47, 0, 47),
+                                &SourcePosition::new(51, 0, 51),
+                                "name",
+                            ),
+                            arguments: Some(Spanning::start_end(
+                                &SourcePosition::new(51, 0, 51),
+                                &SourcePosition::new(73, 0, 73),
+                                vec![Spanning::start_end(
+                                    &SourcePosition::new(52, 0, 52),
+                                    &SourcePosition::new(72, 0, 72),
+                                    InputValueDefinition {
+                                        description: None,
+                                        name: Spanning::start_end(
+                                            &SourcePosition::new(52, 0, 52),
+                                            &SourcePosition::new(56, 0, 56),
+                                            "full",
+                                        ),
+                                        value_type: Spanning::start_end(
+                                            &SourcePosition::new(58, 0, 58),
+                                            &SourcePosition::new(65, 0, 65),
+                                            Type::Named(Cow::Borrowed("Boolean")),
+                                        ),
+                                        default_value: Some(Spanning::start_end(
+                                            &SourcePosition::new(68, 0, 68),
+                                            &SourcePosition::new(72, 0, 72),
+                                            InputValue::boolean(true),
+                                        )),
+                                        directives: None,
+                                    },
+                                )],
+                            )),
+                            field_type: Spanning::start_end(
+                                &SourcePosition::new(75, 0, 75),
+                                &SourcePosition::new(82, 0, 82),
+                                Type::NonNullNamed(Cow::Borrowed("String")),
+                            ),
+                            directives: None,
+                        },
+                    )],
+                }),
+            )
+        ))]
+    );
+}
+
+#[test]
+fn type_system_definitions() {
+    let doc = parse_document(
+        r#"
+        schema @api {
+          query: Query
+          mutation: Mutation
+        }
+
+        """
+        A date and time, as an
+          ISO 8601 string
+        """
+        scalar DateTime
+
+        interface Pet { name: String }
+
+        union SearchResult = | Dog | Cat
+
+        enum Color {
+          "The color red"
+          RED
+          GREEN @deprecated
+        }
+
+        input Filter { color: Color = RED, limit: Int }
+
+        directive @key(fields: [String!]) on | OBJECT | INTERFACE
+        "#,
+    );
+
+    assert_eq!(doc.len(), 7);
+
+    match doc[0] {
+        Definition::TypeSystem(TypeSystemDefinition::Schema(ref def)) => {
+            assert_eq!(def.start, SourcePosition::new(9, 1, 8));
+            assert_eq!(def.end, SourcePosition::new(84, 4, 9));
+            assert_eq!(
+                def.item
+                    .operation_types
+                    .iter()
+                    .map(|&(ref op, ref name)| (op.item.clone(), name.item))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (OperationType::Query, "Query"),
+                    (OperationType::Mutation, "Mutation"),
+                ]
+            );
+        }
+        ref def => panic!("Expected schema definition, got {:#?}", def),
+    }
+
+    match doc[1] {
+        Definition::TypeSystem(TypeSystemDefinition::Type(ref def)) => {
+            assert_eq!(def.start, SourcePosition::new(94, 6, 8));
+            assert_eq!(def.item.name().item, "DateTime");
+            assert_eq!(
+                def.item.description(),
+                Some("A date and time, as an\n  ISO 8601 string")
+            );
+        }
+        ref def => panic!("Expected scalar definition, got {:#?}", def),
+    }
+
+    match doc[3] {
+        Definition::TypeSystem(TypeSystemDefinition::Type(Spanning {
+            item: TypeDefinition::Union(UnionTypeDefinition { ref types, .. }),
+            ..
+        })) => assert_eq!(
+            types.iter().map(|t| t.item).collect::<Vec<_>>(),
+            vec!["Dog", "Cat"]
+        ),
+        ref def => panic!("Expected union definition, got {:#?}", def),
+    }
+
+    match doc[4] {
+        Definition::TypeSystem(TypeSystemDefinition::Type(Spanning {
+            item: TypeDefinition::Enum(EnumTypeDefinition { ref values, .. }),
+            ..
+        })) => {
+            assert_eq!(values.len(), 2);
+            match values[0].item {
+                EnumValueDefinition {
+                    description: Some(ref description),
+                    name: Spanning { item: "RED", .. },
+                    directives: None,
+                } => assert_eq!(description.item, "The color red"),
+                ref value => panic!("Unexpected enum value {:#?}", value),
+            }
+            assert!(values[1].item.directives.is_some());
+        }
+        ref def => panic!("Expected enum definition, got {:#?}", def),
+    }
+
+    match doc[6] {
+        Definition::TypeSystem(TypeSystemDefinition::Directive(Spanning {
+            item:
+                DirectiveDefinition {
+                    ref name,
+                    ref arguments,
+                    ref locations,
+                    ..
+                },
+            ..
+        })) => {
+            assert_eq!(name.item, "key");
+            assert_eq!(arguments.as_ref().map(|a| a.item.len()), Some(1));
+            assert_eq!(
+                locations.iter().map(|l| l.item).collect::<Vec<_>>(),
+                vec!["OBJECT", "INTERFACE"]
+            );
+        }
+        ref def => panic!("Expected directive definition, got {:#?}", def),
+    }
+}
+
+#[test]
+fn type_system_extensions() {
+    assert_eq!(
+        parse_document("extend schema @api extend type Dog @key"),
+        vec![
+            Definition::TypeSystem(TypeSystemDefinition::SchemaExtension(
+                Spanning::start_end(
+                    &SourcePosition::new(0, 0, 0),
+                    &SourcePosition::new(18, 0, 18),
+                    SchemaDefinition {
+                        directives: Some(vec![Spanning::start_end(
+                            &SourcePosition::new(14, 0, 14),
+                            &SourcePosition::new(18, 0, 18),
+                            Directive {
+                                name: Spanning::start_end(
+                                    &SourcePosition::new(15, 0, 15),
+                                    &SourcePosition::new(18, 0, 18),
+                                    "api",
+                                ),
+                                arguments: None,
+                            },
+                        )]),
+                        operation_types: vec![],
+                    },
+                )
+            )),
+            Definition::TypeSystem(TypeSystemDefinition::TypeExtension(
+                Spanning::start_end(
+                    &SourcePosition::new(19, 0, 19),
+                    &SourcePosition::new(39, 0, 39),
+                    TypeDefinition::Object(ObjectTypeDefinition {
+                        description: None,
+                        name: Spanning::start_end(
+                            &SourcePosition::new(31, 0, 31),
+                            &SourcePosition::new(34, 0, 34),
+                            "Dog",
+                        ),
+                        interfaces: vec![],
+                        directives: Some(vec![Spanning::start_end(
+                            &SourcePosition::new(35, 0, 35),
+                            &SourcePosition::new(39, 0, 39),
+                            Directive {
+                                name: Spanning::start_end(
+                                    &SourcePosition::new(36, 0, 36),
+                                    &SourcePosition::new(39, 0, 39),
+                                    "key",
+                                ),
+                                arguments: None,
+                            },
+                        )]),
+                        fields: vec![],
+                    }),
+                )
+            )),
+        ]
+    );
+
+    let names = parse_document("extend union Pet = Fish extend input Filter { name: String }")
+        .iter()
+        .map(|def| match *def {
+            Definition::TypeSystem(TypeSystemDefinition::TypeExtension(ref def)) => {
+                def.item.name().item
+            }
+            ref def => panic!("Expected type extension, got {:#?}", def),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Pet", "Filter"]);
+}
+
+#[test]
+fn variable_definition_directives() {
+    match parse_document("query ($id: ID = 1 @deprecated, $name: String @a @b) { id }")[0] {
+        Definition::Operation(Spanning {
+            item:
+                Operation {
+                    variable_definitions: Some(ref defs),
+                    ..
+                },
+            ..
+        }) => {
+            let directive_names = defs.item
+                .items
+                .iter()
+                .map(|&(_, VariableDefinition { ref directives, .. })| {
+                    directives
+                        .iter()
+                        .flat_map(|d| d.iter().map(|d| d.item.name.item))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(directive_names, vec![vec!["deprecated"], vec!["a", "b"]]);
+            assert_eq!(defs.item.items[1].0.item, "name");
+        }
+        ref def => panic!("Expected operation, got {:#?}", def),
+    }
+}
+
+#[test]
+fn repeatable_directive_definition() {
+    let repeatable = |source| match parse_document(source)[0] {
+        Definition::TypeSystem(TypeSystemDefinition::Directive(Spanning {
+            item: DirectiveDefinition { repeatable, .. },
+            ..
+        })) => repeatable,
+        ref def => panic!("Expected directive definition, got {:#?}", def),
+    };
+
+    assert!(repeatable("directive @tag(name: String) repeatable on OBJECT | FIELD_DEFINITION"));
+    assert!(!repeatable("directive @key on OBJECT"));
+    assert!(!repeatable("directive @key on VARIABLE_DEFINITION"));
+}
+
+#[test]
+fn type_system_errors() {
+    assert_eq!(
+        parse_document_error("type Dog"),
+        Spanning::zero_width(
+            &SourcePosition::new(8, 0, 8),
+            ParseError::UnexpectedEndOfFile
+        )
+    );
+
+    assert_eq!(
+        parse_document_error("extend scalar Date extend type Dog"),
+        Spanning::start_end(
+            &SourcePosition::new(19, 0, 19),
+            &SourcePosition::new(25, 0, 25),
+            ParseError::UnexpectedToken(Token::Name("extend"))
+        )
+    );
+
+    assert_eq!(
+        parse_document_error("enum Bool { true false }"),
+        Spanning::start_end(
+            &SourcePosition::new(12, 0, 12),
+            &SourcePosition::new(16, 0, 16),
+            ParseError::UnexpectedToken(Token::Name("true"))
+        )
+    );
+
+    assert_eq!(
+        parse_document_error("directive @key on OBJECT | TYPE"),
+        Spanning::start_end(
+            &SourcePosition::new(27, 0, 27),
+            &SourcePosition::new(31, 0, 31),
+            ParseError::UnexpectedToken(Token::Name("TYPE"))
+        )
+    );
+
+    assert_eq!(
+        parse_document_error(r#""Schemas have no description" schema { query: Query }"#),
+        Spanning::start_end(
+            &SourcePosition::new(30, 0, 30),
+            &SourcePosition::new(36, 0, 36),
+            ParseError::UnexpectedToken(Token::Name("schema"))
+        )
+    );
+}
@@ -321,6 +321,73 @@ fn string_errors() {
     );
 }
 
+#[test]
+fn block_strings() {
+    assert_eq!(
+        tokenize_single(r#""""simple""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(12, 0, 12),
+            Token::String("simple".to_owned())
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""contains " quote""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(22, 0, 22),
+            Token::String("contains \" quote".to_owned())
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""contains \"""triple quote""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(31, 0, 31),
+            Token::String("contains \"\"\"triple quote".to_owned())
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""no \n escape \u1234""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(25, 0, 25),
+            Token::String("no \\n escape \\u1234".to_owned())
+        )
+    );
+
+    assert_eq!(
+        tokenize_single("\"\"\"\n\n    spans\n      multiple\n    lines\n\n  \"\"\""),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(46, 6, 5),
+            Token::String("spans\n  multiple\nlines".to_owned())
+        )
+    );
+}
+
+#[test]
+fn block_string_errors() {
+    assert_eq!(
+        tokenize_error("\"\"\"no end quote\""),
+        Spanning::zero_width(
+            &SourcePosition::new(16, 0, 16),
+            LexerError::UnterminatedString
+        )
+    );
+
+    assert_eq!(
+        tokenize_error("\"\"\"contains unescaped \u{0007} control char\"\"\""),
+        Spanning::zero_width(
+            &SourcePosition::new(22, 0, 22),
+            LexerError::UnknownCharacterInString('\u{0007}')
+        )
+    );
+}
+
 #[test]
 fn numbers() {
     fn assert_float_token_eq(
@@ -397,6 +464,15 @@ fn numbers() {
         )
     );
 
+    assert_eq!(
+        tokenize_single("9007199254740993"),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(16, 0, 16),
+            Token::Int(9007199254740993)
+        )
+    );
+
     assert_float_token_eq(
         "-4.123",
         SourcePosition::new(0, 0, 0),
@@ -612,6 +688,11 @@ fn punctuation() {
         tokenize_single("|"),
         Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::Pipe)
     );
+
+    assert_eq!(
+        tokenize_single("&"),
+        Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::Amp)
+    );
 }
 
 #[test]
@@ -683,4 +764,5 @@ fn display() {
     assert_eq!(format!("{}", Token::Equals), "=");
     assert_eq!(format!("{}", Token::At), "@");
     assert_eq!(format!("{}", Token::Pipe), "|");
+    assert_eq!(format!("{}", Token::Amp), "&");
 }
@@ -0,0 +1,222 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use linked_hash_map::LinkedHashMap;
+
+use ast::Document;
+use executor::{ExecutionError, QueryFuture, Variables};
+use extensions::RequestInstrumentation;
+use parser::parse_document_source;
+use schema::model::RootNode;
+use types::base::GraphQLType;
+use value::Value;
+use {GraphQLError, Query};
+
+/// A parsed and validated query
+///
+/// Created with `juniper::prepare`, a prepared query can be executed any
+/// number of times against the root node it was prepared for. Only the
+/// variables and the depth and complexity limits of the root node are checked
+/// again on each execution. Executing it against another root node validates
+/// the whole query again.
+///
+/// Prepared queries are cheap to clone, and can be shared between threads.
+#[derive(Clone)]
+pub struct PreparedQuery {
+    inner: Arc<PreparedDocument>,
+    root_node_id: usize,
+}
+
+self_cell!(
+    struct PreparedDocument {
+        owner: Box<str>,
+
+        #[covariant]
+        dependent: Document,
+    }
+);
+
+impl PreparedQuery {
+    pub(crate) fn parse(
+        document_source: &str,
+        root_node_id: usize,
+    ) -> Result<PreparedQuery, GraphQLError> {
+        let source: Box<str> = document_source.into();
+
+        let document = match PreparedDocument::try_new(source, |source| {
+            parse_document_source(source).map_err(|_| ())
+        }) {
+            Ok(document) => document,
+            // The error borrows from the copy of the source, so parse the
+            // original again to get one that can be returned
+            Err(()) => return Err(parse_document_source(document_source).unwrap_err().into()),
+        };
+
+        Ok(PreparedQuery {
+            inner: Arc::new(document),
+            root_node_id: root_node_id,
+        })
+    }
+
+    /// The text of the query
+    pub fn source(&self) -> &str {
+        self.inner.borrow_owner()
+    }
+
+    pub(crate) fn document<'a>(&'a self) -> &'a Document<'a> {
+        self.inner.borrow_dependent()
+    }
+
+    /// Whether the query was validated against the given root node
+    pub(crate) fn is_prepared_for<QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+    ) -> bool
+    where
+        QueryT: GraphQLType,
+        MutationT: GraphQLType,
+        SubscriptionT: GraphQLType,
+    {
+        self.root_node_id == root_node.id
+    }
+
+    /// Execute the query like `juniper::execute`
+    pub fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        operation_name: Option<&str>,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+        variables: &Variables,
+        context: &CtxT,
+    ) -> Result<(Value, Vec<ExecutionError>), GraphQLError<'a>>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+    {
+        let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+        ::execute_instrumented(
+            Query::Prepared(self.clone()),
+            operation_name,
+            root_node,
+            variables,
+            context,
+            &instrumentation,
+            None,
+        )
+    }
+
+    /// Execute the query like `juniper::execute_to_writer`
+    pub fn execute_to_writer<'a, CtxT, QueryT, MutationT, SubscriptionT, W>(
+        &'a self,
+        operation_name: Option<&str>,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT>,
+        variables: &Variables,
+        context: &CtxT,
+        writer: W,
+    ) -> Result<io::Result<Vec<ExecutionError>>, GraphQLError<'a>>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT>,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+        W: io::Write,
+    {
+        let instrumentation = RequestInstrumentation::new(&root_node.extensions);
+
+        ::execute_instrumented_to_writer(
+            Query::Prepared(self.clone()),
+            operation_name,
+            root_node,
+            variables,
+            context,
+            &instrumentation,
+            writer,
+        )
+    }
+
+    /// Execute the query like `juniper::execute_async`
+    pub fn execute_async<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        operation_name: Option<&str>,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT>,
+        variables: &Variables,
+        context: &'a CtxT,
+    ) -> Result<QueryFuture<'a>, GraphQLError<'a>>
+    where
+        QueryT: GraphQLType<Context = CtxT>,
+        MutationT: GraphQLType<Context = CtxT> + Sync,
+        MutationT::TypeInfo: Sync,
+        SubscriptionT: GraphQLType<Context = CtxT>,
+        CtxT: Sync,
+    {
+        ::execute_instrumented_async(
+            Query::Prepared(self.clone()),
+            operation_name,
+            root_node,
+            variables,
+            context,
+            RequestInstrumentation::new(&root_node.extensions),
+        )
+    }
+}
+
+/// Document of a query being executed
+///
+/// Either parsed for this execution only, or shared with a prepared query.
+pub(crate) enum QueryDocument<'a> {
+    Parsed(Document<'a>),
+    Prepared(PreparedQuery),
+}
+
+impl<'a> QueryDocument<'a> {
+    pub(crate) fn get<'b>(&'b self) -> &'b Document<'b>
+    where
+        'a: 'b,
+    {
+        match *self {
+            QueryDocument::Parsed(ref document) => document,
+            QueryDocument::Prepared(ref prepared) => prepared.document(),
+        }
+    }
+}
+
+/// Prepared queries of a root node, looked up by their text
+///
+/// Once the cache is full, the query that was used least recently makes room
+/// for a new one.
+pub(crate) struct QueryCache {
+    capacity: usize,
+    // Ordered from the least to the most recently used query
+    entries: Mutex<LinkedHashMap<String, PreparedQuery>>,
+}
+
+impl QueryCache {
+    pub(crate) fn new(capacity: usize) -> QueryCache {
+        QueryCache {
+            capacity: capacity,
+            entries: Mutex::new(LinkedHashMap::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, document_source: &str) -> Option<PreparedQuery> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get_refresh(document_source)
+            .map(|query| query.clone())
+    }
+
+    pub(crate) fn insert(&self, query: PreparedQuery) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.get_refresh(query.source()).is_none() && entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+
+        entries.insert(query.source().to_owned(), query);
+    }
+}
@@ -0,0 +1,768 @@
+//! Comparing schemas
+//!
+//! `SchemaType::diff` lists the changes between two versions of a schema and
+//! classifies each of them by how it affects existing clients. Schemas can
+//! also be compared as documents in the GraphQL schema definition language
+//! with `diff_schema_language`, or as the results of an introspection query
+//! with `diff_introspection`.
+//!
+//! ```rust
+//! # use juniper::diff::{diff_schema_language, Criticality, SchemaChangeKind};
+//! let changes = diff_schema_language(
+//!     "type Query { user(id: ID!): String }",
+//!     "type Query { user(id: ID!, name: String!): String }",
+//! ).expect("Invalid schema");
+//!
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].kind(), SchemaChangeKind::ArgumentAdded);
+//! assert_eq!(changes[0].criticality(), Criticality::Breaking);
+//! assert_eq!(changes[0].path(), "Query.user.name");
+//! ```
+
+use std::fmt;
+
+use ast::{InputValue, Type};
+use schema::dynamic::{DynamicSchema, DynamicSchemaError};
+use schema::meta::{Argument, EnumValue, Field, MetaType};
+use schema::model::{DirectiveType, SchemaType};
+use schema::printer::is_builtin_type;
+use value::Value;
+
+/// How a change affects existing clients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Criticality {
+    /// Queries that were valid before can fail or be rejected
+    Breaking,
+    /// Queries stay valid, but clients may receive values they can't handle
+    Dangerous,
+    /// Existing clients are not affected
+    Safe,
+}
+
+/// The kind of a change between two schemas
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SchemaChangeKind {
+    /// A type was removed
+    TypeRemoved,
+    /// A type was added
+    TypeAdded,
+    /// A type was replaced by a type of a different kind
+    TypeKindChanged,
+    /// The query, mutation or subscription type was changed or removed
+    RootTypeChanged,
+    /// A field was removed from an object or interface type
+    FieldRemoved,
+    /// A field was added to an object or interface type
+    FieldAdded,
+    /// The type of a field was changed
+    FieldTypeChanged,
+    /// A field was deprecated
+    FieldDeprecated,
+    /// An argument was removed from a field or directive
+    ArgumentRemoved,
+    /// An argument was added to a field or directive
+    ArgumentAdded,
+    /// The type of an argument was changed
+    ArgumentTypeChanged,
+    /// The default value of an argument was changed
+    ArgumentDefaultValueChanged,
+    /// A field was removed from an input object type
+    InputFieldRemoved,
+    /// A field was added to an input object type
+    InputFieldAdded,
+    /// The type of an input object field was changed
+    InputFieldTypeChanged,
+    /// The default value of an input object field was changed
+    InputFieldDefaultValueChanged,
+    /// A value was removed from an enum type
+    EnumValueRemoved,
+    /// A value was added to an enum type
+    EnumValueAdded,
+    /// A value of an enum type was deprecated
+    EnumValueDeprecated,
+    /// A member was removed from a union type
+    UnionMemberRemoved,
+    /// A member was added to a union type
+    UnionMemberAdded,
+    /// An object type no longer implements an interface
+    InterfaceRemoved,
+    /// An object type implements an additional interface
+    InterfaceAdded,
+    /// A directive was removed
+    DirectiveRemoved,
+    /// A directive was added
+    DirectiveAdded,
+    /// A directive can no longer be used at a location
+    DirectiveLocationRemoved,
+    /// A directive can be used at an additional location
+    DirectiveLocationAdded,
+}
+
+/// A change between two schemas
+///
+/// The path names the changed element, like `User`, `User.name`,
+/// `Query.user.id` for the argument of a field, `Episode.JEDI` for an enum
+/// value or `@cached` for a directive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaChange {
+    kind: SchemaChangeKind,
+    criticality: Criticality,
+    path: String,
+    message: String,
+}
+
+/// The query run to compare schemas with `diff_introspection`
+pub const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// Compare two documents in the GraphQL schema definition language
+///
+/// See `DynamicSchema::from_schema_language` for the supported documents.
+pub fn diff_schema_language(
+    old: &str,
+    new: &str,
+) -> Result<Vec<SchemaChange>, DynamicSchemaError> {
+    let old = DynamicSchema::<()>::from_schema_language(old)?.into_root_node()?;
+    let new = DynamicSchema::<()>::from_schema_language(new)?.into_root_node()?;
+
+    Ok(old.schema.diff(&new.schema))
+}
+
+/// Compare the results of two introspection queries
+///
+/// The results must contain the `__schema` field selected by
+/// `INTROSPECTION_QUERY`.
+pub fn diff_introspection(
+    old: &Value,
+    new: &Value,
+) -> Result<Vec<SchemaChange>, DynamicSchemaError> {
+    let old = DynamicSchema::<()>::from_introspection_result(old)?.into_root_node()?;
+    let new = DynamicSchema::<()>::from_introspection_result(new)?.into_root_node()?;
+
+    Ok(old.schema.diff(&new.schema))
+}
+
+impl<'a> SchemaType<'a> {
+    /// List the changes from this schema to a new version of it
+    ///
+    /// Built-in scalars and the introspection types are not compared. The
+    /// changes are ordered by the names of the changed types and directives.
+    pub fn diff(&self, new: &SchemaType) -> Vec<SchemaChange> {
+        let mut changes = Vec::new();
+
+        diff_root_type(
+            &mut changes,
+            "query",
+            self.concrete_query_type().name(),
+            new.concrete_query_type().name(),
+        );
+        diff_root_type(
+            &mut changes,
+            "mutation",
+            self.concrete_mutation_type().and_then(|t| t.name()),
+            new.concrete_mutation_type().and_then(|t| t.name()),
+        );
+        diff_root_type(
+            &mut changes,
+            "subscription",
+            self.concrete_subscription_type().and_then(|t| t.name()),
+            new.concrete_subscription_type().and_then(|t| t.name()),
+        );
+
+        let mut type_names = self
+            .concrete_type_list()
+            .into_iter()
+            .chain(new.concrete_type_list())
+            .filter_map(|t| t.name())
+            .filter(|n| !is_builtin_type(n))
+            .collect::<Vec<_>>();
+        type_names.sort();
+        type_names.dedup();
+
+        for name in type_names {
+            match (
+                self.concrete_type_by_name(name),
+                new.concrete_type_by_name(name),
+            ) {
+                (Some(old_type), Some(new_type)) => diff_type(&mut changes, old_type, new_type),
+                (Some(_), None) => changes.push(SchemaChange::new(
+                    SchemaChangeKind::TypeRemoved,
+                    Criticality::Breaking,
+                    name,
+                    format!("Type {} was removed", name),
+                )),
+                (None, Some(_)) => changes.push(SchemaChange::new(
+                    SchemaChangeKind::TypeAdded,
+                    Criticality::Safe,
+                    name,
+                    format!("Type {} was added", name),
+                )),
+                (None, None) => (),
+            }
+        }
+
+        let mut directive_names = self
+            .directive_list()
+            .into_iter()
+            .chain(new.directive_list())
+            .map(|d| d.name.as_str())
+            .collect::<Vec<_>>();
+        directive_names.sort();
+        directive_names.dedup();
+
+        for name in directive_names {
+            let path = format!("@{}", name);
+
+            match (self.directive_by_name(name), new.directive_by_name(name)) {
+                (Some(old_directive), Some(new_directive)) => {
+                    diff_directive(&mut changes, &path, old_directive, new_directive)
+                }
+                (Some(_), None) => changes.push(SchemaChange::new(
+                    SchemaChangeKind::DirectiveRemoved,
+                    Criticality::Breaking,
+                    &path,
+                    format!("Directive {} was removed", path),
+                )),
+                (None, Some(_)) => changes.push(SchemaChange::new(
+                    SchemaChangeKind::DirectiveAdded,
+                    Criticality::Safe,
+                    &path,
+                    format!("Directive {} was added", path),
+                )),
+                (None, None) => (),
+            }
+        }
+
+        changes
+    }
+}
+
+impl SchemaChange {
+    fn new(
+        kind: SchemaChangeKind,
+        criticality: Criticality,
+        path: &str,
+        message: String,
+    ) -> SchemaChange {
+        SchemaChange {
+            kind: kind,
+            criticality: criticality,
+            path: path.to_owned(),
+            message: message,
+        }
+    }
+
+    /// Access the kind of the change
+    pub fn kind(&self) -> SchemaChangeKind {
+        self.kind
+    }
+
+    /// Access the criticality of the change
+    pub fn criticality(&self) -> Criticality {
+        self.criticality
+    }
+
+    /// Access the path of the changed element
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Access the description of the change
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether existing queries can fail because of the change
+    pub fn is_breaking(&self) -> bool {
+        self.criticality == Criticality::Breaking
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.criticality, self.message)
+    }
+}
+
+fn diff_root_type(
+    changes: &mut Vec<SchemaChange>,
+    operation: &str,
+    old: Option<&str>,
+    new: Option<&str>,
+) {
+    match (old, new) {
+        (Some(old), Some(new)) if old != new => changes.push(SchemaChange::new(
+            SchemaChangeKind::RootTypeChanged,
+            Criticality::Breaking,
+            new,
+            format!("The {} type changed from {} to {}", operation, old, new),
+        )),
+        (Some(old), None) => changes.push(SchemaChange::new(
+            SchemaChangeKind::RootTypeChanged,
+            Criticality::Breaking,
+            old,
+            format!("The {} type {} was removed", operation, old),
+        )),
+        (None, Some(new)) => changes.push(SchemaChange::new(
+            SchemaChangeKind::RootTypeChanged,
+            Criticality::Safe,
+            new,
+            format!("The {} type {} was added", operation, new),
+        )),
+        _ => (),
+    }
+}
+
+fn diff_type(changes: &mut Vec<SchemaChange>, old: &MetaType, new: &MetaType) {
+    let name = old.name().unwrap_or("");
+
+    match (old, new) {
+        (&MetaType::Scalar(_), &MetaType::Scalar(_)) => (),
+        (&MetaType::Object(ref old), &MetaType::Object(ref new)) => {
+            diff_fields(changes, name, &old.fields, &new.fields);
+            diff_members(
+                changes,
+                name,
+                &old.interface_names,
+                &new.interface_names,
+                SchemaChangeKind::InterfaceRemoved,
+                SchemaChangeKind::InterfaceAdded,
+                "interface",
+            );
+        }
+        (&MetaType::Interface(ref old), &MetaType::Interface(ref new)) => {
+            diff_fields(changes, name, &old.fields, &new.fields)
+        }
+        (&MetaType::Union(ref old), &MetaType::Union(ref new)) => diff_members(
+            changes,
+            name,
+            &old.of_type_names,
+            &new.of_type_names,
+            SchemaChangeKind::UnionMemberRemoved,
+            SchemaChangeKind::UnionMemberAdded,
+            "member",
+        ),
+        (&MetaType::Enum(ref old), &MetaType::Enum(ref new)) => {
+            diff_enum_values(changes, name, &old.values, &new.values)
+        }
+        (&MetaType::InputObject(ref old), &MetaType::InputObject(ref new)) => {
+            diff_input_fields(changes, name, &old.input_fields, &new.input_fields)
+        }
+        _ => changes.push(SchemaChange::new(
+            SchemaChangeKind::TypeKindChanged,
+            Criticality::Breaking,
+            name,
+            format!(
+                "Type {} changed from {} to {}",
+                name,
+                kind_name(old),
+                kind_name(new)
+            ),
+        )),
+    }
+}
+
+fn diff_fields(changes: &mut Vec<SchemaChange>, type_name: &str, old: &[Field], new: &[Field]) {
+    for old_field in old {
+        let path = format!("{}.{}", type_name, old_field.name);
+
+        let new_field = match new.iter().find(|f| f.name == old_field.name) {
+            Some(f) => f,
+            None => {
+                changes.push(SchemaChange::new(
+                    SchemaChangeKind::FieldRemoved,
+                    Criticality::Breaking,
+                    &path,
+                    format!("Field {} was removed", path),
+                ));
+                continue;
+            }
+        };
+
+        if old_field.field_type != new_field.field_type {
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::FieldTypeChanged,
+                if is_safe_output_type_change(&old_field.field_type, &new_field.field_type) {
+                    Criticality::Safe
+                } else {
+                    Criticality::Breaking
+                },
+                &path,
+                format!(
+                    "Field {} changed type from {} to {}",
+                    path, old_field.field_type, new_field.field_type
+                ),
+            ));
+        }
+
+        if old_field.deprecation_reason.is_none() && new_field.deprecation_reason.is_some() {
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::FieldDeprecated,
+                Criticality::Safe,
+                &path,
+                format!("Field {} was deprecated", path),
+            ));
+        }
+
+        diff_arguments(
+            changes,
+            &path,
+            old_field.arguments.as_ref().map_or(&[], |a| &a[..]),
+            new_field.arguments.as_ref().map_or(&[], |a| &a[..]),
+        );
+    }
+
+    for new_field in new {
+        if !old.iter().any(|f| f.name == new_field.name) {
+            let path = format!("{}.{}", type_name, new_field.name);
+
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::FieldAdded,
+                Criticality::Safe,
+                &path,
+                format!("Field {} was added", path),
+            ));
+        }
+    }
+}
+
+fn diff_arguments(changes: &mut Vec<SchemaChange>, parent: &str, old: &[Argument], new: &[Argument]) {
+    for old_arg in old {
+        let path = format!("{}.{}", parent, old_arg.name);
+
+        match new.iter().find(|a| a.name == old_arg.name) {
+            Some(new_arg) => {
+                if old_arg.arg_type != new_arg.arg_type {
+                    changes.push(SchemaChange::new(
+                        SchemaChangeKind::ArgumentTypeChanged,
+                        input_type_change_criticality(&old_arg.arg_type, &new_arg.arg_type),
+                        &path,
+                        format!(
+                            "Argument {} changed type from {} to {}",
+                            path, old_arg.arg_type, new_arg.arg_type
+                        ),
+                    ));
+                }
+
+                if !default_values_eq(&old_arg.default_value, &new_arg.default_value) {
+                    changes.push(SchemaChange::new(
+                        SchemaChangeKind::ArgumentDefaultValueChanged,
+                        Criticality::Dangerous,
+                        &path,
+                        format!(
+                            "Argument {} changed its default value from {} to {}",
+                            path,
+                            print_default_value(&old_arg.default_value),
+                            print_default_value(&new_arg.default_value)
+                        ),
+                    ));
+                }
+            }
+            None => changes.push(SchemaChange::new(
+                SchemaChangeKind::ArgumentRemoved,
+                Criticality::Breaking,
+                &path,
+                format!("Argument {} was removed", path),
+            )),
+        }
+    }
+
+    for new_arg in new {
+        if !old.iter().any(|a| a.name == new_arg.name) {
+            let path = format!("{}.{}", parent, new_arg.name);
+            let (criticality, required) = if is_required(new_arg) {
+                (Criticality::Breaking, "Required argument")
+            } else {
+                (Criticality::Dangerous, "Optional argument")
+            };
+
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::ArgumentAdded,
+                criticality,
+                &path,
+                format!("{} {} was added", required, path),
+            ));
+        }
+    }
+}
+
+fn diff_input_fields(
+    changes: &mut Vec<SchemaChange>,
+    type_name: &str,
+    old: &[Argument],
+    new: &[Argument],
+) {
+    for old_field in old {
+        let path = format!("{}.{}", type_name, old_field.name);
+
+        match new.iter().find(|f| f.name == old_field.name) {
+            Some(new_field) => {
+                if old_field.arg_type != new_field.arg_type {
+                    changes.push(SchemaChange::new(
+                        SchemaChangeKind::InputFieldTypeChanged,
+                        input_type_change_criticality(&old_field.arg_type, &new_field.arg_type),
+                        &path,
+                        format!(
+                            "Input field {} changed type from {} to {}",
+                            path, old_field.arg_type, new_field.arg_type
+                        ),
+                    ));
+                }
+
+                if !default_values_eq(&old_field.default_value, &new_field.default_value) {
+                    changes.push(SchemaChange::new(
+                        SchemaChangeKind::InputFieldDefaultValueChanged,
+                        Criticality::Dangerous,
+                        &path,
+                        format!(
+                            "Input field {} changed its default value from {} to {}",
+                            path,
+                            print_default_value(&old_field.default_value),
+                            print_default_value(&new_field.default_value)
+                        ),
+                    ));
+                }
+            }
+            None => changes.push(SchemaChange::new(
+                SchemaChangeKind::InputFieldRemoved,
+                Criticality::Breaking,
+                &path,
+                format!("Input field {} was removed", path),
+            )),
+        }
+    }
+
+    for new_field in new {
+        if !old.iter().any(|f| f.name == new_field.name) {
+            let path = format!("{}.{}", type_name, new_field.name);
+            let (criticality, required) = if is_required(new_field) {
+                (Criticality::Breaking, "Required input field")
+            } else {
+                (Criticality::Dangerous, "Optional input field")
+            };
+
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::InputFieldAdded,
+                criticality,
+                &path,
+                format!("{} {} was added", required, path),
+            ));
+        }
+    }
+}
+
+fn diff_enum_values(
+    changes: &mut Vec<SchemaChange>,
+    type_name: &str,
+    old: &[EnumValue],
+    new: &[EnumValue],
+) {
+    for old_value in old {
+        let path = format!("{}.{}", type_name, old_value.name);
+
+        match new.iter().find(|v| v.name == old_value.name) {
+            Some(new_value) => {
+                if old_value.deprecation_reason.is_none() && new_value.deprecation_reason.is_some()
+                {
+                    changes.push(SchemaChange::new(
+                        SchemaChangeKind::EnumValueDeprecated,
+                        Criticality::Safe,
+                        &path,
+                        format!("Enum value {} was deprecated", path),
+                    ));
+                }
+            }
+            None => changes.push(SchemaChange::new(
+                SchemaChangeKind::EnumValueRemoved,
+                Criticality::Breaking,
+                &path,
+                format!("Enum value {} was removed", path),
+            )),
+        }
+    }
+
+    for new_value in new {
+        if !old.iter().any(|v| v.name == new_value.name) {
+            let path = format!("{}.{}", type_name, new_value.name);
+
+            changes.push(SchemaChange::new(
+                SchemaChangeKind::EnumValueAdded,
+                Criticality::Dangerous,
+                &path,
+                format!("Enum value {} was added", path),
+            ));
+        }
+    }
+}
+
+// Compares the interfaces of an object or the members of a union.
+fn diff_members(
+    changes: &mut Vec<SchemaChange>,
+    type_name: &str,
+    old: &[String],
+    new: &[String],
+    removed: SchemaChangeKind,
+    added: SchemaChangeKind,
+    member: &str,
+) {
+    for name in old.iter().filter(|n| !new.contains(n)) {
+        changes.push(SchemaChange::new(
+            removed,
+            Criticality::Breaking,
+            type_name,
+            format!("The {} {} was removed from {}", member, name, type_name),
+        ));
+    }
+
+    for name in new.iter().filter(|n| !old.contains(n)) {
+        changes.push(SchemaChange::new(
+            added,
+            Criticality::Dangerous,
+            type_name,
+            format!("The {} {} was added to {}", member, name, type_name),
+        ));
+    }
+}
+
+fn diff_directive(
+    changes: &mut Vec<SchemaChange>,
+    path: &str,
+    old: &DirectiveType,
+    new: &DirectiveType,
+) {
+    for location in old.locations.iter().filter(|l| !new.locations.contains(l)) {
+        changes.push(SchemaChange::new(
+            SchemaChangeKind::DirectiveLocationRemoved,
+            Criticality::Breaking,
+            path,
+            format!("Directive {} can no longer be used on {}", path, location),
+        ));
+    }
+
+    for location in new.locations.iter().filter(|l| !old.locations.contains(l)) {
+        changes.push(SchemaChange::new(
+            SchemaChangeKind::DirectiveLocationAdded,
+            Criticality::Safe,
+            path,
+            format!("Directive {} can be used on {}", path, location),
+        ));
+    }
+
+    diff_arguments(changes, path, &old.arguments, &new.arguments);
+}
+
+/// Output types may only become stricter, since clients already handle
+/// `null` values
+fn is_safe_output_type_change(old: &Type, new: &Type) -> bool {
+    match (old, new) {
+        (&Type::Named(ref old), &Type::Named(ref new))
+        | (&Type::Named(ref old), &Type::NonNullNamed(ref new))
+        | (&Type::NonNullNamed(ref old), &Type::NonNullNamed(ref new)) => old == new,
+        (&Type::List(ref old), &Type::List(ref new))
+        | (&Type::List(ref old), &Type::NonNullList(ref new))
+        | (&Type::NonNullList(ref old), &Type::NonNullList(ref new)) => {
+            is_safe_output_type_change(old, new)
+        }
+        _ => false,
+    }
+}
+
+/// Input types may only become less strict, since clients may not send all
+/// values
+fn input_type_change_criticality(old: &Type, new: &Type) -> Criticality {
+    if is_safe_output_type_change(new, old) {
+        Criticality::Safe
+    } else {
+        Criticality::Breaking
+    }
+}
+
+fn is_required(arg: &Argument) -> bool {
+    arg.arg_type.is_non_null() && arg.default_value.is_none()
+}
+
+fn default_values_eq(old: &Option<InputValue>, new: &Option<InputValue>) -> bool {
+    match (old, new) {
+        (&Some(ref old), &Some(ref new)) => old.unlocated_eq(new),
+        (&None, &None) => true,
+        _ => false,
+    }
+}
+
+fn print_default_value(value: &Option<InputValue>) -> String {
+    match *value {
+        Some(ref value) => value.to_string(),
+        None => "none".to_owned(),
+    }
+}
+
+fn kind_name(meta: &MetaType) -> &'static str {
+    match *meta {
+        MetaType::Scalar(_) => "scalar",
+        MetaType::Object(_) => "object",
+        MetaType::Interface(_) => "interface",
+        MetaType::Union(_) => "union",
+        MetaType::Enum(_) => "enum",
+        MetaType::InputObject(_) => "input object",
+        MetaType::List(_) | MetaType::Nullable(_) | MetaType::Placeholder(_) => "wrapper",
+    }
+}
@@ -0,0 +1,2031 @@
+//! Schemas defined at runtime
+//!
+//! A `DynamicSchema` describes its types with plain data instead of Rust
+//! types. It can be read from a document in the GraphQL schema definition
+//! language or from the result of an introspection query, or assembled from
+//! `DynamicType`s, and is turned into a `RootNode` that is executed like any
+//! other schema.
+//!
+//! Every value in a dynamic schema is a `Value`. Fields are resolved by
+//! closures registered with `DynamicSchema::field_resolver`, and all other
+//! fields by a single `DynamicResolver`. The default resolver,
+//! `PropertyResolver`, reads the field from the parent object, so a resolver
+//! returning an object resolves all fields below it.
+//!
+//! ```rust
+//! # #[macro_use] extern crate juniper;
+//! # use juniper::dynamic::DynamicSchema;
+//! # use juniper::{Value, Variables};
+//! # fn main() {
+//! let schema = DynamicSchema::<()>::from_schema_language(r#"
+//!     type Query {
+//!         user(id: ID!): User
+//!     }
+//!
+//!     type User {
+//!         id: ID!
+//!         name: String!
+//!     }
+//! "#)
+//!     .expect("Invalid schema")
+//!     .field_resolver("Query", "user", |_, args, _| {
+//!         let id = args.get::<String>("id").expect("Argument missing");
+//!         Ok(graphql_value!({ "id": id, "name": "Alice" }))
+//!     })
+//!     .into_root_node()
+//!     .expect("Invalid schema");
+//!
+//! let (res, _errors) = juniper::execute(
+//!     r#"{ user(id: "1") { name } }"#,
+//!     None,
+//!     &schema,
+//!     &Variables::new(),
+//!     &(),
+//! ).unwrap();
+//!
+//! assert_eq!(res, graphql_value!({ "user": { "name": "Alice" } }));
+//! # }
+//! ```
+//!
+//! Interfaces and unions are resolved into the object type named by the
+//! `__typename` field of the value, unless the resolver overrides
+//! `DynamicResolver::concrete_type_name`. Subscriptions are not supported.
+//!
+//! Custom directives can be applied to object types and field definitions.
+//! Their hooks are registered on the root node with
+//! `RootNode::directive_hook`.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use ast::{
+    self, Definition, Directive, InputValue, OperationType,
+    TypeDefinition as AstTypeDefinition, TypeSystemDefinition, Selection, Type,
+};
+use executor::{ExecutionFuture, ExecutionResult, Executor, FieldError, FieldResult, Registry};
+use futures::future;
+use parser::{
+    parse_document_source, parse_type, parse_value_literal, Lexer, Parser, SourcePosition,
+    Spanning, Token,
+};
+use schema::meta::{
+    AppliedDirective, Argument, EnumMeta, EnumValue, Field, InputObjectMeta, MetaType, ScalarMeta,
+};
+use schema::model::{DirectiveLocation, DirectiveType, RootNode};
+use types::base::{resolve_selection_set_into, Arguments, GraphQLType};
+use types::scalars::{EmptyMutation, ID};
+use value::{Object, Value};
+
+/// Root node of a dynamic schema
+pub type DynamicRootNode<'a, CtxT> = RootNode<'a, DynamicValue<CtxT>, DynamicValue<CtxT>>;
+
+type FieldResolverFn<CtxT> =
+    Fn(&Value, &Arguments, &Executor<CtxT>) -> FieldResult<Value> + Send + Sync;
+
+/// Schema whose types are defined at runtime
+///
+/// See the module documentation for an example.
+pub struct DynamicSchema<CtxT> {
+    types: Vec<DynamicType>,
+    directives: Vec<DynamicDirective>,
+    query_type_name: String,
+    mutation_type_name: Option<String>,
+    field_resolvers: HashMap<String, HashMap<String, Box<FieldResolverFn<CtxT>>>>,
+    resolver: Box<DynamicResolver<CtxT>>,
+    root_value: Value,
+}
+
+/// Resolver for the fields of a dynamic schema
+///
+/// The default implementations read fields and the `__typename` of abstract
+/// types from the parent object.
+pub trait DynamicResolver<CtxT>: Send + Sync {
+    /// Resolve a field of an object type
+    ///
+    /// `type_name` is the name of the concrete object type, even if the
+    /// field was selected on an interface.
+    #[allow(unused_variables)]
+    fn resolve_field(
+        &self,
+        type_name: &str,
+        field_name: &str,
+        parent: &Value,
+        arguments: &Arguments,
+        executor: &Executor<CtxT>,
+    ) -> FieldResult<Value> {
+        Ok(parent
+            .as_object_value()
+            .and_then(|o| o.get_field_value(field_name))
+            .cloned()
+            .unwrap_or_else(Value::null))
+    }
+
+    /// Name the object type of a value returned for an interface or union
+    #[allow(unused_variables)]
+    fn concrete_type_name(
+        &self,
+        abstract_type_name: &str,
+        value: &Value,
+        context: &CtxT,
+    ) -> Option<String> {
+        value
+            .as_object_value()
+            .and_then(|o| o.get_field_value("__typename"))
+            .and_then(|t| t.as_string_value())
+            .map(|t| t.to_owned())
+    }
+}
+
+/// Resolver reading fields from the parent object
+pub struct PropertyResolver;
+
+impl<CtxT> DynamicResolver<CtxT> for PropertyResolver {}
+
+/// Type definition of a dynamic schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicType {
+    name: String,
+    description: Option<String>,
+    kind: DynamicTypeKind,
+    directives: Vec<AppliedDirective>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum DynamicTypeKind {
+    Scalar,
+    Object {
+        interfaces: Vec<String>,
+        fields: Vec<DynamicField>,
+    },
+    Interface {
+        fields: Vec<DynamicField>,
+    },
+    Union {
+        types: Vec<String>,
+    },
+    Enum {
+        values: Vec<DynamicEnumValue>,
+    },
+    InputObject {
+        fields: Vec<DynamicArgument>,
+    },
+}
+
+/// Field of a dynamic object or interface type
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicField {
+    name: String,
+    description: Option<String>,
+    arguments: Vec<DynamicArgument>,
+    field_type: Type<'static>,
+    deprecation_reason: Option<String>,
+    directives: Vec<AppliedDirective>,
+}
+
+/// Argument of a dynamic field, or field of a dynamic input object type
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicArgument {
+    name: String,
+    description: Option<String>,
+    arg_type: Type<'static>,
+    default_value: Option<InputValue>,
+}
+
+/// Value of a dynamic enum type
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicEnumValue {
+    name: String,
+    description: Option<String>,
+    deprecation_reason: Option<String>,
+}
+
+/// Directive definition of a dynamic schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicDirective {
+    name: String,
+    description: Option<String>,
+    locations: Vec<DirectiveLocation>,
+    arguments: Vec<DynamicArgument>,
+    repeatable: bool,
+}
+
+/// Error building a dynamic schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicSchemaError {
+    message: String,
+    locations: Vec<SourcePosition>,
+}
+
+/// Value of any type in a dynamic schema
+pub struct DynamicValue<CtxT> {
+    value: Value,
+    phantom: PhantomData<CtxT>,
+}
+
+/// Type information of `DynamicValue`
+///
+/// Refers to a possibly wrapped type of a dynamic schema.
+pub struct DynamicTypeInfo<CtxT> {
+    schema: Arc<SchemaData<CtxT>>,
+    type_literal: Type<'static>,
+}
+
+struct SchemaData<CtxT> {
+    types: IndexMap<String, DynamicType>,
+    directives: Vec<DynamicDirective>,
+    field_resolvers: HashMap<String, HashMap<String, Box<FieldResolverFn<CtxT>>>>,
+    resolver: Box<DynamicResolver<CtxT>>,
+}
+
+const BUILT_IN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+const BUILT_IN_DIRECTIVES: &[&str] = &["skip", "include", "deprecated"];
+
+impl<CtxT> DynamicSchema<CtxT> {
+    /// Construct an empty schema with the given query type
+    ///
+    /// Types are added with `add_type`, and the query type is expected to be
+    /// one of them.
+    pub fn new(query_type_name: &str) -> DynamicSchema<CtxT> {
+        DynamicSchema {
+            types: Vec::new(),
+            directives: Vec::new(),
+            query_type_name: query_type_name.to_owned(),
+            mutation_type_name: None,
+            field_resolvers: HashMap::new(),
+            resolver: Box::new(PropertyResolver),
+            root_value: Value::object(Object::with_capacity(0)),
+        }
+    }
+
+    /// Read a schema from a document in the GraphQL schema definition
+    /// language
+    ///
+    /// Without a schema definition, the `Query` and `Mutation` types are used
+    /// as root types. Type extensions are merged into the types they extend.
+    /// Directives applied to object types and field definitions are kept,
+    /// and must be defined in the document.
+    pub fn from_schema_language(source: &str) -> Result<DynamicSchema<CtxT>, DynamicSchemaError> {
+        let document = parse_document_source(source).map_err(|e| {
+            DynamicSchemaError::new(format!("Syntax error: {}", e.item), &[e.start])
+        })?;
+
+        let mut schema = DynamicSchema::new("Query");
+        let mut has_schema_definition = false;
+        let mut extensions = Vec::new();
+
+        for definition in document {
+            let definition = match definition {
+                Definition::Operation(op) => {
+                    return Err(DynamicSchemaError::new(
+                        "Operations can not be part of a schema".to_owned(),
+                        &[op.start],
+                    ))
+                }
+                Definition::Fragment(f) => {
+                    return Err(DynamicSchemaError::new(
+                        "Fragments can not be part of a schema".to_owned(),
+                        &[f.start],
+                    ))
+                }
+                Definition::TypeSystem(definition) => definition,
+            };
+
+            match definition {
+                TypeSystemDefinition::Schema(def) | TypeSystemDefinition::SchemaExtension(def) => {
+                    has_schema_definition = true;
+
+                    for &(ref operation_type, ref type_name) in &def.item.operation_types {
+                        match operation_type.item {
+                            OperationType::Query => {
+                                schema.query_type_name = type_name.item.to_owned()
+                            }
+                            OperationType::Mutation => {
+                                schema.mutation_type_name = Some(type_name.item.to_owned())
+                            }
+                            OperationType::Subscription => {
+                                return Err(DynamicSchemaError::new(
+                                    "Subscriptions are not supported by dynamic schemas"
+                                        .to_owned(),
+                                    &[operation_type.start],
+                                ))
+                            }
+                        }
+                    }
+                }
+                TypeSystemDefinition::Type(def) => {
+                    schema.types.push(DynamicType::from_definition(&def.item))
+                }
+                TypeSystemDefinition::TypeExtension(def) => extensions.push(def),
+                TypeSystemDefinition::Directive(def) => {
+                    schema.directives.push(DynamicDirective::from_definition(&def))
+                }
+            }
+        }
+
+        if !has_schema_definition && schema.types.iter().any(|t| t.name == "Mutation") {
+            schema.mutation_type_name = Some("Mutation".to_owned());
+        }
+
+        for extension in extensions {
+            let extended = DynamicType::from_definition(&extension.item);
+            match schema.types.iter_mut().find(|t| t.name == extended.name) {
+                Some(base) => if !base.extend(extended) {
+                    return Err(DynamicSchemaError::new(
+                        format!(
+                            r#"Type "{}" can not be extended with a different kind"#,
+                            base.name
+                        ),
+                        &[extension.start],
+                    ));
+                },
+                None => {
+                    return Err(DynamicSchemaError::new(
+                        format!(r#"Can not extend unknown type "{}""#, extended.name),
+                        &[extension.start],
+                    ))
+                }
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Read a schema from the result of an introspection query
+    ///
+    /// The result must contain the `__schema` field with the fields selected
+    /// by `diff::INTROSPECTION_QUERY`. Built-in scalars and the introspection
+    /// types are skipped.
+    pub fn from_introspection_result(
+        result: &Value,
+    ) -> Result<DynamicSchema<CtxT>, DynamicSchemaError> {
+        let schema_value = introspected_field(result, "__schema")?;
+        let root_type_name = |field: &str| -> Result<Option<&str>, DynamicSchemaError> {
+            match *introspected_field(schema_value, field)? {
+                Value::Null => Ok(None),
+                ref root_type => introspected_string(root_type, "name"),
+            }
+        };
+
+        let mut schema = match root_type_name("queryType")? {
+            Some(name) => DynamicSchema::new(name),
+            None => return Err(error("Introspection result has no query type".to_owned())),
+        };
+        schema.mutation_type_name = root_type_name("mutationType")?.map(|n| n.to_owned());
+
+        if root_type_name("subscriptionType")?.is_some() {
+            return Err(error(
+                "Subscriptions are not supported by dynamic schemas".to_owned(),
+            ));
+        }
+
+        for type_value in introspected_list(schema_value, "types")? {
+            let name = introspected_name(type_value)?;
+
+            if !name.starts_with("__") && !BUILT_IN_SCALARS.contains(&name) {
+                schema
+                    .types
+                    .push(DynamicType::from_introspection(type_value)?);
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Add a type to the schema
+    pub fn add_type(mut self, dynamic_type: DynamicType) -> DynamicSchema<CtxT> {
+        self.types.push(dynamic_type);
+        self
+    }
+
+    /// Add a directive definition to the schema
+    pub fn add_directive(mut self, directive: DynamicDirective) -> DynamicSchema<CtxT> {
+        self.directives.push(directive);
+        self
+    }
+
+    /// Set the mutation type of the schema
+    pub fn mutation_type(mut self, type_name: &str) -> DynamicSchema<CtxT> {
+        self.mutation_type_name = Some(type_name.to_owned());
+        self
+    }
+
+    /// Resolve a field of an object type with a closure
+    ///
+    /// The closure receives the parent object, the arguments of the field and
+    /// the executor. Fields without a closure are resolved by the
+    /// `DynamicResolver` of the schema.
+    pub fn field_resolver<F>(
+        mut self,
+        type_name: &str,
+        field_name: &str,
+        resolver: F,
+    ) -> DynamicSchema<CtxT>
+    where
+        F: Fn(&Value, &Arguments, &Executor<CtxT>) -> FieldResult<Value> + Send + Sync + 'static,
+    {
+        self.field_resolvers
+            .entry(type_name.to_owned())
+            .or_insert_with(HashMap::new)
+            .insert(field_name.to_owned(), Box::new(resolver));
+        self
+    }
+
+    /// Set the resolver of all fields without a closure
+    ///
+    /// Defaults to `PropertyResolver`.
+    pub fn resolver<R>(mut self, resolver: R) -> DynamicSchema<CtxT>
+    where
+        R: DynamicResolver<CtxT> + 'static,
+    {
+        self.resolver = Box::new(resolver);
+        self
+    }
+
+    /// Set the value the fields of the query and mutation types are resolved
+    /// on
+    ///
+    /// Defaults to an empty object.
+    pub fn root_value(mut self, value: Value) -> DynamicSchema<CtxT> {
+        self.root_value = value;
+        self
+    }
+
+    /// Validate the schema and turn it into a root node
+    pub fn into_root_node<'a>(self) -> Result<DynamicRootNode<'a, CtxT>, DynamicSchemaError> {
+        let mut types = IndexMap::new();
+
+        for dynamic_type in self.types {
+            if BUILT_IN_SCALARS.contains(&dynamic_type.name.as_str()) {
+                return Err(DynamicSchemaError::new(
+                    format!(r#"Built-in type "{}" can not be redefined"#, dynamic_type.name),
+                    &[],
+                ));
+            }
+
+            if dynamic_type.name.starts_with("__") {
+                return Err(DynamicSchemaError::new(
+                    format!(
+                        r#"Type name "{}" is reserved for introspection"#,
+                        dynamic_type.name
+                    ),
+                    &[],
+                ));
+            }
+
+            if types.contains_key(&dynamic_type.name) {
+                return Err(DynamicSchemaError::new(
+                    format!(r#"Type "{}" is defined more than once"#, dynamic_type.name),
+                    &[],
+                ));
+            }
+
+            types.insert(dynamic_type.name.clone(), dynamic_type);
+        }
+
+        let data = SchemaData {
+            types: types,
+            directives: self.directives,
+            field_resolvers: self.field_resolvers,
+            resolver: self.resolver,
+        };
+
+        data.validate(&self.query_type_name, self.mutation_type_name.as_ref())?;
+
+        let data = Arc::new(data);
+        let query_info = DynamicTypeInfo::named(&data, self.query_type_name);
+        // Schemas without mutations use the name of `EmptyMutation`, which
+        // makes the schema leave out the mutation type
+        let mutation_info = DynamicTypeInfo::named(
+            &data,
+            self.mutation_type_name
+                .unwrap_or_else(|| "_EmptyMutation".to_owned()),
+        );
+
+        let mut root_node = RootNode::new_with_info(
+            DynamicValue::new(self.root_value.clone()),
+            DynamicValue::new(self.root_value),
+            query_info,
+            mutation_info,
+        );
+
+        for directive in &data.directives {
+            root_node = root_node.directive(directive.meta());
+        }
+
+        Ok(root_node)
+    }
+}
+
+impl DynamicType {
+    /// Construct a scalar type
+    ///
+    /// Values of custom scalars are passed through unchanged, and any input
+    /// value is accepted for them.
+    pub fn scalar(name: &str) -> DynamicType {
+        DynamicType::new(name, DynamicTypeKind::Scalar)
+    }
+
+    /// Construct an object type
+    pub fn object(name: &str, fields: Vec<DynamicField>) -> DynamicType {
+        DynamicType::new(
+            name,
+            DynamicTypeKind::Object {
+                interfaces: Vec::new(),
+                fields: fields,
+            },
+        )
+    }
+
+    /// Construct an interface type
+    pub fn interface(name: &str, fields: Vec<DynamicField>) -> DynamicType {
+        DynamicType::new(name, DynamicTypeKind::Interface { fields: fields })
+    }
+
+    /// Construct a union type
+    pub fn union(name: &str, types: &[&str]) -> DynamicType {
+        DynamicType::new(
+            name,
+            DynamicTypeKind::Union {
+                types: types.iter().map(|t| (*t).to_owned()).collect(),
+            },
+        )
+    }
+
+    /// Construct an enum type
+    pub fn enum_type(name: &str, values: Vec<DynamicEnumValue>) -> DynamicType {
+        DynamicType::new(name, DynamicTypeKind::Enum { values: values })
+    }
+
+    /// Construct an input object type
+    pub fn input_object(name: &str, fields: Vec<DynamicArgument>) -> DynamicType {
+        DynamicType::new(name, DynamicTypeKind::InputObject { fields: fields })
+    }
+
+    fn new(name: &str, kind: DynamicTypeKind) -> DynamicType {
+        DynamicType {
+            name: name.to_owned(),
+            description: None,
+            kind: kind,
+            directives: Vec::new(),
+        }
+    }
+
+    /// The name of the type
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the description of the type
+    ///
+    /// If a description was provided prior to calling this method, it will be overwritten.
+    pub fn description(mut self, description: &str) -> DynamicType {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Add interfaces implemented by an object type
+    ///
+    /// Panics if the type is not an object type.
+    pub fn interfaces(mut self, names: &[&str]) -> DynamicType {
+        match self.kind {
+            DynamicTypeKind::Object {
+                ref mut interfaces, ..
+            } => interfaces.extend(names.iter().map(|n| (*n).to_owned())),
+            _ => panic!("Only object types can implement interfaces"),
+        }
+        self
+    }
+
+    /// Apply a directive to an object type
+    ///
+    /// Panics if the type is not an object type.
+    pub fn directive(mut self, directive: AppliedDirective) -> DynamicType {
+        match self.kind {
+            DynamicTypeKind::Object { .. } => self.directives.push(directive),
+            _ => panic!("Directives can only be applied to object types"),
+        }
+        self
+    }
+
+    fn from_definition(def: &AstTypeDefinition) -> DynamicType {
+        let kind = match *def {
+            AstTypeDefinition::Scalar(_) => DynamicTypeKind::Scalar,
+            AstTypeDefinition::Object(ref d) => DynamicTypeKind::Object {
+                interfaces: d.interfaces.iter().map(|i| i.item.to_owned()).collect(),
+                fields: d.fields
+                    .iter()
+                    .map(|f| DynamicField::from_definition(&f.item))
+                    .collect(),
+            },
+            AstTypeDefinition::Interface(ref d) => DynamicTypeKind::Interface {
+                fields: d.fields
+                    .iter()
+                    .map(|f| DynamicField::from_definition(&f.item))
+                    .collect(),
+            },
+            AstTypeDefinition::Union(ref d) => DynamicTypeKind::Union {
+                types: d.types.iter().map(|t| t.item.to_owned()).collect(),
+            },
+            AstTypeDefinition::Enum(ref d) => DynamicTypeKind::Enum {
+                values: d.values
+                    .iter()
+                    .map(|v| DynamicEnumValue {
+                        name: v.item.name.item.to_owned(),
+                        description: v.item.description.as_ref().map(|d| d.item.clone()),
+                        deprecation_reason: deprecation_reason(&v.item.directives),
+                    })
+                    .collect(),
+            },
+            AstTypeDefinition::InputObject(ref d) => DynamicTypeKind::InputObject {
+                fields: d.fields
+                    .iter()
+                    .map(|f| DynamicArgument::from_definition(&f.item))
+                    .collect(),
+            },
+        };
+
+        let directives = match *def {
+            AstTypeDefinition::Object(ref d) => applied_directives(&d.directives),
+            _ => Vec::new(),
+        };
+
+        DynamicType {
+            name: def.name().item.to_owned(),
+            description: def.description().map(|d| d.to_owned()),
+            kind: kind,
+            directives: directives,
+        }
+    }
+
+    /// Merge an extension of the same kind into this type
+    fn extend(&mut self, extension: DynamicType) -> bool {
+        self.directives.extend(extension.directives);
+
+        match (&mut self.kind, extension.kind) {
+            (&mut DynamicTypeKind::Scalar, DynamicTypeKind::Scalar) => (),
+            (
+                &mut DynamicTypeKind::Object {
+                    ref mut interfaces,
+                    ref mut fields,
+                },
+                DynamicTypeKind::Object {
+                    interfaces: new_interfaces,
+                    fields: new_fields,
+                },
+            ) => {
+                interfaces.extend(new_interfaces);
+                fields.extend(new_fields);
+            }
+            (
+                &mut DynamicTypeKind::Interface { ref mut fields },
+                DynamicTypeKind::Interface { fields: new_fields },
+            ) => fields.extend(new_fields),
+            (
+                &mut DynamicTypeKind::Union { ref mut types },
+                DynamicTypeKind::Union { types: new_types },
+            ) => types.extend(new_types),
+            (
+                &mut DynamicTypeKind::Enum { ref mut values },
+                DynamicTypeKind::Enum { values: new_values },
+            ) => values.extend(new_values),
+            (
+                &mut DynamicTypeKind::InputObject { ref mut fields },
+                DynamicTypeKind::InputObject { fields: new_fields },
+            ) => fields.extend(new_fields),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn from_introspection(value: &Value) -> Result<DynamicType, DynamicSchemaError> {
+        let name = introspected_name(value)?;
+        let kind = match introspected_string(value, "kind")? {
+            Some("SCALAR") => DynamicTypeKind::Scalar,
+            Some("OBJECT") => DynamicTypeKind::Object {
+                interfaces: introspected_list(value, "interfaces")?
+                    .iter()
+                    .map(|i| introspected_name(i).map(|n| n.to_owned()))
+                    .collect::<Result<_, _>>()?,
+                fields: introspected_list(value, "fields")?
+                    .iter()
+                    .map(DynamicField::from_introspection)
+                    .collect::<Result<_, _>>()?,
+            },
+            Some("INTERFACE") => DynamicTypeKind::Interface {
+                fields: introspected_list(value, "fields")?
+                    .iter()
+                    .map(DynamicField::from_introspection)
+                    .collect::<Result<_, _>>()?,
+            },
+            Some("UNION") => DynamicTypeKind::Union {
+                types: introspected_list(value, "possibleTypes")?
+                    .iter()
+                    .map(|t| introspected_name(t).map(|n| n.to_owned()))
+                    .collect::<Result<_, _>>()?,
+            },
+            Some("ENUM") => DynamicTypeKind::Enum {
+                values: introspected_list(value, "enumValues")?
+                    .iter()
+                    .map(DynamicEnumValue::from_introspection)
+                    .collect::<Result<_, _>>()?,
+            },
+            Some("INPUT_OBJECT") => DynamicTypeKind::InputObject {
+                fields: introspected_list(value, "inputFields")?
+                    .iter()
+                    .map(DynamicArgument::from_introspection)
+                    .collect::<Result<_, _>>()?,
+            },
+            kind => {
+                return Err(error(format!(
+                    r#"Type "{}" has the unknown kind {:?}"#,
+                    name, kind
+                )))
+            }
+        };
+
+        Ok(DynamicType {
+            name: name.to_owned(),
+            description: introspected_string(value, "description")?.map(|d| d.to_owned()),
+            kind: kind,
+            directives: Vec::new(),
+        })
+    }
+
+    fn fields(&self) -> Option<&[DynamicField]> {
+        match self.kind {
+            DynamicTypeKind::Object { ref fields, .. } | DynamicTypeKind::Interface { ref fields } => {
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+
+    fn field_by_name(&self, name: &str) -> Option<&DynamicField> {
+        self.fields()
+            .and_then(|fields| fields.iter().find(|f| f.name == name))
+    }
+}
+
+impl DynamicField {
+    /// Construct a field
+    ///
+    /// The type is written as in the schema language, e.g. `[String!]`.
+    /// Panics if it is not a valid type literal.
+    pub fn new(name: &str, field_type: &str) -> DynamicField {
+        DynamicField {
+            name: name.to_owned(),
+            description: None,
+            arguments: Vec::new(),
+            field_type: parse_type_literal(field_type),
+            deprecation_reason: None,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Set the description of the field
+    ///
+    /// If a description was provided prior to calling this method, it will be overwritten.
+    pub fn description(mut self, description: &str) -> DynamicField {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Add an argument to the field
+    pub fn argument(mut self, argument: DynamicArgument) -> DynamicField {
+        self.arguments.push(argument);
+        self
+    }
+
+    /// Set the deprecation reason
+    ///
+    /// This overwrites the deprecation reason if any was previously set.
+    pub fn deprecated(mut self, reason: &str) -> DynamicField {
+        self.deprecation_reason = Some(reason.to_owned());
+        self
+    }
+
+    /// Apply a directive to the field definition
+    pub fn directive(mut self, directive: AppliedDirective) -> DynamicField {
+        self.directives.push(directive);
+        self
+    }
+
+    fn from_definition(def: &ast::FieldDefinition) -> DynamicField {
+        DynamicField {
+            name: def.name.item.to_owned(),
+            description: def.description.as_ref().map(|d| d.item.clone()),
+            arguments: def.arguments
+                .as_ref()
+                .map(|args| {
+                    args.item
+                        .iter()
+                        .map(|a| DynamicArgument::from_definition(&a.item))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            field_type: owned_type(&def.field_type.item),
+            deprecation_reason: deprecation_reason(&def.directives),
+            directives: applied_directives(&def.directives),
+        }
+    }
+
+    fn from_introspection(value: &Value) -> Result<DynamicField, DynamicSchemaError> {
+        Ok(DynamicField {
+            name: introspected_name(value)?.to_owned(),
+            description: introspected_string(value, "description")?.map(|d| d.to_owned()),
+            arguments: introspected_list(value, "args")?
+                .iter()
+                .map(DynamicArgument::from_introspection)
+                .collect::<Result<_, _>>()?,
+            field_type: introspected_type(introspected_field(value, "type")?)?,
+            deprecation_reason: introspected_deprecation_reason(value)?,
+            directives: Vec::new(),
+        })
+    }
+
+    fn meta<'r, CtxT>(&self, info: &DynamicTypeInfo<CtxT>, registry: &mut Registry<'r>) -> Field<'r> {
+        let mut field = registry.field::<DynamicValue<CtxT>>(
+            &self.name,
+            &info.with_type(self.field_type.clone()),
+        );
+
+        if let Some(ref description) = self.description {
+            field = field.description(description);
+        }
+
+        for argument in &self.arguments {
+            field = field.argument(argument.meta(info, registry));
+        }
+
+        if let Some(ref reason) = self.deprecation_reason {
+            field = field.deprecated(reason);
+        }
+
+        for directive in &self.directives {
+            field = field.directive(directive.clone());
+        }
+
+        field
+    }
+}
+
+impl DynamicArgument {
+    /// Construct an argument or input object field
+    ///
+    /// The type is written as in the schema language, e.g. `[String!]`.
+    /// Panics if it is not a valid type literal.
+    pub fn new(name: &str, arg_type: &str) -> DynamicArgument {
+        DynamicArgument {
+            name: name.to_owned(),
+            description: None,
+            arg_type: parse_type_literal(arg_type),
+            default_value: None,
+        }
+    }
+
+    /// Set the description of the argument
+    ///
+    /// If a description was provided prior to calling this method, it will be overwritten.
+    pub fn description(mut self, description: &str) -> DynamicArgument {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Set the default value of the argument
+    ///
+    /// This overwrites the default value if any was previously set.
+    pub fn default_value(mut self, default_value: InputValue) -> DynamicArgument {
+        self.default_value = Some(default_value);
+        self
+    }
+
+    fn from_definition(def: &ast::InputValueDefinition) -> DynamicArgument {
+        DynamicArgument {
+            name: def.name.item.to_owned(),
+            description: def.description.as_ref().map(|d| d.item.clone()),
+            arg_type: owned_type(&def.value_type.item),
+            default_value: def.default_value.as_ref().map(|v| v.item.clone()),
+        }
+    }
+
+    fn from_introspection(value: &Value) -> Result<DynamicArgument, DynamicSchemaError> {
+        let default_value = match introspected_string(value, "defaultValue")? {
+            Some(source) => Some(parse_default_value(source)?),
+            None => None,
+        };
+
+        Ok(DynamicArgument {
+            name: introspected_name(value)?.to_owned(),
+            description: introspected_string(value, "description")?.map(|d| d.to_owned()),
+            arg_type: introspected_type(introspected_field(value, "type")?)?,
+            default_value: default_value,
+        })
+    }
+
+    fn meta<'r, CtxT>(
+        &self,
+        info: &DynamicTypeInfo<CtxT>,
+        registry: &mut Registry<'r>,
+    ) -> Argument<'r> {
+        let arg_type =
+            registry.get_type::<DynamicValue<CtxT>>(&info.with_type(self.arg_type.clone()));
+        let mut argument = Argument::new(&self.name, arg_type);
+
+        if let Some(ref description) = self.description {
+            argument = argument.description(description);
+        }
+
+        if let Some(ref default_value) = self.default_value {
+            argument = argument.default_value(default_value.clone());
+        }
+
+        argument
+    }
+}
+
+impl DynamicEnumValue {
+    /// Construct an enum value
+    pub fn new(name: &str) -> DynamicEnumValue {
+        DynamicEnumValue {
+            name: name.to_owned(),
+            description: None,
+            deprecation_reason: None,
+        }
+    }
+
+    /// Set the description of the enum value
+    ///
+    /// If a description was provided prior to calling this method, it will be overwritten.
+    pub fn description(mut self, description: &str) -> DynamicEnumValue {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Set the deprecation reason
+    ///
+    /// This overwrites the deprecation reason if any was previously set.
+    pub fn deprecated(mut self, reason: &str) -> DynamicEnumValue {
+        self.deprecation_reason = Some(reason.to_owned());
+        self
+    }
+
+    fn from_introspection(value: &Value) -> Result<DynamicEnumValue, DynamicSchemaError> {
+        Ok(DynamicEnumValue {
+            name: introspected_name(value)?.to_owned(),
+            description: introspected_string(value, "description")?.map(|d| d.to_owned()),
+            deprecation_reason: introspected_deprecation_reason(value)?,
+        })
+    }
+}
+
+impl DynamicDirective {
+    /// Construct a directive that can be used at the given locations
+    pub fn new(name: &str, locations: &[DirectiveLocation]) -> DynamicDirective {
+        DynamicDirective {
+            name: name.to_owned(),
+            description: None,
+            locations: locations.to_vec(),
+            arguments: Vec::new(),
+            repeatable: false,
+        }
+    }
+
+    /// Set the description of the directive
+    ///
+    /// If a description was provided prior to calling this method, it will be overwritten.
+    pub fn description(mut self, description: &str) -> DynamicDirective {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Add an argument to the directive
+    pub fn argument(mut self, argument: DynamicArgument) -> DynamicDirective {
+        self.arguments.push(argument);
+        self
+    }
+
+    /// Allow the directive to be used more than once at the same location
+    pub fn repeatable(mut self) -> DynamicDirective {
+        self.repeatable = true;
+        self
+    }
+
+    fn from_definition(
+        def: &Spanning<ast::DirectiveDefinition>,
+    ) -> DynamicDirective {
+        // The parser only accepts the locations of the specification
+        let locations = def.item
+            .locations
+            .iter()
+            .map(|location| match location.item {
+                "QUERY" => DirectiveLocation::Query,
+                "MUTATION" => DirectiveLocation::Mutation,
+                "SUBSCRIPTION" => DirectiveLocation::Subscription,
+                "FIELD" => DirectiveLocation::Field,
+                "FRAGMENT_DEFINITION" => DirectiveLocation::FragmentDefinition,
+                "FRAGMENT_SPREAD" => DirectiveLocation::FragmentSpread,
+                "INLINE_FRAGMENT" => DirectiveLocation::InlineFragment,
+                "VARIABLE_DEFINITION" => DirectiveLocation::VariableDefinition,
+                "SCHEMA" => DirectiveLocation::Schema,
+                "SCALAR" => DirectiveLocation::Scalar,
+                "OBJECT" => DirectiveLocation::Object,
+                "FIELD_DEFINITION" => DirectiveLocation::FieldDefinition,
+                "ARGUMENT_DEFINITION" => DirectiveLocation::ArgumentDefinition,
+                "INTERFACE" => DirectiveLocation::Interface,
+                "UNION" => DirectiveLocation::Union,
+                "ENUM" => DirectiveLocation::Enum,
+                "ENUM_VALUE" => DirectiveLocation::EnumValue,
+                "INPUT_OBJECT" => DirectiveLocation::InputObject,
+                "INPUT_FIELD_DEFINITION" => DirectiveLocation::InputFieldDefinition,
+                name => panic!("Unknown directive location {}", name),
+            })
+            .collect();
+
+        DynamicDirective {
+            name: def.item.name.item.to_owned(),
+            description: def.item.description.as_ref().map(|d| d.item.clone()),
+            locations: locations,
+            arguments: def.item
+                .arguments
+                .as_ref()
+                .map(|args| {
+                    args.item
+                        .iter()
+                        .map(|a| DynamicArgument::from_definition(&a.item))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            repeatable: def.item.repeatable,
+        }
+    }
+
+    fn meta<'r>(&self) -> DirectiveType<'r> {
+        let arguments = self.arguments
+            .iter()
+            .map(|a| {
+                let mut argument = Argument::new(&a.name, a.arg_type.clone());
+
+                if let Some(ref description) = a.description {
+                    argument = argument.description(description);
+                }
+
+                if let Some(ref default_value) = a.default_value {
+                    argument = argument.default_value(default_value.clone());
+                }
+
+                argument
+            })
+            .collect::<Vec<_>>();
+
+        let mut meta = DirectiveType::new(&self.name, &self.locations, &arguments);
+        if let Some(ref description) = self.description {
+            meta = meta.description(description);
+        }
+        if self.repeatable {
+            meta = meta.repeatable();
+        }
+        meta
+    }
+}
+
+impl DynamicSchemaError {
+    fn new(message: String, locations: &[SourcePosition]) -> DynamicSchemaError {
+        DynamicSchemaError {
+            message: message,
+            locations: locations.to_vec(),
+        }
+    }
+
+    /// Access the message of the error
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Access the positions in the schema source the error refers to
+    ///
+    /// Only errors found while reading the source have positions.
+    pub fn locations(&self) -> &[SourcePosition] {
+        &self.locations
+    }
+}
+
+impl fmt::Display for DynamicSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl<CtxT> DynamicValue<CtxT> {
+    /// Wrap a value
+    pub fn new(value: Value) -> DynamicValue<CtxT> {
+        DynamicValue {
+            value: value,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Access the wrapped value
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl<CtxT> DynamicTypeInfo<CtxT> {
+    fn named(schema: &Arc<SchemaData<CtxT>>, name: String) -> DynamicTypeInfo<CtxT> {
+        DynamicTypeInfo {
+            schema: schema.clone(),
+            type_literal: Type::NonNullNamed(Cow::Owned(name)),
+        }
+    }
+
+    fn with_type(&self, type_literal: Type<'static>) -> DynamicTypeInfo<CtxT> {
+        DynamicTypeInfo {
+            schema: self.schema.clone(),
+            type_literal: type_literal,
+        }
+    }
+
+    /// The type literal this type information refers to
+    pub fn type_literal(&self) -> &Type<'static> {
+        &self.type_literal
+    }
+}
+
+impl<CtxT> SchemaData<CtxT> {
+    fn validate(
+        &self,
+        query_type_name: &str,
+        mutation_type_name: Option<&String>,
+    ) -> Result<(), DynamicSchemaError> {
+        self.expect_object(query_type_name, "Query")?;
+        if let Some(name) = mutation_type_name {
+            self.expect_object(name, "Mutation")?;
+        }
+
+        for (i, directive) in self.directives.iter().enumerate() {
+            if BUILT_IN_DIRECTIVES.contains(&directive.name.as_str()) {
+                return Err(error(format!(
+                    r#"Built-in directive "@{}" can not be redefined"#,
+                    directive.name
+                )));
+            }
+
+            if self.directives[..i].iter().any(|d| d.name == directive.name) {
+                return Err(error(format!(
+                    r#"Directive "@{}" is defined more than once"#,
+                    directive.name
+                )));
+            }
+
+            for argument in &directive.arguments {
+                self.expect_input_type(
+                    &argument.arg_type,
+                    &format!("@{}({})", directive.name, argument.name),
+                )?;
+            }
+        }
+
+        for dynamic_type in self.types.values() {
+            let name = &dynamic_type.name;
+
+            self.validate_applied_directives(
+                &dynamic_type.directives,
+                DirectiveLocation::Object,
+                name,
+            )?;
+
+            match dynamic_type.kind {
+                DynamicTypeKind::Scalar => (),
+                DynamicTypeKind::Object {
+                    ref interfaces,
+                    ref fields,
+                } => {
+                    self.validate_fields(name, fields)?;
+
+                    for interface in interfaces {
+                        let interface_fields = match self.types.get(interface) {
+                            Some(&DynamicType {
+                                kind: DynamicTypeKind::Interface { ref fields },
+                                ..
+                            }) => fields,
+                            _ => {
+                                return Err(error(format!(
+                                    r#"Type "{}" implements "{}", which is not an interface type"#,
+                                    name, interface
+                                )))
+                            }
+                        };
+
+                        for field in interface_fields {
+                            if !fields.iter().any(|f| f.name == field.name) {
+                                return Err(error(format!(
+                                    r#"Type "{}" does not define the field "{}" of interface "{}""#,
+                                    name, field.name, interface
+                                )));
+                            }
+                        }
+                    }
+                }
+                DynamicTypeKind::Interface { ref fields } => self.validate_fields(name, fields)?,
+                DynamicTypeKind::Union { ref types } => {
+                    if types.is_empty() {
+                        return Err(error(format!(
+                            r#"Union type "{}" must have one or more member types"#,
+                            name
+                        )));
+                    }
+
+                    for member in types {
+                        self.expect_object(member, &format!(r#"Member of union "{}""#, name))?;
+                    }
+                }
+                DynamicTypeKind::Enum { ref values } => if values.is_empty() {
+                    return Err(error(format!(
+                        r#"Enum type "{}" must have one or more values"#,
+                        name
+                    )));
+                },
+                DynamicTypeKind::InputObject { ref fields } => {
+                    if fields.is_empty() {
+                        return Err(error(format!(
+                            r#"Input object type "{}" must have one or more fields"#,
+                            name
+                        )));
+                    }
+
+                    for field in fields {
+                        self.expect_input_type(&field.arg_type, &format!("{}.{}", name, field.name))?;
+                    }
+                }
+            }
+        }
+
+        for (type_name, resolvers) in &self.field_resolvers {
+            for field_name in resolvers.keys() {
+                let has_field = match self.types.get(type_name) {
+                    Some(&DynamicType {
+                        kind: DynamicTypeKind::Object { ref fields, .. },
+                        ..
+                    }) => fields.iter().any(|f| &f.name == field_name),
+                    _ => false,
+                };
+
+                if !has_field {
+                    return Err(error(format!(
+                        r#"Resolver for unknown field "{}.{}""#,
+                        type_name, field_name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expect_object(&self, name: &str, role: &str) -> Result<(), DynamicSchemaError> {
+        match self.types.get(name) {
+            Some(&DynamicType {
+                kind: DynamicTypeKind::Object { .. },
+                ..
+            }) => Ok(()),
+            Some(_) => Err(error(format!(
+                r#"{} type "{}" is not an object type"#,
+                role, name
+            ))),
+            None => Err(error(format!(r#"{} type "{}" is not defined"#, role, name))),
+        }
+    }
+
+    fn validate_fields(
+        &self,
+        type_name: &str,
+        fields: &[DynamicField],
+    ) -> Result<(), DynamicSchemaError> {
+        if fields.is_empty() {
+            return Err(error(format!(
+                r#"Type "{}" must have one or more fields"#,
+                type_name
+            )));
+        }
+
+        for field in fields {
+            let path = format!("{}.{}", type_name, field.name);
+
+            match self.kind_of(field.field_type.innermost_name()) {
+                Some(Some(&DynamicTypeKind::InputObject { .. })) => {
+                    return Err(error(format!(
+                        r#"Field "{}" can not have the input type "{}""#,
+                        path, field.field_type
+                    )))
+                }
+                Some(_) => (),
+                None => return Err(unknown_type(&field.field_type, &path)),
+            }
+
+            for argument in &field.arguments {
+                self.expect_input_type(&argument.arg_type, &format!("{}({})", path, argument.name))?;
+            }
+
+            self.validate_applied_directives(
+                &field.directives,
+                DirectiveLocation::FieldDefinition,
+                &path,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_applied_directives(
+        &self,
+        directives: &[AppliedDirective],
+        location: DirectiveLocation,
+        path: &str,
+    ) -> Result<(), DynamicSchemaError> {
+        for (i, directive) in directives.iter().enumerate() {
+            let definition = match self.directives.iter().find(|d| d.name == directive.name) {
+                Some(definition) => definition,
+                None => {
+                    return Err(error(format!(
+                        r#"Unknown directive "@{}" on "{}""#,
+                        directive.name, path
+                    )))
+                }
+            };
+
+            if !definition.locations.contains(&location) {
+                return Err(error(format!(
+                    r#"Directive "@{}" can not be used on the {} "{}""#,
+                    directive.name, location, path
+                )));
+            }
+
+            if !definition.repeatable && directives[..i].iter().any(|d| d.name == directive.name) {
+                return Err(error(format!(
+                    r#"Directive "@{}" can not be repeated on "{}""#,
+                    directive.name, path
+                )));
+            }
+
+            self.validate_directive_arguments(directive, definition, path)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_directive_arguments(
+        &self,
+        directive: &AppliedDirective,
+        definition: &DynamicDirective,
+        path: &str,
+    ) -> Result<(), DynamicSchemaError> {
+
+        for &(ref name, _) in &directive.arguments {
+            if !definition.arguments.iter().any(|a| &a.name == name) {
+                return Err(error(format!(
+                    r#"Unknown argument "{}" of directive "@{}" on "{}""#,
+                    name, directive.name, path
+                )));
+            }
+        }
+
+        for argument in &definition.arguments {
+            let is_required = argument.arg_type.is_non_null() && argument.default_value.is_none();
+
+            if is_required && !directive.arguments.iter().any(|a| a.0 == argument.name) {
+                return Err(error(format!(
+                    r#"Directive "@{}" on "{}" is missing the required argument "{}""#,
+                    directive.name, path, argument.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expect_input_type(&self, t: &Type, path: &str) -> Result<(), DynamicSchemaError> {
+        match self.kind_of(t.innermost_name()) {
+            Some(None)
+            | Some(Some(&DynamicTypeKind::Scalar))
+            | Some(Some(&DynamicTypeKind::Enum { .. }))
+            | Some(Some(&DynamicTypeKind::InputObject { .. })) => Ok(()),
+            Some(_) => Err(error(format!(
+                r#""{}" can not have the output type "{}""#,
+                path, t
+            ))),
+            None => Err(unknown_type(t, path)),
+        }
+    }
+
+    /// Look up the kind of a named type, which is `None` for built-in
+    /// scalars
+    fn kind_of(&self, name: &str) -> Option<Option<&DynamicTypeKind>> {
+        if BUILT_IN_SCALARS.contains(&name) {
+            Some(None)
+        } else {
+            self.types.get(name).map(|t| Some(&t.kind))
+        }
+    }
+
+    fn named_meta<'r>(
+        &self,
+        name: &str,
+        info: &DynamicTypeInfo<CtxT>,
+        registry: &mut Registry<'r>,
+    ) -> MetaType<'r> {
+        match name {
+            "String" => return <String as GraphQLType>::meta(&(), registry),
+            "Int" => return <i32 as GraphQLType>::meta(&(), registry),
+            "Float" => return <f64 as GraphQLType>::meta(&(), registry),
+            "Boolean" => return <bool as GraphQLType>::meta(&(), registry),
+            "ID" => return <ID as GraphQLType>::meta(&(), registry),
+            "_EmptyMutation" => return <EmptyMutation<CtxT> as GraphQLType>::meta(&(), registry),
+            _ => (),
+        }
+
+        let dynamic_type = &self.types[name];
+        let description = dynamic_type.description.as_ref();
+
+        match dynamic_type.kind {
+            DynamicTypeKind::Scalar => ScalarMeta {
+                name: Cow::Owned(name.to_owned()),
+                description: description.cloned(),
+                try_parse_fn: Box::new(|_| true),
+            }.into_meta(),
+            DynamicTypeKind::Object {
+                ref interfaces,
+                ref fields,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|f| f.meta(info, registry))
+                    .collect::<Vec<_>>();
+                let interfaces = interfaces
+                    .iter()
+                    .map(|i| {
+                        registry.get_type::<DynamicValue<CtxT>>(&DynamicTypeInfo::named(
+                            &info.schema,
+                            i.clone(),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+
+                // Types only used by directive arguments are not reachable
+                // from the root types, so they are registered here
+                for directive in &self.directives {
+                    for argument in &directive.arguments {
+                        registry.get_type::<DynamicValue<CtxT>>(
+                            &info.with_type(argument.arg_type.clone()),
+                        );
+                    }
+                }
+
+                let mut meta = registry
+                    .build_object_type::<DynamicValue<CtxT>>(info, &fields)
+                    .interfaces(&interfaces);
+                if let Some(description) = description {
+                    meta = meta.description(description);
+                }
+                for directive in &dynamic_type.directives {
+                    meta = meta.directive(directive.clone());
+                }
+                meta.into_meta()
+            }
+            DynamicTypeKind::Interface { ref fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|f| f.meta(info, registry))
+                    .collect::<Vec<_>>();
+
+                let mut meta = registry.build_interface_type::<DynamicValue<CtxT>>(info, &fields);
+                if let Some(description) = description {
+                    meta = meta.description(description);
+                }
+                meta.into_meta()
+            }
+            DynamicTypeKind::Union { ref types } => {
+                let types = types
+                    .iter()
+                    .map(|t| {
+                        registry.get_type::<DynamicValue<CtxT>>(&DynamicTypeInfo::named(
+                            &info.schema,
+                            t.clone(),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut meta = registry.build_union_type::<DynamicValue<CtxT>>(info, &types);
+                if let Some(description) = description {
+                    meta = meta.description(description);
+                }
+                meta.into_meta()
+            }
+            DynamicTypeKind::Enum { ref values } => {
+                let names = values.iter().map(|v| v.name.clone()).collect::<Vec<_>>();
+
+                EnumMeta {
+                    name: Cow::Owned(name.to_owned()),
+                    description: description.cloned(),
+                    values: values
+                        .iter()
+                        .map(|v| EnumValue {
+                            name: v.name.clone(),
+                            description: v.description.clone(),
+                            deprecation_reason: v.deprecation_reason.clone(),
+                        })
+                        .collect(),
+                    try_parse_fn: Box::new(move |v: &InputValue| {
+                        v.as_enum_value()
+                            .or_else(|| v.as_string_value())
+                            .map_or(false, |v| names.iter().any(|n| n == v))
+                    }),
+                }.into_meta()
+            }
+            DynamicTypeKind::InputObject { ref fields } => {
+                let fields = fields
+                    .iter()
+                    .map(|f| f.meta(info, registry))
+                    .collect::<Vec<_>>();
+
+                InputObjectMeta {
+                    name: Cow::Owned(name.to_owned()),
+                    description: description.cloned(),
+                    input_fields: fields,
+                    try_parse_fn: Box::new(|v: &InputValue| v.to_object_value().is_some()),
+                }.into_meta()
+            }
+        }
+    }
+
+    fn is_composite(&self, name: &str) -> bool {
+        match self.kind_of(name) {
+            Some(Some(&DynamicTypeKind::Object { .. }))
+            | Some(Some(&DynamicTypeKind::Interface { .. }))
+            | Some(Some(&DynamicTypeKind::Union { .. })) => true,
+            _ => false,
+        }
+    }
+
+    /// Find the object type of a value of an object, interface or union type
+    fn concrete_type_name(&self, type_name: &str, value: &Value, context: &CtxT) -> Option<String> {
+        match self.types.get(type_name).map(|t| &t.kind) {
+            Some(&DynamicTypeKind::Object { .. }) => Some(type_name.to_owned()),
+            Some(&DynamicTypeKind::Interface { .. }) | Some(&DynamicTypeKind::Union { .. }) => self
+                .resolver
+                .concrete_type_name(type_name, value, context)
+                .filter(|name| self.is_possible_type(type_name, name)),
+            _ => None,
+        }
+    }
+
+    /// Check if an object type can be used where the given type is expected
+    fn is_possible_type(&self, type_name: &str, object_type_name: &str) -> bool {
+        if type_name == object_type_name {
+            return true;
+        }
+
+        match self.types.get(type_name).map(|t| &t.kind) {
+            Some(&DynamicTypeKind::Union { ref types }) => types.iter().any(|t| t == object_type_name),
+            Some(&DynamicTypeKind::Interface { .. }) => match self.types.get(object_type_name) {
+                Some(&DynamicType {
+                    kind: DynamicTypeKind::Object { ref interfaces, .. },
+                    ..
+                }) => interfaces.iter().any(|i| i == type_name),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn resolve_field(
+        &self,
+        type_name: &str,
+        concrete_type_name: &str,
+        field_name: &str,
+        parent: &Value,
+        arguments: &Arguments,
+        executor: &Executor<CtxT>,
+    ) -> FieldResult<Value> {
+        let resolver = self.field_resolvers
+            .get(concrete_type_name)
+            .and_then(|r| r.get(field_name))
+            .or_else(|| {
+                self.field_resolvers
+                    .get(type_name)
+                    .and_then(|r| r.get(field_name))
+            });
+
+        match resolver {
+            Some(resolver) => resolver(parent, arguments, executor),
+            None => self.resolver.resolve_field(
+                concrete_type_name,
+                field_name,
+                parent,
+                arguments,
+                executor,
+            ),
+        }
+    }
+
+    /// Check that a resolved value matches the type of its field
+    fn check_value(&self, t: &Type, value: &Value, context: &CtxT) -> FieldResult<()> {
+        if value.is_null() {
+            return if t.is_non_null() {
+                Err(FieldError::from(format!(
+                    r#"Can not return null for the non-null type "{}""#,
+                    t
+                )))
+            } else {
+                Ok(())
+            };
+        }
+
+        match *t {
+            Type::List(ref inner) | Type::NonNullList(ref inner) => match *value {
+                Value::List(ref items) => {
+                    for item in items {
+                        self.check_value(inner, item, context)?;
+                    }
+                    Ok(())
+                }
+                _ => Err(FieldError::from(format!(
+                    r#"Expected a list for the type "{}""#,
+                    t
+                ))),
+            },
+            Type::Named(ref name) | Type::NonNullNamed(ref name) => {
+                let valid = match (self.kind_of(name), value) {
+                    (Some(None), _) => match (&**name, value) {
+                        ("Int", &Value::Int(i)) => is_32_bit(i),
+                        ("Float", &Value::Int(_))
+                        | ("Float", &Value::Float(_))
+                        | ("String", &Value::String(_))
+                        | ("Boolean", &Value::Boolean(_))
+                        | ("ID", &Value::Int(_))
+                        | ("ID", &Value::String(_)) => true,
+                        _ => false,
+                    },
+                    (Some(Some(&DynamicTypeKind::Scalar)), _) => true,
+                    (Some(Some(&DynamicTypeKind::Enum { ref values })), &Value::String(ref s)) => {
+                        values.iter().any(|v| &v.name == s)
+                    }
+                    (Some(Some(&DynamicTypeKind::Object { .. })), &Value::Object(_)) => true,
+                    (Some(Some(&DynamicTypeKind::Interface { .. })), &Value::Object(_))
+                    | (Some(Some(&DynamicTypeKind::Union { .. })), &Value::Object(_)) => {
+                        if self.concrete_type_name(name, value, context).is_none() {
+                            return Err(FieldError::from(format!(
+                                r#"Could not determine the object type of a value of type "{}""#,
+                                name
+                            )));
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+
+                if valid {
+                    Ok(())
+                } else {
+                    Err(FieldError::from(format!(
+                        r#"Expected a value of type "{}""#,
+                        name
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl<CtxT> GraphQLType for DynamicValue<CtxT> {
+    type Context = CtxT;
+    type TypeInfo = DynamicTypeInfo<CtxT>;
+
+    fn name(info: &DynamicTypeInfo<CtxT>) -> Option<&str> {
+        match info.type_literal {
+            Type::NonNullNamed(ref name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn meta<'r>(info: &DynamicTypeInfo<CtxT>, registry: &mut Registry<'r>) -> MetaType<'r> {
+        match info.type_literal {
+            Type::Named(ref name) => registry
+                .build_nullable_type::<Self>(&info.with_type(Type::NonNullNamed(name.clone())))
+                .into_meta(),
+            Type::List(ref inner) => registry
+                .build_nullable_type::<Self>(&info.with_type(Type::NonNullList(inner.clone())))
+                .into_meta(),
+            Type::NonNullList(ref inner) => registry
+                .build_list_type::<Self>(&info.with_type((**inner).clone()))
+                .into_meta(),
+            Type::NonNullNamed(ref name) => info.schema.named_meta(name, info, registry),
+        }
+    }
+
+    fn resolve_field(
+        &self,
+        info: &DynamicTypeInfo<CtxT>,
+        field_name: &str,
+        arguments: &Arguments,
+        executor: &Executor<CtxT>,
+    ) -> ExecutionResult {
+        let schema = &info.schema;
+        let type_name = Self::name(info).expect("Resolving field of unnamed type");
+        let concrete_type_name = self.concrete_type_name(executor.context(), info);
+        let field_type = &schema.types[type_name]
+            .field_by_name(field_name)
+            .expect("Field not found")
+            .field_type;
+
+        let value = schema.resolve_field(
+            type_name,
+            &concrete_type_name,
+            field_name,
+            &self.value,
+            arguments,
+            executor,
+        )?;
+        schema.check_value(field_type, &value, executor.context())?;
+
+        executor.resolve(
+            &info.with_type(field_type.clone()),
+            &DynamicValue::new(value),
+        )
+    }
+
+    fn resolve_into_type(
+        &self,
+        info: &DynamicTypeInfo<CtxT>,
+        type_name: &str,
+        _: Option<&[Selection]>,
+        executor: &Executor<CtxT>,
+    ) -> ExecutionResult {
+        let concrete_type_name = self.concrete_type_name(executor.context(), info);
+
+        if info.schema.is_possible_type(type_name, &concrete_type_name) {
+            executor.resolve(
+                &DynamicTypeInfo::named(&info.schema, concrete_type_name),
+                self,
+            )
+        } else {
+            Ok(Value::null())
+        }
+    }
+
+    fn concrete_type_name(&self, context: &CtxT, info: &DynamicTypeInfo<CtxT>) -> String {
+        let type_name = Self::name(info).expect("Resolving type name of unnamed type");
+
+        info.schema
+            .concrete_type_name(type_name, &self.value, context)
+            .expect("Object type of value not found")
+    }
+
+    fn resolve(
+        &self,
+        info: &DynamicTypeInfo<CtxT>,
+        selection_set: Option<&[Selection]>,
+        executor: &Executor<CtxT>,
+    ) -> Value {
+        match info.type_literal {
+            Type::Named(_) | Type::List(_) => if self.value.is_null() {
+                Value::null()
+            } else {
+                let non_null = match info.type_literal {
+                    Type::Named(ref name) => Type::NonNullNamed(name.clone()),
+                    Type::List(ref inner) => Type::NonNullList(inner.clone()),
+                    _ => unreachable!(),
+                };
+
+                executor.resolve_into_value(&info.with_type(non_null), self)
+            },
+            Type::NonNullList(ref inner) => {
+                let item_info = info.with_type((**inner).clone());
+
+                Value::list(
+                    self.value
+                        .as_list_value()
+                        .map(|items| &items[..])
+                        .unwrap_or(&[])
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            executor
+                                .index_sub_executor(i)
+                                .resolve_into_value(&item_info, &DynamicValue::new(item.clone()))
+                        })
+                        .collect(),
+                )
+            }
+            Type::NonNullNamed(ref name) => if info.schema.is_composite(name) {
+                let selection_set = selection_set.expect("Object without selection set");
+                let mut result = Object::with_capacity(selection_set.len());
+
+                if resolve_selection_set_into(self, info, selection_set, executor, &mut result) {
+                    Value::Object(result)
+                } else {
+                    Value::null()
+                }
+            } else {
+                match (&**name, &self.value) {
+                    ("Float", &Value::Int(i)) => Value::float(i as f64),
+                    ("ID", &Value::Int(i)) => Value::string(i.to_string()),
+                    (_, value) => value.clone(),
+                }
+            },
+        }
+    }
+
+    fn resolve_async<'a>(
+        &self,
+        info: &'a DynamicTypeInfo<CtxT>,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        // Dynamic resolvers are synchronous
+        Box::new(future::ok(self.resolve(info, selection_set, executor)))
+    }
+}
+
+impl<CtxT> fmt::Debug for DynamicValue<CtxT> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DynamicValue").field(&self.value).finish()
+    }
+}
+
+fn is_32_bit(i: i64) -> bool {
+    i >= i64::from(i32::min_value()) && i <= i64::from(i32::max_value())
+}
+
+fn error(message: String) -> DynamicSchemaError {
+    DynamicSchemaError::new(message, &[])
+}
+
+fn unknown_type(t: &Type, path: &str) -> DynamicSchemaError {
+    error(format!(
+        r#""{}" has the unknown type "{}""#,
+        path,
+        t.innermost_name()
+    ))
+}
+
+fn deprecation_reason(directives: &Option<Vec<Spanning<Directive>>>) -> Option<String> {
+    directives
+        .as_ref()
+        .and_then(|directives| {
+            directives
+                .iter()
+                .find(|d| d.item.name.item == "deprecated")
+        })
+        .map(|d| {
+            d.item
+                .arguments
+                .as_ref()
+                .and_then(|args| {
+                    args.item
+                        .items
+                        .iter()
+                        .find(|&&(ref name, _)| name.item == "reason")
+                })
+                .and_then(|&(_, ref reason)| reason.item.as_string_value())
+                .unwrap_or("No longer supported")
+                .to_owned()
+        })
+}
+
+fn applied_directives(directives: &Option<Vec<Spanning<Directive>>>) -> Vec<AppliedDirective> {
+    directives
+        .iter()
+        .flat_map(|directives| directives.iter())
+        .filter(|d| d.item.name.item != "deprecated")
+        .map(|d| AppliedDirective {
+            name: d.item.name.item.to_owned(),
+            arguments: d.item
+                .arguments
+                .iter()
+                .flat_map(|args| args.item.iter())
+                .map(|&(ref name, ref value)| (name.item.to_owned(), value.item.clone()))
+                .collect(),
+        })
+        .collect()
+}
+
+fn owned_type(t: &Type) -> Type<'static> {
+    match *t {
+        Type::Named(ref name) => Type::Named(Cow::Owned(name.to_string())),
+        Type::NonNullNamed(ref name) => Type::NonNullNamed(Cow::Owned(name.to_string())),
+        Type::List(ref inner) => Type::List(Box::new(owned_type(inner))),
+        Type::NonNullList(ref inner) => Type::NonNullList(Box::new(owned_type(inner))),
+    }
+}
+
+fn parse_type_literal(source: &str) -> Type<'static> {
+    let mut lexer = Lexer::new(source);
+    let parsed = Parser::new(&mut lexer).ok().and_then(|mut parser| {
+        let parsed = parse_type(&mut parser).ok();
+
+        if parser.peek().item == Token::EndOfFile {
+            parsed
+        } else {
+            None
+        }
+    });
+
+    match parsed {
+        Some(t) => owned_type(&t.item),
+        None => panic!("Invalid type literal {:?}", source),
+    }
+}
+
+fn parse_default_value(source: &str) -> Result<InputValue, DynamicSchemaError> {
+    let mut lexer = Lexer::new(source);
+    let parsed = Parser::new(&mut lexer).ok().and_then(|mut parser| {
+        let parsed = parse_value_literal(&mut parser, true).ok();
+
+        if parser.peek().item == Token::EndOfFile {
+            parsed
+        } else {
+            None
+        }
+    });
+
+    parsed
+        .map(|v| v.item)
+        .ok_or_else(|| error(format!("Invalid default value {:?}", source)))
+}
+
+fn introspected_field<'v>(value: &'v Value, name: &str) -> Result<&'v Value, DynamicSchemaError> {
+    value
+        .as_object_value()
+        .and_then(|o| o.get_field_value(name))
+        .ok_or_else(|| {
+            error(format!(
+                r#"Introspection result is missing the field "{}""#,
+                name
+            ))
+        })
+}
+
+fn introspected_string<'v>(
+    value: &'v Value,
+    name: &str,
+) -> Result<Option<&'v str>, DynamicSchemaError> {
+    match *introspected_field(value, name)? {
+        Value::Null => Ok(None),
+        Value::String(ref s) => Ok(Some(s)),
+        _ => Err(error(format!(
+            r#"Field "{}" of the introspection result is not a string"#,
+            name
+        ))),
+    }
+}
+
+fn introspected_name(value: &Value) -> Result<&str, DynamicSchemaError> {
+    introspected_string(value, "name")?
+        .ok_or_else(|| error("Introspection result has an unnamed type or field".to_owned()))
+}
+
+// Missing lists are treated like empty ones, since introspection returns
+// `null` for the fields of types that don't have any.
+fn introspected_list<'v>(value: &'v Value, name: &str) -> Result<&'v [Value], DynamicSchemaError> {
+    match *introspected_field(value, name)? {
+        Value::Null => Ok(&[]),
+        Value::List(ref l) => Ok(l),
+        _ => Err(error(format!(
+            r#"Field "{}" of the introspection result is not a list"#,
+            name
+        ))),
+    }
+}
+
+fn introspected_type(value: &Value) -> Result<Type<'static>, DynamicSchemaError> {
+    match introspected_string(value, "kind")? {
+        Some("NON_NULL") => match introspected_type(introspected_field(value, "ofType")?)? {
+            Type::Named(name) => Ok(Type::NonNullNamed(name)),
+            Type::List(inner) => Ok(Type::NonNullList(inner)),
+            _ => Err(error(
+                "Introspection result has a non-null type of a non-null type".to_owned(),
+            )),
+        },
+        Some("LIST") => Ok(Type::List(Box::new(introspected_type(
+            introspected_field(value, "ofType")?,
+        )?))),
+        _ => Ok(Type::Named(Cow::Owned(introspected_name(value)?.to_owned()))),
+    }
+}
+
+fn introspected_deprecation_reason(value: &Value) -> Result<Option<String>, DynamicSchemaError> {
+    match *introspected_field(value, "isDeprecated")? {
+        Value::Boolean(true) => Ok(Some(
+            introspected_string(value, "deprecationReason")?
+                .unwrap_or("No longer supported")
+                .to_owned(),
+        )),
+        _ => Ok(None),
+    }
+}
@@ -41,6 +41,8 @@ pub struct ObjectMeta<'a> {
     pub fields: Vec<Field<'a>>,
     #[doc(hidden)]
     pub interface_names: Vec<String>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
 }
 
 /// Enum type metadata
@@ -135,6 +137,12 @@ pub struct Field<'a> {
     pub field_type: Type<'a>,
     #[doc(hidden)]
     pub deprecation_reason: Option<String>,
+    #[doc(hidden)]
+    pub complexity: Option<usize>,
+    #[doc(hidden)]
+    pub complexity_multiplier: Option<String>,
+    #[doc(hidden)]
+    pub directives: Vec<AppliedDirective>,
 }
 
 /// Metadata for an argument to a field
@@ -150,6 +158,18 @@ pub struct Argument<'a> {
     pub default_value: Option<InputValue>,
 }
 
+/// Directive applied to an object type or a field definition
+///
+/// The arguments are passed to the hook of the directive whenever one of the
+/// fields it applies to is resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedDirective {
+    #[doc(hidden)]
+    pub name: String,
+    #[doc(hidden)]
+    pub arguments: Vec<(String, InputValue)>,
+}
+
 /// Metadata for a single value in an enum
 #[derive(Debug, Clone)]
 pub struct EnumValue {
@@ -397,6 +417,7 @@ impl<'a> ObjectMeta<'a> {
             description: None,
             fields: fields.to_vec(),
             interface_names: vec![],
+            directives: vec![],
         }
     }
 
@@ -420,6 +441,14 @@ impl<'a> ObjectMeta<'a> {
         self
     }
 
+    /// Apply a directive to the object type
+    ///
+    /// The directive applies to every field of the type.
+    pub fn directive(mut self, directive: AppliedDirective) -> ObjectMeta<'a> {
+        self.directives.push(directive);
+        self
+    }
+
     /// Wrap this object type in a generic meta type
     pub fn into_meta(self) -> MetaType<'a> {
         MetaType::Object(self)
@@ -566,6 +595,46 @@ impl<'a> Field<'a> {
         self.deprecation_reason = Some(reason.to_owned());
         self
     }
+
+    /// Set the cost of the field used by the query complexity limit
+    ///
+    /// Fields cost 1 unless specified otherwise.
+    pub fn complexity(mut self, cost: usize) -> Field<'a> {
+        self.complexity = Some(cost);
+        self
+    }
+
+    /// Multiply the complexity of the field by the value of an argument
+    ///
+    /// This is meant for arguments limiting the length of returned lists, like
+    /// `first`. The complexity is not multiplied if the argument is not an
+    /// integer.
+    pub fn complexity_multiplier(mut self, argument: &str) -> Field<'a> {
+        self.complexity_multiplier = Some(argument.to_owned());
+        self
+    }
+
+    /// Apply a directive to the field definition
+    pub fn directive(mut self, directive: AppliedDirective) -> Field<'a> {
+        self.directives.push(directive);
+        self
+    }
+}
+
+impl AppliedDirective {
+    /// Construct a directive application without arguments
+    pub fn new(name: &str) -> AppliedDirective {
+        AppliedDirective {
+            name: name.to_owned(),
+            arguments: Vec::new(),
+        }
+    }
+
+    /// Pass an argument to the directive
+    pub fn argument(mut self, name: &str, value: InputValue) -> AppliedDirective {
+        self.arguments.push((name.to_owned(), value));
+        self
+    }
 }
 
 impl<'a> Argument<'a> {
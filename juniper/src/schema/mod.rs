@@ -1,3 +1,6 @@
+pub mod diff;
+pub mod dynamic;
 pub mod meta;
 pub mod model;
 pub mod schema;
+pub mod printer;
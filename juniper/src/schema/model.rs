@@ -1,18 +1,32 @@
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use fnv::FnvHashMap;
 
 use ast::Type;
-use executor::{Context, Registry};
+use executor::{Context, DirectiveHook, DirectiveHooks, Registry};
+use extensions::Extension;
+use http::persisted::{PersistedQueries, PersistedQueryStore};
+use prepared::QueryCache;
 use schema::meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta};
 use types::base::GraphQLType;
 use types::name::Name;
+use types::scalars::EmptySubscription;
+use validation::{BuiltInRule, ValidationRule};
 
 /// Root query node of a schema
 ///
-/// This brings the mutation and query types together, and provides the
-/// predefined metadata fields.
-pub struct RootNode<'a, QueryT: GraphQLType, MutationT: GraphQLType> {
+/// This brings the query, mutation and subscription types together, and
+/// provides the predefined metadata fields.
+///
+/// The subscription type defaults to `EmptySubscription`, which means that
+/// schemas built with `new` or `new_with_info` do not support subscriptions.
+pub struct RootNode<
+    'a,
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType = EmptySubscription<<QueryT as GraphQLType>::Context>,
+> {
     #[doc(hidden)]
     pub query_type: QueryT,
     #[doc(hidden)]
@@ -22,7 +36,35 @@ pub struct RootNode<'a, QueryT: GraphQLType, MutationT: GraphQLType> {
     #[doc(hidden)]
     pub mutation_info: MutationT::TypeInfo,
     #[doc(hidden)]
+    pub subscription_type: SubscriptionT,
+    #[doc(hidden)]
+    pub subscription_info: SubscriptionT::TypeInfo,
+    #[doc(hidden)]
     pub schema: SchemaType<'a>,
+    #[doc(hidden)]
+    pub max_depth: Option<usize>,
+    #[doc(hidden)]
+    pub max_complexity: Option<usize>,
+    #[doc(hidden)]
+    pub disabled_rules: Vec<BuiltInRule>,
+    #[doc(hidden)]
+    pub validation_rules: Vec<Box<ValidationRule>>,
+    #[doc(hidden)]
+    pub extensions: Vec<Box<Extension>>,
+    pub(crate) directive_hooks: DirectiveHooks<QueryT::Context>,
+    // Identifies the schema and validation rules that prepared queries were
+    // validated against
+    pub(crate) id: usize,
+    pub(crate) query_cache: Option<QueryCache>,
+    pub(crate) persisted_queries: Option<PersistedQueries>,
+    pub(crate) max_batch_size: Option<usize>,
+    pub(crate) parallel_batches: bool,
+}
+
+static NEXT_ROOT_NODE_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn next_root_node_id() -> usize {
+    NEXT_ROOT_NODE_ID.fetch_add(1, Ordering::Relaxed)
 }
 
 /// Metadata for a schema
@@ -30,6 +72,7 @@ pub struct SchemaType<'a> {
     types: FnvHashMap<Name, MetaType<'a>>,
     query_type_name: String,
     mutation_type_name: Option<String>,
+    subscription_type_name: Option<String>,
     directives: FnvHashMap<String, DirectiveType<'a>>,
 }
 
@@ -47,6 +90,7 @@ pub struct DirectiveType<'a> {
     pub description: Option<String>,
     pub locations: Vec<DirectiveLocation>,
     pub arguments: Vec<Argument<'a>>,
+    pub is_repeatable: bool,
 }
 
 #[derive(GraphQLEnum, Clone, PartialEq, Eq, Debug)]
@@ -54,13 +98,32 @@ pub struct DirectiveType<'a> {
 pub enum DirectiveLocation {
     Query,
     Mutation,
+    Subscription,
     Field,
     #[graphql(name = "FRAGMENT_DEFINITION")]
     FragmentDefinition,
     #[graphql(name = "FRAGMENT_SPREAD")]
     FragmentSpread,
-    #[graphql(name = "INLINE_SPREAD")]
+    #[graphql(name = "INLINE_FRAGMENT")]
     InlineFragment,
+    #[graphql(name = "VARIABLE_DEFINITION")]
+    VariableDefinition,
+    Schema,
+    Scalar,
+    Object,
+    #[graphql(name = "FIELD_DEFINITION")]
+    FieldDefinition,
+    #[graphql(name = "ARGUMENT_DEFINITION")]
+    ArgumentDefinition,
+    Interface,
+    Union,
+    Enum,
+    #[graphql(name = "ENUM_VALUE")]
+    EnumValue,
+    #[graphql(name = "INPUT_OBJECT")]
+    InputObject,
+    #[graphql(name = "INPUT_FIELD_DEFINITION")]
+    InputFieldDefinition,
 }
 
 impl<'a, QueryT, MutationT> RootNode<'a, QueryT, MutationT>
@@ -91,28 +154,290 @@ where
         query_info: QueryT::TypeInfo,
         mutation_info: MutationT::TypeInfo,
     ) -> RootNode<'a, QueryT, MutationT> {
+        RootNode::new_with_subscription_and_info(
+            query_obj,
+            mutation_obj,
+            EmptySubscription::new(),
+            query_info,
+            mutation_info,
+            (),
+        )
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT> RootNode<'a, QueryT, MutationT, SubscriptionT>
+where
+    QueryT: GraphQLType<TypeInfo = ()>,
+    MutationT: GraphQLType<TypeInfo = ()>,
+    SubscriptionT: GraphQLType<TypeInfo = ()>,
+{
+    /// Construct a new root node from query, mutation and subscription nodes
+    pub fn new_with_subscription(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        RootNode::new_with_subscription_and_info(
+            query_obj,
+            mutation_obj,
+            subscription_obj,
+            (),
+            (),
+            (),
+        )
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT> RootNode<'a, QueryT, MutationT, SubscriptionT>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    /// Construct a new root node from query, mutation and subscription
+    /// nodes, while also providing type info objects for all three types.
+    pub fn new_with_subscription_and_info(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+        query_info: QueryT::TypeInfo,
+        mutation_info: MutationT::TypeInfo,
+        subscription_info: SubscriptionT::TypeInfo,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
         RootNode {
             query_type: query_obj,
             mutation_type: mutation_obj,
-            schema: SchemaType::new::<QueryT, MutationT>(&query_info, &mutation_info),
+            subscription_type: subscription_obj,
+            schema: SchemaType::new::<QueryT, MutationT, SubscriptionT>(
+                &query_info,
+                &mutation_info,
+                &subscription_info,
+            ),
             query_info: query_info,
             mutation_info: mutation_info,
+            subscription_info: subscription_info,
+            max_depth: None,
+            max_complexity: None,
+            disabled_rules: Vec::new(),
+            validation_rules: Vec::new(),
+            extensions: Vec::new(),
+            directive_hooks: FnvHashMap::default(),
+            id: next_root_node_id(),
+            query_cache: None,
+            persisted_queries: None,
+            max_batch_size: None,
+            parallel_batches: false,
+        }
+    }
+
+    /// Reject queries nesting fields deeper than the given depth
+    ///
+    /// The depth of a query is the largest number of fields enclosing each
+    /// other, counting fields in fragments where they are spread. Queries
+    /// exceeding it fail validation before they are executed.
+    pub fn max_depth(mut self, depth: usize) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Reject queries more complex than the given limit
+    ///
+    /// Each selected field costs 1, or the cost set with
+    /// `meta::Field::complexity`, plus the complexity of its own selections.
+    /// If the field has a complexity multiplier, the sum is multiplied by the
+    /// value of that argument. Only the operation that is executed counts.
+    /// Queries exceeding the limit fail validation before they are executed.
+    pub fn max_complexity(
+        mut self,
+        complexity: usize,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.max_complexity = Some(complexity);
+        self
+    }
+
+    /// Check queries with an additional validation rule
+    ///
+    /// Custom rules run together with the built-in rules, and queries they
+    /// report errors for are rejected before they are executed.
+    pub fn add_validation_rule<R>(mut self, rule: R) -> RootNode<'a, QueryT, MutationT, SubscriptionT>
+    where
+        R: ValidationRule + 'static,
+    {
+        self.validation_rules.push(Box::new(rule));
+        self.id = next_root_node_id();
+        self
+    }
+
+    /// Instrument the execution of queries with an extension
+    ///
+    /// Extensions are notified in the order they were added.
+    pub fn add_extension<E>(mut self, extension: E) -> RootNode<'a, QueryT, MutationT, SubscriptionT>
+    where
+        E: Extension + 'static,
+    {
+        self.extensions.push(Box::new(extension));
+        self
+    }
+
+    /// Keep up to the given number of queries parsed and validated
+    ///
+    /// Executing query text looks it up in the cache first, and only parses
+    /// and validates it if it is not cached yet. Only the variables and the
+    /// depth and complexity limits are checked again for cached queries. Once
+    /// the cache is full, the query used least recently is removed from it.
+    pub fn query_cache(
+        mut self,
+        capacity: usize,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.query_cache = Some(QueryCache::new(capacity));
+        self
+    }
+
+    /// Accept hashes of persisted queries in HTTP requests
+    ///
+    /// Hashes are looked up in the given store, and the queries of requests
+    /// containing both a hash and the query are added to it once they have
+    /// passed validation. See the `http::persisted` module.
+    pub fn persisted_queries<S>(mut self, store: S) -> RootNode<'a, QueryT, MutationT, SubscriptionT>
+    where
+        S: PersistedQueryStore + 'static,
+    {
+        self.persisted_queries = Some(PersistedQueries::new(store, false));
+        self
+    }
+
+    /// Only execute the persisted queries in the given store
+    ///
+    /// HTTP requests have to contain the hash of a query registered in the
+    /// store up front. Requests only containing the query are rejected, and
+    /// the store is never added to.
+    pub fn strict_persisted_queries<S>(
+        mut self,
+        store: S,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT>
+    where
+        S: PersistedQueryStore + 'static,
+    {
+        self.persisted_queries = Some(PersistedQueries::new(store, true));
+        self
+    }
+
+    /// Reject HTTP batches containing more than the given number of requests
+    ///
+    /// See `http::GraphQLBatchRequest`. Batches exceeding the limit get a
+    /// single error response, without executing any of their requests.
+    pub fn max_batch_size(
+        mut self,
+        size: usize,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.max_batch_size = Some(size);
+        self
+    }
+
+    /// Execute the requests of HTTP batches all at once
+    ///
+    /// Only applies to `http::GraphQLBatchRequest::execute_async`, which
+    /// otherwise executes the requests of a batch one after another.
+    pub fn parallel_batches(mut self) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.parallel_batches = true;
+        self
+    }
+
+    /// Stop checking queries with one of the built-in validation rules
+    ///
+    /// Rules are meant to keep the executor from running invalid queries, so
+    /// turning one off may let queries through that fail or panic during
+    /// execution.
+    pub fn disable_validation_rule(
+        mut self,
+        rule: BuiltInRule,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        self.disabled_rules.push(rule);
+        self.id = next_root_node_id();
+        self
+    }
+
+    /// Declare a custom directive
+    ///
+    /// Declared directives are listed by introspection, and pass validation
+    /// wherever their locations allow them in a query. They don't affect
+    /// execution unless a hook is registered for them with `directive_hook`.
+    ///
+    /// Panics if the type of one of the arguments is not part of the schema.
+    pub fn directive(
+        mut self,
+        directive: DirectiveType<'a>,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT> {
+        for arg in &directive.arguments {
+            if self.schema
+                .concrete_type_by_name(arg.arg_type.innermost_name())
+                .is_none()
+            {
+                panic!(
+                    "Type {} of argument {} of directive @{} is not part of the schema",
+                    arg.arg_type, arg.name, directive.name
+                );
+            }
+        }
+
+        self.schema.add_directive(directive);
+        self.id = next_root_node_id();
+        self
+    }
+
+    /// Run a hook whenever a field the named directive applies to is
+    /// resolved
+    ///
+    /// See `DirectiveHook` for the fields a directive applies to. Registering
+    /// another hook for the same directive replaces the previous one.
+    ///
+    /// Panics if the directive has not been declared.
+    pub fn directive_hook<H>(
+        mut self,
+        name: &str,
+        hook: H,
+    ) -> RootNode<'a, QueryT, MutationT, SubscriptionT>
+    where
+        H: DirectiveHook<QueryT::Context> + 'static,
+    {
+        if self.schema.directive_by_name(name).is_none() {
+            panic!("Directive @{} has not been declared", name);
         }
+
+        self.directive_hooks.insert(name.to_owned(), Box::new(hook));
+        self
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT> RootNode<'a, QueryT, MutationT, SubscriptionT>
+where
+    QueryT: GraphQLType,
+    MutationT: GraphQLType,
+    SubscriptionT: GraphQLType,
+{
+    /// Print the schema in the GraphQL schema definition language
+    ///
+    /// See `SchemaType::as_schema_language` for details.
+    pub fn as_schema_language(&self) -> String {
+        self.schema.as_schema_language()
     }
 }
 
 impl<'a> SchemaType<'a> {
-    pub fn new<QueryT, MutationT>(
+    pub fn new<QueryT, MutationT, SubscriptionT>(
         query_info: &QueryT::TypeInfo,
         mutation_info: &MutationT::TypeInfo,
+        subscription_info: &SubscriptionT::TypeInfo,
     ) -> SchemaType<'a>
     where
         QueryT: GraphQLType,
         MutationT: GraphQLType,
+        SubscriptionT: GraphQLType,
     {
         let mut directives = FnvHashMap::default();
         let query_type_name: String;
         let mutation_type_name: String;
+        let subscription_type_name: String;
 
         let mut registry = Registry::new(FnvHashMap::default());
         query_type_name = registry
@@ -123,6 +448,10 @@ impl<'a> SchemaType<'a> {
             .get_type::<MutationT>(mutation_info)
             .innermost_name()
             .to_owned();
+        subscription_type_name = registry
+            .get_type::<SubscriptionT>(subscription_info)
+            .innermost_name()
+            .to_owned();
 
         registry.get_type::<SchemaType>(&());
         directives.insert("skip".to_owned(), DirectiveType::new_skip(&mut registry));
@@ -162,6 +491,11 @@ impl<'a> SchemaType<'a> {
             } else {
                 None
             },
+            subscription_type_name: if &subscription_type_name != "_EmptySubscription" {
+                Some(subscription_type_name)
+            } else {
+                None
+            },
             directives: directives,
         }
     }
@@ -210,6 +544,24 @@ impl<'a> SchemaType<'a> {
         })
     }
 
+    pub fn subscription_type(&self) -> Option<TypeType> {
+        if let Some(ref subscription_type_name) = self.subscription_type_name {
+            Some(
+                self.type_by_name(subscription_type_name)
+                    .expect("Subscription type does not exist in schema"),
+            )
+        } else {
+            None
+        }
+    }
+
+    pub fn concrete_subscription_type(&self) -> Option<&MetaType> {
+        self.subscription_type_name.as_ref().map(|name| {
+            self.concrete_type_by_name(name)
+                .expect("Subscription type does not exist in schema")
+        })
+    }
+
     pub fn type_list(&self) -> Vec<TypeType> {
         self.types.values().map(|t| TypeType::Concrete(t)).collect()
     }
@@ -364,6 +716,7 @@ impl<'a> DirectiveType<'a> {
             description: None,
             locations: locations.to_vec(),
             arguments: arguments.to_vec(),
+            is_repeatable: false,
         }
     }
 
@@ -395,6 +748,12 @@ impl<'a> DirectiveType<'a> {
         self.description = Some(description.to_owned());
         self
     }
+
+    /// Allow the directive to be used more than once at the same location
+    pub fn repeatable(mut self) -> DirectiveType<'a> {
+        self.is_repeatable = true;
+        self
+    }
 }
 
 impl fmt::Display for DirectiveLocation {
@@ -402,10 +761,23 @@ impl fmt::Display for DirectiveLocation {
         f.write_str(match *self {
             DirectiveLocation::Query => "query",
             DirectiveLocation::Mutation => "mutation",
+            DirectiveLocation::Subscription => "subscription",
             DirectiveLocation::Field => "field",
             DirectiveLocation::FragmentDefinition => "fragment definition",
             DirectiveLocation::FragmentSpread => "fragment spread",
             DirectiveLocation::InlineFragment => "inline fragment",
+            DirectiveLocation::VariableDefinition => "variable definition",
+            DirectiveLocation::Schema => "schema",
+            DirectiveLocation::Scalar => "scalar",
+            DirectiveLocation::Object => "object",
+            DirectiveLocation::FieldDefinition => "field definition",
+            DirectiveLocation::ArgumentDefinition => "argument definition",
+            DirectiveLocation::Interface => "interface",
+            DirectiveLocation::Union => "union",
+            DirectiveLocation::Enum => "enum",
+            DirectiveLocation::EnumValue => "enum value",
+            DirectiveLocation::InputObject => "input object",
+            DirectiveLocation::InputFieldDefinition => "input field definition",
         })
     }
 }
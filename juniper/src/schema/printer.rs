@@ -0,0 +1,539 @@
+//! Printing schemas in the GraphQL schema definition language
+
+use ast::InputValue;
+use schema::meta::{
+    AppliedDirective, Argument, EnumMeta, EnumValue, Field, InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta,
+    ScalarMeta, UnionMeta,
+};
+use schema::model::{DirectiveLocation, DirectiveType, SchemaType};
+
+const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+const BUILTIN_DIRECTIVES: &[&str] = &["skip", "include", "deprecated"];
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+impl<'a> SchemaType<'a> {
+    /// Print the schema in the GraphQL schema definition language
+    ///
+    /// Built-in scalars and directives as well as the introspection types are
+    /// left out. Directives and types are sorted by name, so the output only
+    /// changes when the schema does.
+    pub fn as_schema_language(&self) -> String {
+        let mut definitions = Vec::new();
+
+        if let Some(schema_definition) = print_schema_definition(self) {
+            definitions.push(schema_definition);
+        }
+
+        let mut directives = self
+            .directive_list()
+            .into_iter()
+            .filter(|d| !BUILTIN_DIRECTIVES.contains(&d.name.as_str()))
+            .collect::<Vec<_>>();
+        directives.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions.extend(directives.into_iter().map(print_directive));
+
+        let mut types = self
+            .concrete_type_list()
+            .into_iter()
+            .filter(|t| t.name().map(|n| !is_builtin_type(n)).unwrap_or(false))
+            .collect::<Vec<_>>();
+        types.sort_by(|a, b| a.name().cmp(&b.name()));
+        definitions.extend(types.into_iter().filter_map(print_type));
+
+        let mut sdl = definitions.join("\n\n");
+        sdl.push('\n');
+        sdl
+    }
+}
+
+pub(crate) fn is_builtin_type(name: &str) -> bool {
+    name.starts_with("__")
+        || name == "_EmptyMutation"
+        || name == "_EmptySubscription"
+        || BUILTIN_SCALARS.contains(&name)
+}
+
+/// The schema definition is only needed when the root types don't use the
+/// default names
+fn print_schema_definition(schema: &SchemaType) -> Option<String> {
+    let query_name = schema.concrete_query_type().name().unwrap_or("Query");
+    let mutation_name = schema.concrete_mutation_type().and_then(|t| t.name());
+    let subscription_name = schema.concrete_subscription_type().and_then(|t| t.name());
+
+    if query_name == "Query"
+        && mutation_name.map(|n| n == "Mutation").unwrap_or(true)
+        && subscription_name
+            .map(|n| n == "Subscription")
+            .unwrap_or(true)
+    {
+        return None;
+    }
+
+    let mut out = format!("schema {{\n  query: {}\n", query_name);
+    if let Some(name) = mutation_name {
+        out.push_str(&format!("  mutation: {}\n", name));
+    }
+    if let Some(name) = subscription_name {
+        out.push_str(&format!("  subscription: {}\n", name));
+    }
+    out.push('}');
+
+    Some(out)
+}
+
+fn print_directive(directive: &DirectiveType) -> String {
+    format!(
+        "{}directive @{}{}{} on {}",
+        print_description(directive.description.as_ref(), ""),
+        directive.name,
+        print_arguments(&directive.arguments, ""),
+        if directive.is_repeatable {
+            " repeatable"
+        } else {
+            ""
+        },
+        directive
+            .locations
+            .iter()
+            .map(directive_location_name)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    )
+}
+
+fn directive_location_name(location: &DirectiveLocation) -> &'static str {
+    match *location {
+        DirectiveLocation::Query => "QUERY",
+        DirectiveLocation::Mutation => "MUTATION",
+        DirectiveLocation::Subscription => "SUBSCRIPTION",
+        DirectiveLocation::Field => "FIELD",
+        DirectiveLocation::FragmentDefinition => "FRAGMENT_DEFINITION",
+        DirectiveLocation::FragmentSpread => "FRAGMENT_SPREAD",
+        DirectiveLocation::InlineFragment => "INLINE_FRAGMENT",
+        DirectiveLocation::VariableDefinition => "VARIABLE_DEFINITION",
+        DirectiveLocation::Schema => "SCHEMA",
+        DirectiveLocation::Scalar => "SCALAR",
+        DirectiveLocation::Object => "OBJECT",
+        DirectiveLocation::FieldDefinition => "FIELD_DEFINITION",
+        DirectiveLocation::ArgumentDefinition => "ARGUMENT_DEFINITION",
+        DirectiveLocation::Interface => "INTERFACE",
+        DirectiveLocation::Union => "UNION",
+        DirectiveLocation::Enum => "ENUM",
+        DirectiveLocation::EnumValue => "ENUM_VALUE",
+        DirectiveLocation::InputObject => "INPUT_OBJECT",
+        DirectiveLocation::InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+    }
+}
+
+fn print_type(meta_type: &MetaType) -> Option<String> {
+    match *meta_type {
+        MetaType::Scalar(ref s) => Some(print_scalar(s)),
+        MetaType::Object(ref o) => Some(print_object(o)),
+        MetaType::Interface(ref i) => Some(print_interface(i)),
+        MetaType::Union(ref u) => Some(print_union(u)),
+        MetaType::Enum(ref e) => Some(print_enum(e)),
+        MetaType::InputObject(ref i) => Some(print_input_object(i)),
+        MetaType::List(_) | MetaType::Nullable(_) | MetaType::Placeholder(_) => None,
+    }
+}
+
+fn print_scalar(meta: &ScalarMeta) -> String {
+    format!(
+        "{}scalar {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name
+    )
+}
+
+fn print_object(meta: &ObjectMeta) -> String {
+    let implements = if meta.interface_names.is_empty() {
+        String::new()
+    } else {
+        format!(" implements {}", meta.interface_names.join(" & "))
+    };
+
+    format!(
+        "{}type {}{}{} {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name,
+        implements,
+        print_applied_directives(&meta.directives),
+        print_fields(&meta.fields)
+    )
+}
+
+fn print_interface(meta: &InterfaceMeta) -> String {
+    format!(
+        "{}interface {} {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name,
+        print_fields(&meta.fields)
+    )
+}
+
+fn print_union(meta: &UnionMeta) -> String {
+    format!(
+        "{}union {} = {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name,
+        meta.of_type_names.join(" | ")
+    )
+}
+
+fn print_enum(meta: &EnumMeta) -> String {
+    format!(
+        "{}enum {} {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name,
+        print_block(meta.values.iter().map(print_enum_value))
+    )
+}
+
+fn print_enum_value(value: &EnumValue) -> String {
+    format!(
+        "{}  {}{}",
+        print_description(value.description.as_ref(), "  "),
+        value.name,
+        print_deprecation(value.deprecation_reason.as_ref())
+    )
+}
+
+fn print_input_object(meta: &InputObjectMeta) -> String {
+    format!(
+        "{}input {} {}",
+        print_description(meta.description.as_ref(), ""),
+        meta.name,
+        print_block(meta.input_fields.iter().map(|f| format!(
+            "{}  {}",
+            print_description(f.description.as_ref(), "  "),
+            print_input_value(f)
+        )))
+    )
+}
+
+fn print_fields(fields: &[Field]) -> String {
+    print_block(
+        fields
+            .iter()
+            .filter(|f| !f.name.starts_with("__"))
+            .map(print_field),
+    )
+}
+
+fn print_field(field: &Field) -> String {
+    format!(
+        "{}  {}{}: {}{}{}",
+        print_description(field.description.as_ref(), "  "),
+        field.name,
+        field
+            .arguments
+            .as_ref()
+            .map(|args| print_arguments(args, "  "))
+            .unwrap_or_default(),
+        field.field_type,
+        print_deprecation(field.deprecation_reason.as_ref()),
+        print_applied_directives(&field.directives)
+    )
+}
+
+fn print_block<I: Iterator<Item = String>>(items: I) -> String {
+    let items = items.collect::<Vec<_>>();
+
+    if items.is_empty() {
+        "{}".to_owned()
+    } else {
+        format!("{{\n{}\n}}", items.join("\n"))
+    }
+}
+
+/// Arguments are printed on a single line, unless one of them has a
+/// description
+fn print_arguments(args: &[Argument], indentation: &str) -> String {
+    if args.is_empty() {
+        String::new()
+    } else if args.iter().all(|a| a.description.is_none()) {
+        format!(
+            "({})",
+            args.iter()
+                .map(print_input_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    } else {
+        let arg_indentation = format!("{}  ", indentation);
+
+        format!(
+            "(\n{}\n{})",
+            args.iter()
+                .map(|a| format!(
+                    "{}{}{}",
+                    print_description(a.description.as_ref(), &arg_indentation),
+                    arg_indentation,
+                    print_input_value(a)
+                ))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            indentation
+        )
+    }
+}
+
+fn print_input_value(arg: &Argument) -> String {
+    match arg.default_value {
+        Some(ref value) => format!("{}: {} = {}", arg.name, arg.arg_type, print_value(value)),
+        None => format!("{}: {}", arg.name, arg.arg_type),
+    }
+}
+
+fn print_value(value: &InputValue) -> String {
+    match *value {
+        InputValue::String(ref s) => print_string(s),
+        InputValue::List(ref items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|i| print_value(&i.item))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InputValue::Object(ref fields) => format!(
+            "{{{}}}",
+            fields
+                .iter()
+                .map(|&(ref k, ref v)| format!("{}: {}", k.item, print_value(&v.item)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ref other => other.to_string(),
+    }
+}
+
+fn print_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+fn print_deprecation(reason: Option<&String>) -> String {
+    match reason {
+        None => String::new(),
+        Some(reason) if reason == DEFAULT_DEPRECATION_REASON => " @deprecated".to_owned(),
+        Some(reason) => format!(" @deprecated(reason: {})", print_string(reason)),
+    }
+}
+
+fn print_applied_directives(directives: &[AppliedDirective]) -> String {
+    directives
+        .iter()
+        .map(|d| {
+            if d.arguments.is_empty() {
+                format!(" @{}", d.name)
+            } else {
+                format!(
+                    " @{}({})",
+                    d.name,
+                    d.arguments
+                        .iter()
+                        .map(|&(ref name, ref value)| format!("{}: {}", name, value))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        })
+        .collect()
+}
+
+/// Descriptions are printed as block strings, on a single line if they are
+/// short enough
+fn print_description(description: Option<&String>, indentation: &str) -> String {
+    let description = match description {
+        Some(d) => d.replace("\"\"\"", "\\\"\"\""),
+        None => return String::new(),
+    };
+
+    if description.len() <= 70 && !description.contains('\n') && !description.ends_with('"') {
+        format!("{}\"\"\"{}\"\"\"\n", indentation, description)
+    } else {
+        let mut out = format!("{}\"\"\"\n", indentation);
+        for line in description.lines() {
+            if !line.is_empty() {
+                out.push_str(indentation);
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&format!("{}\"\"\"\n", indentation));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::model::{DirectiveLocation, DirectiveType, RootNode};
+    use tests::model::Database;
+    use types::scalars::EmptyMutation;
+
+    #[test]
+    fn prints_star_wars_schema() {
+        let database = Database::new();
+        let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+
+        assert_eq!(
+            schema.as_schema_language(),
+            r#""""A character in the Star Wars Trilogy"""
+interface Character {
+  """The id of the character"""
+  id: String!
+  """The name of the character"""
+  name: String
+  """The friends of the character"""
+  friends: [Character!]!
+  """Which movies they appear in"""
+  appearsIn: [Episode!]!
+}
+
+"""A mechanical creature in the Star Wars universe."""
+type Droid implements Character {
+  """The id of the droid"""
+  id: String!
+  """The name of the droid"""
+  name: String
+  """The friends of the droid"""
+  friends: [Character!]!
+  """Which movies they appear in"""
+  appearsIn: [Episode!]!
+  """The primary function of the droid"""
+  primaryFunction: String
+}
+
+enum Episode {
+  NEW_HOPE
+  EMPIRE
+  JEDI
+}
+
+"""A humanoid creature in the Star Wars universe."""
+type Human implements Character {
+  """The id of the human"""
+  id: String!
+  """The name of the human"""
+  name: String
+  """The friends of the human"""
+  friends: [Character!]!
+  """Which movies they appear in"""
+  appearsIn: [Episode!]!
+  """The home planet of the human"""
+  homePlanet: String
+}
+
+"""The root query object of the schema"""
+type Query {
+  human(
+    """id of the human"""
+    id: String!
+  ): Human
+  droid(
+    """id of the droid"""
+    id: String!
+  ): Droid
+  hero(
+    """
+    If omitted, returns the hero of the whole saga. If provided, returns the hero of that particular episode
+    """
+    episode: Episode
+  ): Character
+}
+"#
+        );
+    }
+
+    #[derive(GraphQLInputObject)]
+    #[graphql(_internal)]
+    struct Filter {
+        name: Option<String>,
+        #[graphql(
+            default = "vec![1, 2]",
+            description = "Only these \"\"\"ids\"\"\"\nPlease"
+        )]
+        ids: Vec<i32>,
+    }
+
+    struct Root;
+
+    graphql_object!(Root: () |&self| {
+        description: "A description that is long enough to be printed over multiple lines of text"
+
+        field search(
+            query = ("\"all\"".to_owned()): String,
+            first: Option<i32>,
+            filter: Option<Filter>,
+        ) -> Option<String> {
+            None
+        }
+
+        field deprecated "No longer supported" old() -> Option<String> {
+            None
+        }
+
+        field deprecated "Use \"search\"" older() -> Option<String> {
+            None
+        }
+    });
+
+    #[test]
+    fn prints_arguments_deprecations_and_schema_definition() {
+        let mut schema = RootNode::new(Root, EmptyMutation::<()>::new());
+        schema.schema.add_directive(
+            DirectiveType::new(
+                "cached",
+                &[DirectiveLocation::Query, DirectiveLocation::Field],
+                &[],
+            )
+            .description("Cache the result")
+            .repeatable(),
+        );
+
+        assert_eq!(
+            schema.as_schema_language(),
+            r#"schema {
+  query: Root
+}
+
+"""Cache the result"""
+directive @cached repeatable on QUERY | FIELD
+
+input Filter {
+  name: String
+  """
+  Only these \"""ids\"""
+  Please
+  """
+  ids: [Int!] = [1, 2]
+}
+
+"""
+A description that is long enough to be printed over multiple lines of text
+"""
+type Root {
+  search(query: String = "\"all\"", first: Int, filter: Filter): String
+  old: String @deprecated
+  older: String @deprecated(reason: "Use \"search\"")
+}
+"#
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use executor::{ExecutionResult, Executor, Registry};
+use executor::{ExecutionFuture, ExecutionResult, Executor, Registry};
 use types::base::{Arguments, GraphQLType, TypeKind};
 use value::Value;
 use ast::Selection;
@@ -9,10 +9,12 @@ use schema::meta::{
 };
 use schema::model::{DirectiveLocation, DirectiveType, RootNode, SchemaType, TypeType};
 
-impl<'a, CtxT, QueryT, MutationT> GraphQLType for RootNode<'a, QueryT, MutationT>
+impl<'a, CtxT, QueryT, MutationT, SubscriptionT> GraphQLType
+    for RootNode<'a, QueryT, MutationT, SubscriptionT>
 where
     QueryT: GraphQLType<Context = CtxT>,
     MutationT: GraphQLType<Context = CtxT>,
+    SubscriptionT: GraphQLType<Context = CtxT>,
 {
     type Context = CtxT;
     type TypeInfo = QueryT::TypeInfo;
@@ -46,6 +48,22 @@ where
         }
     }
 
+    fn resolve_field_async<'b>(
+        &self,
+        info: &'b QueryT::TypeInfo,
+        field: &str,
+        args: &Arguments,
+        executor: &Executor<'b, CtxT>,
+    ) -> ExecutionFuture<'b> {
+        match field {
+            "__schema" | "__type" => {
+                Box::new(::futures::future::result(self.resolve_field(info, field, args, executor)))
+            }
+            _ => self.query_type
+                .resolve_field_async(info, field, args, executor),
+        }
+    }
+
     fn resolve(
         &self,
         info: &Self::TypeInfo,
@@ -67,11 +85,12 @@ where
     }
 }
 
-graphql_object!(<'a> SchemaType<'a>: SchemaType<'a> as "__Schema" |&self| {
+graphql_object!(@sync_only <'a> SchemaType<'a>: SchemaType<'a> as "__Schema" |&self| {
     field types() -> Vec<TypeType> {
         self.type_list()
             .into_iter()
             .filter(|t| t.to_concrete().map(|t| t.name() != Some("_EmptyMutation")).unwrap_or(false))
+            .filter(|t| t.to_concrete().map(|t| t.name() != Some("_EmptySubscription")).unwrap_or(false))
             .collect()
     }
 
@@ -83,9 +102,8 @@ graphql_object!(<'a> SchemaType<'a>: SchemaType<'a> as "__Schema" |&self| {
         self.mutation_type()
     }
 
-    // Included for compatibility with the introspection query in GraphQL.js
     field subscription_type() -> Option<TypeType> {
-        None
+        self.subscription_type()
     }
 
     field directives() -> Vec<&DirectiveType> {
@@ -93,7 +111,7 @@ graphql_object!(<'a> SchemaType<'a>: SchemaType<'a> as "__Schema" |&self| {
     }
 });
 
-graphql_object!(<'a> TypeType<'a>: SchemaType<'a> as "__Type" |&self| {
+graphql_object!(@sync_only <'a> TypeType<'a>: SchemaType<'a> as "__Type" |&self| {
     field name() -> Option<&str> {
         match *self {
             TypeType::Concrete(t) => t.name(),
@@ -198,7 +216,7 @@ graphql_object!(<'a> TypeType<'a>: SchemaType<'a> as "__Type" |&self| {
     }
 });
 
-graphql_object!(<'a> Field<'a>: SchemaType<'a> as "__Field" |&self| {
+graphql_object!(@sync_only <'a> Field<'a>: SchemaType<'a> as "__Field" |&self| {
     field name() -> &String {
         &self.name
     }
@@ -224,7 +242,7 @@ graphql_object!(<'a> Field<'a>: SchemaType<'a> as "__Field" |&self| {
     }
 });
 
-graphql_object!(<'a> Argument<'a>: SchemaType<'a> as "__InputValue" |&self| {
+graphql_object!(@sync_only <'a> Argument<'a>: SchemaType<'a> as "__InputValue" |&self| {
     field name() -> &String {
         &self.name
     }
@@ -260,7 +278,7 @@ graphql_object!(EnumValue: () as "__EnumValue" |&self| {
     }
 });
 
-graphql_object!(<'a> DirectiveType<'a>: SchemaType<'a> as "__Directive" |&self| {
+graphql_object!(@sync_only <'a> DirectiveType<'a>: SchemaType<'a> as "__Directive" |&self| {
     field name() -> &String {
         &self.name
     }
@@ -277,6 +295,10 @@ graphql_object!(<'a> DirectiveType<'a>: SchemaType<'a> as "__Directive" |&self|
         &self.arguments
     }
 
+    field is_repeatable() -> bool {
+        self.is_repeatable
+    }
+
     // Included for compatibility with the introspection query in GraphQL.js
     field deprecated "Use the locations array instead"
     on_operation() -> bool {
@@ -0,0 +1,367 @@
+use ast::InputValue;
+use executor::{FieldResult, Variables};
+use schema::dynamic::{
+    DynamicArgument, DynamicEnumValue, DynamicField, DynamicRootNode, DynamicSchema, DynamicType,
+};
+use value::Value;
+
+static SCHEMA: &str = r#"
+    schema {
+        query: Root
+        mutation: Mutations
+    }
+
+    "A character of the saga"
+    interface Character {
+        id: ID!
+        name: String!
+        friends: [Character!]!
+    }
+
+    type Human implements Character {
+        id: ID!
+        name: String!
+        friends: [Character!]!
+        homePlanet: String
+    }
+
+    type Droid implements Character {
+        id: ID!
+        name: String!
+        friends: [Character!]!
+        primaryFunction: String @deprecated(reason: "Use tags")
+    }
+
+    union SearchResult = Human | Droid
+
+    enum Episode {
+        NEW_HOPE
+        EMPIRE
+        JEDI
+    }
+
+    input Filter {
+        name: String
+        episode: Episode = NEW_HOPE
+    }
+
+    type Root {
+        hero(episode: Episode): Character
+        search(filter: Filter!): [SearchResult!]!
+        rating: Float!
+        missing: String!
+    }
+
+    type Mutations {
+        rename(name: String!): String!
+    }
+"#;
+
+fn luke() -> Value {
+    graphql_value!({
+        "__typename": "Human",
+        "id": "1000",
+        "name": "Luke Skywalker",
+        "homePlanet": "Tatooine",
+        "friends": [
+            { "__typename": "Droid", "id": "2001", "name": "R2-D2", "friends": [] },
+        ],
+    })
+}
+
+fn schema<'a>() -> DynamicRootNode<'a, ()> {
+    DynamicSchema::from_schema_language(SCHEMA)
+        .expect("Invalid schema")
+        .field_resolver("Root", "hero", |_, args, _| {
+            match args.get::<InputValue>("episode") {
+                Some(InputValue::Enum(ref e)) if e == "EMPIRE" => Ok(luke()),
+                _ => Ok(graphql_value!({
+                    "__typename": "Droid",
+                    "id": "2001",
+                    "name": "R2-D2",
+                    "friends": [],
+                })),
+            }
+        })
+        .field_resolver("Root", "search", |_, args, _| {
+            let filter = args.get::<InputValue>("filter").expect("Filter missing");
+            let filter = filter.to_object_value().expect("Filter is no object");
+            assert_eq!(filter["name"], &InputValue::string("Luke"));
+
+            Ok(Value::list(vec![luke()]))
+        })
+        .field_resolver("Mutations", "rename", |_, args, _| {
+            let name = args.get::<String>("name").expect("Name missing");
+            Ok(Value::string(name))
+        })
+        .root_value(graphql_value!({ "rating": 5 }))
+        .into_root_node()
+        .expect("Invalid schema")
+}
+
+fn run_query(query: &str) -> (Value, Vec<String>) {
+    let (result, errs) =
+        ::execute(query, None, &schema(), &Variables::new(), &()).expect("Execution failed");
+
+    (
+        result,
+        errs.iter().map(|e| e.error().message().to_owned()).collect(),
+    )
+}
+
+#[test]
+fn resolves_fields_of_interfaces() {
+    let (result, errs) = run_query(
+        r#"{
+            hero(episode: EMPIRE) {
+                __typename
+                name
+                ... on Human { homePlanet }
+                friends { name ... on Droid { primaryFunction } }
+            }
+        }"#,
+    );
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(
+        result,
+        graphql_value!({
+            "hero": {
+                "__typename": "Human",
+                "name": "Luke Skywalker",
+                "homePlanet": "Tatooine",
+                "friends": [{ "name": "R2-D2", "primaryFunction": None }],
+            },
+        })
+    );
+}
+
+#[test]
+fn resolves_unions_and_input_objects() {
+    let (result, errs) = run_query(
+        r#"{
+            search(filter: { name: "Luke" }) {
+                ... on Human { id }
+                ... on Droid { primaryFunction }
+            }
+        }"#,
+    );
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(result, graphql_value!({ "search": [{ "id": "1000" }] }));
+}
+
+#[test]
+fn resolves_fields_of_the_root_value() {
+    let (result, errs) = run_query("{ rating }");
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(result, graphql_value!({ "rating": 5.0 }));
+}
+
+#[test]
+fn resolves_mutations() {
+    let (result, errs) = run_query(r#"mutation { rename(name: "Leia") }"#);
+
+    assert_eq!(errs, Vec::<String>::new());
+    assert_eq!(result, graphql_value!({ "rename": "Leia" }));
+}
+
+#[test]
+fn reports_values_not_matching_the_schema() {
+    let (result, errs) = run_query("{ missing }");
+
+    assert_eq!(
+        errs,
+        vec![r#"Can not return null for the non-null type "String!""#.to_owned()]
+    );
+    assert_eq!(result, Value::null());
+}
+
+#[test]
+fn builds_schemas_programmatically() {
+    let schema: DynamicRootNode<()> = DynamicSchema::new("Query")
+        .add_type(
+            DynamicType::enum_type(
+                "Color",
+                vec![
+                    DynamicEnumValue::new("RED"),
+                    DynamicEnumValue::new("GREEN").deprecated("Too natural"),
+                ],
+            ).description("A color"),
+        )
+        .add_type(DynamicType::object(
+            "Query",
+            vec![
+                DynamicField::new("colors", "[Color!]!").argument(
+                    DynamicArgument::new("count", "Int").default_value(InputValue::int(2)),
+                ),
+            ],
+        ))
+        .field_resolver("Query", "colors", |_, args, _| -> FieldResult<Value> {
+            let count = args.get::<i32>("count").expect("Count missing");
+
+            Ok(Value::list(
+                (0..count).map(|_| Value::string("RED")).collect(),
+            ))
+        })
+        .into_root_node()
+        .expect("Invalid schema");
+
+    assert_eq!(
+        ::execute("{ colors }", None, &schema, &Variables::new(), &()),
+        Ok((graphql_value!({ "colors": ["RED", "RED"] }), vec![]))
+    );
+
+    assert_eq!(
+        schema.as_schema_language(),
+        r#""""A color"""
+enum Color {
+  RED
+  GREEN @deprecated(reason: "Too natural")
+}
+
+type Query {
+  colors(count: Int = 2): [Color!]!
+}
+"#
+    );
+}
+
+#[test]
+fn prints_the_schema_it_was_read_from() {
+    let printed = schema().as_schema_language();
+    let reread: DynamicRootNode<()> = DynamicSchema::from_schema_language(&printed)
+        .expect("Invalid schema")
+        .into_root_node()
+        .expect("Invalid schema");
+
+    assert_eq!(reread.as_schema_language(), printed);
+}
+
+#[test]
+fn merges_type_extensions() {
+    let schema: DynamicRootNode<()> = DynamicSchema::from_schema_language(
+        r#"
+        type Query { a: Int }
+        extend type Query { b: Int }
+        "#,
+    ).expect("Invalid schema")
+        .root_value(graphql_value!({ "a": 1, "b": 2 }))
+        .into_root_node()
+        .expect("Invalid schema");
+
+    assert_eq!(
+        ::execute("{ a b }", None, &schema, &Variables::new(), &()),
+        Ok((graphql_value!({ "a": 1, "b": 2 }), vec![]))
+    );
+}
+
+#[test]
+fn rejects_invalid_schemas() {
+    fn error(source: &str) -> String {
+        let schema = DynamicSchema::<()>::from_schema_language(source)
+            .and_then(|schema| schema.into_root_node().map(|_| ()));
+
+        match schema {
+            Ok(()) => panic!("Schema is valid"),
+            Err(e) => e.message().to_owned(),
+        }
+    }
+
+    assert_eq!(
+        error("type Query { a: Int"),
+        "Syntax error: Unexpected end of input"
+    );
+    assert_eq!(
+        error("{ a }"),
+        "Operations can not be part of a schema"
+    );
+    assert_eq!(
+        error("type Other { a: Int }"),
+        r#"Query type "Query" is not defined"#
+    );
+    assert_eq!(
+        error("type Query { a: User }"),
+        r#""Query.a" has the unknown type "User""#
+    );
+    assert_eq!(
+        error("type Query { a(b: Query): Int }"),
+        r#""Query.a(b)" can not have the output type "Query""#
+    );
+    assert_eq!(
+        error("interface Node { id: ID! } type Query implements Node { a: Int }"),
+        r#"Type "Query" does not define the field "id" of interface "Node""#
+    );
+    assert_eq!(
+        error("type Query { a: Int } extend union Query = Query"),
+        r#"Type "Query" can not be extended with a different kind"#
+    );
+    assert_eq!(
+        error("type Query { a: Int } type Query { b: Int }"),
+        r#"Type "Query" is defined more than once"#
+    );
+}
+
+#[test]
+fn rejects_invalid_directives() {
+    fn error(source: &str) -> String {
+        let schema = DynamicSchema::<()>::from_schema_language(source)
+            .and_then(|schema| schema.into_root_node().map(|_| ()));
+
+        match schema {
+            Ok(()) => panic!("Schema is valid"),
+            Err(e) => e.message().to_owned(),
+        }
+    }
+
+    assert_eq!(
+        error("type Query { a: Int @auth }"),
+        r#"Unknown directive "@auth" on "Query.a""#
+    );
+    assert_eq!(
+        error("directive @auth on OBJECT type Query { a: Int @auth }"),
+        r#"Directive "@auth" can not be used on the field definition "Query.a""#
+    );
+    assert_eq!(
+        error("directive @auth(role: String!) on OBJECT type Query @auth { a: Int }"),
+        r#"Directive "@auth" on "Query" is missing the required argument "role""#
+    );
+    assert_eq!(
+        error(r#"directive @auth on OBJECT type Query @auth(role: "A") { a: Int }"#),
+        r#"Unknown argument "role" of directive "@auth" on "Query""#
+    );
+    assert_eq!(
+        error("directive @auth(role: Query) on OBJECT type Query { a: Int }"),
+        r#""@auth(role)" can not have the output type "Query""#
+    );
+    assert_eq!(
+        error("directive @skip on FIELD type Query { a: Int }"),
+        r#"Built-in directive "@skip" can not be redefined"#
+    );
+    assert_eq!(
+        error("directive @auth on OBJECT type Query @auth @auth { a: Int }"),
+        r#"Directive "@auth" can not be repeated on "Query""#
+    );
+    assert!(
+        DynamicSchema::<()>::from_schema_language(
+            "directive @tag repeatable on SCALAR | OBJECT type Query @tag @tag { a: Int }"
+        ).and_then(|schema| schema.into_root_node())
+            .is_ok()
+    );
+}
+
+#[test]
+fn registers_types_of_directive_arguments() {
+    let schema = DynamicSchema::<()>::from_schema_language(
+        "directive @auth(role: Role!) on OBJECT enum Role { ADMIN } type Query @auth(role: ADMIN) { a: Int }",
+    ).expect("Invalid schema")
+        .into_root_node()
+        .expect("Invalid schema");
+
+    assert!(schema.schema.concrete_type_by_name("Role").is_some());
+    assert_eq!(
+        schema.schema.directive_by_name("auth").map(|d| d.arguments.len()),
+        Some(1)
+    );
+}
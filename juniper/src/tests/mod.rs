@@ -1,5 +1,7 @@
 //! Library tests and fixtures
 
+#[cfg(test)]
+mod dynamic_schema_tests;
 #[cfg(test)]
 mod introspection_tests;
 pub mod model;
@@ -7,4 +9,6 @@ pub mod model;
 mod query_tests;
 mod schema;
 #[cfg(test)]
+mod schema_diff_tests;
+#[cfg(test)]
 mod type_info_tests;
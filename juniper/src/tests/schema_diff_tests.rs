@@ -0,0 +1,264 @@
+use executor::Variables;
+use schema::diff::{
+    diff_introspection, diff_schema_language, Criticality, SchemaChangeKind, INTROSPECTION_QUERY,
+};
+use schema::dynamic::DynamicSchema;
+use schema::model::RootNode;
+use tests::model::Database;
+use types::scalars::EmptyMutation;
+use value::Value;
+
+static OLD_SCHEMA: &str = r#"
+    interface Character {
+        id: ID!
+        name: String
+    }
+
+    type Human implements Character {
+        id: ID!
+        name: String
+        homePlanet: String
+        height: Float
+    }
+
+    type Droid implements Character {
+        id: ID!
+        name: String
+        primaryFunction: String!
+    }
+
+    type Starship {
+        id: ID!
+    }
+
+    union SearchResult = Human | Droid
+
+    enum Episode {
+        NEW_HOPE
+        EMPIRE
+        JEDI
+    }
+
+    input Filter {
+        name: String
+        episode: Episode = NEW_HOPE
+    }
+
+    type Query {
+        hero(episode: Episode): Character
+        search(filter: Filter!, first: Int = 10): [SearchResult]
+        starship(id: ID!): Starship
+    }
+"#;
+
+static NEW_SCHEMA: &str = r#"
+    interface Character {
+        id: ID!
+        name: String!
+    }
+
+    interface Node {
+        id: ID!
+    }
+
+    type Human implements Character & Node {
+        id: ID!
+        name: String!
+        homePlanet: String @deprecated
+    }
+
+    type Droid {
+        id: ID!
+        name: String!
+        primaryFunction: String
+    }
+
+    scalar Starship
+
+    union SearchResult = Human
+
+    enum Episode {
+        NEW_HOPE
+        EMPIRE
+        CLONES
+    }
+
+    input Filter {
+        name: String
+        episode: Episode = EMPIRE
+        since: Int!
+    }
+
+    type Query {
+        hero(episode: Episode!): Character
+        search(filter: Filter, first: Int = 10, orderBy: String): [SearchResult]
+        starship(id: ID!): Starship
+        droids: [Droid!]!
+    }
+"#;
+
+fn changes(old: &str, new: &str) -> Vec<(SchemaChangeKind, Criticality, String)> {
+    diff_schema_language(old, new)
+        .expect("Invalid schema")
+        .into_iter()
+        .map(|c| (c.kind(), c.criticality(), c.path().to_owned()))
+        .collect()
+}
+
+fn change(
+    kind: SchemaChangeKind,
+    criticality: Criticality,
+    path: &str,
+) -> (SchemaChangeKind, Criticality, String) {
+    (kind, criticality, path.to_owned())
+}
+
+fn introspect(sdl: &str) -> Value {
+    let schema = DynamicSchema::<()>::from_schema_language(sdl)
+        .expect("Invalid schema")
+        .into_root_node()
+        .expect("Invalid schema");
+
+    let (result, errs) = ::execute(INTROSPECTION_QUERY, None, &schema, &Variables::new(), &())
+        .expect("Introspection failed");
+    assert_eq!(errs, []);
+
+    result
+}
+
+#[test]
+fn unchanged_schemas_have_no_changes() {
+    assert_eq!(changes(OLD_SCHEMA, OLD_SCHEMA), vec![]);
+
+    let database = Database::new();
+    let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+    assert_eq!(schema.schema.diff(&schema.schema), vec![]);
+}
+
+#[test]
+fn classifies_changes() {
+    use self::Criticality::*;
+    use self::SchemaChangeKind::*;
+
+    assert_eq!(
+        changes(OLD_SCHEMA, NEW_SCHEMA),
+        vec![
+            change(FieldTypeChanged, Safe, "Character.name"),
+            change(FieldTypeChanged, Safe, "Droid.name"),
+            change(FieldTypeChanged, Breaking, "Droid.primaryFunction"),
+            change(InterfaceRemoved, Breaking, "Droid"),
+            change(EnumValueRemoved, Breaking, "Episode.JEDI"),
+            change(EnumValueAdded, Dangerous, "Episode.CLONES"),
+            change(InputFieldDefaultValueChanged, Dangerous, "Filter.episode"),
+            change(InputFieldAdded, Breaking, "Filter.since"),
+            change(FieldTypeChanged, Safe, "Human.name"),
+            change(FieldDeprecated, Safe, "Human.homePlanet"),
+            change(FieldRemoved, Breaking, "Human.height"),
+            change(InterfaceAdded, Dangerous, "Human"),
+            change(TypeAdded, Safe, "Node"),
+            change(ArgumentTypeChanged, Breaking, "Query.hero.episode"),
+            change(ArgumentTypeChanged, Safe, "Query.search.filter"),
+            change(ArgumentAdded, Dangerous, "Query.search.orderBy"),
+            change(FieldAdded, Safe, "Query.droids"),
+            change(UnionMemberRemoved, Breaking, "SearchResult"),
+            change(TypeKindChanged, Breaking, "Starship"),
+        ]
+    );
+}
+
+#[test]
+fn compares_wrapped_types() {
+    use self::Criticality::*;
+    use self::SchemaChangeKind::*;
+
+    assert_eq!(
+        changes(
+            "type Query { a: [String] b: [String!]! c: [String] d(x: [Int!]): Int e(x: [Int]): Int }",
+            "type Query { a: [String!]! b: [String]! c: String d(x: [Int]): Int e(x: [Int!]): Int }",
+        ),
+        vec![
+            change(FieldTypeChanged, Safe, "Query.a"),
+            change(FieldTypeChanged, Breaking, "Query.b"),
+            change(FieldTypeChanged, Breaking, "Query.c"),
+            change(ArgumentTypeChanged, Safe, "Query.d.x"),
+            change(ArgumentTypeChanged, Breaking, "Query.e.x"),
+        ]
+    );
+}
+
+#[test]
+fn reports_required_arguments_and_removed_types() {
+    let changes = diff_schema_language(
+        "type Query { user(id: ID!): User } type User { id: ID! }",
+        "type Query { user(id: ID!, name: String!): String }",
+    ).expect("Invalid schema");
+
+    let messages = changes.iter().map(|c| c.message()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "Field Query.user changed type from User to String",
+            "Required argument Query.user.name was added",
+            "Type User was removed",
+        ]
+    );
+    assert!(changes.iter().all(|c| c.is_breaking()));
+}
+
+#[test]
+fn compares_root_types() {
+    use self::Criticality::*;
+    use self::SchemaChangeKind::*;
+
+    assert_eq!(
+        changes(
+            "type Query { a: Int } type Mutation { b: Int }",
+            "schema { query: Root } type Root { a: Int } type Mutation { b: Int }",
+        ),
+        vec![
+            change(RootTypeChanged, Breaking, "Root"),
+            change(RootTypeChanged, Breaking, "Mutation"),
+            change(TypeRemoved, Breaking, "Mutation"),
+            change(TypeRemoved, Breaking, "Query"),
+            change(TypeAdded, Safe, "Root"),
+        ]
+    );
+}
+
+#[test]
+fn compares_introspection_results() {
+    let changes = diff_introspection(&introspect(OLD_SCHEMA), &introspect(NEW_SCHEMA))
+        .expect("Invalid introspection result");
+
+    assert_eq!(
+        changes,
+        diff_schema_language(OLD_SCHEMA, NEW_SCHEMA).expect("Invalid schema")
+    );
+}
+
+#[test]
+fn reads_introspection_of_static_schemas() {
+    let database = Database::new();
+    let schema = RootNode::new(&database, EmptyMutation::<Database>::new());
+
+    let (result, errs) = ::execute(INTROSPECTION_QUERY, None, &schema, &Variables::new(), &database)
+        .expect("Introspection failed");
+    assert_eq!(errs, []);
+
+    let introspected = DynamicSchema::<()>::from_introspection_result(&result)
+        .expect("Invalid introspection result")
+        .into_root_node()
+        .expect("Invalid schema");
+    assert_eq!(schema.schema.diff(&introspected.schema), vec![]);
+}
+
+#[test]
+fn rejects_invalid_introspection_results() {
+    let err = diff_introspection(&graphql_value!({ "data": None }), &introspect(OLD_SCHEMA))
+        .unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        r#"Introspection result is missing the field "__schema""#
+    );
+}
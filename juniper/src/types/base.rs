@@ -1,10 +1,16 @@
+use std::slice;
+
+use futures::future::{self, Future};
+use futures::stream::{self, Stream};
 use indexmap::IndexMap;
 
 use ast::{Directive, FromInputValue, InputValue, Selection};
 use executor::Variables;
 use value::{Object, Value};
 
-use executor::{ExecutionResult, Executor, Registry};
+use executor::{
+    ExecutionFuture, ExecutionResult, Executor, FieldError, FieldFuture, Registry, ResponseWriter,
+};
 use parser::Spanning;
 use schema::meta::{Argument, MetaType};
 
@@ -75,26 +81,32 @@ pub struct Arguments<'a> {
 impl<'a> Arguments<'a> {
     #[doc(hidden)]
     pub fn new(
-        mut args: Option<IndexMap<&'a str, InputValue>>,
+        args: Option<IndexMap<&'a str, InputValue>>,
         meta_args: &'a Option<Vec<Argument>>,
     ) -> Arguments<'a> {
-        if meta_args.is_some() && args.is_none() {
-            args = Some(IndexMap::new());
+        match *meta_args {
+            Some(ref meta_args) => Arguments::with_defaults(args, meta_args),
+            None => Arguments { args: args },
         }
+    }
 
-        if let (&mut Some(ref mut args), &Some(ref meta_args)) = (&mut args, meta_args) {
-            for arg in meta_args {
-                if !args.contains_key(arg.name.as_str()) || args[arg.name.as_str()].is_null() {
-                    if let Some(ref default_value) = arg.default_value {
-                        args.insert(arg.name.as_str(), default_value.clone());
-                    } else {
-                        args.insert(arg.name.as_str(), InputValue::null());
-                    }
+    pub(crate) fn with_defaults(
+        args: Option<IndexMap<&'a str, InputValue>>,
+        meta_args: &'a [Argument],
+    ) -> Arguments<'a> {
+        let mut args = args.unwrap_or_else(IndexMap::new);
+
+        for arg in meta_args {
+            if !args.contains_key(arg.name.as_str()) || args[arg.name.as_str()].is_null() {
+                if let Some(ref default_value) = arg.default_value {
+                    args.insert(arg.name.as_str(), default_value.clone());
+                } else {
+                    args.insert(arg.name.as_str(), InputValue::null());
                 }
             }
         }
 
-        Arguments { args: args }
+        Arguments { args: Some(args) }
     }
 
     /// Get and convert an argument into the desired type.
@@ -319,8 +331,84 @@ pub trait GraphQLType: Sized {
             panic!("resolve() must be implemented by non-object output types");
         }
     }
+
+    /// Resolve the value of a single field on this type asynchronously.
+    ///
+    /// The returned future must not borrow `self`, `arguments` or the
+    /// executor: everything it needs from them should be computed or cloned
+    /// before it is returned.
+    ///
+    /// The default implementation calls `resolve_field` and returns its
+    /// result as a finished future.
+    fn resolve_field_async<'a>(
+        &self,
+        info: &'a Self::TypeInfo,
+        field_name: &str,
+        arguments: &Arguments,
+        executor: &Executor<'a, Self::Context>,
+    ) -> ExecutionFuture<'a> {
+        Box::new(future::result(self.resolve_field(
+            info,
+            field_name,
+            arguments,
+            executor,
+        )))
+    }
+
+    /// Resolve this interface or union into a concrete type asynchronously
+    ///
+    /// The default implementation resolves the instance with `resolve_async`
+    /// if the type name matches, and defers to `resolve_into_type`
+    /// otherwise.
+    fn resolve_into_type_async<'a>(
+        &self,
+        info: &'a Self::TypeInfo,
+        type_name: &str,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, Self::Context>,
+    ) -> ExecutionFuture<'a> {
+        if Self::name(info).unwrap() == type_name {
+            self.resolve_async(info, selection_set, executor)
+        } else {
+            Box::new(future::result(self.resolve_into_type(
+                info,
+                type_name,
+                selection_set,
+                executor,
+            )))
+        }
+    }
+
+    /// Resolve the provided selection set against the current object
+    /// asynchronously.
+    ///
+    /// The default implementation uses `resolve_field_async` to resolve all
+    /// fields of object types, driving all of them concurrently. For
+    /// non-object types, it calls `resolve` and returns its value as a
+    /// finished future. Types overriding `resolve` with custom object
+    /// resolution logic should override this method as well.
+    fn resolve_async<'a>(
+        &self,
+        info: &'a Self::TypeInfo,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, Self::Context>,
+    ) -> ExecutionFuture<'a> {
+        if let Some(selection_set) = selection_set {
+            Box::new(
+                resolve_selection_set_async(self, info, selection_set, executor)
+                    .map(|result| result.map_or_else(Value::null, Value::Object)),
+            )
+        } else {
+            Box::new(future::ok(self.resolve(info, selection_set, executor)))
+        }
+    }
 }
 
+/// Resolves the fields of the selection set into `result`
+///
+/// If the executor has an output, the object is written into it instead and
+/// `result` stays empty. Returns `false` if a non-null field resolved to
+/// `null`, which makes the whole object `null`.
 pub(crate) fn resolve_selection_set_into<T, CtxT>(
     instance: &T,
     info: &T::TypeInfo,
@@ -331,15 +419,35 @@ pub(crate) fn resolve_selection_set_into<T, CtxT>(
 where
     T: GraphQLType<Context = CtxT>,
 {
+    let type_name = T::name(info).expect("Resolving named type's selection set");
     let meta_type = executor
         .schema()
-        .concrete_type_by_name(
-            T::name(info)
-                .expect("Resolving named type's selection set")
-                .as_ref(),
-        )
+        .concrete_type_by_name(type_name.as_ref())
         .expect("Type not found in schema");
 
+    if let Some(output) = executor.output() {
+        let mut response_names = Vec::new();
+
+        return match plan_output(meta_type, selection_set, executor, &mut response_names) {
+            Some(may_become_null) => write_selection_set(
+                instance,
+                info,
+                meta_type,
+                selection_set,
+                executor,
+                output,
+                may_become_null,
+            ),
+            None => resolve_selection_set_into(
+                instance,
+                info,
+                selection_set,
+                &executor.with_output(None),
+                result,
+            ),
+        };
+    }
+
     for selection in selection_set {
         match *selection {
             Selection::Field(Spanning {
@@ -378,22 +486,31 @@ where
                     f.selection_set.as_ref().map(|v| &v[..]),
                 );
 
-                let field_result = instance.resolve_field(
-                    info,
-                    f.name.item,
-                    &Arguments::new(
-                        f.arguments.as_ref().map(|m| {
-                            m.item
-                                .iter()
-                                .map(|&(ref k, ref v)| {
-                                    (k.item, v.item.clone().into_const(exec_vars))
-                                })
-                                .collect()
-                        }),
-                        &meta_field.arguments,
-                    ),
-                    &sub_exec,
-                );
+                let field_hooks = executor.field_hooks(meta_type, meta_field, &f.directives);
+                let field_info = sub_exec.field_started(meta_type, meta_field);
+
+                let field_result = field_hooks.before().and_then(|()| {
+                    let value = instance.resolve_field(
+                        info,
+                        f.name.item,
+                        &Arguments::new(
+                            f.arguments.as_ref().map(|m| {
+                                m.item
+                                    .iter()
+                                    .map(|&(ref k, ref v)| {
+                                        (k.item, v.item.clone().into_const(exec_vars))
+                                    })
+                                    .collect()
+                            }),
+                            &meta_field.arguments,
+                        ),
+                        &sub_exec,
+                    )?;
+
+                    field_hooks.after(value)
+                });
+
+                sub_exec.field_finished(field_info);
 
                 match field_result {
                     Ok(Value::Null) if meta_field.field_type.is_non_null() => return false,
@@ -477,13 +594,575 @@ where
     true
 }
 
-fn is_excluded(directives: &Option<Vec<Spanning<Directive>>>, vars: &Variables) -> bool {
+/// Checks whether the fields of an object can be written as they resolve,
+/// and whether a non-null field can make the object `null`
+///
+/// Returns `None` if the object has to be resolved as a whole: when a
+/// response name occurs more than once, its values have to be merged, and
+/// inline fragments on other types are resolved by `resolve_into_type`.
+fn plan_output<'a, CtxT>(
+    meta_type: &MetaType,
+    selection_set: &'a [Selection<'a>],
+    executor: &Executor<'a, CtxT>,
+    response_names: &mut Vec<&'a str>,
+) -> Option<bool> {
+    let mut may_become_null = false;
+
+    for selection in selection_set {
+        match *selection {
+            Selection::Field(Spanning { item: ref f, .. }) => {
+                if is_excluded(&f.directives, executor.variables()) {
+                    continue;
+                }
+
+                let response_name = f.alias.as_ref().unwrap_or(&f.name).item;
+
+                if response_names.contains(&response_name) {
+                    return None;
+                }
+
+                response_names.push(response_name);
+
+                if f.name.item != "__typename" {
+                    let meta_field = meta_type.field_by_name(f.name.item)?;
+                    may_become_null |= meta_field.field_type.is_non_null();
+                }
+            }
+            Selection::FragmentSpread(Spanning {
+                item: ref spread, ..
+            }) => {
+                if is_excluded(&spread.directives, executor.variables()) {
+                    continue;
+                }
+
+                let fragment = executor
+                    .fragment_by_name(spread.name.item)
+                    .expect("Fragment could not be found");
+
+                may_become_null |=
+                    plan_output(meta_type, &fragment.selection_set[..], executor, response_names)?;
+            }
+            Selection::InlineFragment(Spanning {
+                item: ref fragment,
+                ..
+            }) => {
+                if is_excluded(&fragment.directives, executor.variables()) {
+                    continue;
+                }
+
+                if let Some(ref type_condition) = fragment.type_condition {
+                    if meta_type.name() != Some(type_condition.item) {
+                        return None;
+                    }
+                }
+
+                may_become_null |=
+                    plan_output(meta_type, &fragment.selection_set[..], executor, response_names)?;
+            }
+        }
+    }
+
+    Some(may_become_null)
+}
+
+fn write_selection_set<T, CtxT>(
+    instance: &T,
+    info: &T::TypeInfo,
+    meta_type: &MetaType,
+    selection_set: &[Selection],
+    executor: &Executor<CtxT>,
+    output: &ResponseWriter,
+    may_become_null: bool,
+) -> bool
+where
+    T: GraphQLType<Context = CtxT>,
+{
+    let checkpoint = if may_become_null {
+        Some(output.checkpoint())
+    } else {
+        None
+    };
+
+    output.write_raw(b"{");
+
+    let mut first = true;
+    let complete = write_selections(
+        instance,
+        info,
+        meta_type,
+        selection_set,
+        executor,
+        output,
+        &mut first,
+    );
+
+    if complete {
+        output.write_raw(b"}");
+    }
+
+    match checkpoint {
+        Some(checkpoint) if complete => output.commit(checkpoint),
+        Some(checkpoint) => output.rollback(checkpoint),
+        None => debug_assert!(complete, "Object without non-null fields became null"),
+    }
+
+    complete
+}
+
+fn write_selections<T, CtxT>(
+    instance: &T,
+    info: &T::TypeInfo,
+    meta_type: &MetaType,
+    selection_set: &[Selection],
+    executor: &Executor<CtxT>,
+    output: &ResponseWriter,
+    first: &mut bool,
+) -> bool
+where
+    T: GraphQLType<Context = CtxT>,
+{
+    for selection in selection_set {
+        match *selection {
+            Selection::Field(Spanning {
+                item: ref f,
+                start: ref start_pos,
+                ..
+            }) => {
+                if is_excluded(&f.directives, executor.variables()) {
+                    continue;
+                }
+
+                let response_name = f.alias.as_ref().unwrap_or(&f.name).item;
+                output.write_key(response_name, first);
+
+                if f.name.item == "__typename" {
+                    output.write_value(&instance.concrete_type_name(executor.context(), info));
+                    continue;
+                }
+
+                let meta_field = meta_type
+                    .field_by_name(f.name.item)
+                    .expect("Field not found on type");
+
+                let exec_vars = executor.variables();
+
+                let sub_exec = executor.field_sub_executor(
+                    response_name,
+                    f.name.item,
+                    start_pos.clone(),
+                    f.selection_set.as_ref().map(|v| &v[..]),
+                );
+
+                let field_hooks = executor.field_hooks(meta_type, meta_field, &f.directives);
+                let field_info = sub_exec.field_started(meta_type, meta_field);
+
+                // Hooks transform the resolved value, so it can only be
+                // written once they have run
+                let field_exec = if field_hooks.is_empty() {
+                    sub_exec.with_output(Some(output))
+                } else {
+                    sub_exec.clone()
+                };
+
+                let start = output.position();
+
+                let field_result = field_hooks.before().and_then(|()| {
+                    let value = instance.resolve_field(
+                        info,
+                        f.name.item,
+                        &Arguments::new(
+                            f.arguments.as_ref().map(|m| {
+                                m.item
+                                    .iter()
+                                    .map(|&(ref k, ref v)| {
+                                        (k.item, v.item.clone().into_const(exec_vars))
+                                    })
+                                    .collect()
+                            }),
+                            &meta_field.arguments,
+                        ),
+                        &field_exec,
+                    )?;
+
+                    field_hooks.after(value)
+                });
+
+                sub_exec.field_finished(field_info);
+
+                let is_non_null = meta_field.field_type.is_non_null();
+                let written = output.position() > start;
+
+                match field_result {
+                    Ok(_) if written => (),
+                    Ok(Value::Null) if is_non_null => return false,
+                    Ok(v) => output.write_value(&v),
+                    Err(e) => {
+                        sub_exec.push_error_at(e, start_pos.clone());
+
+                        if is_non_null {
+                            return false;
+                        }
+
+                        // A value that already wrote itself is kept
+                        if !written {
+                            output.write_value(&Value::null());
+                        }
+                    }
+                }
+
+                output.flush_committed();
+            }
+            Selection::FragmentSpread(Spanning {
+                item: ref spread, ..
+            }) => {
+                if is_excluded(&spread.directives, executor.variables()) {
+                    continue;
+                }
+
+                let fragment = &executor
+                    .fragment_by_name(spread.name.item)
+                    .expect("Fragment could not be found");
+
+                if !write_selections(
+                    instance,
+                    info,
+                    meta_type,
+                    &fragment.selection_set[..],
+                    executor,
+                    output,
+                    first,
+                ) {
+                    return false;
+                }
+            }
+            Selection::InlineFragment(Spanning {
+                item: ref fragment,
+                ..
+            }) => {
+                if is_excluded(&fragment.directives, executor.variables()) {
+                    continue;
+                }
+
+                let sub_exec = executor.type_sub_executor(
+                    fragment.type_condition.as_ref().map(|c| c.item),
+                    Some(&fragment.selection_set[..]),
+                );
+
+                if !write_selections(
+                    instance,
+                    info,
+                    meta_type,
+                    &fragment.selection_set[..],
+                    &sub_exec,
+                    output,
+                    first,
+                ) {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Resolves to `None` if a non-null field resolved to `null`, which makes
+/// the whole object `null`
+type ObjectFuture<'a> = FieldFuture<'a, Option<Object>>;
+
+pub(crate) fn resolve_selection_set_async<'a, T, CtxT>(
+    instance: &T,
+    info: &'a T::TypeInfo,
+    selection_set: &'a [Selection<'a>],
+    executor: &Executor<'a, CtxT>,
+) -> ObjectFuture<'a>
+where
+    T: GraphQLType<Context = CtxT>,
+{
+    let meta_type = executor
+        .schema()
+        .concrete_type_by_name(
+            T::name(info)
+                .expect("Resolving named type's selection set")
+                .as_ref(),
+        )
+        .expect("Type not found in schema");
+
+    let mut fields: Vec<ObjectFuture<'a>> = Vec::with_capacity(selection_set.len());
+
+    for selection in selection_set {
+        match *selection {
+            Selection::Field(Spanning {
+                item: ref f,
+                start: ref start_pos,
+                ..
+            }) => {
+                if is_excluded(&f.directives, executor.variables()) {
+                    continue;
+                }
+
+                let response_name = f.alias.as_ref().unwrap_or(&f.name).item;
+
+                if f.name.item == "__typename" {
+                    let type_name = instance.concrete_type_name(executor.context(), info);
+                    let mut result = Object::with_capacity(1);
+                    result.add_field(response_name, Value::string(type_name));
+                    fields.push(Box::new(future::ok(Some(result))));
+                    continue;
+                }
+
+                let meta_field = meta_type.field_by_name(f.name.item).unwrap_or_else(|| {
+                    panic!(format!(
+                        "Field {} not found on type {:?}",
+                        f.name.item,
+                        meta_type.name()
+                    ))
+                });
+
+                let exec_vars = executor.variables();
+
+                let sub_exec = executor.field_sub_executor(
+                    response_name,
+                    f.name.item,
+                    start_pos.clone(),
+                    f.selection_set.as_ref().map(|v| &v[..]),
+                );
+
+                let field_hooks = executor.shared_field_hooks(meta_type, meta_field, &f.directives);
+                let field_info = sub_exec.field_started(meta_type, meta_field);
+
+                let field_future: ExecutionFuture<'a> = match field_hooks.before() {
+                    Ok(()) => {
+                        let field_future = instance.resolve_field_async(
+                            info,
+                            f.name.item,
+                            &Arguments::new(
+                                f.arguments.as_ref().map(|m| {
+                                    m.item
+                                        .iter()
+                                        .map(|&(ref k, ref v)| {
+                                            (k.item, v.item.clone().into_const(exec_vars))
+                                        })
+                                        .collect()
+                                }),
+                                &meta_field.arguments,
+                            ),
+                            &sub_exec,
+                        );
+
+                        if field_hooks.is_empty() {
+                            field_future
+                        } else {
+                            Box::new(field_future.and_then(move |v| field_hooks.after(v)))
+                        }
+                    }
+                    Err(e) => Box::new(future::err(e)),
+                };
+
+                let is_non_null = meta_field.field_type.is_non_null();
+                let start_pos = start_pos.clone();
+                let sub_exec = sub_exec.detach_without_context();
+
+                fields.push(Box::new(field_future.then(move |field_result| {
+                    let sub_exec = sub_exec.attach();
+                    sub_exec.field_finished(field_info);
+
+                    let value = match field_result {
+                        Ok(Value::Null) if is_non_null => return Ok(None),
+                        Ok(v) => v,
+                        Err(e) => {
+                            sub_exec.push_error_at(e, start_pos);
+
+                            if is_non_null {
+                                return Ok(None);
+                            }
+
+                            Value::null()
+                        }
+                    };
+
+                    let mut result = Object::with_capacity(1);
+                    result.add_field(response_name, value);
+                    Ok(Some(result))
+                })));
+            }
+            Selection::FragmentSpread(Spanning {
+                item: ref spread, ..
+            }) => {
+                if is_excluded(&spread.directives, executor.variables()) {
+                    continue;
+                }
+
+                let fragment = executor
+                    .fragment_by_name(spread.name.item)
+                    .expect("Fragment could not be found");
+
+                fields.push(resolve_selection_set_async(
+                    instance,
+                    info,
+                    &fragment.selection_set[..],
+                    executor,
+                ));
+            }
+            Selection::InlineFragment(Spanning {
+                item: ref fragment,
+                start: ref start_pos,
+                ..
+            }) => {
+                if is_excluded(&fragment.directives, executor.variables()) {
+                    continue;
+                }
+
+                let sub_exec = executor.type_sub_executor(
+                    fragment.type_condition.as_ref().map(|c| c.item),
+                    Some(&fragment.selection_set[..]),
+                );
+
+                if let Some(ref type_condition) = fragment.type_condition {
+                    let sub_result = instance.resolve_into_type_async(
+                        info,
+                        type_condition.item,
+                        Some(&fragment.selection_set[..]),
+                        &sub_exec,
+                    );
+                    let start_pos = start_pos.clone();
+                    let sub_exec = sub_exec.detach_without_context();
+
+                    fields.push(Box::new(sub_result.then(move |sub_result| {
+                        match sub_result {
+                            Ok(Value::Object(object)) => return Ok(Some(object)),
+                            Ok(_) => (),
+                            Err(e) => sub_exec.attach().push_error_at(e, start_pos),
+                        }
+
+                        Ok(Some(Object::with_capacity(0)))
+                    })));
+                } else {
+                    fields.push(resolve_selection_set_async(
+                        instance,
+                        info,
+                        &fragment.selection_set[..],
+                        &sub_exec,
+                    ));
+                }
+            }
+        }
+    }
+
+    Box::new(future::join_all(fields).map(merge_objects))
+}
+
+/// Resolve the selection set of a mutation, only starting to resolve each
+/// field after the previous one has completed
+pub(crate) fn resolve_selection_set_serially<'a, T, CtxT>(
+    instance: &'a T,
+    info: &'a T::TypeInfo,
+    selection_set: &'a [Selection<'a>],
+    executor: &Executor<'a, CtxT>,
+) -> ExecutionFuture<'a>
+where
+    T: GraphQLType<Context = CtxT> + Sync,
+    T::TypeInfo: Sync,
+    CtxT: Sync,
+{
+    Box::new(
+        resolve_selections_serially(instance, info, selection_set, executor)
+            .map(|result| result.map_or_else(Value::null, Value::Object)),
+    )
+}
+
+fn resolve_selections_serially<'a, T, CtxT>(
+    instance: &'a T,
+    info: &'a T::TypeInfo,
+    selection_set: &'a [Selection<'a>],
+    executor: &Executor<'a, CtxT>,
+) -> ObjectFuture<'a>
+where
+    T: GraphQLType<Context = CtxT> + Sync,
+    T::TypeInfo: Sync,
+    CtxT: Sync,
+{
+    let executor = executor.detach();
+
+    Box::new(
+        stream::iter_ok::<_, FieldError>(selection_set)
+            .fold(Some(Object::with_capacity(selection_set.len())), move |result, selection| {
+                let result = match result {
+                    Some(result) => result,
+                    None => return Box::new(future::ok(None)) as ObjectFuture<'a>,
+                };
+                let executor = executor.attach();
+
+                let selection_result = match *selection {
+                    Selection::FragmentSpread(Spanning {
+                        item: ref spread, ..
+                    }) if !is_excluded(&spread.directives, executor.variables()) =>
+                    {
+                        let fragment = executor
+                            .fragment_by_name(spread.name.item)
+                            .expect("Fragment could not be found");
+
+                        resolve_selections_serially(
+                            instance,
+                            info,
+                            &fragment.selection_set[..],
+                            &executor,
+                        )
+                    }
+                    Selection::InlineFragment(Spanning {
+                        item: ref fragment, ..
+                    }) if fragment.type_condition.is_none()
+                        && !is_excluded(&fragment.directives, executor.variables()) =>
+                    {
+                        resolve_selections_serially(
+                            instance,
+                            info,
+                            &fragment.selection_set[..],
+                            &executor,
+                        )
+                    }
+                    _ => resolve_selection_set_async(
+                        instance,
+                        info,
+                        slice::from_ref(selection),
+                        &executor,
+                    ),
+                };
+
+                Box::new(selection_result.map(move |object| {
+                    let mut result = result;
+                    for (k, v) in object? {
+                        merge_key_into(&mut result, &k, v);
+                    }
+                    Some(result)
+                }))
+            }),
+    )
+}
+
+fn merge_objects(objects: Vec<Option<Object>>) -> Option<Object> {
+    let mut result = Object::with_capacity(objects.len());
+
+    for object in objects {
+        for (k, v) in object? {
+            merge_key_into(&mut result, &k, v);
+        }
+    }
+
+    Some(result)
+}
+
+pub(crate) fn is_excluded(directives: &Option<Vec<Spanning<Directive>>>, vars: &Variables) -> bool {
     if let Some(ref directives) = *directives {
         for &Spanning {
             item: ref directive,
             ..
         } in directives
         {
+            if directive.name.item != "skip" && directive.name.item != "include" {
+                continue;
+            }
+
             let condition: bool = directive
                 .arguments
                 .iter()
@@ -1,8 +1,10 @@
+use futures::future::{self, Future};
+
 use ast::{FromInputValue, InputValue, Selection, ToInputValue};
 use schema::meta::MetaType;
 use value::Value;
 
-use executor::{Executor, Registry};
+use executor::{ExecutionFuture, Executor, Registry, ResponseWriter};
 use types::base::GraphQLType;
 
 impl<T, CtxT> GraphQLType for Option<T>
@@ -31,6 +33,18 @@ where
             None => Value::null(),
         }
     }
+
+    fn resolve_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        _: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        match *self {
+            Some(ref obj) => executor.resolve_into_value_async(info, obj),
+            None => Box::new(future::ok(Value::null())),
+        }
+    }
 }
 
 impl<T> FromInputValue for Option<T>
@@ -83,6 +97,15 @@ where
     ) -> Value {
         resolve_into_list(executor, info, self.iter())
     }
+
+    fn resolve_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        _: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        resolve_into_list_async(executor, info, self.iter())
+    }
 }
 
 impl<T> FromInputValue for Vec<T>
@@ -141,6 +164,15 @@ where
     ) -> Value {
         resolve_into_list(executor, info, self.iter())
     }
+
+    fn resolve_async<'b>(
+        &self,
+        info: &'b T::TypeInfo,
+        _: Option<&'b [Selection<'b>]>,
+        executor: &Executor<'b, CtxT>,
+    ) -> ExecutionFuture<'b> {
+        resolve_into_list_async(executor, info, self.iter())
+    }
 }
 
 impl<'a, T> ToInputValue for &'a [T]
@@ -163,10 +195,14 @@ where
         .expect("Current type is not a list type")
         .is_non_null();
 
+    if let Some(output) = executor.output() {
+        return write_list(executor, output, info, iter, stop_on_null);
+    }
+
     let mut result = Vec::with_capacity(iter.len());
 
-    for o in iter {
-        let value = executor.resolve_into_value(info, &o);
+    for (i, o) in iter.enumerate() {
+        let value = executor.index_sub_executor(i).resolve_into_value(info, &o);
         if stop_on_null && value.is_null() {
             return value;
         }
@@ -176,3 +212,89 @@ where
 
     Value::list(result)
 }
+
+/// Writes the items of a list into the output as they resolve
+///
+/// Returns an empty list once the items have been written, or `null` if a
+/// non-null item resolved to `null`.
+fn write_list<T, I>(
+    executor: &Executor<T::Context>,
+    output: &ResponseWriter,
+    info: &T::TypeInfo,
+    iter: I,
+    stop_on_null: bool,
+) -> Value
+where
+    I: Iterator<Item = T>,
+    T: GraphQLType,
+{
+    let checkpoint = if stop_on_null {
+        Some(output.checkpoint())
+    } else {
+        None
+    };
+
+    output.write_raw(b"[");
+
+    for (i, o) in iter.enumerate() {
+        if i > 0 {
+            output.write_raw(b",");
+        }
+
+        let start = output.position();
+        let value = executor
+            .index_sub_executor(i)
+            .with_output(Some(output))
+            .resolve_into_value(info, &o);
+
+        if output.position() == start {
+            if stop_on_null && value.is_null() {
+                if let Some(checkpoint) = checkpoint {
+                    output.rollback(checkpoint);
+                }
+
+                return value;
+            }
+
+            output.write_value(&value);
+        }
+
+        output.flush_committed();
+    }
+
+    output.write_raw(b"]");
+
+    if let Some(checkpoint) = checkpoint {
+        output.commit(checkpoint);
+    }
+
+    Value::list(Vec::new())
+}
+
+fn resolve_into_list_async<'a, T, I>(
+    executor: &Executor<'a, T::Context>,
+    info: &'a T::TypeInfo,
+    iter: I,
+) -> ExecutionFuture<'a>
+where
+    I: Iterator<Item = T> + ExactSizeIterator,
+    T: GraphQLType,
+{
+    let stop_on_null = executor
+        .current_type()
+        .list_contents()
+        .expect("Current type is not a list type")
+        .is_non_null();
+
+    let items = iter.enumerate()
+        .map(|(i, o)| executor.index_sub_executor(i).resolve_into_value_async(info, &o))
+        .collect::<Vec<_>>();
+
+    Box::new(future::join_all(items).map(move |result| {
+        if stop_on_null && result.iter().any(Value::is_null) {
+            Value::null()
+        } else {
+            Value::list(result)
+        }
+    }))
+}
@@ -3,4 +3,6 @@ pub mod containers;
 pub mod name;
 pub mod pointers;
 pub mod scalars;
+pub mod subscriptions;
+pub mod upload;
 pub mod utilities;
@@ -2,7 +2,7 @@ use ast::{FromInputValue, InputValue, Selection, ToInputValue};
 use std::sync::Arc;
 use value::Value;
 
-use executor::{ExecutionResult, Executor, Registry};
+use executor::{ExecutionFuture, ExecutionResult, Executor, Registry};
 use schema::meta::MetaType;
 use types::base::{Arguments, GraphQLType};
 
@@ -49,6 +49,35 @@ where
     ) -> Value {
         (**self).resolve(info, selection_set, executor)
     }
+
+    fn resolve_into_type_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        name: &str,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_into_type_async(info, name, selection_set, executor)
+    }
+
+    fn resolve_field_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        field: &str,
+        args: &Arguments,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_field_async(info, field, args, executor)
+    }
+
+    fn resolve_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_async(info, selection_set, executor)
+    }
 }
 
 impl<T> FromInputValue for Box<T>
@@ -115,6 +144,35 @@ where
     ) -> Value {
         (**self).resolve(info, selection_set, executor)
     }
+
+    fn resolve_into_type_async<'b>(
+        &self,
+        info: &'b T::TypeInfo,
+        name: &str,
+        selection_set: Option<&'b [Selection<'b>]>,
+        executor: &Executor<'b, CtxT>,
+    ) -> ExecutionFuture<'b> {
+        (**self).resolve_into_type_async(info, name, selection_set, executor)
+    }
+
+    fn resolve_field_async<'b>(
+        &self,
+        info: &'b T::TypeInfo,
+        field: &str,
+        args: &Arguments,
+        executor: &Executor<'b, CtxT>,
+    ) -> ExecutionFuture<'b> {
+        (**self).resolve_field_async(info, field, args, executor)
+    }
+
+    fn resolve_async<'b>(
+        &self,
+        info: &'b T::TypeInfo,
+        selection_set: Option<&'b [Selection<'b>]>,
+        executor: &Executor<'b, CtxT>,
+    ) -> ExecutionFuture<'b> {
+        (**self).resolve_async(info, selection_set, executor)
+    }
 }
 
 impl<'a, T> ToInputValue for &'a T
@@ -169,6 +227,35 @@ where
     ) -> Value {
         (**self).resolve(info, selection_set, executor)
     }
+
+    fn resolve_into_type_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        name: &str,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_into_type_async(info, name, selection_set, executor)
+    }
+
+    fn resolve_field_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        field: &str,
+        args: &Arguments,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_field_async(info, field, args, executor)
+    }
+
+    fn resolve_async<'a>(
+        &self,
+        info: &'a T::TypeInfo,
+        selection_set: Option<&'a [Selection<'a>]>,
+        executor: &Executor<'a, CtxT>,
+    ) -> ExecutionFuture<'a> {
+        (**self).resolve_async(info, selection_set, executor)
+    }
 }
 
 impl<T> ToInputValue for Arc<T>
@@ -9,6 +9,7 @@ use schema::meta::MetaType;
 
 use executor::{Executor, Registry};
 use types::base::GraphQLType;
+use types::subscriptions::GraphQLSubscriptionType;
 
 /// An ID as defined by the GraphQL specification
 ///
@@ -95,10 +96,26 @@ graphql_scalar!(bool as "Boolean" {
 
 graphql_scalar!(i32 as "Int" {
     resolve(&self) -> Value {
-        Value::int(*self)
+        Value::int(i64::from(*self))
     }
 
     from_input_value(v: &InputValue) -> Option<i32> {
+        match *v {
+            InputValue::Int(i) if i >= i64::from(i32::min_value())
+                && i <= i64::from(i32::max_value()) => Some(i as i32),
+            _ => None,
+        }
+    }
+});
+
+graphql_scalar!(i64 as "Long" {
+    description: "A signed 64-bit integer"
+
+    resolve(&self) -> Value {
+        Value::int(*self)
+    }
+
+    from_input_value(v: &InputValue) -> Option<i64> {
         match *v {
             InputValue::Int(i) => Some(i),
             _ => None,
@@ -106,6 +123,29 @@ graphql_scalar!(i32 as "Int" {
     }
 });
 
+// Integers are stored as `i64`. Larger values are represented as floats, the
+// way JSON input parses them, so they lose precision.
+graphql_scalar!(u64 as "UnsignedLong" {
+    description: "An unsigned 64-bit integer"
+
+    resolve(&self) -> Value {
+        if *self <= i64::max_value() as u64 {
+            Value::int(*self as i64)
+        } else {
+            Value::float(*self as f64)
+        }
+    }
+
+    from_input_value(v: &InputValue) -> Option<u64> {
+        match *v {
+            InputValue::Int(i) if i >= 0 => Some(i as u64),
+            InputValue::Float(f) if f >= 9_223_372_036_854_775_808.0
+                && f < 18_446_744_073_709_551_616.0 => Some(f as u64),
+            _ => None,
+        }
+    }
+});
+
 graphql_scalar!(f64 as "Float" {
     resolve(&self) -> Value {
         Value::float(*self)
@@ -113,7 +153,7 @@ graphql_scalar!(f64 as "Float" {
 
     from_input_value(v: &InputValue) -> Option<f64> {
         match *v {
-            InputValue::Int(i) => Some(f64::from(i)),
+            InputValue::Int(i) => Some(i as f64),
             InputValue::Float(f) => Some(f),
             _ => None,
         }
@@ -169,6 +209,38 @@ impl<T> GraphQLType for EmptyMutation<T> {
     }
 }
 
+/// Utility type to define schemas without subscriptions
+///
+/// If you instantiate `RootNode` with this as the subscription, no
+/// subscription will be generated for the schema.
+pub struct EmptySubscription<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> EmptySubscription<T> {
+    /// Construct a new empty subscription
+    pub fn new() -> EmptySubscription<T> {
+        EmptySubscription {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> GraphQLType for EmptySubscription<T> {
+    type Context = T;
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("_EmptySubscription")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r> {
+        registry.build_object_type::<Self>(&(), &[]).into_meta()
+    }
+}
+
+impl<T> GraphQLSubscriptionType for EmptySubscription<T> {}
+
 #[cfg(test)]
 mod tests {
     use super::ID;
@@ -0,0 +1,145 @@
+use futures::stream::{self, Stream};
+use futures::Poll;
+
+use executor::{ExecutionResult, Executor, FieldError, FieldResult, FromContext, IntoFieldError};
+use types::base::{Arguments, GraphQLType};
+
+type Event<'a, CtxT> = Box<Fn(&Executor<CtxT>) -> ExecutionResult + Send + 'a>;
+
+/// The source event stream produced by a subscription field
+///
+/// Each event is resolved against the selection set of the subscription
+/// field when the stream returned by `execute_subscription` is polled.
+/// Sources waiting for new data, e.g. from a channel, should be built with
+/// `from_stream` so that polling them never blocks.
+///
+/// Source event streams are `Send`, so that subscriptions can be driven by
+/// any task of a multithreaded executor.
+pub struct SourceEvents<'a, CtxT: 'a> {
+    events: Box<Stream<Item = Event<'a, CtxT>, Error = FieldError> + Send + 'a>,
+}
+
+impl<'a, CtxT> SourceEvents<'a, CtxT> {
+    /// Construct a source event stream from an iterator of GraphQL values
+    ///
+    /// Every item yielded by `events` will be resolved as the subscription
+    /// field's type, using `info` as its type info. The iterator is advanced
+    /// whenever the stream is polled, so it should not block.
+    pub fn new<T, I>(info: &'a T::TypeInfo, events: I) -> SourceEvents<'a, CtxT>
+    where
+        T: GraphQLType + Send + 'a,
+        T::TypeInfo: Sync,
+        T::Context: FromContext<CtxT>,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: Send + 'a,
+    {
+        SourceEvents::from_stream(info, stream::iter_ok::<_, FieldError>(events))
+    }
+
+    /// Construct a source event stream from a stream of GraphQL values
+    ///
+    /// An error of the stream ends the subscription after it has been
+    /// reported to the client.
+    pub fn from_stream<T, S>(info: &'a T::TypeInfo, events: S) -> SourceEvents<'a, CtxT>
+    where
+        T: GraphQLType + Send + 'a,
+        T::TypeInfo: Sync,
+        T::Context: FromContext<CtxT>,
+        S: Stream<Item = T> + Send + 'a,
+        S::Error: IntoFieldError,
+    {
+        SourceEvents {
+            events: Box::new(
+                events
+                    .map_err(IntoFieldError::into_field_error)
+                    .map(move |event| {
+                        Box::new(move |executor: &Executor<CtxT>| {
+                            executor.resolve_with_ctx(info, &event)
+                        }) as Event<'a, CtxT>
+                    }),
+            ),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn poll_event(&mut self) -> Poll<Option<Event<'a, CtxT>>, FieldError> {
+        self.events.poll()
+    }
+}
+
+/**
+Trait implemented by the root subscription type of a schema
+
+Subscription root types are exposed in the schema like any other object
+through `GraphQLType`, which declares the fields and the types of the values
+each of them produces. Rather than resolving a field to a single value, this
+trait resolves it into a stream of source events. Every event is executed
+against the selection set of the subscription and produces one response.
+
+The default implementation returns an error for every field, which makes it
+suitable for types without any fields such as `EmptySubscription`.
+
+## Example
+
+```rust
+use juniper::{Arguments, FieldResult, GraphQLSubscriptionType, GraphQLType, Registry,
+              SourceEvents};
+use juniper::meta::MetaType;
+
+struct Subscription;
+
+impl GraphQLType for Subscription {
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("Subscription")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r> {
+        let fields = &[registry.field::<i32>("counter", &())];
+
+        registry.build_object_type::<Subscription>(&(), fields).into_meta()
+    }
+}
+
+impl GraphQLSubscriptionType for Subscription {
+    fn resolve_field_into_stream<'a>(
+        &'a self,
+        info: &'a (),
+        field_name: &str,
+        _: &Arguments,
+        _: &'a (),
+    ) -> FieldResult<SourceEvents<'a, ()>> {
+        match field_name {
+            "counter" => Ok(SourceEvents::new(info, 0..10)),
+            _ => panic!("Field {} not found on type Subscription", field_name),
+        }
+    }
+}
+# fn main() { }
+```
+
+*/
+pub trait GraphQLSubscriptionType: GraphQLType {
+    /// Resolve a single field of the subscription root into a stream of
+    /// source events.
+    ///
+    /// The arguments object contain all specified arguments, with default
+    /// values substituted for the ones not provided by the query.
+    ///
+    /// The default implementation returns an error.
+    #[allow(unused_variables)]
+    fn resolve_field_into_stream<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        field_name: &str,
+        arguments: &Arguments,
+        context: &'a Self::Context,
+    ) -> FieldResult<SourceEvents<'a, Self::Context>> {
+        Err(FieldError::from(format!(
+            "Field {} can not be subscribed to",
+            field_name
+        )))
+    }
+}
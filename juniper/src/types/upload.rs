@@ -0,0 +1,191 @@
+use std::borrow::Cow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use ast::{FromInputValue, InputValue, Selection, ToInputValue};
+use executor::{Executor, Registry};
+use schema::meta::MetaType;
+use types::base::GraphQLType;
+use value::Value;
+
+/// A file uploaded with a multipart request
+///
+/// Uploads are put into the variables of a request by `http::multipart`.
+/// Small files are kept in memory, larger ones are written to a temporary
+/// file that is removed once the last clone of the upload is dropped.
+///
+/// As an output type, an upload resolves to its file name.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Upload {
+    filename: Option<String>,
+    content_type: Option<String>,
+    contents: Arc<UploadContents>,
+}
+
+#[derive(PartialEq, Debug)]
+enum UploadContents {
+    Memory(Vec<u8>),
+    File(TempFile),
+}
+
+#[derive(PartialEq, Debug)]
+struct TempFile {
+    path: PathBuf,
+    len: usize,
+}
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl Upload {
+    /// Construct an upload kept in memory
+    pub fn new(filename: Option<String>, content_type: Option<String>, bytes: Vec<u8>) -> Upload {
+        Upload {
+            filename: filename,
+            content_type: content_type,
+            contents: Arc::new(UploadContents::Memory(bytes)),
+        }
+    }
+
+    /// Construct an upload by writing its contents to a new file in `dir`
+    pub(crate) fn write_to_temp_file(
+        filename: Option<String>,
+        content_type: Option<String>,
+        bytes: &[u8],
+        dir: &Path,
+    ) -> io::Result<Upload> {
+        let (path, mut file) = create_temp_file(dir)?;
+        let temp_file = TempFile {
+            path: path,
+            len: bytes.len(),
+        };
+        file.write_all(bytes)?;
+
+        Ok(Upload {
+            filename: filename,
+            content_type: content_type,
+            contents: Arc::new(UploadContents::File(temp_file)),
+        })
+    }
+
+    /// The file name sent by the client, if any
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_ref().map(|s| s as &str)
+    }
+
+    /// The content type sent by the client, if any
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_ref().map(|s| s as &str)
+    }
+
+    /// The size of the file in bytes
+    pub fn len(&self) -> usize {
+        match *self.contents {
+            UploadContents::Memory(ref bytes) => bytes.len(),
+            UploadContents::File(ref file) => file.len,
+        }
+    }
+
+    /// Is the file empty?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The path of the temporary file holding the contents, if the upload was
+    /// too large to be kept in memory
+    ///
+    /// The file is removed once the last clone of the upload is dropped.
+    pub fn path(&self) -> Option<&Path> {
+        match *self.contents {
+            UploadContents::Memory(_) => None,
+            UploadContents::File(ref file) => Some(&file.path),
+        }
+    }
+
+    /// The contents of the file, read from the temporary file if necessary
+    pub fn bytes(&self) -> io::Result<Cow<[u8]>> {
+        match *self.contents {
+            UploadContents::Memory(ref bytes) => Ok(Cow::Borrowed(bytes)),
+            UploadContents::File(ref file) => {
+                let mut bytes = Vec::with_capacity(file.len);
+                File::open(&file.path)?.read_to_end(&mut bytes)?;
+                Ok(Cow::Owned(bytes))
+            }
+        }
+    }
+}
+
+fn create_temp_file(dir: &Path) -> io::Result<(PathBuf, File)> {
+    loop {
+        let path = dir.join(format!(
+            "juniper-upload-{}-{}",
+            process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        restrict_permissions(&mut options);
+
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Uploads may be private, so their files are only readable by their owner
+#[cfg(unix)]
+fn restrict_permissions(options: &mut OpenOptions) {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    options.mode(0o600);
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_: &mut OpenOptions) {}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl GraphQLType for Upload {
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("Upload")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r> {
+        registry
+            .build_scalar_type::<Upload>(&())
+            .description("A file uploaded with a multipart request")
+            .into_meta()
+    }
+
+    fn resolve(&self, _: &(), _: Option<&[Selection]>, _: &Executor<Self::Context>) -> Value {
+        self.filename().map_or_else(Value::null, Value::string)
+    }
+}
+
+impl FromInputValue for Upload {
+    fn from_input_value(v: &InputValue) -> Option<Upload> {
+        match *v {
+            InputValue::Upload(ref upload) => Some(upload.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl ToInputValue for Upload {
+    fn to_input_value(&self) -> InputValue {
+        InputValue::Upload(self.clone())
+    }
+}
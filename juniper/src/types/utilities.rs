@@ -35,7 +35,8 @@ pub fn is_valid_literal_value(
                 | ref v @ InputValue::Float(_)
                 | ref v @ InputValue::String(_)
                 | ref v @ InputValue::Boolean(_)
-                | ref v @ InputValue::Enum(_) => if let Some(parse_fn) = t.input_value_parse_fn() {
+                | ref v @ InputValue::Enum(_)
+                | ref v @ InputValue::Upload(_) => if let Some(parse_fn) = t.input_value_parse_fn() {
                     parse_fn(v)
                 } else {
                     false
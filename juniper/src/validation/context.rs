@@ -14,8 +14,12 @@ pub struct RuleError {
     message: String,
 }
 
-#[doc(hidden)]
+/// State of the validation of a query document
+///
+/// The context is passed to the methods of `Visitor`, and tracks the types of
+/// the visited nodes.
 pub struct ValidatorContext<'a> {
+    #[doc(hidden)]
     pub schema: &'a SchemaType<'a>,
     errors: Vec<RuleError>,
     type_stack: Vec<Option<&'a MetaType<'a>>>,
@@ -75,7 +79,7 @@ impl<'a> ValidatorContext<'a> {
         self.errors.append(&mut errors);
     }
 
-    #[doc(hidden)]
+    /// Report a validation error at the given positions
     pub fn report_error(&mut self, message: &str, locations: &[SourcePosition]) {
         self.errors.push(RuleError::new(message, locations))
     }
@@ -143,12 +147,14 @@ impl<'a> ValidatorContext<'a> {
         res
     }
 
-    #[doc(hidden)]
+    /// The type of the currently visited field, fragment or operation
+    ///
+    /// Returns `None` if the type is not part of the schema.
     pub fn current_type(&self) -> Option<&'a MetaType<'a>> {
         *self.type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The type of the currently visited field, including its wrapping types
     pub fn current_type_literal(&self) -> Option<&Type<'a>> {
         match self.type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -156,12 +162,12 @@ impl<'a> ValidatorContext<'a> {
         }
     }
 
-    #[doc(hidden)]
+    /// The type the currently visited field is selected on
     pub fn parent_type(&self) -> Option<&'a MetaType<'a>> {
         *self.parent_type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// The expected type of the currently visited argument or input value
     pub fn current_input_type_literal(&self) -> Option<&Type<'a>> {
         match self.input_type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -169,7 +175,7 @@ impl<'a> ValidatorContext<'a> {
         }
     }
 
-    #[doc(hidden)]
+    /// Check if the document defines a fragment with the given name
     pub fn is_known_fragment(&self, name: &str) -> bool {
         self.fragment_names.contains(name)
     }
@@ -15,6 +15,7 @@ enum Path<'a> {
     ObjectField(&'a str, &'a Path<'a>),
 }
 
+#[doc(hidden)]
 pub fn validate_input_values(
     values: &Variables,
     document: &Document,
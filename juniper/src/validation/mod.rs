@@ -1,4 +1,45 @@
 //! Query validation related methods and data structures
+//!
+//! Queries are checked against all `BuiltInRule`s, which implement the
+//! validation rules of the specification, before they are executed. Schemas can
+//! turn individual rules off with `RootNode::disable_validation_rule`, and add
+//! their own rules with `RootNode::add_validation_rule`:
+//!
+//! ```rust
+//! # use juniper::{EmptyMutation, RootNode};
+//! use juniper::parser::{Field, Spanning};
+//! use juniper::validation::{ValidatorContext, Visitor};
+//!
+//! struct NoIntrospection;
+//!
+//! impl<'a> Visitor<'a> for NoIntrospection {
+//!     fn enter_field(&mut self, ctx: &mut ValidatorContext<'a>, field: &'a Spanning<Field>) {
+//!         let name = field.item.name.item;
+//!
+//!         if name == "__schema" || name == "__type" {
+//!             ctx.report_error("Introspection is disabled", &[field.start.clone()]);
+//!         }
+//!     }
+//! }
+//!
+//! struct Query;
+//!
+//! juniper::graphql_object!(Query: () |&self| {
+//!     field hello() -> &str { "world" }
+//! });
+//!
+//! let schema = RootNode::new(Query, EmptyMutation::<()>::new())
+//!     .add_validation_rule(|| NoIntrospection);
+//!
+//! let result = juniper::execute(
+//!     "{ __schema { queryType { name } } }",
+//!     None,
+//!     &schema,
+//!     &juniper::Variables::new(),
+//!     &(),
+//! );
+//! assert!(result.is_err());
+//! ```
 
 mod context;
 mod input_value;
@@ -11,10 +52,13 @@ mod visitor;
 mod test_harness;
 
 pub use self::context::{RuleError, ValidatorContext};
+#[doc(hidden)]
 pub use self::input_value::validate_input_values;
+#[doc(hidden)]
 pub use self::multi_visitor::{MultiVisitor, MultiVisitorNil};
-pub use self::rules::visit_all_rules;
-pub use self::traits::Visitor;
+pub use self::rules::{visit_all_rules, visit_limit_rules, visit_rules, BuiltInRule};
+pub use self::traits::{ValidationRule, Visitor};
+#[doc(hidden)]
 pub use self::visitor::visit;
 
 #[cfg(test)]
@@ -34,6 +34,14 @@ impl<'a, A: Visitor<'a>, B: MultiVisitor<'a>> MultiVisitor<'a> for MultiVisitorC
     }
 }
 
+impl<'a> MultiVisitor<'a> for Vec<Box<Visitor<'a> + 'a>> {
+    fn visit_all<F: FnMut(&mut Visitor<'a>) -> ()>(&mut self, mut f: F) {
+        for visitor in self {
+            f(&mut **visitor);
+        }
+    }
+}
+
 impl<'a, M> Visitor<'a> for M
 where
     M: MultiVisitor<'a>,
@@ -164,10 +172,10 @@ where
         self.visit_all(|v| v.exit_null_value(ctx, n.clone()));
     }
 
-    fn enter_int_value(&mut self, ctx: &mut ValidatorContext<'a>, i: Spanning<i32>) {
+    fn enter_int_value(&mut self, ctx: &mut ValidatorContext<'a>, i: Spanning<i64>) {
         self.visit_all(|v| v.enter_int_value(ctx, i.clone()));
     }
-    fn exit_int_value(&mut self, ctx: &mut ValidatorContext<'a>, i: Spanning<i32>) {
+    fn exit_int_value(&mut self, ctx: &mut ValidatorContext<'a>, i: Spanning<i64>) {
         self.visit_all(|v| v.exit_int_value(ctx, i.clone()));
     }
 
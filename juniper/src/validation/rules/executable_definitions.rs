@@ -0,0 +1,111 @@
+use ast::{Definition, Document, TypeSystemDefinition};
+use validation::{ValidatorContext, Visitor};
+
+pub struct ExecutableDefinitions;
+
+pub fn factory() -> ExecutableDefinitions {
+    ExecutableDefinitions
+}
+
+impl<'a> Visitor<'a> for ExecutableDefinitions {
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a>, doc: &'a Document) {
+        for def in doc {
+            if let Definition::TypeSystem(ref def) = *def {
+                ctx.report_error(&error_message(&definition_name(def)), &[def.start().clone()]);
+            }
+        }
+    }
+}
+
+fn definition_name(def: &TypeSystemDefinition) -> String {
+    match *def {
+        TypeSystemDefinition::Schema(_) | TypeSystemDefinition::SchemaExtension(_) => {
+            "schema".to_owned()
+        }
+        TypeSystemDefinition::Directive(ref d) => format!("@{}", d.item.name.item),
+        TypeSystemDefinition::Type(ref d) | TypeSystemDefinition::TypeExtension(ref d) => {
+            d.item.name().item.to_owned()
+        }
+    }
+}
+
+fn error_message(def_name: &str) -> String {
+    format!(r#"The "{}" definition is not executable"#, def_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use parser::SourcePosition;
+    use validation::{expect_fails_rule, expect_passes_rule, RuleError};
+
+    #[test]
+    fn only_operations_and_fragments() {
+        expect_passes_rule(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              ...Frag
+            }
+          }
+
+          fragment Frag on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn type_definitions() {
+        expect_fails_rule(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+
+          type Cow {
+            name: String
+          }
+
+          extend type Dog {
+            color: String
+          }
+        "#,
+            &[
+                RuleError::new(&error_message("Cow"), &[SourcePosition::new(97, 7, 10)]),
+                RuleError::new(&error_message("Dog"), &[SourcePosition::new(156, 11, 10)]),
+            ],
+        );
+    }
+
+    #[test]
+    fn schema_and_directive_definitions() {
+        expect_fails_rule(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+
+          schema {
+            query: QueryRoot
+          }
+
+          "A directive"
+          directive @example on FIELD
+        "#,
+            &[
+                RuleError::new(&error_message("schema"), &[SourcePosition::new(97, 7, 10)]),
+                RuleError::new(&error_message("@example"), &[SourcePosition::new(158, 11, 10)]),
+            ],
+        );
+    }
+}
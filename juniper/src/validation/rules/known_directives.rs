@@ -1,4 +1,7 @@
-use ast::{Directive, Field, Fragment, FragmentSpread, InlineFragment, Operation, OperationType};
+use ast::{
+    Directive, Field, Fragment, FragmentSpread, InlineFragment, Operation, OperationType,
+    VariableDefinition,
+};
 use parser::Spanning;
 use schema::model::DirectiveLocation;
 use validation::{ValidatorContext, Visitor};
@@ -22,6 +25,7 @@ impl<'a> Visitor<'a> for KnownDirectives {
         self.location_stack.push(match op.item.operation_type {
             OperationType::Query => DirectiveLocation::Query,
             OperationType::Mutation => DirectiveLocation::Mutation,
+            OperationType::Subscription => DirectiveLocation::Subscription,
         });
     }
 
@@ -31,7 +35,29 @@ impl<'a> Visitor<'a> for KnownDirectives {
         _: &'a Spanning<Operation>,
     ) {
         let top = self.location_stack.pop();
-        assert!(top == Some(DirectiveLocation::Query) || top == Some(DirectiveLocation::Mutation));
+        assert!(
+            top == Some(DirectiveLocation::Query)
+                || top == Some(DirectiveLocation::Mutation)
+                || top == Some(DirectiveLocation::Subscription)
+        );
+    }
+
+    fn enter_variable_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a>,
+        _: &'a (Spanning<&'a str>, VariableDefinition),
+    ) {
+        self.location_stack
+            .push(DirectiveLocation::VariableDefinition);
+    }
+
+    fn exit_variable_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a>,
+        _: &'a (Spanning<&'a str>, VariableDefinition),
+    ) {
+        let top = self.location_stack.pop();
+        assert_eq!(top, Some(DirectiveLocation::VariableDefinition));
     }
 
     fn enter_field(&mut self, _: &mut ValidatorContext<'a>, _: &'a Spanning<Field>) {
@@ -283,4 +309,32 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn with_well_placed_variable_definition_directive() {
+        expect_passes_rule(
+            factory,
+            r#"
+          query Foo($var: Boolean @onVariableDefinition) {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn with_misplaced_variable_definition_directive() {
+        expect_fails_rule(
+            factory,
+            r#"
+          query Foo($var: Boolean @onField) {
+            name
+          }
+        "#,
+            &[RuleError::new(
+                &misplaced_error_message("onField", &DirectiveLocation::VariableDefinition),
+                &[SourcePosition::new(35, 1, 34)],
+            )],
+        );
+    }
 }
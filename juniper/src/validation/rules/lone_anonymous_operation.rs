@@ -18,7 +18,7 @@ impl<'a> Visitor<'a> for LoneAnonymousOperation {
             doc.iter()
                 .filter(|d| match **d {
                     Definition::Operation(_) => true,
-                    Definition::Fragment(_) => false,
+                    Definition::Fragment(_) | Definition::TypeSystem(_) => false,
                 })
                 .count(),
         );
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use ast::{Arguments, Definition, Document, Fragment, InputValue, OperationType, Selection};
+use executor::Variables;
+use parser::Spanning;
+use schema::meta::{Field, MetaType};
+use schema::model::SchemaType;
+use validation::{ValidatorContext, Visitor};
+
+pub struct MaxComplexity<'a> {
+    max_complexity: usize,
+    operation_name: Option<&'a str>,
+    variables: &'a Variables,
+}
+
+struct ComplexityCounter<'a> {
+    schema: &'a SchemaType<'a>,
+    variables: &'a Variables,
+    max_complexity: usize,
+    fragments: HashMap<&'a str, &'a Fragment<'a>>,
+    fragment_complexities: HashMap<&'a str, usize>,
+    fragment_stack: Vec<&'a str>,
+}
+
+pub fn factory<'a>(
+    max_complexity: usize,
+    operation_name: Option<&'a str>,
+    variables: &'a Variables,
+) -> MaxComplexity<'a> {
+    MaxComplexity {
+        max_complexity: max_complexity,
+        operation_name: operation_name,
+        variables: variables,
+    }
+}
+
+impl<'a> Visitor<'a> for MaxComplexity<'a> {
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a>, doc: &'a Document) {
+        let mut counter = ComplexityCounter {
+            schema: ctx.schema,
+            variables: self.variables,
+            max_complexity: self.max_complexity,
+            fragments: doc.iter()
+                .filter_map(|def| match *def {
+                    Definition::Fragment(ref f) => Some((f.item.name.item, &f.item)),
+                    _ => None,
+                })
+                .collect(),
+            fragment_complexities: HashMap::new(),
+            fragment_stack: Vec::new(),
+        };
+
+        for def in doc {
+            if let Definition::Operation(ref op) = *def {
+                // Only the operation that is executed counts
+                if let Some(name) = self.operation_name {
+                    if op.item.name.as_ref().map(|n| n.item) != Some(name) {
+                        continue;
+                    }
+                }
+
+                let root_type = match op.item.operation_type {
+                    OperationType::Query => Some(ctx.schema.concrete_query_type()),
+                    OperationType::Mutation => ctx.schema.concrete_mutation_type(),
+                    OperationType::Subscription => ctx.schema.concrete_subscription_type(),
+                };
+                let complexity = counter.complexity(root_type, &op.item.selection_set);
+
+                if complexity > self.max_complexity {
+                    ctx.report_error(
+                        &error_message(complexity, self.max_complexity),
+                        &[op.start.clone()],
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ComplexityCounter<'a> {
+    /// Complexity of a selection set, or a lower bound of it that exceeds the
+    /// maximum complexity
+    fn complexity(
+        &mut self,
+        parent_type: Option<&'a MetaType<'a>>,
+        selection_set: &'a [Selection<'a>],
+    ) -> usize {
+        let mut complexity = 0usize;
+
+        for selection in selection_set {
+            let selection_complexity = match *selection {
+                Selection::Field(ref field) => {
+                    let meta_field = parent_type.and_then(|t| t.field_by_name(field.item.name.item));
+                    let field_type = meta_field.and_then(|f| {
+                        self.schema
+                            .concrete_type_by_name(f.field_type.innermost_name())
+                    });
+
+                    let children = field
+                        .item
+                        .selection_set
+                        .as_ref()
+                        .map_or(0, |s| self.complexity(field_type, s));
+                    let cost = meta_field.and_then(|f| f.complexity).unwrap_or(1);
+                    let multiplier = meta_field.map_or(1, |f| {
+                        self.multiplier(f, field.item.arguments.as_ref().map(|a| &a.item))
+                    });
+
+                    cost.saturating_add(children).saturating_mul(multiplier)
+                }
+                Selection::InlineFragment(ref fragment) => {
+                    let fragment_type = match fragment.item.type_condition {
+                        Some(ref name) => self.schema.concrete_type_by_name(name.item),
+                        None => parent_type,
+                    };
+
+                    self.complexity(fragment_type, &fragment.item.selection_set)
+                }
+                Selection::FragmentSpread(ref spread) => {
+                    let name = spread.item.name.item;
+
+                    // Cycles are reported by `NoFragmentCycles`
+                    match self.fragments.get(name).cloned() {
+                        Some(_) if self.fragment_stack.contains(&name) => 0,
+                        Some(fragment) => match self.fragment_complexities.get(name).cloned() {
+                            Some(fragment_complexity) => fragment_complexity,
+                            None => {
+                                let fragment_type = self.schema
+                                    .concrete_type_by_name(fragment.type_condition.item);
+
+                                self.fragment_stack.push(name);
+                                let fragment_complexity =
+                                    self.complexity(fragment_type, &fragment.selection_set);
+                                self.fragment_stack.pop();
+
+                                self.fragment_complexities.insert(name, fragment_complexity);
+                                fragment_complexity
+                            }
+                        },
+                        None => 0,
+                    }
+                }
+            };
+
+            complexity = complexity.saturating_add(selection_complexity);
+
+            // The query is rejected either way, so the rest doesn't need to be
+            // counted
+            if complexity > self.max_complexity {
+                break;
+            }
+        }
+
+        complexity
+    }
+
+    fn multiplier(&self, field: &Field, arguments: Option<&Arguments>) -> usize {
+        let argument_name = match field.complexity_multiplier {
+            Some(ref name) => name,
+            None => return 1,
+        };
+
+        let value = match arguments.and_then(|args| args.get(argument_name)) {
+            Some(&Spanning {
+                item: InputValue::Variable(ref var),
+                ..
+            }) => self.variables.get(var),
+            Some(value) => Some(&value.item),
+            None => None,
+        }.or_else(|| {
+            field
+                .arguments
+                .as_ref()
+                .and_then(|args| args.iter().find(|a| &a.name == argument_name))
+                .and_then(|a| a.default_value.as_ref())
+        });
+
+        match value {
+            Some(&InputValue::Int(i)) if i >= 0 => i as usize,
+            Some(&InputValue::Int(_)) => 0,
+            _ => 1,
+        }
+    }
+}
+
+fn error_message(complexity: usize, max_complexity: usize) -> String {
+    format!(
+        "Query has a complexity of at least {}, which exceeds the maximum complexity of {}",
+        complexity, max_complexity
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use executor::Variables;
+    use parser::SourcePosition;
+    use validation::{
+        expect_fails_rule, expect_fails_rule_with_schema, expect_passes_rule,
+        expect_passes_rule_with_schema, RuleError,
+    };
+    use InputValue;
+
+    struct Query;
+    struct User;
+
+    graphql_object!(Query: () |&self| {
+        field complexity 5 * first users(first = 10: i32) -> Vec<User> {
+            Vec::new()
+        }
+    });
+
+    graphql_object!(User: () |&self| {
+        field complexity 2 name() -> &str { "" }
+
+        field id() -> i32 { 0 }
+    });
+
+    #[test]
+    fn simple_query() {
+        let variables = Variables::new();
+
+        expect_passes_rule(
+            || factory(3, None, &variables),
+            r#"
+          {
+            human {
+              name
+              ... on Human { iq }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn complex_query() {
+        let variables = Variables::new();
+
+        expect_fails_rule(
+            || factory(2, None, &variables),
+            r#"
+          {
+            human {
+              name
+              ...humanFields
+            }
+          }
+
+          fragment humanFields on Human {
+            iq
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn field_costs_and_multipliers() {
+        let variables = vec![("count".to_owned(), InputValue::int(3))]
+            .into_iter()
+            .collect::<Variables>();
+
+        expect_passes_rule_with_schema(
+            Query,
+            || factory(30, None, &variables),
+            r#"
+          query Literal {
+            users(first: 2) { name id }
+          }
+
+          query Variable($count: Int!) {
+            users(first: $count) { name id }
+          }
+        "#,
+        );
+
+        expect_fails_rule_with_schema(
+            Query,
+            || factory(30, None, &variables),
+            r#"
+          {
+            users { name id }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(80, 30),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    /// Query doubling its complexity with each of the given number of nested
+    /// fragments
+    fn double_spreads(fragments: usize) -> String {
+        let mut query = "{ human { ...F0 } }".to_owned();
+
+        for i in 0..fragments {
+            query.push_str(&format!(
+                " fragment F{} on Human {{ ...F{} ...F{} }}",
+                i,
+                i + 1,
+                i + 1
+            ));
+        }
+        query.push_str(&format!(" fragment F{} on Human {{ name }}", fragments));
+
+        query
+    }
+
+    #[test]
+    fn nested_double_spreads() {
+        let variables = Variables::new();
+        let query = double_spreads(60);
+
+        expect_passes_rule(|| factory(usize::max_value(), None, &variables), &query);
+
+        expect_fails_rule(
+            || factory(100, None, &variables),
+            &query,
+            &[RuleError::new(
+                &error_message(129, 100),
+                &[SourcePosition::new(0, 0, 0)],
+            )],
+        );
+    }
+
+    #[test]
+    fn only_counts_the_executed_operation() {
+        let variables = Variables::new();
+        let query = r#"
+          query Small {
+            human { name }
+          }
+
+          query Large {
+            human { name ... on Human { iq } }
+          }
+        "#;
+
+        expect_passes_rule(|| factory(2, Some("Small"), &variables), query);
+
+        expect_fails_rule(
+            || factory(2, Some("Large"), &variables),
+            query,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(75, 5, 10)],
+            )],
+        );
+    }
+}
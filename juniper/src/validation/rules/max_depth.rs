@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use ast::{Definition, Document, Fragment, Selection};
+use validation::{ValidatorContext, Visitor};
+
+pub struct MaxDepth {
+    max_depth: usize,
+}
+
+struct DepthCounter<'a> {
+    fragments: HashMap<&'a str, &'a Fragment<'a>>,
+    fragment_stack: Vec<&'a str>,
+}
+
+pub fn factory(max_depth: usize) -> MaxDepth {
+    MaxDepth {
+        max_depth: max_depth,
+    }
+}
+
+impl<'a> Visitor<'a> for MaxDepth {
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a>, doc: &'a Document) {
+        let mut counter = DepthCounter {
+            fragments: doc.iter()
+                .filter_map(|def| match *def {
+                    Definition::Fragment(ref f) => Some((f.item.name.item, &f.item)),
+                    _ => None,
+                })
+                .collect(),
+            fragment_stack: Vec::new(),
+        };
+
+        for def in doc {
+            if let Definition::Operation(ref op) = *def {
+                let depth = counter.depth(&op.item.selection_set);
+
+                if depth > self.max_depth {
+                    ctx.report_error(
+                        &error_message(depth, self.max_depth),
+                        &[op.start.clone()],
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<'a> DepthCounter<'a> {
+    fn depth(&mut self, selection_set: &'a [Selection<'a>]) -> usize {
+        let mut depth = 0;
+
+        for selection in selection_set {
+            let selection_depth = match *selection {
+                Selection::Field(ref field) => {
+                    1 + field
+                        .item
+                        .selection_set
+                        .as_ref()
+                        .map_or(0, |s| self.depth(s))
+                }
+                Selection::InlineFragment(ref fragment) => self.depth(&fragment.item.selection_set),
+                Selection::FragmentSpread(ref spread) => {
+                    let name = spread.item.name.item;
+
+                    // Cycles are reported by `NoFragmentCycles`
+                    match self.fragments.get(name).cloned() {
+                        Some(fragment) if !self.fragment_stack.contains(&name) => {
+                            self.fragment_stack.push(name);
+                            let fragment_depth = self.depth(&fragment.selection_set);
+                            self.fragment_stack.pop();
+                            fragment_depth
+                        }
+                        _ => 0,
+                    }
+                }
+            };
+
+            depth = depth.max(selection_depth);
+        }
+
+        depth
+    }
+}
+
+fn error_message(depth: usize, max_depth: usize) -> String {
+    format!(
+        "Query has a depth of {}, which exceeds the maximum depth of {}",
+        depth, max_depth
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use parser::SourcePosition;
+    use validation::{expect_fails_rule, expect_passes_rule, RuleError};
+
+    #[test]
+    fn shallow_query() {
+        expect_passes_rule(
+            || factory(2),
+            r#"
+          {
+            human {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn deep_query() {
+        expect_fails_rule(
+            || factory(2),
+            r#"
+          {
+            human {
+              relatives {
+                name
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn depth_of_fragments() {
+        expect_fails_rule(
+            || factory(2),
+            r#"
+          query Shallow {
+            human { ...humanFields }
+          }
+
+          query Deep {
+            human { ...relativeFields }
+          }
+
+          fragment humanFields on Human {
+            name
+            ... on Human { iq }
+          }
+
+          fragment relativeFields on Human {
+            relatives { ...humanFields }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(87, 5, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fragment_cycles() {
+        expect_passes_rule(
+            || factory(2),
+            r#"
+          {
+            human { ...humanFields }
+          }
+
+          fragment humanFields on Human {
+            name
+            ...humanFields
+          }
+        "#,
+        );
+    }
+}
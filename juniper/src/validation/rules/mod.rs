@@ -1,5 +1,6 @@
 mod arguments_of_correct_type;
 mod default_values_of_correct_type;
+mod executable_definitions;
 mod fields_on_correct_type;
 mod fragments_on_composite_types;
 mod known_argument_names;
@@ -7,6 +8,8 @@ mod known_directives;
 mod known_fragment_names;
 mod known_type_names;
 mod lone_anonymous_operation;
+mod max_complexity;
+mod max_depth;
 mod no_fragment_cycles;
 mod no_undefined_variables;
 mod no_unused_fragments;
@@ -15,7 +18,9 @@ mod overlapping_fields_can_be_merged;
 mod possible_fragment_spreads;
 mod provided_non_null_arguments;
 mod scalar_leafs;
+mod single_field_subscriptions;
 mod unique_argument_names;
+mod unique_directives_per_location;
 mod unique_fragment_names;
 mod unique_input_field_names;
 mod unique_operation_names;
@@ -24,35 +29,115 @@ mod variables_are_input_types;
 mod variables_in_allowed_position;
 
 use ast::Document;
-use validation::{visit, MultiVisitor, MultiVisitorNil, ValidatorContext};
+use executor::Variables;
+use validation::{visit, ValidationRule, ValidatorContext, Visitor};
+
+/// Validation rules of the GraphQL specification
+///
+/// All of them are checked by default, and can be turned off with
+/// `RootNode::disable_validation_rule`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum BuiltInRule {
+    ArgumentsOfCorrectType,
+    DefaultValuesOfCorrectType,
+    ExecutableDefinitions,
+    FieldsOnCorrectType,
+    FragmentsOnCompositeTypes,
+    KnownArgumentNames,
+    KnownDirectives,
+    KnownFragmentNames,
+    KnownTypeNames,
+    LoneAnonymousOperation,
+    NoFragmentCycles,
+    NoUndefinedVariables,
+    NoUnusedFragments,
+    NoUnusedVariables,
+    OverlappingFieldsCanBeMerged,
+    PossibleFragmentSpreads,
+    ProvidedNonNullArguments,
+    ScalarLeafs,
+    SingleFieldSubscriptions,
+    UniqueArgumentNames,
+    UniqueDirectivesPerLocation,
+    UniqueFragmentNames,
+    UniqueInputFieldNames,
+    UniqueOperationNames,
+    UniqueVariableNames,
+    VariablesAreInputTypes,
+    VariablesInAllowedPosition,
+}
 
 #[doc(hidden)]
 pub fn visit_all_rules<'a>(ctx: &mut ValidatorContext<'a>, doc: &'a Document) {
-    let mut mv = MultiVisitorNil
-        .with(self::arguments_of_correct_type::factory())
-        .with(self::default_values_of_correct_type::factory())
-        .with(self::fields_on_correct_type::factory())
-        .with(self::fragments_on_composite_types::factory())
-        .with(self::known_argument_names::factory())
-        .with(self::known_directives::factory())
-        .with(self::known_fragment_names::factory())
-        .with(self::known_type_names::factory())
-        .with(self::lone_anonymous_operation::factory())
-        .with(self::no_fragment_cycles::factory())
-        .with(self::no_undefined_variables::factory())
-        .with(self::no_unused_fragments::factory())
-        .with(self::no_unused_variables::factory())
-        .with(self::overlapping_fields_can_be_merged::factory())
-        .with(self::possible_fragment_spreads::factory())
-        .with(self::provided_non_null_arguments::factory())
-        .with(self::scalar_leafs::factory())
-        .with(self::unique_argument_names::factory())
-        .with(self::unique_fragment_names::factory())
-        .with(self::unique_input_field_names::factory())
-        .with(self::unique_operation_names::factory())
-        .with(self::unique_variable_names::factory())
-        .with(self::variables_are_input_types::factory())
-        .with(self::variables_in_allowed_position::factory());
-
-    visit(&mut mv, ctx, doc);
+    visit_rules(ctx, doc, &[], &[]);
+}
+
+#[doc(hidden)]
+pub fn visit_rules<'a>(
+    ctx: &mut ValidatorContext<'a>,
+    doc: &'a Document,
+    disabled_rules: &[BuiltInRule],
+    custom_rules: &[Box<ValidationRule>],
+) {
+    let built_in_rules: Vec<(BuiltInRule, Box<Visitor<'a> + 'a>)> = vec![
+        (BuiltInRule::ArgumentsOfCorrectType, Box::new(self::arguments_of_correct_type::factory())),
+        (BuiltInRule::DefaultValuesOfCorrectType, Box::new(self::default_values_of_correct_type::factory())),
+        (BuiltInRule::ExecutableDefinitions, Box::new(self::executable_definitions::factory())),
+        (BuiltInRule::FieldsOnCorrectType, Box::new(self::fields_on_correct_type::factory())),
+        (BuiltInRule::FragmentsOnCompositeTypes, Box::new(self::fragments_on_composite_types::factory())),
+        (BuiltInRule::KnownArgumentNames, Box::new(self::known_argument_names::factory())),
+        (BuiltInRule::KnownDirectives, Box::new(self::known_directives::factory())),
+        (BuiltInRule::KnownFragmentNames, Box::new(self::known_fragment_names::factory())),
+        (BuiltInRule::KnownTypeNames, Box::new(self::known_type_names::factory())),
+        (BuiltInRule::LoneAnonymousOperation, Box::new(self::lone_anonymous_operation::factory())),
+        (BuiltInRule::NoFragmentCycles, Box::new(self::no_fragment_cycles::factory())),
+        (BuiltInRule::NoUndefinedVariables, Box::new(self::no_undefined_variables::factory())),
+        (BuiltInRule::NoUnusedFragments, Box::new(self::no_unused_fragments::factory())),
+        (BuiltInRule::NoUnusedVariables, Box::new(self::no_unused_variables::factory())),
+        (BuiltInRule::OverlappingFieldsCanBeMerged, Box::new(self::overlapping_fields_can_be_merged::factory())),
+        (BuiltInRule::PossibleFragmentSpreads, Box::new(self::possible_fragment_spreads::factory())),
+        (BuiltInRule::ProvidedNonNullArguments, Box::new(self::provided_non_null_arguments::factory())),
+        (BuiltInRule::ScalarLeafs, Box::new(self::scalar_leafs::factory())),
+        (BuiltInRule::SingleFieldSubscriptions, Box::new(self::single_field_subscriptions::factory())),
+        (BuiltInRule::UniqueArgumentNames, Box::new(self::unique_argument_names::factory())),
+        (BuiltInRule::UniqueDirectivesPerLocation, Box::new(self::unique_directives_per_location::factory())),
+        (BuiltInRule::UniqueFragmentNames, Box::new(self::unique_fragment_names::factory())),
+        (BuiltInRule::UniqueInputFieldNames, Box::new(self::unique_input_field_names::factory())),
+        (BuiltInRule::UniqueOperationNames, Box::new(self::unique_operation_names::factory())),
+        (BuiltInRule::UniqueVariableNames, Box::new(self::unique_variable_names::factory())),
+        (BuiltInRule::VariablesAreInputTypes, Box::new(self::variables_are_input_types::factory())),
+        (BuiltInRule::VariablesInAllowedPosition, Box::new(self::variables_in_allowed_position::factory())),
+    ];
+
+    let mut visitors = built_in_rules
+        .into_iter()
+        .filter(|&(rule, _)| !disabled_rules.contains(&rule))
+        .map(|(_, visitor)| visitor)
+        .chain(custom_rules.iter().map(|rule| rule.visitor()))
+        .collect::<Vec<_>>();
+
+    visit(&mut visitors, ctx, doc);
+}
+
+#[doc(hidden)]
+pub fn visit_limit_rules<'a>(
+    ctx: &mut ValidatorContext<'a>,
+    doc: &'a Document,
+    max_depth: Option<usize>,
+    max_complexity: Option<usize>,
+    operation_name: Option<&'a str>,
+    variables: &'a Variables,
+) {
+    if let Some(max_depth) = max_depth {
+        visit(&mut self::max_depth::factory(max_depth), ctx, doc);
+    }
+
+    if let Some(max_complexity) = max_complexity {
+        visit(
+            &mut self::max_complexity::factory(max_complexity, operation_name, variables),
+            ctx,
+            doc,
+        );
+    }
 }
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use ast::{Definition, Document, Fragment, OperationType, Selection};
+use parser::SourcePosition;
+use validation::{ValidatorContext, Visitor};
+
+pub struct SingleFieldSubscriptions;
+
+pub fn factory() -> SingleFieldSubscriptions {
+    SingleFieldSubscriptions
+}
+
+impl<'a> Visitor<'a> for SingleFieldSubscriptions {
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a>, document: &'a Document) {
+        let fragments = document
+            .iter()
+            .filter_map(|def| match *def {
+                Definition::Fragment(ref f) => Some((f.item.name.item, &f.item)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        for def in document {
+            let op = match *def {
+                Definition::Operation(ref op)
+                    if op.item.operation_type == OperationType::Subscription =>
+                {
+                    op
+                }
+                _ => continue,
+            };
+
+            let mut fields = Vec::new();
+            collect_fields(
+                &op.item.selection_set,
+                &fragments,
+                &mut HashSet::new(),
+                &mut fields,
+            );
+
+            let op_name = op.item.name.as_ref().map(|s| s.item);
+
+            if fields.len() > 1 {
+                ctx.report_error(
+                    &error_message(op_name),
+                    &fields[1..]
+                        .iter()
+                        .map(|&(_, _, ref pos)| pos.clone())
+                        .collect::<Vec<_>>(),
+                );
+            }
+
+            for &(_, name, ref pos) in &fields {
+                if name.starts_with("__") {
+                    ctx.report_error(&introspection_error_message(op_name), &[pos.clone()]);
+                }
+            }
+        }
+    }
+}
+
+/// Collect the response names and field names of the fields selected by a
+/// selection set, including the fields of its fragments
+///
+/// Fields selected more than once are only collected the first time.
+fn collect_fields<'a>(
+    selection_set: &'a [Selection<'a>],
+    fragments: &HashMap<&'a str, &'a Fragment<'a>>,
+    visited_fragments: &mut HashSet<&'a str>,
+    fields: &mut Vec<(&'a str, &'a str, SourcePosition)>,
+) {
+    for selection in selection_set {
+        match *selection {
+            Selection::Field(ref f) => {
+                let response_name = f.item.alias.as_ref().unwrap_or(&f.item.name).item;
+
+                if fields.iter().all(|&(name, _, _)| name != response_name) {
+                    fields.push((response_name, f.item.name.item, f.start.clone()));
+                }
+            }
+            Selection::FragmentSpread(ref spread) => {
+                let name = spread.item.name.item;
+
+                // Unknown and cyclic fragments are reported by other rules
+                if visited_fragments.insert(name) {
+                    if let Some(fragment) = fragments.get(name) {
+                        collect_fields(
+                            &fragment.selection_set,
+                            fragments,
+                            visited_fragments,
+                            fields,
+                        );
+                    }
+                }
+            }
+            Selection::InlineFragment(ref fragment) => {
+                collect_fields(
+                    &fragment.item.selection_set,
+                    fragments,
+                    visited_fragments,
+                    fields,
+                );
+            }
+        }
+    }
+}
+
+fn error_message(op_name: Option<&str>) -> String {
+    if let Some(op_name) = op_name {
+        format!(r#"Subscription "{}" must select only one top level field"#, op_name)
+    } else {
+        "Anonymous subscription must select only one top level field".to_owned()
+    }
+}
+
+fn introspection_error_message(op_name: Option<&str>) -> String {
+    if let Some(op_name) = op_name {
+        format!(
+            r#"Subscription "{}" must not select an introspection top level field"#,
+            op_name
+        )
+    } else {
+        "Anonymous subscription must not select an introspection top level field".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory, introspection_error_message};
+
+    use parser::SourcePosition;
+    use validation::{expect_fails_rule, expect_passes_rule, RuleError};
+
+    #[test]
+    fn valid_subscription() {
+        expect_passes_rule(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            importantEmails
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn queries_with_multiple_fields() {
+        expect_passes_rule(
+            factory,
+            r#"
+          query Foo {
+            fieldA
+            fieldB
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            importantEmails
+            notImportantEmails
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(Some("ImportantEmails")),
+                &[SourcePosition::new(82, 3, 12)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_in_anonymous_subscription() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription {
+            importantEmails
+            notImportantEmails
+            spamEmails
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(None),
+                &[
+                    SourcePosition::new(66, 3, 12),
+                    SourcePosition::new(97, 4, 12),
+                ],
+            )],
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_through_fragments() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription {
+            ...F
+          }
+
+          fragment F on Subscription {
+            a
+            b
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(None),
+                &[SourcePosition::new(121, 7, 12)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_through_inline_fragments() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            importantEmails
+            ... on Subscription {
+              notImportantEmails
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(Some("ImportantEmails")),
+                &[SourcePosition::new(118, 4, 14)],
+            )],
+        );
+    }
+
+    #[test]
+    fn same_field_selected_twice_through_fragments() {
+        expect_passes_rule(
+            factory,
+            r#"
+          subscription {
+            importantEmails
+            ...F
+          }
+
+          fragment F on Subscription {
+            importantEmails
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_introspection_root_field() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            __typename
+          }
+        "#,
+            &[RuleError::new(
+                &introspection_error_message(Some("ImportantEmails")),
+                &[SourcePosition::new(54, 2, 12)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fails_with_introspection_root_field_through_fragments() {
+        expect_fails_rule(
+            factory,
+            r#"
+          subscription {
+            ...F
+          }
+
+          fragment F on Subscription {
+            __typename
+          }
+        "#,
+            &[RuleError::new(
+                &introspection_error_message(None),
+                &[SourcePosition::new(107, 6, 12)],
+            )],
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use ast::{Directive, Field, Fragment, FragmentSpread, InlineFragment, Operation, VariableDefinition};
+use parser::Spanning;
+use validation::{ValidatorContext, Visitor};
+
+pub struct UniqueDirectivesPerLocation;
+
+pub fn factory() -> UniqueDirectivesPerLocation {
+    UniqueDirectivesPerLocation
+}
+
+impl<'a> Visitor<'a> for UniqueDirectivesPerLocation {
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        op: &'a Spanning<Operation>,
+    ) {
+        check_directives(ctx, &op.item.directives);
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        f: &'a Spanning<Fragment>,
+    ) {
+        check_directives(ctx, &f.item.directives);
+    }
+
+    fn enter_variable_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        def: &'a (Spanning<&'a str>, VariableDefinition),
+    ) {
+        check_directives(ctx, &def.1.directives);
+    }
+
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a>, field: &'a Spanning<Field>) {
+        check_directives(ctx, &field.item.directives);
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        spread: &'a Spanning<FragmentSpread>,
+    ) {
+        check_directives(ctx, &spread.item.directives);
+    }
+
+    fn enter_inline_fragment(
+        &mut self,
+        ctx: &mut ValidatorContext<'a>,
+        fragment: &'a Spanning<InlineFragment>,
+    ) {
+        check_directives(ctx, &fragment.item.directives);
+    }
+}
+
+fn check_directives<'a>(
+    ctx: &mut ValidatorContext<'a>,
+    directives: &'a Option<Vec<Spanning<Directive>>>,
+) {
+    if let Some(ref directives) = *directives {
+        for (i, directive) in directives.iter().enumerate() {
+            let name = directive.item.name.item;
+
+            let is_repeatable = ctx.schema
+                .directive_by_name(name)
+                .map(|d| d.is_repeatable)
+                .unwrap_or(true);
+
+            if is_repeatable {
+                continue;
+            }
+
+            if let Some(first) = directives[..i].iter().find(|d| d.item.name.item == name) {
+                ctx.report_error(
+                    &error_message(name),
+                    &[first.start.clone(), directive.start.clone()],
+                );
+            }
+        }
+    }
+}
+
+fn error_message(directive_name: &str) -> String {
+    format!(
+        r#"The directive "{}" can only be used once at this location"#,
+        directive_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use parser::SourcePosition;
+    use validation::{expect_fails_rule, expect_passes_rule, RuleError};
+
+    #[test]
+    fn no_directives() {
+        expect_passes_rule(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unique_directives_in_different_locations() {
+        expect_passes_rule(
+            factory,
+            r#"
+          fragment Test on Dog @onFragmentDefinition {
+            name @onField
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn same_directives_in_different_locations() {
+        expect_passes_rule(
+            factory,
+            r#"
+          fragment Test on Dog @onFragmentDefinition {
+            name @onField @include(if: true)
+            nickname @onField @include(if: true)
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn repeatable_directives_in_same_location() {
+        expect_passes_rule(
+            factory,
+            r#"
+          query Test @repeatable @repeatable {
+            dog {
+              name @repeatable @repeatable @repeatable
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unknown_directives_are_left_to_known_directives() {
+        expect_passes_rule(
+            factory,
+            r#"
+          {
+            dog @unknown @unknown
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_in_one_location() {
+        expect_fails_rule(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @onField @onField
+          }
+        "#,
+            &[RuleError::new(
+                &error_message("onField"),
+                &[
+                    SourcePosition::new(51, 2, 17),
+                    SourcePosition::new(60, 2, 26),
+                ],
+            )],
+        );
+    }
+
+    #[test]
+    fn many_duplicate_directives_in_one_location() {
+        expect_fails_rule(
+            factory,
+            r#"
+          fragment Test on Dog {
+            name @onField @onField @onField
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("onField"),
+                    &[
+                        SourcePosition::new(51, 2, 17),
+                        SourcePosition::new(60, 2, 26),
+                    ],
+                ),
+                RuleError::new(
+                    &error_message("onField"),
+                    &[
+                        SourcePosition::new(51, 2, 17),
+                        SourcePosition::new(69, 2, 35),
+                    ],
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_on_variable_definitions_and_operations() {
+        expect_fails_rule(
+            factory,
+            r#"
+          query Test($var: Boolean @onVariableDefinition @onVariableDefinition)
+            @onQuery @onQuery {
+            dog
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("onVariableDefinition"),
+                    &[
+                        SourcePosition::new(36, 1, 35),
+                        SourcePosition::new(58, 1, 57),
+                    ],
+                ),
+                RuleError::new(
+                    &error_message("onQuery"),
+                    &[
+                        SourcePosition::new(93, 2, 12),
+                        SourcePosition::new(102, 2, 21),
+                    ],
+                ),
+            ],
+        );
+    }
+}
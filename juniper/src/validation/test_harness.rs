@@ -494,6 +494,11 @@ where
         &[DirectiveLocation::Mutation],
         &[],
     ));
+    root.schema.add_directive(DirectiveType::new(
+        "onSubscription",
+        &[DirectiveLocation::Subscription],
+        &[],
+    ));
     root.schema.add_directive(DirectiveType::new(
         "onField",
         &[DirectiveLocation::Field],
@@ -514,6 +519,18 @@ where
         &[DirectiveLocation::InlineFragment],
         &[],
     ));
+    root.schema.add_directive(DirectiveType::new(
+        "onVariableDefinition",
+        &[DirectiveLocation::VariableDefinition],
+        &[],
+    ));
+    root.schema.add_directive(
+        DirectiveType::new(
+            "repeatable",
+            &[DirectiveLocation::Query, DirectiveLocation::Field],
+            &[],
+        ).repeatable(),
+    );
 
     let doc = parse_document_source(q).expect(&format!("Parse error on input {:#?}", q));
     let mut ctx = ValidatorContext::new(unsafe { ::std::mem::transmute(&root.schema) }, &doc);
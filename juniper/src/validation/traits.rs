@@ -5,7 +5,13 @@ use ast::{
 use parser::Spanning;
 use validation::ValidatorContext;
 
-#[doc(hidden)]
+/// Visitor of the nodes of a query document
+///
+/// Validation rules implement this trait to inspect the document. Every node is
+/// passed to the `enter_` method of its kind before its children are visited,
+/// and to the `exit_` method afterwards. All methods do nothing by default, and
+/// errors are reported through `ValidatorContext::report_error`.
+#[allow(missing_docs)]
 pub trait Visitor<'a> {
     fn enter_document(&mut self, _: &mut ValidatorContext<'a>, _: &'a Document) {}
     fn exit_document(&mut self, _: &mut ValidatorContext<'a>, _: &'a Document) {}
@@ -100,8 +106,8 @@ pub trait Visitor<'a> {
     fn enter_null_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<()>) {}
     fn exit_null_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<()>) {}
 
-    fn enter_int_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<i32>) {}
-    fn exit_int_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<i32>) {}
+    fn enter_int_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<i64>) {}
+    fn exit_int_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<i64>) {}
 
     fn enter_float_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<f64>) {}
     fn exit_float_value(&mut self, _: &mut ValidatorContext<'a>, _: Spanning<f64>) {}
@@ -157,3 +163,25 @@ pub trait Visitor<'a> {
     ) {
     }
 }
+
+/// Validation rule run on every query
+///
+/// Rules are registered with `RootNode::add_validation_rule`, and create a
+/// fresh visitor for every validated document. Functions and closures returning
+/// a visitor that does not borrow from the document implement this trait, e.g.
+/// a unit struct implementing `Visitor` can be registered with
+/// `add_validation_rule(|| MyRule)`.
+pub trait ValidationRule: Send + Sync {
+    /// Create the visitor checking a document
+    fn visitor<'a>(&self) -> Box<Visitor<'a> + 'a>;
+}
+
+impl<F, V> ValidationRule for F
+where
+    F: Fn() -> V + Send + Sync,
+    V: for<'a> Visitor<'a> + 'static,
+{
+    fn visitor<'a>(&self) -> Box<Visitor<'a> + 'a> {
+        Box::new(self())
+    }
+}
@@ -50,6 +50,17 @@ fn visit_definitions<'a, V: Visitor<'a>>(
             }) => ctx.schema
                 .concrete_mutation_type()
                 .map(|t| Type::NonNullNamed(Cow::Borrowed(t.name().unwrap()))),
+            Definition::Operation(Spanning {
+                item:
+                    Operation {
+                        operation_type: OperationType::Subscription,
+                        ..
+                    },
+                ..
+            }) => ctx.schema
+                .concrete_subscription_type()
+                .map(|t| Type::NonNullNamed(Cow::Borrowed(t.name().unwrap()))),
+            Definition::TypeSystem(_) => None,
         };
 
         ctx.with_pushed_type(def_type.as_ref(), |ctx| {
@@ -68,6 +79,7 @@ fn enter_definition<'a, V: Visitor<'a>>(
     match *def {
         Definition::Operation(ref op) => v.enter_operation_definition(ctx, op),
         Definition::Fragment(ref f) => v.enter_fragment_definition(ctx, f),
+        Definition::TypeSystem(_) => (),
     }
 }
 
@@ -79,6 +91,7 @@ fn exit_definition<'a, V: Visitor<'a>>(
     match *def {
         Definition::Operation(ref op) => v.exit_operation_definition(ctx, op),
         Definition::Fragment(ref f) => v.exit_fragment_definition(ctx, f),
+        Definition::TypeSystem(_) => (),
     }
 }
 
@@ -97,6 +110,7 @@ fn visit_definition<'a, V: Visitor<'a>>(
             visit_directives(v, ctx, &f.item.directives);
             visit_selection_set(v, ctx, &f.item.selection_set);
         }
+        Definition::TypeSystem(_) => (),
     }
 }
 
@@ -116,6 +130,8 @@ fn visit_variable_definitions<'a, V: Visitor<'a>>(
                     visit_input_value(v, ctx, default_value);
                 }
 
+                visit_directives(v, ctx, &def.1.directives);
+
                 v.exit_variable_definition(ctx, def);
             })
         }
@@ -320,6 +336,8 @@ fn enter_input_value<'a, V: Visitor<'a>>(
         Variable(ref s) => v.enter_variable_value(ctx, Spanning::start_end(start, end, s)),
         List(ref l) => v.enter_list_value(ctx, Spanning::start_end(start, end, l)),
         Object(ref o) => v.enter_object_value(ctx, Spanning::start_end(start, end, o)),
+        // Uploads are never part of a parsed document
+        Upload(_) => (),
     }
 }
 
@@ -343,5 +361,7 @@ fn exit_input_value<'a, V: Visitor<'a>>(
         Variable(ref s) => v.exit_variable_value(ctx, Spanning::start_end(start, end, s)),
         List(ref l) => v.exit_list_value(ctx, Spanning::start_end(start, end, l)),
         Object(ref o) => v.exit_object_value(ctx, Spanning::start_end(start, end, o)),
+        // Uploads are never part of a parsed document
+        Upload(_) => (),
     }
 }
@@ -16,7 +16,7 @@ use std::vec::IntoIter;
 #[allow(missing_docs)]
 pub enum Value {
     Null,
-    Int(i32),
+    Int(i64),
     Float(f64),
     String(String),
     Boolean(bool),
@@ -178,7 +178,7 @@ impl Value {
     }
 
     /// Construct an integer value.
-    pub fn int(i: i32) -> Value {
+    pub fn int(i: i64) -> Value {
         Value::Int(i)
     }
 
@@ -305,6 +305,12 @@ impl From<bool> for Value {
 
 impl From<i32> for Value {
     fn from(i: i32) -> Value {
+        Value::int(i64::from(i))
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Value {
         Value::int(i)
     }
 }
@@ -374,6 +380,14 @@ mod tests {
         assert_eq!(graphql_value!(123), Value::int(123));
     }
 
+    #[test]
+    fn value_macro_big_int() {
+        assert_eq!(
+            graphql_value!(9_007_199_254_740_993i64),
+            Value::int(9_007_199_254_740_993)
+        );
+    }
+
     #[test]
     fn value_macro_float() {
         assert_eq!(graphql_value!(123.5), Value::float(123.5));
@@ -0,0 +1,36 @@
+use actix_web::{rt, web, App, HttpRequest, HttpResponse, HttpServer};
+use juniper::tests::model::Database;
+use juniper::EmptyMutation;
+use juniper::RootNode;
+
+type Schema = RootNode<'static, Database, EmptyMutation<Database>>;
+
+async fn graphql(
+    request: HttpRequest,
+    body: web::Bytes,
+    schema: web::Data<Schema>,
+) -> actix_web::Result<HttpResponse> {
+    juniper_actix::graphql(schema.into_inner(), |_| Ok(Database::new()), &request, body).await
+}
+
+fn main() -> std::io::Result<()> {
+    let addr = "127.0.0.1:3000";
+
+    let schema = web::Data::new(Schema::new(Database::new(), EmptyMutation::new()));
+
+    rt::System::new().block_on(async move {
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(schema.clone())
+                .route(
+                    "/",
+                    web::get().to(|| async { juniper_actix::graphiql("/graphql") }),
+                )
+                .route("/graphql", web::route().to(graphql))
+        })
+        .bind(addr)?;
+        println!("Listening on http://{}", addr);
+
+        server.run().await
+    })
+}
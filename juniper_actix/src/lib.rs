@@ -0,0 +1,283 @@
+/*!
+
+# juniper_actix
+
+This repository contains the [Actix][Actix] web framework integration for
+[Juniper][Juniper], a [GraphQL][GraphQL] implementation for Rust.
+
+For documentation, including guides and examples, check out [Juniper][Juniper].
+
+## Integrating with Actix
+
+`graphql` handles both GET and POST requests, including batches of queries.
+The context of every request is built from the `HttpRequest` by a context
+extractor, and queries are executed on the blocking thread pool of Actix so
+they don't block its workers.
+
+```rust,no_run
+use actix_web::{rt, web, App, HttpRequest, HttpResponse, HttpServer};
+use juniper::tests::model::Database;
+use juniper::{EmptyMutation, RootNode};
+
+type Schema = RootNode<'static, Database, EmptyMutation<Database>>;
+
+async fn graphql(
+    request: HttpRequest,
+    body: web::Bytes,
+    schema: web::Data<Schema>,
+) -> actix_web::Result<HttpResponse> {
+    // The context would usually be built from the headers or the state of
+    // the request
+    juniper_actix::graphql(schema.into_inner(), |_| Ok(Database::new()), &request, body).await
+}
+
+fn main() -> std::io::Result<()> {
+    let schema = web::Data::new(Schema::new(Database::new(), EmptyMutation::new()));
+
+    rt::System::new().block_on(async move {
+        HttpServer::new(move || {
+            App::new()
+                .app_data(schema.clone())
+                .route(
+                    "/graphiql",
+                    web::get().to(|| async { juniper_actix::graphiql("/graphql") }),
+                )
+                .route("/graphql", web::route().to(graphql))
+        })
+        .bind("127.0.0.1:8080")?
+        .run()
+        .await
+    })
+}
+```
+
+## Links
+
+* [Juniper][Juniper]
+* [Api Reference][documentation]
+* [Actix][Actix]
+
+[Actix]: https://actix.rs
+[Juniper]: https://github.com/graphql-rust/juniper
+[GraphQL]: http://graphql.org
+[documentation]: https://docs.rs/juniper_actix
+
+*/
+
+use actix_web::http::{Method, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use juniper::http::{self, GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest};
+use juniper::{GraphQLType, RootNode};
+use serde_json::error::Error as SerdeError;
+use std::error::Error;
+use std::fmt;
+use std::str::{self, Utf8Error};
+use std::sync::Arc;
+use url::form_urlencoded;
+
+/// Handle a GraphQL GET or POST request
+///
+/// `body` is the body of the request, as extracted by Actix. The
+/// `context_extractor` builds the context of the request, an error returned
+/// by it is sent as the response instead. Queries are executed with
+/// `web::block`, on the thread pool Actix keeps for blocking work.
+pub async fn graphql<CtxT, QueryT, MutationT, F>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT>>,
+    context_extractor: F,
+    request: &HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, actix_web::Error>
+where
+    F: FnOnce(&HttpRequest) -> Result<CtxT, actix_web::Error>,
+    CtxT: Send + Sync + 'static,
+    QueryT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    MutationT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+{
+    let context = context_extractor(request)?;
+
+    let gql_request = match *request.method() {
+        Method::GET => {
+            gql_request_from_get(request.query_string()).map(GraphQLBatchRequest::Single)
+        }
+        Method::POST => gql_request_from_post(&body),
+        _ => return Ok(HttpResponse::MethodNotAllowed().finish()),
+    };
+
+    match gql_request {
+        Ok(gql_request) => execute_request(root_node, context, gql_request).await,
+        Err(err) => Ok(render_error(err)),
+    }
+}
+
+/// Create a response containing GraphiQL, which sends its queries to
+/// `graphql_endpoint`
+pub fn graphiql(graphql_endpoint: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(juniper::graphiql::graphiql_source(graphql_endpoint))
+}
+
+fn render_error(err: GraphQLRequestError) -> HttpResponse {
+    HttpResponse::BadRequest().body(format!("{}", err))
+}
+
+async fn execute_request<CtxT, QueryT, MutationT>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT>>,
+    context: CtxT,
+    request: GraphQLBatchRequest,
+) -> Result<HttpResponse, actix_web::Error>
+where
+    CtxT: Send + Sync + 'static,
+    QueryT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    MutationT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+{
+    let (is_ok, body) = web::block(move || {
+        let res = request.execute(&root_node, &context);
+        (res.is_ok(), serde_json::to_string(&res).unwrap())
+    })
+    .await?;
+
+    let code = if is_ok {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    Ok(HttpResponse::build(code)
+        .content_type("application/json")
+        .body(body))
+}
+
+fn gql_request_from_get(input: &str) -> Result<JuniperGraphQLRequest, GraphQLRequestError> {
+    let parameters = form_urlencoded::parse(input.as_bytes()).into_owned();
+    JuniperGraphQLRequest::from_get_parameters(parameters).map_err(GraphQLRequestError::Get)
+}
+
+fn gql_request_from_post(body: &[u8]) -> Result<GraphQLBatchRequest, GraphQLRequestError> {
+    str::from_utf8(body)
+        .map_err(GraphQLRequestError::BodyUtf8)
+        .and_then(|input| {
+            serde_json::from_str::<GraphQLBatchRequest>(input)
+                .map_err(GraphQLRequestError::BodyJSONError)
+        })
+}
+
+#[derive(Debug)]
+enum GraphQLRequestError {
+    BodyUtf8(Utf8Error),
+    BodyJSONError(SerdeError),
+    Get(http::GraphQLRequestError),
+}
+
+impl fmt::Display for GraphQLRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphQLRequestError::BodyUtf8(ref err) => fmt::Display::fmt(err, f),
+            GraphQLRequestError::BodyJSONError(ref err) => fmt::Display::fmt(err, f),
+            GraphQLRequestError::Get(ref err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for GraphQLRequestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GraphQLRequestError::BodyUtf8(ref err) => Some(err),
+            GraphQLRequestError::BodyJSONError(ref err) => Some(err),
+            GraphQLRequestError::Get(ref err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{header, StatusCode};
+    use actix_web::rt::System;
+    use actix_web::{test, web, App, HttpRequest};
+    use juniper::http::tests as http_tests;
+    use juniper::tests::model::Database;
+    use juniper::EmptyMutation;
+    use juniper::RootNode;
+    use std::sync::Arc;
+
+    type Schema = RootNode<'static, Database, EmptyMutation<Database>>;
+
+    struct TestActixIntegration {
+        root_node: Arc<Schema>,
+    }
+
+    impl TestActixIntegration {
+        fn new() -> TestActixIntegration {
+            TestActixIntegration {
+                root_node: Arc::new(RootNode::new(
+                    Database::new(),
+                    EmptyMutation::<Database>::new(),
+                )),
+            }
+        }
+
+        fn send(&self, request: test::TestRequest) -> http_tests::TestResponse {
+            let root_node = self.root_node.clone();
+
+            System::new().block_on(async move {
+                let handler = move |req: HttpRequest, body: web::Bytes| {
+                    let root_node = root_node.clone();
+                    async move {
+                        super::graphql(root_node, |_| Ok(Database::new()), &req, body).await
+                    }
+                };
+                let app = test::init_service(App::new().default_service(web::to(handler))).await;
+                let response = test::call_service(&app, request.to_request()).await;
+
+                let status_code = i32::from(response.status().as_u16());
+                let content_type = response
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .map(|ct| ct.to_str().unwrap().to_owned())
+                    .unwrap_or_default();
+                let body = test::read_body(response).await;
+
+                http_tests::TestResponse {
+                    status_code,
+                    body: Some(String::from_utf8(body.to_vec()).unwrap()),
+                    content_type,
+                }
+            })
+        }
+    }
+
+    impl http_tests::HTTPIntegration for TestActixIntegration {
+        fn get(&self, url: &str) -> http_tests::TestResponse {
+            self.send(test::TestRequest::get().uri(&format!("/graphql{}", url)))
+        }
+
+        fn post(&self, url: &str, body: &str) -> http_tests::TestResponse {
+            self.send(
+                test::TestRequest::post()
+                    .uri(&format!("/graphql{}", url))
+                    .set_payload(body.to_owned()),
+            )
+        }
+    }
+
+    #[test]
+    fn test_actix_integration() {
+        let integration = TestActixIntegration::new();
+
+        http_tests::run_http_test_suite(&integration);
+    }
+
+    #[test]
+    fn test_graphiql() {
+        let response = super::graphiql("/dogs-api/graphql");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+    }
+}
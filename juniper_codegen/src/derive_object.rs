@@ -50,6 +50,7 @@ struct ObjFieldAttrs {
     name: Option<String>,
     description: Option<String>,
     deprecation: Option<String>,
+    complexity: Option<usize>,
     skip: bool,
 }
 
@@ -82,6 +83,18 @@ impl ObjFieldAttrs {
                     res.deprecation = Some(val);
                     continue;
                 }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "complexity", AttributeValidation::String) {
+                    match val.parse() {
+                        Ok(complexity) => {
+                            res.complexity = Some(complexity);
+                            continue;
+                        }
+                        Err(_) => panic!(
+                            "Complexity must be a non-negative integer but \"{}\" is not",
+                            &*val
+                        ),
+                    }
+                }
                 if let Some(_) = keyed_item_value(&item, "skip", AttributeValidation::Bare) {
                     res.skip = true;
                     continue;
@@ -122,6 +135,7 @@ pub fn impl_object(ast: &syn::DeriveInput) -> TokenStream {
 
     let mut meta_fields = TokenStream::new();
     let mut resolvers = TokenStream::new();
+    let mut async_resolvers = TokenStream::new();
 
     for field in fields {
         let field_ty = &field.ty;
@@ -154,11 +168,17 @@ pub fn impl_object(ast: &syn::DeriveInput) -> TokenStream {
             None => quote!{ field },
         };
 
+        let build_complexity = match field_attrs.complexity {
+            Some(c) => quote!{ field.complexity(#c)  },
+            None => quote!{ field },
+        };
+
         meta_fields.extend(quote!{
             {
                 let field = registry.field::<#field_ty>(#name, &());
                 let field = #build_description;
                 let field = #build_deprecation;
+                let field = #build_complexity;
                 field
             },
         });
@@ -168,6 +188,10 @@ pub fn impl_object(ast: &syn::DeriveInput) -> TokenStream {
         resolvers.extend(quote!{
             #name => executor.resolve_with_ctx(&(), &self.#field_ident),
         });
+
+        async_resolvers.extend(quote!{
+            #name => executor.resolve_with_ctx_async(&(), &self.#field_ident),
+        });
     }
 
     let toks = quote! {
@@ -210,6 +234,22 @@ pub fn impl_object(ast: &syn::DeriveInput) -> TokenStream {
                 }
 
             }
+
+            fn resolve_field_async<'r>(
+                &self,
+                _: &'r (),
+                field_name: &str,
+                _: &::juniper::Arguments,
+                executor: &::juniper::Executor<'r, Self::Context>
+            ) -> ::juniper::ExecutionFuture<'r>
+            {
+
+                match field_name {
+                    #(#async_resolvers)*
+                    _ => panic!("Field {} not found on type {}", field_name, #ident_name),
+                }
+
+            }
         }
     };
 
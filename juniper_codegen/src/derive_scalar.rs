@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use syn;
+use syn::{Data, DeriveInput, Fields, Path};
+
+use util::*;
+
+#[derive(Default, Debug)]
+struct ScalarAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    parse_with: Option<Path>,
+    serialize_with: Option<Path>,
+}
+
+impl ScalarAttrs {
+    fn from_input(input: &DeriveInput) -> ScalarAttrs {
+        let mut res = ScalarAttrs::default();
+
+        // Check doc comments for description.
+        res.description = get_doc_comment(&input.attrs);
+
+        // Check attributes for name, description and conversion functions.
+        if let Some(items) = get_graphql_attr(&input.attrs) {
+            for item in items {
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "name", AttributeValidation::String)  {
+                    if is_valid_name(&*val) {
+                        res.name = Some(val);
+                        continue;
+                    } else {
+                        panic!(
+                            "Names must match /^[_a-zA-Z][_a-zA-Z0-9]*$/ but \"{}\" does not",
+                            &*val
+                        );
+                    }
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "description", AttributeValidation::String)  {
+                    res.description = Some(val);
+                    continue;
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "parse_with", AttributeValidation::String)  {
+                    res.parse_with = Some(parse_path(&val));
+                    continue;
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "serialize_with", AttributeValidation::String)  {
+                    res.serialize_with = Some(parse_path(&val));
+                    continue;
+                }
+                panic!(format!(
+                    "Unknown attribute for #[derive(GraphQLScalar)]: {:?}",
+                    item
+                ));
+            }
+        }
+        res
+    }
+}
+
+fn parse_path(source: &str) -> Path {
+    match syn::parse_str(source) {
+        Ok(path) => path,
+        Err(_) => panic!("\"{}\" is not a valid function path", source),
+    }
+}
+
+pub fn impl_scalar(ast: &syn::DeriveInput) -> TokenStream {
+    let inner_ty = match ast.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            _ => panic!(
+                "#[derive(GraphQLScalar)] may only be applied to structs with a single unnamed field, e.g. struct UserId(String)"
+            ),
+        },
+        _ => panic!("#[derive(GraphQLScalar)] may only be applied to structs"),
+    };
+
+    // Parse attributes.
+    let ident = &ast.ident;
+    let attrs = ScalarAttrs::from_input(ast);
+    let name = attrs.name.clone().unwrap_or(ast.ident.to_string());
+
+    let meta_description = match attrs.description {
+        Some(ref descr) => quote!{ let meta = meta.description(#descr); },
+        None => quote!{ let meta = meta; },
+    };
+
+    // The inner value the scalar is resolved and serialized as.
+    let inner_value = match attrs.serialize_with {
+        Some(ref serialize) => quote!{ &#serialize(self) },
+        None => quote!{ &self.0 },
+    };
+
+    // Input values that are rejected by the parse function are invalid.
+    let from_inner = match attrs.parse_with {
+        Some(ref parse) => quote!{ .and_then(#parse) },
+        None => quote!{ .map(#ident) },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::juniper::GraphQLType for #ident #ty_generics #where_clause {
+            type Context = ();
+            type TypeInfo = ();
+
+            fn name(_: &()) -> Option<&str> {
+                Some(#name)
+            }
+
+            fn meta<'r>(
+                info: &(),
+                registry: &mut ::juniper::Registry<'r>
+            ) -> ::juniper::meta::MetaType<'r> {
+                let meta = registry.build_scalar_type::<Self>(info);
+                #meta_description
+                meta.into_meta()
+            }
+
+            fn resolve(
+                &self,
+                info: &(),
+                _: Option<&[::juniper::Selection]>,
+                executor: &::juniper::Executor<Self::Context>,
+            ) -> ::juniper::Value {
+                <#inner_ty as ::juniper::GraphQLType>::resolve(#inner_value, info, None, executor)
+            }
+        }
+
+        impl #impl_generics ::juniper::ToInputValue for #ident #ty_generics #where_clause {
+            fn to_input_value(&self) -> ::juniper::InputValue {
+                <#inner_ty as ::juniper::ToInputValue>::to_input_value(#inner_value)
+            }
+        }
+
+        impl #impl_generics ::juniper::FromInputValue for #ident #ty_generics #where_clause {
+            fn from_input_value(v: &::juniper::InputValue) -> Option<Self> {
+                <#inner_ty as ::juniper::FromInputValue>::from_input_value(v)#from_inner
+            }
+        }
+    }
+}
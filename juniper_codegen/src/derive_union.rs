@@ -0,0 +1,179 @@
+use proc_macro2::TokenStream;
+
+use syn;
+use syn::{Data, DeriveInput, Fields, Type};
+
+use util::*;
+
+#[derive(Default, Debug)]
+struct UnionAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    context: Option<Type>,
+}
+
+impl UnionAttrs {
+    fn from_input(input: &DeriveInput) -> UnionAttrs {
+        let mut res = UnionAttrs::default();
+
+        // Check doc comments for description.
+        res.description = get_doc_comment(&input.attrs);
+
+        // Check attributes for name, description and context.
+        if let Some(items) = get_graphql_attr(&input.attrs) {
+            for item in items {
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "name", AttributeValidation::String)  {
+                    if is_valid_name(&*val) {
+                        res.name = Some(val);
+                        continue;
+                    } else {
+                        panic!(
+                            "Names must match /^[_a-zA-Z][_a-zA-Z0-9]*$/ but \"{}\" does not",
+                            &*val
+                        );
+                    }
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "description", AttributeValidation::String)  {
+                    res.description = Some(val);
+                    continue;
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "context", AttributeValidation::String)  {
+                    match syn::parse_str(&val) {
+                        Ok(ty) => {
+                            res.context = Some(ty);
+                            continue;
+                        }
+                        Err(_) => panic!("\"{}\" is not a valid type", &*val),
+                    }
+                }
+                panic!(format!(
+                    "Unknown attribute for #[derive(GraphQLUnion)]: {:?}",
+                    item
+                ));
+            }
+        }
+        res
+    }
+}
+
+pub fn impl_union(ast: &syn::DeriveInput) -> TokenStream {
+    let variants = match ast.data {
+        Data::Enum(ref enum_data) => enum_data.variants.iter().collect::<Vec<_>>(),
+        _ => {
+            panic!("#[derive(GraphQLUnion)] may only be applied to enums, not to structs");
+        }
+    };
+
+    // Parse attributes.
+    let ident = &ast.ident;
+    let attrs = UnionAttrs::from_input(ast);
+    let name = attrs.name.unwrap_or(ast.ident.to_string());
+
+    let meta_description = match attrs.description {
+        Some(descr) => quote!{ let meta = meta.description(#descr); },
+        None => quote!{ let meta = meta; },
+    };
+
+    let mut types = Vec::new();
+    let mut concrete_type_names = TokenStream::new();
+    let mut resolves = TokenStream::new();
+    let mut async_resolves = TokenStream::new();
+
+    for variant in variants {
+        let var_ty = match variant.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            _ => {
+                panic!(format!(
+                    "Invalid enum variant {}.\nGraphQL unions may only contain variants with a single unnamed field, e.g. Human(Human).",
+                    variant.ident
+                ));
+            }
+        };
+        let var_ident = &variant.ident;
+
+        types.push(var_ty);
+
+        concrete_type_names.extend(quote!{
+            #ident::#var_ident(_) =>
+                <#var_ty as ::juniper::GraphQLType>::name(&()).unwrap().to_owned(),
+        });
+
+        // Fragments on other types of the union resolve to nothing.
+        resolves.extend(quote!{
+            #ident::#var_ident(ref inner)
+                if <#var_ty as ::juniper::GraphQLType>::name(&()) == Some(type_name) =>
+                    executor.resolve(&(), inner),
+        });
+
+        async_resolves.extend(quote!{
+            #ident::#var_ident(ref inner)
+                if <#var_ty as ::juniper::GraphQLType>::name(&()) == Some(type_name) =>
+                    executor.resolve_async(&(), inner),
+        });
+    }
+
+    let context = match attrs.context {
+        Some(ref context) => quote!{ #context },
+        None => match types.first() {
+            Some(ty) => quote!{ <#ty as ::juniper::GraphQLType>::Context },
+            None => panic!("#[derive(GraphQLUnion)] requires at least one variant"),
+        },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::juniper::GraphQLType for #ident #ty_generics #where_clause {
+            type Context = #context;
+            type TypeInfo = ();
+
+            fn name(_: &()) -> Option<&str> {
+                Some(#name)
+            }
+
+            fn meta<'r>(
+                _: &(),
+                registry: &mut ::juniper::Registry<'r>
+            ) -> ::juniper::meta::MetaType<'r> {
+                let types = &[
+                    #( registry.get_type::<#types>(&()), )*
+                ];
+                let meta = registry.build_union_type::<#ident #ty_generics>(&(), types);
+                #meta_description
+                meta.into_meta()
+            }
+
+            fn concrete_type_name(&self, _: &Self::Context, _: &()) -> String {
+                match *self {
+                    #concrete_type_names
+                }
+            }
+
+            fn resolve_into_type(
+                &self,
+                _: &(),
+                type_name: &str,
+                _: Option<&[::juniper::Selection]>,
+                executor: &::juniper::Executor<Self::Context>,
+            ) -> ::juniper::ExecutionResult {
+                match *self {
+                    #resolves
+                    _ => Ok(::juniper::Value::null()),
+                }
+            }
+
+            fn resolve_into_type_async<'r>(
+                &self,
+                _: &'r (),
+                type_name: &str,
+                _: Option<&'r [::juniper::Selection<'r>]>,
+                executor: &::juniper::Executor<'r, Self::Context>,
+            ) -> ::juniper::ExecutionFuture<'r> {
+                match *self {
+                    #async_resolves
+                    _ => Box::new(::juniper::futures::future::ok(::juniper::Value::null())),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use proc_macro2::{Span, TokenStream};
+use syn;
+use syn::{Ident, ItemTrait, Lit, Meta, NestedMeta, TraitItem, Type, TypeParamBound};
+
+use impl_object::{impl_field, parse_args, parse_type, FieldTokens};
+use util::*;
+
+#[derive(Default, Debug)]
+struct InterfaceAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    context: Option<Type>,
+    implementers: Vec<Type>,
+}
+
+impl InterfaceAttrs {
+    fn from_input(args: TokenStream, item: &ItemTrait) -> InterfaceAttrs {
+        let mut res = InterfaceAttrs::default();
+
+        // Check doc comments for description.
+        res.description = get_doc_comment(&item.attrs);
+
+        for item in parse_args(args, "interface") {
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "name", AttributeValidation::String)  {
+                if is_valid_name(&*val) {
+                    res.name = Some(val);
+                    continue;
+                } else {
+                    panic!(
+                        "Names must match /^[_a-zA-Z][_a-zA-Z0-9]*$/ but \"{}\" does not",
+                        &*val
+                    );
+                }
+            }
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "description", AttributeValidation::String)  {
+                res.description = Some(val);
+                continue;
+            }
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "context", AttributeValidation::String)  {
+                res.context = Some(parse_type(&val));
+                continue;
+            }
+            if let NestedMeta::Meta(Meta::List(ref list)) = item {
+                if list.ident == "implementers" {
+                    for nested in list.nested.iter() {
+                        match *nested {
+                            NestedMeta::Literal(Lit::Str(ref strlit)) => {
+                                res.implementers.push(parse_type(&strlit.value()));
+                            }
+                            _ => panic!("Implementers must be given as strings, e.g. implementers(\"Human\")"),
+                        }
+                    }
+                    continue;
+                }
+            }
+            panic!(format!(
+                "Unknown interface attribute for #[juniper::interface]: {:?}",
+                item
+            ));
+        }
+        res
+    }
+}
+
+pub fn impl_interface(args: TokenStream, item: &ItemTrait) -> TokenStream {
+    if !item.generics.params.is_empty() {
+        panic!("#[juniper::interface] does not support generic traits");
+    }
+
+    // Parse attributes.
+    let ident = &item.ident;
+    let vis = &item.vis;
+    let attrs = InterfaceAttrs::from_input(args, item);
+    let name = attrs.name.clone().unwrap_or_else(|| ident.to_string());
+    let context = match attrs.context {
+        Some(ref context) => quote!{ #context },
+        None => quote!{ () },
+    };
+    let build_description = match attrs.description {
+        Some(ref s) => quote!{ builder.description(#s)  },
+        None => quote!{ builder },
+    };
+    let implementers = &attrs.implementers;
+    if implementers.is_empty() {
+        panic!("#[juniper::interface] requires at least one implementer, e.g. implementers(\"Human\")");
+    }
+
+    let mut trait_item = item.clone();
+    let mut tokens = FieldTokens::default();
+
+    for trait_item in trait_item.items.iter_mut() {
+        if let TraitItem::Method(ref mut method) = *trait_item {
+            impl_field(&mut method.attrs, &method.sig, &context, None, &mut tokens);
+        }
+    }
+
+    // Trait objects are resolved as their concrete type by a hidden
+    // supertrait, which is only implemented by the implementers.
+    let resolver = Ident::new(&format!("__Juniper{}Resolver", ident), Span::call_site());
+    let resolver_bound: TypeParamBound = syn::parse_str(&resolver.to_string()).unwrap();
+    if trait_item.colon_token.is_none() {
+        trait_item.colon_token = Some(Default::default());
+    }
+    trait_item.supertraits.push(resolver_bound);
+
+    let mut register_implementations = TokenStream::new();
+    let mut resolver_impls = TokenStream::new();
+
+    for ty in implementers {
+        register_implementations.extend(quote!{
+            registry.add_implementation::<#ty>(&(), &builder);
+        });
+
+        // Fragments on other implementers resolve to nothing.
+        resolver_impls.extend(quote!{
+            impl #resolver for #ty {
+                fn __juniper_concrete_type_name(&self) -> String {
+                    <#ty as ::juniper::GraphQLType>::name(&()).unwrap().to_owned()
+                }
+
+                fn __juniper_resolve_into_type(
+                    &self,
+                    type_name: &str,
+                    executor: &::juniper::Executor<#context>,
+                ) -> ::juniper::ExecutionResult {
+                    if <#ty as ::juniper::GraphQLType>::name(&()) == Some(type_name) {
+                        executor.resolve(&(), self)
+                    } else {
+                        Ok(::juniper::Value::null())
+                    }
+                }
+
+                fn __juniper_resolve_into_type_async<'r>(
+                    &self,
+                    type_name: &str,
+                    executor: &::juniper::Executor<'r, #context>,
+                ) -> ::juniper::ExecutionFuture<'r> {
+                    if <#ty as ::juniper::GraphQLType>::name(&()) == Some(type_name) {
+                        executor.resolve_async(&(), self)
+                    } else {
+                        Box::new(::juniper::futures::future::ok(::juniper::Value::null()))
+                    }
+                }
+            }
+        });
+    }
+
+    let FieldTokens { meta_fields, resolvers, async_resolvers } = tokens;
+
+    quote! {
+        #trait_item
+
+        #[doc(hidden)]
+        #vis trait #resolver {
+            fn __juniper_concrete_type_name(&self) -> String;
+
+            fn __juniper_resolve_into_type(
+                &self,
+                type_name: &str,
+                executor: &::juniper::Executor<#context>,
+            ) -> ::juniper::ExecutionResult;
+
+            fn __juniper_resolve_into_type_async<'r>(
+                &self,
+                type_name: &str,
+                executor: &::juniper::Executor<'r, #context>,
+            ) -> ::juniper::ExecutionFuture<'r>;
+        }
+
+        #resolver_impls
+
+        impl<'a> ::juniper::GraphQLType for &'a #ident {
+            type Context = #context;
+            type TypeInfo = ();
+
+            fn name(_: &()) -> Option<&str> {
+                Some(#name)
+            }
+
+            fn meta<'r>(
+                _: &(),
+                registry: &mut ::juniper::Registry<'r>
+            ) -> ::juniper::meta::MetaType<'r> {
+                // Fails to compile if an implementer does not implement the trait.
+                fn implements<T: #ident>() {}
+                #( implements::<#implementers>(); )*
+
+                let fields = &[
+                    #meta_fields
+                ];
+                let builder = registry.build_interface_type::<Self>(&(), fields);
+                let builder = #build_description;
+
+                #register_implementations
+                builder.into_meta()
+            }
+
+            fn concrete_type_name(&self, _: &Self::Context, _: &()) -> String {
+                (**self).__juniper_concrete_type_name()
+            }
+
+            fn resolve_into_type(
+                &self,
+                _: &(),
+                type_name: &str,
+                _: Option<&[::juniper::Selection]>,
+                executor: &::juniper::Executor<Self::Context>,
+            ) -> ::juniper::ExecutionResult {
+                (**self).__juniper_resolve_into_type(type_name, executor)
+            }
+
+            fn resolve_into_type_async<'r>(
+                &self,
+                _: &'r (),
+                type_name: &str,
+                _: Option<&'r [::juniper::Selection<'r>]>,
+                executor: &::juniper::Executor<'r, Self::Context>,
+            ) -> ::juniper::ExecutionFuture<'r> {
+                (**self).__juniper_resolve_into_type_async(type_name, executor)
+            }
+
+            #[allow(unused_variables)]
+            #[allow(deprecated)]
+            fn resolve_field(
+                &self,
+                _: &(),
+                field_name: &str,
+                args: &::juniper::Arguments,
+                executor: &::juniper::Executor<Self::Context>
+            ) -> ::juniper::ExecutionResult
+            {
+                match field_name {
+                    #resolvers
+                    _ => panic!("Field {} not found on type {}", field_name, #name),
+                }
+            }
+
+            #[allow(unused_variables)]
+            #[allow(deprecated)]
+            fn resolve_field_async<'r>(
+                &self,
+                _: &'r (),
+                field_name: &str,
+                args: &::juniper::Arguments,
+                executor: &::juniper::Executor<'r, Self::Context>
+            ) -> ::juniper::ExecutionFuture<'r>
+            {
+                match field_name {
+                    #async_resolvers
+                    _ => panic!("Field {} not found on type {}", field_name, #name),
+                }
+            }
+        }
+    }
+}
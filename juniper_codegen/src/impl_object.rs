@@ -0,0 +1,581 @@
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use syn;
+use syn::{
+    AttrStyle, Attribute, FnArg, Ident, ImplItem, ItemImpl, Lit, Meta, MethodSig, NestedMeta,
+    Pat, ReturnType, Type,
+};
+
+use util::*;
+
+#[derive(Default, Debug)]
+struct ObjAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    context: Option<Type>,
+    interfaces: Vec<Type>,
+}
+
+impl ObjAttrs {
+    fn from_input(args: TokenStream, item: &ItemImpl) -> ObjAttrs {
+        let mut res = ObjAttrs::default();
+
+        // Check doc comments for description.
+        res.description = get_doc_comment(&item.attrs);
+
+        for item in parse_args(args, "object") {
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "name", AttributeValidation::String)  {
+                if is_valid_name(&*val) {
+                    res.name = Some(val);
+                    continue;
+                } else {
+                    panic!(
+                        "Names must match /^[_a-zA-Z][_a-zA-Z0-9]*$/ but \"{}\" does not",
+                        &*val
+                    );
+                }
+            }
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "description", AttributeValidation::String)  {
+                res.description = Some(val);
+                continue;
+            }
+            if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "context", AttributeValidation::String)  {
+                res.context = Some(parse_type(&val));
+                continue;
+            }
+            if let NestedMeta::Meta(Meta::List(ref list)) = item {
+                if list.ident == "interfaces" {
+                    for nested in list.nested.iter() {
+                        match *nested {
+                            NestedMeta::Literal(Lit::Str(ref strlit)) => {
+                                res.interfaces.push(parse_type(&strlit.value()));
+                            }
+                            _ => panic!("Interfaces must be given as strings, e.g. interfaces(\"&Character\")"),
+                        }
+                    }
+                    continue;
+                }
+            }
+            panic!(format!(
+                "Unknown object attribute for #[juniper::object]: {:?}",
+                item
+            ));
+        }
+        res
+    }
+}
+
+#[derive(Default)]
+struct ArgAttrs {
+    default: Option<syn::Expr>,
+    description: Option<String>,
+}
+
+#[derive(Default)]
+struct ObjFieldAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    deprecation: Option<String>,
+    complexity: Option<usize>,
+    skip: bool,
+    arguments: Vec<(String, ArgAttrs)>,
+}
+
+impl ObjFieldAttrs {
+    fn from_input(attrs: &Vec<Attribute>) -> ObjFieldAttrs {
+        let mut res = ObjFieldAttrs::default();
+
+        // Check doc comments for description.
+        res.description = get_doc_comment(attrs);
+
+        // Check the #[deprecated] attribute for the deprecation reason.
+        res.deprecation = get_deprecation(attrs);
+
+        // Check attributes.
+        if let Some(items) = get_graphql_attr(attrs) {
+            for item in items {
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "name", AttributeValidation::String)  {
+                    if is_valid_name(&*val) {
+                        res.name = Some(val);
+                        continue;
+                    } else {
+                        panic!(
+                            "Names must match /^[_a-zA-Z][_a-zA-Z0-9]*$/ but \"{}\" does not",
+                            &*val
+                        );
+                    }
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "description", AttributeValidation::String)  {
+                    res.description = Some(val);
+                    continue;
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "deprecation", AttributeValidation::String) {
+                    res.deprecation = Some(val);
+                    continue;
+                }
+                if let Some(AttributeValue::String(val)) = keyed_item_value(&item, "complexity", AttributeValidation::String) {
+                    match val.parse() {
+                        Ok(complexity) => {
+                            res.complexity = Some(complexity);
+                            continue;
+                        }
+                        Err(_) => panic!(
+                            "Complexity must be a non-negative integer but \"{}\" is not",
+                            &*val
+                        ),
+                    }
+                }
+                if let Some(_) = keyed_item_value(&item, "skip", AttributeValidation::Bare) {
+                    res.skip = true;
+                    continue;
+                }
+                if let NestedMeta::Meta(Meta::List(ref list)) = item {
+                    if list.ident == "arguments" {
+                        for nested in list.nested.iter() {
+                            res.arguments.push(ArgAttrs::from_input(nested));
+                        }
+                        continue;
+                    }
+                }
+                panic!(format!(
+                    "Unknown field attribute: {:?}",
+                    item
+                ));
+            }
+        }
+        res
+    }
+
+    fn argument(&self, name: &str) -> Option<&ArgAttrs> {
+        self.arguments
+            .iter()
+            .find(|&&(ref arg_name, _)| arg_name == name)
+            .map(|&(_, ref attrs)| attrs)
+    }
+}
+
+impl ArgAttrs {
+    // Parses `name(default = "...", description = "...")`.
+    fn from_input(item: &NestedMeta) -> (String, ArgAttrs) {
+        let list = match *item {
+            NestedMeta::Meta(Meta::List(ref list)) => list,
+            _ => panic!(format!(
+                "Invalid argument attribute: {:?}",
+                item
+            )),
+        };
+        let mut res = ArgAttrs::default();
+
+        for item in list.nested.iter() {
+            if let Some(AttributeValue::String(val)) = keyed_item_value(item, "default", AttributeValidation::String)  {
+                match syn::parse_str(&val) {
+                    Ok(expr) => {
+                        res.default = Some(expr);
+                        continue;
+                    }
+                    Err(_) => panic!("Default values must be expressions but \"{}\" is not", &*val),
+                }
+            }
+            if let Some(AttributeValue::String(val)) = keyed_item_value(item, "description", AttributeValidation::String)  {
+                res.description = Some(val);
+                continue;
+            }
+            panic!(format!(
+                "Unknown argument attribute: {:?}",
+                item
+            ));
+        }
+
+        (list.ident.to_string(), res)
+    }
+}
+
+// How a method parameter is filled in when resolving a field.
+enum FieldArg<'a> {
+    Executor,
+    Context,
+    Argument(&'a Ident, &'a Type),
+}
+
+pub fn impl_object(args: TokenStream, item: &ItemImpl) -> TokenStream {
+    if item.trait_.is_some() {
+        panic!("#[juniper::object] may only be applied to inherent impl blocks, not to trait impls");
+    }
+
+    // Parse attributes.
+    let self_ty = &*item.self_ty;
+    let attrs = ObjAttrs::from_input(args, item);
+    let name = attrs.name.clone().unwrap_or_else(|| type_name(self_ty));
+    let context = match attrs.context {
+        Some(ref context) => quote!{ #context },
+        None => quote!{ () },
+    };
+    let build_description = match attrs.description {
+        Some(ref s) => quote!{ builder.description(#s)  },
+        None => quote!{ builder },
+    };
+    let build_interfaces = if attrs.interfaces.is_empty() {
+        quote!{ builder }
+    } else {
+        let interfaces = &attrs.interfaces;
+        quote!{ builder.interfaces(&[ #( registry.get_type::<#interfaces>(&()) ),* ]) }
+    };
+
+    let mut impl_item = item.clone();
+    let mut tokens = FieldTokens::default();
+    let self_tokens = quote!{ #self_ty };
+
+    for impl_item in impl_item.items.iter_mut() {
+        if let ImplItem::Method(ref mut method) = *impl_item {
+            impl_field(&mut method.attrs, &method.sig, &context, Some(&self_tokens), &mut tokens);
+        }
+    }
+
+    let FieldTokens { meta_fields, resolvers, async_resolvers } = tokens;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+    quote! {
+        #impl_item
+
+        impl #impl_generics ::juniper::GraphQLType for #self_ty #where_clause {
+            type Context = #context;
+            type TypeInfo = ();
+
+            fn name(_: &()) -> Option<&str> {
+                Some(#name)
+            }
+
+            fn concrete_type_name(&self, _: &Self::Context, _: &()) -> String {
+                #name.to_string()
+            }
+
+            fn meta<'r>(
+                _: &(),
+                registry: &mut ::juniper::Registry<'r>
+            ) -> ::juniper::meta::MetaType<'r> {
+                let fields = &[
+                    #meta_fields
+                ];
+                let builder = registry.build_object_type::<#self_ty>(&(), fields);
+                let builder = #build_description;
+                let builder = #build_interfaces;
+                builder.into_meta()
+            }
+
+            #[allow(unused_variables)]
+            #[allow(deprecated)]
+            fn resolve_field(
+                &self,
+                _: &(),
+                field_name: &str,
+                args: &::juniper::Arguments,
+                executor: &::juniper::Executor<Self::Context>
+            ) -> ::juniper::ExecutionResult
+            {
+                match field_name {
+                    #resolvers
+                    _ => panic!("Field {} not found on type {}", field_name, #name),
+                }
+            }
+
+            #[allow(unused_variables)]
+            #[allow(deprecated)]
+            fn resolve_field_async<'r>(
+                &self,
+                _: &'r (),
+                field_name: &str,
+                args: &::juniper::Arguments,
+                executor: &::juniper::Executor<'r, Self::Context>
+            ) -> ::juniper::ExecutionFuture<'r>
+            {
+                match field_name {
+                    #async_resolvers
+                    _ => panic!("Field {} not found on type {}", field_name, #name),
+                }
+            }
+        }
+    }
+}
+
+// The generated code for the fields of an object or interface.
+#[derive(Default)]
+pub struct FieldTokens {
+    pub meta_fields: TokenStream,
+    pub resolvers: TokenStream,
+    pub async_resolvers: TokenStream,
+}
+
+// Adds the field for a method to `tokens` and removes our attributes from the
+// method. Methods without a self parameter are called on `self_ty`.
+pub fn impl_field(
+    attrs: &mut Vec<Attribute>,
+    sig: &MethodSig,
+    context: &TokenStream,
+    self_ty: Option<&TokenStream>,
+    tokens: &mut FieldTokens,
+) {
+    let field_attrs = ObjFieldAttrs::from_input(attrs);
+
+    // Remove our attributes, the compiler does not know about them.
+    attrs.retain(|attr| attr.path.segments.len() != 1 || attr.path.segments[0].ident != "graphql");
+
+    // Check if we should skip this method.
+    if field_attrs.skip {
+        return;
+    }
+
+    let method_ident = &sig.ident;
+    let field_ty = match sig.decl.output {
+        ReturnType::Type(_, ref ty) => ty,
+        ReturnType::Default => panic!(
+            "Field \"{}\" must have a return type",
+            method_ident
+        ),
+    };
+
+    // Build value.
+    let name = match field_attrs.name {
+        Some(ref name) => {
+            // Custom name specified.
+            name.to_string()
+        }
+        None => {
+            // Note: auto camel casing when no custom name specified.
+            ::util::to_camel_case(&method_ident.to_string())
+        }
+    };
+
+    let mut has_self = false;
+    let mut field_args = Vec::new();
+
+    for input in sig.decl.inputs.iter() {
+        match *input {
+            FnArg::SelfRef(ref arg) if arg.mutability.is_none() => has_self = true,
+            FnArg::Captured(ref arg) => {
+                let ident = match arg.pat {
+                    Pat::Ident(ref pat) => &pat.ident,
+                    _ => panic!(
+                        "Arguments of field \"{}\" must be plain identifiers",
+                        method_ident
+                    ),
+                };
+
+                field_args.push(match arg.ty {
+                    Type::Reference(ref r) if is_executor(&r.elem) => FieldArg::Executor,
+                    Type::Reference(ref r) if is_context(&r.elem, context) => FieldArg::Context,
+                    ref ty => FieldArg::Argument(ident, ty),
+                });
+            }
+            _ => panic!(
+                "Field \"{}\" must take &self, or no self parameter at all",
+                method_ident
+            ),
+        }
+    }
+
+    let mut build_args = TokenStream::new();
+    let mut arg_values = Vec::new();
+
+    for field_arg in field_args.iter() {
+        match *field_arg {
+            FieldArg::Executor => arg_values.push(quote!{ executor }),
+            FieldArg::Context => arg_values.push(quote!{ executor.context() }),
+            FieldArg::Argument(ident, ty) => {
+                let arg_name = ::util::to_camel_case(&ident.to_string());
+                let arg_attrs = field_attrs.argument(&ident.to_string());
+
+                let build_arg = match arg_attrs.and_then(|a| a.default.as_ref()) {
+                    Some(default) => quote!{
+                        registry.arg_with_default::<#ty>(#arg_name, &#default, &())
+                    },
+                    None => quote!{ registry.arg::<#ty>(#arg_name, &()) },
+                };
+                let build_arg = match arg_attrs.and_then(|a| a.description.as_ref()) {
+                    Some(s) => quote!{ #build_arg.description(#s) },
+                    None => build_arg,
+                };
+
+                build_args.extend(quote!{
+                    let field = field.argument(#build_arg);
+                });
+                arg_values.push(quote!{
+                    args.get::<#ty>(#arg_name)
+                        .expect("Argument missing - validation must have failed")
+                });
+            }
+        }
+    }
+
+    for &(ref arg_name, _) in field_attrs.arguments.iter() {
+        let known = field_args.iter().any(|a| match *a {
+            FieldArg::Argument(ident, _) => ident == arg_name,
+            _ => false,
+        });
+        if !known {
+            panic!(
+                "Field \"{}\" has no argument \"{}\"",
+                method_ident, arg_name
+            );
+        }
+    }
+
+    let build_description = match field_attrs.description {
+        Some(ref s) => quote!{ field.description(#s)  },
+        None => quote!{ field },
+    };
+
+    let build_deprecation = match field_attrs.deprecation {
+        Some(ref s) => quote!{ field.deprecated(#s)  },
+        None => quote!{ field },
+    };
+
+    let build_complexity = match field_attrs.complexity {
+        Some(c) => quote!{ field.complexity(#c)  },
+        None => quote!{ field },
+    };
+
+    // Lifetimes of the method are not in scope when building the meta type.
+    let meta_field_ty = erase_lifetimes(quote!{ #field_ty });
+
+    tokens.meta_fields.extend(quote!{
+        {
+            let field = registry.field_convert::<#meta_field_ty, _, Self::Context>(#name, &());
+            #build_args
+            let field = #build_description;
+            let field = #build_deprecation;
+            let field = #build_complexity;
+            field
+        },
+    });
+
+    // Build resolve_field clause.
+    let call = match self_ty {
+        _ if has_self => quote!{ self.#method_ident(#(#arg_values),*) },
+        Some(self_ty) => quote!{ <#self_ty>::#method_ident(#(#arg_values),*) },
+        None => panic!("Field \"{}\" must take &self", method_ident),
+    };
+
+    tokens.resolvers.extend(quote!{
+        #name => {
+            let result = #call;
+
+            ::juniper::IntoResolvable::into(result, executor.context()).and_then(
+                |res| match res {
+                    Some((ctx, r)) =>
+                        executor.replaced_context(ctx).resolve_with_ctx(&(), &r),
+                    None => Ok(::juniper::Value::null()),
+                })
+        },
+    });
+
+    tokens.async_resolvers.extend(quote!{
+        #name => {
+            let result = #call;
+
+            executor.resolve_resolvable_async(result)
+        },
+    });
+}
+
+// Replaces all lifetimes except 'static with '_.
+fn erase_lifetimes(tokens: TokenStream) -> TokenStream {
+    let mut result = Vec::new();
+    let mut after_quote = false;
+
+    for tt in tokens {
+        let tt = match tt {
+            TokenTree::Group(ref group) => {
+                let mut new_group = Group::new(group.delimiter(), erase_lifetimes(group.stream()));
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            TokenTree::Ident(ref ident) if after_quote && ident != "static" => {
+                TokenTree::Ident(Ident::new("_", ident.span()))
+            }
+            tt => tt,
+        };
+        after_quote = match tt {
+            TokenTree::Punct(ref punct) => punct.as_char() == '\'',
+            _ => false,
+        };
+        result.push(tt);
+    }
+
+    result.into_iter().collect()
+}
+
+// Parses the arguments of an attribute macro the same way as the contents of
+// a #[graphql(...)] attribute.
+pub fn parse_args(args: TokenStream, macro_name: &str) -> Vec<NestedMeta> {
+    let attr = Attribute {
+        pound_token: Default::default(),
+        style: AttrStyle::Outer,
+        bracket_token: Default::default(),
+        path: Ident::new(macro_name, Span::call_site()).into(),
+        tts: quote!{ (#args) },
+        is_sugared_doc: false,
+    };
+    match attr.interpret_meta() {
+        Some(Meta::List(list)) => list.nested.into_iter().collect(),
+        _ => panic!("Invalid arguments for #[juniper::{}]", macro_name),
+    }
+}
+
+pub fn parse_type(source: &str) -> Type {
+    match syn::parse_str(source) {
+        Ok(ty) => ty,
+        Err(_) => panic!("\"{}\" is not a valid type", source),
+    }
+}
+
+// The name of the last path segment, e.g. `Query` for `schema::Query<'a>`.
+fn type_name(ty: &Type) -> String {
+    match *ty {
+        Type::Path(ref path) => match path.path.segments.iter().last() {
+            Some(segment) => segment.ident.to_string(),
+            None => panic!("#[juniper::object] requires a name for this type"),
+        },
+        _ => panic!("#[juniper::object] requires a name for this type, e.g. #[juniper::object(name = \"Name\")]"),
+    }
+}
+
+fn is_context(ty: &Type, context: &TokenStream) -> bool {
+    quote!{ #ty }.to_string() == context.to_string()
+}
+
+fn is_executor(ty: &Type) -> bool {
+    match *ty {
+        Type::Path(ref path) => path
+            .path
+            .segments
+            .iter()
+            .last()
+            .map_or(false, |segment| segment.ident == "Executor"),
+        _ => false,
+    }
+}
+
+// Gets the deprecation reason from a #[deprecated] attribute.
+fn get_deprecation(attrs: &Vec<Attribute>) -> Option<String> {
+    for attr in attrs {
+        match attr.interpret_meta() {
+            Some(Meta::Word(ref ident)) if ident == "deprecated" => {
+                return Some("No longer supported".to_owned());
+            }
+            Some(Meta::NameValue(ref nv)) if nv.ident == "deprecated" => {
+                if let Lit::Str(ref strlit) = nv.lit {
+                    return Some(strlit.value());
+                }
+            }
+            Some(Meta::List(ref list)) if list.ident == "deprecated" => {
+                for nested in list.nested.iter() {
+                    if let Some(AttributeValue::String(val)) = keyed_item_value(nested, "note", AttributeValidation::String) {
+                        return Some(val);
+                    }
+                }
+                return Some("No longer supported".to_owned());
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -18,6 +18,10 @@ extern crate regex;
 mod derive_enum;
 mod derive_input_object;
 mod derive_object;
+mod derive_scalar;
+mod derive_union;
+mod impl_interface;
+mod impl_object;
 mod util;
 
 use proc_macro::TokenStream;
@@ -42,3 +46,101 @@ pub fn derive_object(input: TokenStream) -> TokenStream {
     let gen = derive_object::impl_object(&ast);
     gen.into()
 }
+
+#[proc_macro_derive(GraphQLUnion, attributes(graphql))]
+pub fn derive_union(input: TokenStream) -> TokenStream {
+    let ast = syn::parse::<syn::DeriveInput>(input).unwrap();
+    let gen = derive_union::impl_union(&ast);
+    gen.into()
+}
+
+/// Expose a newtype struct as a GraphQL scalar.
+///
+/// The scalar is resolved and parsed like the wrapped type. The
+/// `#[graphql(parse_with = "path")]` attribute names a function
+/// `fn(Inner) -> Option<Self>` that validates input values, and
+/// `#[graphql(serialize_with = "path")]` a function `fn(&Self) -> Inner` that
+/// converts the scalar for output.
+///
+/// ```ignore
+/// #[derive(GraphQLScalar)]
+/// #[graphql(description = "The id of a user", parse_with = "UserId::parse")]
+/// struct UserId(String);
+///
+/// impl UserId {
+///     fn parse(id: String) -> Option<UserId> {
+///         if id.starts_with("user-") { Some(UserId(id)) } else { None }
+///     }
+/// }
+/// ```
+#[proc_macro_derive(GraphQLScalar, attributes(graphql))]
+pub fn derive_scalar(input: TokenStream) -> TokenStream {
+    let ast = syn::parse::<syn::DeriveInput>(input).unwrap();
+    let gen = derive_scalar::impl_scalar(&ast);
+    gen.into()
+}
+
+/// Expose the methods of an impl block as the fields of a GraphQL object.
+///
+/// Methods taking `&self` or no `self` at all become fields. Parameters of the
+/// type `&Executor<..>` or a reference to the context type are filled in by the
+/// executor, all others become arguments. Doc comments are used as
+/// descriptions, and `#[deprecated]` marks fields as deprecated.
+///
+/// ```ignore
+/// #[juniper::object(context = "Database", name = "Query")]
+/// impl Query {
+///     /// Find a user by id
+///     #[graphql(arguments(id(description = "The id of the user")))]
+///     fn user(&self, context: &Database, id: i32) -> FieldResult<Option<User>> {
+///         context.find_user(id)
+///     }
+///
+///     #[graphql(arguments(first(default = "10")))]
+///     fn users(&self, context: &Database, first: i32) -> Vec<User> {
+///         context.users(first)
+///     }
+/// }
+/// ```
+///
+/// The attribute takes the `context`, `name`, `description` and
+/// `interfaces("&Character", ..)` options. Methods can be annotated with
+/// `#[graphql(...)]` to set `name`, `description`, `deprecation` or
+/// `complexity`, to `skip` them, or to set the `default` and `description` of
+/// their `arguments`.
+#[proc_macro_attribute]
+pub fn object(args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = syn::parse::<syn::ItemImpl>(input).unwrap();
+    let gen = impl_object::impl_object(args.into(), &item);
+    gen.into()
+}
+
+/// Turn a trait into a GraphQL interface.
+///
+/// The methods of the trait become the fields of the interface, like with
+/// `#[juniper::object]`, and must take `&self`. The interface is implemented
+/// by the `implementers` listed in the attribute, which must implement the
+/// trait and are registered as implementations of the interface
+/// automatically. Fields can return the interface as `&Trait`.
+///
+/// ```ignore
+/// #[juniper::interface(context = "Database", implementers("Human", "Droid"))]
+/// trait Character {
+///     /// The id of the character
+///     fn id(&self) -> &str;
+///
+///     fn friends<'a>(&self, context: &'a Database) -> Vec<&'a Character>;
+/// }
+/// ```
+///
+/// The attribute takes the `context`, `name`, `description` and
+/// `implementers("Human", ..)` options. Implementers must have the same
+/// context type as the interface, and only they can implement the trait.
+/// Building a schema panics if an implementer does not declare all fields of
+/// the interface.
+#[proc_macro_attribute]
+pub fn interface(args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = syn::parse::<syn::ItemTrait>(input).unwrap();
+    let gen = impl_interface::impl_interface(args.into(), &item);
+    gen.into()
+}
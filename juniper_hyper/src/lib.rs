@@ -2,8 +2,6 @@
 extern crate futures;
 extern crate hyper;
 extern crate juniper;
-#[macro_use]
-extern crate serde_derive;
 #[cfg(test)]
 extern crate reqwest;
 extern crate serde_json;
@@ -14,9 +12,10 @@ extern crate url;
 use futures::future::Either;
 use hyper::header::HeaderValue;
 use hyper::rt::Stream;
-use hyper::{header, Body, Method, Request, Response, StatusCode};
-use juniper::http::GraphQLRequest as JuniperGraphQLRequest;
-use juniper::{GraphQLType, InputValue, RootNode};
+use hyper::{header, Body, Chunk, Method, Request, Response, StatusCode};
+use juniper::http::multipart::{self, MultipartBody, MultipartError, MultipartOptions};
+use juniper::http::{self, GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest};
+use juniper::{GraphQLType, RootNode};
 use serde_json::error::Error as SerdeError;
 use std::error::Error;
 use std::fmt;
@@ -37,16 +36,35 @@ where
     QueryT::TypeInfo: Send + Sync,
     MutationT::TypeInfo: Send + Sync,
 {
+    graphql_with_upload_options(root_node, context, MultipartOptions::new(), request)
+}
+
+/// Same as `graphql`, but limits and stores the files of multipart requests
+/// according to the given options
+pub fn graphql_with_upload_options<CtxT, QueryT, MutationT>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT>>,
+    context: Arc<CtxT>,
+    upload_options: MultipartOptions,
+    request: Request<Body>,
+) -> impl Future<Item = Response<Body>, Error = hyper::Error>
+where
+    CtxT: Send + Sync + 'static,
+    QueryT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    MutationT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+{
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned());
+
     match request.method() {
         &Method::GET => Either::A(Either::A(
             future::done(
-                request
-                    .uri()
-                    .query()
-                    .map(|q| gql_request_from_get(q).map(GraphQLRequest::Single))
-                    .unwrap_or(Err(GraphQLRequestError::Invalid(
-                        "'query' parameter is missing".to_string(),
-                    ))),
+                gql_request_from_get(request.uri().query().unwrap_or(""))
+                    .map(GraphQLBatchRequest::Single),
             ).and_then(move |gql_req| {
                 execute_request(root_node, context, gql_req).map_err(|_| {
                     unreachable!("thread pool has shut down?!");
@@ -54,19 +72,13 @@ where
             }).or_else(|err| future::ok(render_error(err))),
         )),
         &Method::POST => Either::A(Either::B(
-            request
-                .into_body()
-                .concat2()
-                .or_else(|err| future::done(Err(GraphQLRequestError::BodyHyper(err))))
+            read_body(request.into_body(), content_type.as_ref(), &upload_options)
                 .and_then(move |chunk| {
-                    future::done({
-                        String::from_utf8(chunk.iter().cloned().collect::<Vec<u8>>())
-                            .map_err(GraphQLRequestError::BodyUtf8)
-                            .and_then(|input| {
-                                serde_json::from_str::<GraphQLRequest>(&input)
-                                    .map_err(GraphQLRequestError::BodyJSONError)
-                            })
-                    })
+                    future::done(gql_request_from_post(
+                        content_type.as_ref().map(|s| s as &str),
+                        &chunk,
+                        &upload_options,
+                    ))
                 }).and_then(move |gql_req| {
                     execute_request(root_node, context, gql_req).map_err(|_| {
                         unreachable!("thread pool has shut down?!");
@@ -96,7 +108,7 @@ fn render_error(err: GraphQLRequestError) -> Response<Body> {
 fn execute_request<CtxT, QueryT, MutationT>(
     root_node: Arc<RootNode<'static, QueryT, MutationT>>,
     context: Arc<CtxT>,
-    request: GraphQLRequest,
+    request: GraphQLBatchRequest,
 ) -> impl Future<Item = Response<Body>, Error = tokio_threadpool::BlockingError>
 where
     CtxT: Send + Sync + 'static,
@@ -105,7 +117,30 @@ where
     QueryT::TypeInfo: Send + Sync,
     MutationT::TypeInfo: Send + Sync,
 {
-    request.execute(root_node, context).map(|(is_ok, body)| {
+    let result = if let Err(response) = request.check_batch_size(&root_node) {
+        Either::A(future::ok((
+            false,
+            serde_json::to_string_pretty(&response).unwrap(),
+        )))
+    } else {
+        Either::B(match request {
+            GraphQLBatchRequest::Single(request) => {
+                Either::A(execute_blocking(root_node, context, request))
+            }
+            GraphQLBatchRequest::Batch(requests) => Either::B(
+                future::join_all(requests.into_iter().map(move |request| {
+                    execute_blocking(root_node.clone(), context.clone(), request)
+                })).map(|results| {
+                    let is_ok = results.iter().all(|&(is_ok, _)| is_ok);
+                    // concatenate json bodies as array
+                    let bodies: Vec<_> = results.into_iter().map(|(_, body)| body).collect();
+                    (is_ok, format!("[{}]", bodies.join(",")))
+                }),
+            ),
+        })
+    };
+
+    result.map(|(is_ok, body)| {
         let code = if is_ok {
             StatusCode::OK
         } else {
@@ -116,55 +151,77 @@ where
             header::CONTENT_TYPE,
             HeaderValue::from_static("application/json"),
         );
-        *resp.body_mut() = body;
+        *resp.body_mut() = Body::from(body);
         resp
     })
 }
 
-fn gql_request_from_get(input: &str) -> Result<JuniperGraphQLRequest, GraphQLRequestError> {
-    let mut query = None;
-    let operation_name = None;
-    let mut variables = None;
-    for (key, value) in form_urlencoded::parse(input.as_bytes()).into_owned() {
-        match key.as_ref() {
-            "query" => {
-                if query.is_some() {
-                    return Err(invalid_err("query"));
-                }
-                query = Some(value)
-            }
-            "operationName" => {
-                if operation_name.is_some() {
-                    return Err(invalid_err("operationName"));
-                }
-            }
-            "variables" => {
-                if variables.is_some() {
-                    return Err(invalid_err("variables"));
-                }
-                match serde_json::from_str::<InputValue>(&value)
-                    .map_err(GraphQLRequestError::Variables)
-                {
-                    Ok(parsed_variables) => variables = Some(parsed_variables),
-                    Err(e) => return Err(e),
-                }
-            }
-            _ => continue,
-        }
-    }
-    match query {
-        Some(query) => Ok(JuniperGraphQLRequest::new(query, operation_name, variables)),
-        None => Err(GraphQLRequestError::Invalid(
-            "'query' parameter is missing".to_string(),
-        )),
+// Each request of a batch gets its own blocking section, and the sections are
+// joined with `join_all`
+fn execute_blocking<CtxT, QueryT, MutationT>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT>>,
+    context: Arc<CtxT>,
+    request: JuniperGraphQLRequest,
+) -> impl Future<Item = (bool, String), Error = tokio_threadpool::BlockingError>
+where
+    CtxT: Send + Sync + 'static,
+    QueryT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    MutationT: GraphQLType<Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+{
+    future::poll_fn(move || {
+        let res = try_ready!(tokio_threadpool::blocking(
+            || request.execute(&root_node, &context)
+        ));
+        let body = serde_json::to_string_pretty(&res).unwrap();
+        Ok(Async::Ready((res.is_ok(), body)))
+    })
+}
+
+// Multipart bodies are checked against the size limit of `upload_options`
+// while they are read
+fn read_body(
+    body: Body,
+    content_type: Option<&String>,
+    upload_options: &MultipartOptions,
+) -> impl Future<Item = Chunk, Error = GraphQLRequestError> {
+    if content_type.map_or(false, |content_type| multipart::is_multipart_request(content_type)) {
+        Either::A(
+            body.map_err(GraphQLRequestError::BodyHyper)
+                .fold(MultipartBody::new(upload_options), |mut body, chunk| {
+                    body.push(&chunk)
+                        .map(|()| body)
+                        .map_err(GraphQLRequestError::Multipart)
+                }).map(|body| Chunk::from(body.into_bytes())),
+        )
+    } else {
+        Either::B(body.concat2().map_err(GraphQLRequestError::BodyHyper))
     }
 }
 
-fn invalid_err(parameter_name: &str) -> GraphQLRequestError {
-    GraphQLRequestError::Invalid(format!(
-        "'{}' parameter is specified multiple times",
-        parameter_name
-    ))
+fn gql_request_from_get(input: &str) -> Result<JuniperGraphQLRequest, GraphQLRequestError> {
+    let parameters = form_urlencoded::parse(input.as_bytes()).into_owned();
+    JuniperGraphQLRequest::from_get_parameters(parameters).map_err(GraphQLRequestError::Get)
+}
+
+fn gql_request_from_post(
+    content_type: Option<&str>,
+    body: &[u8],
+    upload_options: &MultipartOptions,
+) -> Result<GraphQLBatchRequest, GraphQLRequestError> {
+    match content_type {
+        Some(content_type) if multipart::is_multipart_request(content_type) => {
+            multipart::parse_multipart_request(content_type, body, upload_options)
+                .map_err(GraphQLRequestError::Multipart)
+        }
+        _ => String::from_utf8(body.to_vec())
+            .map_err(GraphQLRequestError::BodyUtf8)
+            .and_then(|input| {
+                serde_json::from_str::<GraphQLBatchRequest>(&input)
+                    .map_err(GraphQLRequestError::BodyJSONError)
+            }),
+    }
 }
 
 fn new_response(code: StatusCode) -> Response<Body> {
@@ -182,67 +239,13 @@ fn new_html_response(code: StatusCode) -> Response<Body> {
     resp
 }
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum GraphQLRequest {
-    Single(JuniperGraphQLRequest),
-    Batch(Vec<JuniperGraphQLRequest>),
-}
-
-impl GraphQLRequest {
-    fn execute<'a, CtxT: 'a, QueryT, MutationT>(
-        self,
-        root_node: Arc<RootNode<'a, QueryT, MutationT>>,
-        context: Arc<CtxT>,
-    ) -> impl Future<Item = (bool, hyper::Body), Error = tokio_threadpool::BlockingError> + 'a
-    where
-        QueryT: GraphQLType<Context = CtxT> + 'a,
-        MutationT: GraphQLType<Context = CtxT> + 'a,
-    {
-        match self {
-            GraphQLRequest::Single(request) => Either::A(future::poll_fn(move || {
-                let res = try_ready!(tokio_threadpool::blocking(
-                    || request.execute(&root_node, &context)
-                ));
-                let is_ok = res.is_ok();
-                let body = Body::from(serde_json::to_string_pretty(&res).unwrap());
-                Ok(Async::Ready((is_ok, body)))
-            })),
-            GraphQLRequest::Batch(requests) => {
-                Either::B(
-                    future::join_all(requests.into_iter().map(move |request| {
-                        // TODO: these clones are sad
-                        let root_node = root_node.clone();
-                        let context = context.clone();
-                        future::poll_fn(move || {
-                            let res = try_ready!(tokio_threadpool::blocking(
-                                || request.execute(&root_node, &context)
-                            ));
-                            let is_ok = res.is_ok();
-                            let body = serde_json::to_string_pretty(&res).unwrap();
-                            Ok(Async::Ready((is_ok, body)))
-                        })
-                    })).map(|results| {
-                        let is_ok = results.iter().all(|&(is_ok, _)| is_ok);
-                        // concatenate json bodies as array
-                        // TODO: maybe use Body chunks instead?
-                        let bodies: Vec<_> = results.into_iter().map(|(_, body)| body).collect();
-                        let body = hyper::Body::from(format!("[{}]", bodies.join(",")));
-                        (is_ok, body)
-                    }),
-                )
-            }
-        }
-    }
-}
-
 #[derive(Debug)]
 enum GraphQLRequestError {
     BodyHyper(hyper::Error),
     BodyUtf8(FromUtf8Error),
     BodyJSONError(SerdeError),
-    Variables(SerdeError),
-    Invalid(String),
+    Multipart(MultipartError),
+    Get(http::GraphQLRequestError),
 }
 
 impl fmt::Display for GraphQLRequestError {
@@ -251,8 +254,8 @@ impl fmt::Display for GraphQLRequestError {
             GraphQLRequestError::BodyHyper(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::BodyUtf8(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLRequestError::BodyJSONError(ref err) => fmt::Display::fmt(err, &mut f),
-            GraphQLRequestError::Variables(ref err) => fmt::Display::fmt(err, &mut f),
-            GraphQLRequestError::Invalid(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Multipart(ref err) => fmt::Display::fmt(err, &mut f),
+            GraphQLRequestError::Get(ref err) => fmt::Display::fmt(err, &mut f),
         }
     }
 }
@@ -263,8 +266,8 @@ impl Error for GraphQLRequestError {
             GraphQLRequestError::BodyHyper(ref err) => err.description(),
             GraphQLRequestError::BodyUtf8(ref err) => err.description(),
             GraphQLRequestError::BodyJSONError(ref err) => err.description(),
-            GraphQLRequestError::Variables(ref err) => err.description(),
-            GraphQLRequestError::Invalid(ref err) => err,
+            GraphQLRequestError::Multipart(ref err) => err.description(),
+            GraphQLRequestError::Get(ref err) => err.description(),
         }
     }
 
@@ -273,8 +276,8 @@ impl Error for GraphQLRequestError {
             GraphQLRequestError::BodyHyper(ref err) => Some(err),
             GraphQLRequestError::BodyUtf8(ref err) => Some(err),
             GraphQLRequestError::BodyJSONError(ref err) => Some(err),
-            GraphQLRequestError::Variables(ref err) => Some(err),
-            GraphQLRequestError::Invalid(_) => None,
+            GraphQLRequestError::Multipart(ref err) => Some(err),
+            GraphQLRequestError::Get(ref err) => Some(err),
         }
     }
 }
@@ -288,7 +291,7 @@ mod tests {
     use juniper::http::tests as http_tests;
     use juniper::tests::model::Database;
     use juniper::EmptyMutation;
-    use juniper::RootNode;
+    use juniper::{GraphQLType, RootNode};
     use reqwest;
     use reqwest::Response as ReqwestResponse;
     use std::sync::Arc;
@@ -333,12 +336,39 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_hyper_integration() {
-        let addr = ([127, 0, 0, 1], 3001).into();
+    impl http_tests::HTTPUploadIntegration for TestHyperIntegration {
+        fn post_multipart(
+            &self,
+            url: &str,
+            content_type: &str,
+            body: &[u8],
+        ) -> http_tests::TestResponse {
+            let url = format!("http://127.0.0.1:3002/graphql{}", url);
+            let client = reqwest::Client::new();
+            let res = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body.to_vec())
+                .send()
+                .expect(&format!("failed POST {}", url));
+            make_test_response(res)
+        }
+    }
+
+    fn run_server<QueryT, MutationT>(
+        port: u16,
+        root_node: RootNode<'static, QueryT, MutationT>,
+    ) -> Runtime
+    where
+        QueryT: GraphQLType<Context = Database> + Send + Sync + 'static,
+        MutationT: GraphQLType<Context = Database> + Send + Sync + 'static,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+    {
+        let addr = ([127, 0, 0, 1], port).into();
 
         let db = Arc::new(Database::new());
-        let root_node = Arc::new(RootNode::new(db.clone(), EmptyMutation::<Database>::new()));
+        let root_node = Arc::new(root_node);
 
         let new_service = move || {
             let root_node = root_node.clone();
@@ -370,9 +400,29 @@ mod tests {
         runtime.spawn(server);
         thread::sleep(time::Duration::from_millis(10)); // wait 10ms for server to bind
 
+        runtime
+    }
+
+    #[test]
+    fn test_hyper_integration() {
+        let runtime = run_server(
+            3001,
+            RootNode::new(Database::new(), EmptyMutation::<Database>::new()),
+        );
+
         let integration = TestHyperIntegration;
         http_tests::run_http_test_suite(&integration);
 
         runtime.shutdown_now().wait().unwrap();
     }
+
+    #[test]
+    fn test_hyper_upload_integration() {
+        let runtime = run_server(3002, http_tests::upload_schema());
+
+        let integration = TestHyperIntegration;
+        http_tests::run_http_upload_test_suite(&integration);
+
+        runtime.shutdown_now().wait().unwrap();
+    }
 }
@@ -107,8 +107,6 @@ extern crate iron;
 extern crate iron_test;
 extern crate juniper;
 extern crate serde_json;
-#[macro_use]
-extern crate serde_derive;
 #[cfg(test)]
 extern crate url;
 extern crate urlencoded;
@@ -126,57 +124,8 @@ use std::io::Read;
 
 use serde_json::error::Error as SerdeError;
 
-use juniper::http;
-use juniper::{GraphQLType, InputValue, RootNode};
-
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum GraphQLBatchRequest {
-    Single(http::GraphQLRequest),
-    Batch(Vec<http::GraphQLRequest>),
-}
-
-#[derive(Serialize)]
-#[serde(untagged)]
-enum GraphQLBatchResponse<'a> {
-    Single(http::GraphQLResponse<'a>),
-    Batch(Vec<http::GraphQLResponse<'a>>),
-}
-
-impl GraphQLBatchRequest {
-    pub fn execute<'a, CtxT, QueryT, MutationT>(
-        &'a self,
-        root_node: &RootNode<QueryT, MutationT>,
-        context: &CtxT,
-    ) -> GraphQLBatchResponse<'a>
-    where
-        QueryT: GraphQLType<Context = CtxT>,
-        MutationT: GraphQLType<Context = CtxT>,
-    {
-        match self {
-            &GraphQLBatchRequest::Single(ref request) => {
-                GraphQLBatchResponse::Single(request.execute(root_node, context))
-            }
-            &GraphQLBatchRequest::Batch(ref requests) => GraphQLBatchResponse::Batch(
-                requests
-                    .iter()
-                    .map(|request| request.execute(root_node, context))
-                    .collect(),
-            ),
-        }
-    }
-}
-
-impl<'a> GraphQLBatchResponse<'a> {
-    fn is_ok(&self) -> bool {
-        match self {
-            &GraphQLBatchResponse::Single(ref response) => response.is_ok(),
-            &GraphQLBatchResponse::Batch(ref responses) => responses
-                .iter()
-                .fold(true, |ok, response| ok && response.is_ok()),
-        }
-    }
-}
+use juniper::http::{self, GraphQLBatchRequest};
+use juniper::{GraphQLType, RootNode};
 
 /// Handler that executes `GraphQL` queries in the given schema
 ///
@@ -204,34 +153,6 @@ pub struct GraphiQLHandler {
     graphql_url: String,
 }
 
-fn get_single_value<T>(mut values: Vec<T>) -> IronResult<T> {
-    if values.len() == 1 {
-        Ok(values.remove(0))
-    } else {
-        Err(GraphQLIronError::InvalidData("Duplicate URL query parameter").into())
-    }
-}
-
-fn parse_url_param(params: Option<Vec<String>>) -> IronResult<Option<String>> {
-    if let Some(values) = params {
-        get_single_value(values).map(Some)
-    } else {
-        Ok(None)
-    }
-}
-
-fn parse_variable_param(params: Option<Vec<String>>) -> IronResult<Option<InputValue>> {
-    if let Some(values) = params {
-        Ok(
-            serde_json::from_str::<InputValue>(get_single_value(values)?.as_ref())
-                .map(Some)
-                .map_err(GraphQLIronError::Serde)?,
-        )
-    } else {
-        Ok(None)
-    }
-}
-
 impl<'a, CtxFactory, Query, Mutation, CtxT> GraphQLHandler<'a, CtxFactory, Query, Mutation, CtxT>
 where
     CtxFactory: Fn(&mut Request) -> IronResult<CtxT> + Send + Sync + 'static,
@@ -256,16 +177,13 @@ where
         let url_query_string = req.get_mut::<UrlEncodedQuery>()
             .map_err(GraphQLIronError::Url)?;
 
-        let input_query = parse_url_param(url_query_string.remove("query"))?
-            .ok_or_else(|| GraphQLIronError::InvalidData("No query provided"))?;
-        let operation_name = parse_url_param(url_query_string.remove("operationName"))?;
-        let variables = parse_variable_param(url_query_string.remove("variables"))?;
+        let parameters = url_query_string.drain().flat_map(|(key, values)| {
+            values.into_iter().map(move |value| (key.clone(), value))
+        });
+        let request =
+            http::GraphQLRequest::from_get_parameters(parameters).map_err(GraphQLIronError::Get)?;
 
-        Ok(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            input_query,
-            operation_name,
-            variables,
-        )))
+        Ok(GraphQLBatchRequest::Single(request))
     }
 
     fn handle_post(&self, req: &mut Request) -> IronResult<GraphQLBatchRequest> {
@@ -341,7 +259,7 @@ impl Handler for GraphiQLHandler {
 enum GraphQLIronError {
     Serde(SerdeError),
     Url(UrlDecodingError),
-    InvalidData(&'static str),
+    Get(http::GraphQLRequestError),
 }
 
 impl fmt::Display for GraphQLIronError {
@@ -349,7 +267,7 @@ impl fmt::Display for GraphQLIronError {
         match *self {
             GraphQLIronError::Serde(ref err) => fmt::Display::fmt(err, &mut f),
             GraphQLIronError::Url(ref err) => fmt::Display::fmt(err, &mut f),
-            GraphQLIronError::InvalidData(err) => fmt::Display::fmt(err, &mut f),
+            GraphQLIronError::Get(ref err) => fmt::Display::fmt(err, &mut f),
         }
     }
 }
@@ -359,7 +277,7 @@ impl Error for GraphQLIronError {
         match *self {
             GraphQLIronError::Serde(ref err) => err.description(),
             GraphQLIronError::Url(ref err) => err.description(),
-            GraphQLIronError::InvalidData(err) => err,
+            GraphQLIronError::Get(ref err) => err.description(),
         }
     }
 
@@ -367,7 +285,7 @@ impl Error for GraphQLIronError {
         match *self {
             GraphQLIronError::Serde(ref err) => Some(err),
             GraphQLIronError::Url(ref err) => Some(err),
-            GraphQLIronError::InvalidData(_) => None,
+            GraphQLIronError::Get(ref err) => Some(err),
         }
     }
 }
@@ -42,8 +42,6 @@ Check the LICENSE file for details.
 extern crate juniper;
 extern crate rocket;
 extern crate serde_json;
-#[macro_use]
-extern crate serde_derive;
 
 use std::error::Error;
 use std::io::{Cursor, Read};
@@ -56,62 +54,12 @@ use rocket::Data;
 use rocket::Outcome::{Failure, Forward, Success};
 use rocket::Request;
 
-use juniper::http;
-use juniper::InputValue;
+use juniper::http::{self, GraphQLBatchRequest};
 
 use juniper::FieldError;
 use juniper::GraphQLType;
 use juniper::RootNode;
 
-#[derive(Debug, Deserialize, PartialEq)]
-#[serde(untagged)]
-enum GraphQLBatchRequest {
-    Single(http::GraphQLRequest),
-    Batch(Vec<http::GraphQLRequest>),
-}
-
-#[derive(Serialize)]
-#[serde(untagged)]
-enum GraphQLBatchResponse<'a> {
-    Single(http::GraphQLResponse<'a>),
-    Batch(Vec<http::GraphQLResponse<'a>>),
-}
-
-impl GraphQLBatchRequest {
-    pub fn execute<'a, CtxT, QueryT, MutationT>(
-        &'a self,
-        root_node: &RootNode<QueryT, MutationT>,
-        context: &CtxT,
-    ) -> GraphQLBatchResponse<'a>
-    where
-        QueryT: GraphQLType<Context = CtxT>,
-        MutationT: GraphQLType<Context = CtxT>,
-    {
-        match self {
-            &GraphQLBatchRequest::Single(ref request) => {
-                GraphQLBatchResponse::Single(request.execute(root_node, context))
-            }
-            &GraphQLBatchRequest::Batch(ref requests) => GraphQLBatchResponse::Batch(
-                requests
-                    .iter()
-                    .map(|request| request.execute(root_node, context))
-                    .collect(),
-            ),
-        }
-    }
-}
-
-impl<'a> GraphQLBatchResponse<'a> {
-    fn is_ok(&self) -> bool {
-        match self {
-            &GraphQLBatchResponse::Single(ref response) => response.is_ok(),
-            &GraphQLBatchResponse::Batch(ref responses) => responses
-                .iter()
-                .fold(true, |ok, response| ok && response.is_ok()),
-        }
-    }
-}
-
 /// Simple wrapper around an incoming GraphQL request
 ///
 /// See the `http` module for more information. This type can be constructed
@@ -209,64 +157,32 @@ impl<'f> FromForm<'f> for GraphQLRequest {
     type Error = String;
 
     fn from_form(form_items: &mut FormItems<'f>, strict: bool) -> Result<Self, String> {
-        let mut query = None;
-        let mut operation_name = None;
-        let mut variables = None;
+        let mut parameters = Vec::new();
 
         for (key, value) in form_items {
-            // Note: we explicitly decode in the match arms to save work rather
-            // than decoding every form item blindly.
-            match key.as_str() {
-                "query" => {
-                    if query.is_some() {
-                        return Err("Query parameter must not occur more than once".to_owned());
-                    } else {
-                        match value.url_decode() {
-                            Ok(v) => query = Some(v),
-                            Err(e) => return Err(e.description().to_string()),
-                        }
-                    }
-                }
-                "operation_name" => {
-                    if operation_name.is_some() {
-                        return Err(
-                            "Operation name parameter must not occur more than once".to_owned()
-                        );
-                    } else {
-                        match value.url_decode() {
-                            Ok(v) => operation_name = Some(v),
-                            Err(e) => return Err(e.description().to_string()),
-                        }
-                    }
-                }
-                "variables" => {
-                    if variables.is_some() {
-                        return Err("Variables parameter must not occur more than once".to_owned());
-                    } else {
-                        let decoded;
-                        match value.url_decode() {
-                            Ok(v) => decoded = v,
-                            Err(e) => return Err(e.description().to_string()),
-                        }
-                        variables = Some(serde_json::from_str::<InputValue>(&decoded)
-                            .map_err(|err| err.description().to_owned())?);
-                    }
-                }
+            // Note: we only decode the form items the request is built from
+            // rather than decoding every form item blindly.
+            let name = match key.as_str() {
+                "query" => "query",
+                "operation_name" => "operationName",
+                "variables" => "variables",
+                "extensions" => "extensions",
                 _ => {
                     if strict {
                         return Err(format!("Prohibited extra field '{}'", key).to_owned());
                     }
+                    continue;
                 }
+            };
+            match value.url_decode() {
+                Ok(v) => parameters.push((name.to_owned(), v)),
+                Err(e) => return Err(e.description().to_string()),
             }
         }
 
-        if let Some(query) = query {
-            Ok(GraphQLRequest(GraphQLBatchRequest::Single(
-                http::GraphQLRequest::new(query, operation_name, variables),
-            )))
-        } else {
-            Err("Query parameter missing".to_owned())
-        }
+        http::GraphQLRequest::from_get_parameters(parameters)
+            .map(|request| GraphQLRequest(GraphQLBatchRequest::Single(request)))
+            .map_err(|err| err.to_string())
     }
 }
 
@@ -318,14 +234,14 @@ mod fromform_tests {
 
     #[test]
     fn test_empty_form() {
-        check_error("", "Query parameter missing", false);
+        check_error("", "'query' parameter is missing", false);
     }
 
     #[test]
     fn test_no_query() {
         check_error(
             "operation_name=foo&variables={}",
-            "Query parameter missing",
+            "'query' parameter is missing",
             false,
         );
     }
@@ -339,7 +255,7 @@ mod fromform_tests {
     fn test_duplicate_query() {
         check_error(
             "query=foo&query=bar",
-            "Query parameter must not occur more than once",
+            "'query' parameter is specified multiple times",
             false,
         );
     }
@@ -348,7 +264,7 @@ mod fromform_tests {
     fn test_duplicate_operation_name() {
         check_error(
             "query=test&operation_name=op1&operation_name=op2",
-            "Operation name parameter must not occur more than once",
+            "'operationName' parameter is specified multiple times",
             false,
         );
     }
@@ -357,14 +273,26 @@ mod fromform_tests {
     fn test_duplicate_variables() {
         check_error(
             "query=test&variables={}&variables={}",
-            "Variables parameter must not occur more than once",
+            "'variables' parameter is specified multiple times",
             false,
         );
     }
 
     #[test]
     fn test_variables_invalid_json() {
-        check_error("query=test&variables=NOT_JSON", "JSON error", false);
+        check_error(
+            "query=test&variables=NOT_JSON",
+            "Invalid 'variables' parameter: expected value at line 1 column 1",
+            false,
+        );
+    }
+
+    #[test]
+    fn test_persisted_query_without_query() {
+        let form_string = r#"extensions={"persistedQuery":{"version":1,"sha256Hash":"abc"}}"#;
+        let mut items = FormItems::from(form_string);
+        let result = GraphQLRequest::from_form(&mut items, true);
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -4,7 +4,12 @@ use fnv::FnvHashMap;
 use juniper::Object;
 
 #[cfg(test)]
-use juniper::{self, execute, EmptyMutation, GraphQLType, RootNode, Value, Variables};
+use juniper::futures::Future;
+#[cfg(test)]
+use juniper::{
+    self, execute, execute_async, EmptyMutation, GraphQLError, GraphQLType, RootNode, Value,
+    Variables,
+};
 
 #[derive(GraphQLObject, Debug, PartialEq)]
 #[graphql(name = "MyObj", description = "obj descr")]
@@ -60,6 +65,13 @@ struct SkippedFieldObj {
     skipped: i32,
 }
 
+#[derive(GraphQLObject, Debug, PartialEq)]
+struct ExpensiveFieldObj {
+    regular_field: bool,
+    #[graphql(complexity = "10")]
+    expensive: i32,
+}
+
 graphql_object!(Query: () |&self| {
     field obj() -> Obj {
       Obj{
@@ -101,6 +113,13 @@ graphql_object!(Query: () |&self| {
             skipped: 42,
         }
     }
+
+    field expensive_field_obj() -> ExpensiveFieldObj {
+        ExpensiveFieldObj{
+            regular_field: true,
+            expensive: 42,
+        }
+    }
 });
 
 #[test]
@@ -215,6 +234,36 @@ fn test_skipped_field_siblings_unaffected() {
     execute(doc, None, &schema, &Variables::new(), &()).unwrap();
 }
 
+#[test]
+fn test_field_complexity() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new()).max_complexity(10);
+
+    execute(
+        "{ expensiveFieldObj { regularField } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    ).unwrap();
+
+    let errors = match execute(
+        "{ expensiveFieldObj { expensive } }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    ) {
+        Err(GraphQLError::ValidationError(errors)) => errors,
+        _ => panic!("Expected query to be rejected"),
+    };
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message(),
+        "Query has a complexity of at least 11, which exceeds the maximum complexity of 10"
+    );
+}
+
 #[test]
 fn test_derived_object_nested() {
     let doc = r#"
@@ -256,6 +305,28 @@ fn test_derived_object_nested() {
     );
 }
 
+#[test]
+fn test_derived_object_nested_async() {
+    let doc = r#"
+        {
+            nested {
+                obj {
+                    regularField
+                    renamedField
+                }
+            }
+        }"#;
+
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+
+    assert_eq!(
+        execute_async(doc, None, &schema, &Variables::new(), &())
+            .expect("Execution failed")
+            .wait(),
+        Ok(execute(doc, None, &schema, &Variables::new(), &()).expect("Execution failed"))
+    );
+}
+
 #[cfg(test)]
 fn check_descriptions(
     object_name: &str,
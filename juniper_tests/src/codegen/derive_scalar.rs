@@ -0,0 +1,121 @@
+#[cfg(test)]
+use fnv::FnvHashMap;
+
+#[cfg(test)]
+use juniper::{
+    self, execute, EmptyMutation, FromInputValue, GraphQLType, InputValue, RootNode,
+    ToInputValue, Value, Variables,
+};
+
+/// The id of a user.
+#[derive(GraphQLScalar, Debug, PartialEq)]
+struct UserId(String);
+
+#[derive(GraphQLScalar, Debug, PartialEq)]
+#[graphql(name = "Count", description = "A number of things")]
+struct Counter(i32);
+
+#[derive(GraphQLScalar, Debug, PartialEq)]
+#[graphql(parse_with = "Percentage::parse", serialize_with = "Percentage::fraction")]
+struct Percentage(f64);
+
+impl Percentage {
+    // Percentages are given as fractions between 0 and 1.
+    fn parse(fraction: f64) -> Option<Percentage> {
+        if fraction >= 0.0 && fraction <= 1.0 {
+            Some(Percentage(fraction * 100.0))
+        } else {
+            None
+        }
+    }
+
+    fn fraction(&self) -> f64 {
+        self.0 / 100.0
+    }
+}
+
+struct Query;
+
+graphql_object!(Query: () |&self| {
+    field user_id(id: UserId) -> UserId {
+        UserId(format!("user-{}", id.0))
+    }
+
+    field count() -> Counter {
+        Counter(3)
+    }
+
+    field percentage(value: Percentage) -> String {
+        format!("{}%", value.0)
+    }
+
+    field half() -> Percentage {
+        Percentage(50.0)
+    }
+});
+
+#[cfg(test)]
+fn run_query(doc: &str) -> (Value, Vec<String>) {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+
+    let (result, errs) =
+        execute(doc, None, &schema, &Variables::new(), &()).expect("Execution failed");
+
+    (
+        result,
+        errs.iter()
+            .map(|e| e.error().message().to_owned())
+            .collect(),
+    )
+}
+
+#[test]
+fn test_derived_scalar() {
+    assert_eq!(UserId::name(&()), Some("UserId"));
+    assert_eq!(Counter::name(&()), Some("Count"));
+
+    let mut registry = juniper::Registry::new(FnvHashMap::default());
+    let meta = Counter::meta(&(), &mut registry);
+    assert_eq!(meta.name(), Some("Count"));
+    assert_eq!(meta.description(), Some(&"A number of things".to_string()));
+
+    let meta = UserId::meta(&(), &mut registry);
+    assert_eq!(meta.description(), Some(&"The id of a user.".to_string()));
+}
+
+#[test]
+fn test_delegates_to_inner_type() {
+    assert_eq!(
+        UserId::from_input_value(&InputValue::string("1")),
+        Some(UserId("1".to_owned()))
+    );
+    assert_eq!(UserId::from_input_value(&InputValue::int(1)), None);
+    assert_eq!(Counter(2).to_input_value(), InputValue::int(2));
+
+    assert_eq!(
+        run_query(r#"{ userId(id: "1") count }"#),
+        (graphql_value!({ "userId": "user-1", "count": 3 }), vec![])
+    );
+}
+
+#[test]
+fn test_custom_conversions() {
+    assert_eq!(
+        Percentage::from_input_value(&InputValue::float(0.25)),
+        Some(Percentage(25.0))
+    );
+    assert_eq!(Percentage::from_input_value(&InputValue::float(2.0)), None);
+    assert_eq!(Percentage(50.0).to_input_value(), InputValue::float(0.5));
+
+    assert_eq!(
+        run_query("{ percentage(value: 0.25) half }"),
+        (graphql_value!({ "percentage": "25%", "half": 0.5 }), vec![])
+    );
+}
+
+#[test]
+fn test_rejects_invalid_input() {
+    let schema = RootNode::new(Query, EmptyMutation::<()>::new());
+
+    assert!(execute("{ percentage(value: 2.0) }", None, &schema, &Variables::new(), &()).is_err());
+}
@@ -0,0 +1,169 @@
+#[cfg(test)]
+use fnv::FnvHashMap;
+
+#[cfg(test)]
+use juniper::futures::Future;
+#[cfg(test)]
+use juniper::{
+    self, execute, execute_async, EmptyMutation, GraphQLType, RootNode, Value, Variables,
+};
+
+#[derive(GraphQLObject)]
+struct Human {
+    id: String,
+    home_planet: String,
+}
+
+#[derive(GraphQLObject)]
+struct Droid {
+    id: String,
+    primary_function: String,
+}
+
+#[derive(GraphQLUnion)]
+#[graphql(name = "Result", description = "A search result")]
+enum SearchResult {
+    Human(Human),
+    Droid(Droid),
+}
+
+/// Union doc.
+#[derive(GraphQLUnion)]
+enum DocUnion {
+    Human(Human),
+}
+
+#[derive(GraphQLUnion)]
+enum RefUnion<'a> {
+    Human(&'a Human),
+    Droid(&'a Droid),
+}
+
+struct Query {
+    humans: Vec<Human>,
+    droids: Vec<Droid>,
+}
+
+graphql_object!(Query: () |&self| {
+    field search() -> Vec<SearchResult> {
+        vec![
+            SearchResult::Human(Human {
+                id: "1000".to_owned(),
+                home_planet: "Tatooine".to_owned(),
+            }),
+            SearchResult::Droid(Droid {
+                id: "2001".to_owned(),
+                primary_function: "Astromech".to_owned(),
+            }),
+        ]
+    }
+
+    field search_refs() -> Vec<RefUnion> {
+        self.droids.iter().map(RefUnion::Droid)
+            .chain(self.humans.iter().map(RefUnion::Human))
+            .collect()
+    }
+});
+
+#[cfg(test)]
+fn schema<'a>() -> RootNode<'a, Query, EmptyMutation<()>> {
+    RootNode::new(
+        Query {
+            humans: vec![Human {
+                id: "1001".to_owned(),
+                home_planet: "Alderaan".to_owned(),
+            }],
+            droids: vec![Droid {
+                id: "2000".to_owned(),
+                primary_function: "Protocol".to_owned(),
+            }],
+        },
+        EmptyMutation::new(),
+    )
+}
+
+#[test]
+fn test_derived_union() {
+    assert_eq!(SearchResult::name(&()), Some("Result"));
+
+    let mut registry = juniper::Registry::new(FnvHashMap::default());
+    let meta = SearchResult::meta(&(), &mut registry);
+
+    assert_eq!(meta.name(), Some("Result"));
+    assert_eq!(meta.description(), Some(&"A search result".to_string()));
+}
+
+#[test]
+fn test_doc_comment() {
+    let mut registry = juniper::Registry::new(FnvHashMap::default());
+    let meta = DocUnion::meta(&(), &mut registry);
+
+    assert_eq!(meta.description(), Some(&"Union doc.".to_string()));
+}
+
+#[test]
+fn test_resolves_variants() {
+    let doc = r#"
+        {
+            search {
+                __typename
+                ... on Human { id homePlanet }
+                ... on Droid { id primaryFunction }
+            }
+            searchRefs {
+                __typename
+                ... on Human { id }
+                ... on Droid { id }
+            }
+        }"#;
+
+    let schema = schema();
+
+    assert_eq!(
+        execute(doc, None, &schema, &Variables::new(), &()),
+        Ok((
+            graphql_value!({
+                "search": [
+                    { "__typename": "Human", "id": "1000", "homePlanet": "Tatooine" },
+                    { "__typename": "Droid", "id": "2001", "primaryFunction": "Astromech" },
+                ],
+                "searchRefs": [
+                    { "__typename": "Droid", "id": "2000" },
+                    { "__typename": "Human", "id": "1001" },
+                ],
+            }),
+            vec![]
+        ))
+    );
+
+    assert_eq!(
+        execute_async(doc, None, &schema, &Variables::new(), &())
+            .expect("Execution failed")
+            .wait(),
+        Ok(execute(doc, None, &schema, &Variables::new(), &()).expect("Execution failed"))
+    );
+}
+
+#[test]
+fn test_introspection() {
+    let doc = r#"
+        {
+            __type(name: "Result") {
+                kind
+                possibleTypes { name }
+            }
+        }"#;
+
+    assert_eq!(
+        execute(doc, None, &schema(), &Variables::new(), &()),
+        Ok((
+            graphql_value!({
+                "__type": {
+                    "kind": "UNION",
+                    "possibleTypes": [{ "name": "Human" }, { "name": "Droid" }],
+                },
+            }),
+            vec![]
+        ))
+    );
+}
@@ -0,0 +1,374 @@
+#[cfg(test)]
+use juniper::futures::Future;
+#[cfg(test)]
+use juniper::{execute, execute_async, EmptyMutation, RootNode, Value, Variables};
+
+struct Database {
+    humans: Vec<Human>,
+    droids: Vec<Droid>,
+}
+
+impl juniper::Context for Database {}
+
+/// A character in the saga.
+#[juniper::interface(context = "Database", implementers("Human", "Droid"))]
+trait Character {
+    /// The id of the character.
+    fn id(&self) -> &str;
+
+    fn name(&self) -> &str;
+
+    #[graphql(arguments(greeting(default = "\"Hello\".to_owned()")))]
+    fn greet(&self, greeting: String) -> String;
+}
+
+struct Human {
+    id: String,
+    name: String,
+    home_planet: String,
+}
+
+#[juniper::object(context = "Database")]
+impl Human {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[graphql(arguments(greeting(default = "\"Hello\".to_owned()")))]
+    fn greet(&self, greeting: String) -> String {
+        Character::greet(self, greeting)
+    }
+
+    fn home_planet(&self) -> &str {
+        &self.home_planet
+    }
+
+    fn friends<'a>(&self, context: &'a Database) -> Vec<&'a Character> {
+        context.droids.iter().map(|d| d as &Character).collect()
+    }
+}
+
+impl Character for Human {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn greet(&self, greeting: String) -> String {
+        format!("{}, {}!", greeting, self.name)
+    }
+}
+
+struct Droid {
+    id: String,
+    name: String,
+    primary_function: String,
+}
+
+#[juniper::object(context = "Database")]
+impl Droid {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[graphql(arguments(greeting(default = "\"Hello\".to_owned()")))]
+    fn greet(&self, greeting: String) -> String {
+        Character::greet(self, greeting)
+    }
+
+    fn primary_function(&self) -> &str {
+        &self.primary_function
+    }
+}
+
+impl Character for Droid {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn greet(&self, greeting: String) -> String {
+        format!("{}, {}! Beep.", greeting, self.name)
+    }
+}
+
+struct Query;
+
+#[juniper::object(context = "Database")]
+impl Query {
+    // Registers `Human` before `Character`.
+    fn luke<'a>(&self, context: &'a Database) -> Option<&'a Human> {
+        context.humans.first()
+    }
+
+    fn characters<'a>(&self, context: &'a Database) -> Vec<&'a Character> {
+        let humans = context.humans.iter().map(|h| h as &Character);
+        let droids = context.droids.iter().map(|d| d as &Character);
+        humans.chain(droids).collect()
+    }
+
+    fn character<'a>(&self, context: &'a Database, id: String) -> Option<&'a Character> {
+        context
+            .humans
+            .iter()
+            .map(|h| h as &Character)
+            .chain(context.droids.iter().map(|d| d as &Character))
+            .find(|c| c.id() == id)
+    }
+}
+
+#[cfg(test)]
+fn database() -> Database {
+    Database {
+        humans: vec![Human {
+            id: "1000".to_owned(),
+            name: "Luke".to_owned(),
+            home_planet: "Tatooine".to_owned(),
+        }],
+        droids: vec![Droid {
+            id: "2001".to_owned(),
+            name: "R2-D2".to_owned(),
+            primary_function: "Astromech".to_owned(),
+        }],
+    }
+}
+
+#[cfg(test)]
+fn run_query(doc: &str) -> Value {
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+
+    let (result, errs) =
+        execute(doc, None, &schema, &Variables::new(), &database()).expect("Execution failed");
+
+    assert_eq!(errs, []);
+    result
+}
+
+#[test]
+fn test_resolves_fields_and_implementers() {
+    assert_eq!(
+        run_query(
+            r#"{
+                characters {
+                    __typename
+                    id
+                    greet
+                    ... on Human { homePlanet }
+                    ... on Droid { primaryFunction }
+                }
+                luke {
+                    friends { name }
+                }
+                character(id: "2001") {
+                    name
+                    greet(greeting: "Hi")
+                }
+            }"#
+        ),
+        graphql_value!({
+            "characters": [
+                {
+                    "__typename": "Human",
+                    "id": "1000",
+                    "greet": "Hello, Luke!",
+                    "homePlanet": "Tatooine",
+                },
+                {
+                    "__typename": "Droid",
+                    "id": "2001",
+                    "greet": "Hello, R2-D2! Beep.",
+                    "primaryFunction": "Astromech",
+                },
+            ],
+            "luke": { "friends": [{ "name": "R2-D2" }] },
+            "character": { "name": "R2-D2", "greet": "Hi, R2-D2! Beep." },
+        })
+    );
+}
+
+#[test]
+fn test_async_resolution() {
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    let doc = r#"{
+        characters {
+            __typename
+            name
+            ... on Human { homePlanet }
+            ... on Droid { primaryFunction }
+        }
+    }"#;
+    let db = database();
+
+    assert_eq!(
+        execute_async(doc, None, &schema, &Variables::new(), &db)
+            .expect("Execution failed")
+            .wait(),
+        Ok(execute(doc, None, &schema, &Variables::new(), &db).expect("Execution failed"))
+    );
+}
+
+#[test]
+fn test_introspection() {
+    assert_eq!(
+        run_query(
+            r#"{
+                character: __type(name: "Character") {
+                    kind
+                    description
+                    fields { name description }
+                }
+                human: __type(name: "Human") {
+                    interfaces { name }
+                }
+                droid: __type(name: "Droid") {
+                    interfaces { name }
+                }
+            }"#
+        ),
+        graphql_value!({
+            "character": {
+                "kind": "INTERFACE",
+                "description": "A character in the saga.",
+                "fields": [
+                    { "name": "id", "description": "The id of the character." },
+                    { "name": "name", "description": None },
+                    { "name": "greet", "description": None },
+                ],
+            },
+            "human": {
+                "interfaces": [{ "name": "Character" }],
+            },
+            "droid": {
+                "interfaces": [{ "name": "Character" }],
+            },
+        })
+    );
+}
+
+#[test]
+fn test_possible_types() {
+    let result = run_query(r#"{ __type(name: "Character") { possibleTypes { name } } }"#);
+
+    let mut names = result
+        .as_object_value()
+        .and_then(|r| r.get_field_value("__type"))
+        .and_then(|t| t.as_object_value())
+        .and_then(|t| t.get_field_value("possibleTypes"))
+        .and_then(|p| p.as_list_value())
+        .expect("possibleTypes missing")
+        .iter()
+        .map(|t| {
+            t.as_object_value()
+                .and_then(|t| t.get_field_value("name"))
+                .and_then(|n| n.as_string_value())
+                .expect("name missing")
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+
+    assert_eq!(names, vec!["Droid", "Human"]);
+}
+
+// `Robot` does not declare the `serialNumber` field of the interface.
+#[juniper::interface(implementers("Robot"))]
+trait Machine {
+    fn name(&self) -> &str;
+
+    fn serial_number(&self) -> i32;
+}
+
+struct Robot;
+
+#[juniper::object]
+impl Robot {
+    fn name(&self) -> &str {
+        "C-3PO"
+    }
+}
+
+impl Machine for Robot {
+    fn name(&self) -> &str {
+        "C-3PO"
+    }
+
+    fn serial_number(&self) -> i32 {
+        3
+    }
+}
+
+struct MachineQuery;
+
+#[juniper::object]
+impl MachineQuery {
+    fn machine(&self) -> &Machine {
+        &Robot
+    }
+}
+
+#[test]
+#[should_panic(expected = "Type Robot does not declare the field serialNumber of its interface Machine")]
+fn test_rejects_implementers_missing_fields() {
+    RootNode::new(MachineQuery, EmptyMutation::<()>::new());
+}
+
+// `Android` is still being registered when it is added to the interface, and
+// does not declare its `model` field.
+#[juniper::interface(implementers("Android"))]
+trait Replicant {
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+}
+
+struct Android;
+
+#[juniper::object]
+impl Android {
+    fn name(&self) -> &str {
+        "Bishop"
+    }
+
+    fn replicant(&self) -> &Replicant {
+        self
+    }
+}
+
+impl Replicant for Android {
+    fn name(&self) -> &str {
+        "Bishop"
+    }
+
+    fn model(&self) -> &str {
+        "341-B"
+    }
+}
+
+struct AndroidQuery;
+
+#[juniper::object]
+impl AndroidQuery {
+    fn android(&self) -> Android {
+        Android
+    }
+}
+
+#[test]
+#[should_panic(expected = "Type Android does not declare the field model of its interface Replicant")]
+fn test_rejects_implementers_registered_earlier_missing_fields() {
+    RootNode::new(AndroidQuery, EmptyMutation::<()>::new());
+}
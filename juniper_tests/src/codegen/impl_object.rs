@@ -0,0 +1,248 @@
+#[cfg(test)]
+use juniper::futures::Future;
+#[cfg(test)]
+use juniper::{execute, execute_async, EmptyMutation, RootNode, Value, Variables};
+use juniper::{Executor, FieldError, FieldResult};
+
+struct Database {
+    users: Vec<User>,
+}
+
+impl juniper::Context for Database {}
+
+#[derive(GraphQLObject, Clone)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+struct Query;
+
+/// The root query.
+#[juniper::object(context = "Database")]
+impl Query {
+    /// The API version.
+    fn api_version() -> &'static str {
+        "1.0"
+    }
+
+    #[graphql(arguments(first(default = "2", description = "Number of users to return")))]
+    fn users(&self, context: &Database, first: i32) -> Vec<User> {
+        context.users.iter().take(first as usize).cloned().collect()
+    }
+
+    fn user<'a>(&self, executor: &Executor<'a, Database>, id: i32) -> FieldResult<&'a User> {
+        executor
+            .context()
+            .users
+            .iter()
+            .find(|u| u.id == id)
+            .ok_or_else(|| FieldError::from(format!("User {} not found", id)))
+    }
+
+    fn name_of(&self, context: &Database, user_id: Option<i32>) -> Option<String> {
+        user_id
+            .and_then(|id| context.users.iter().find(|u| u.id == id))
+            .map(|u| u.name.clone())
+    }
+
+    #[deprecated(note = "Use users")]
+    fn first_user<'a>(&self, context: &'a Database) -> Option<&'a User> {
+        context.users.first()
+    }
+
+    #[graphql(name = "renamed", description = "descr", complexity = "5")]
+    fn original(&self) -> bool {
+        true
+    }
+
+    #[graphql(skip)]
+    #[allow(dead_code)]
+    fn helper(&self) -> i32 {
+        42
+    }
+}
+
+#[cfg(test)]
+fn database() -> Database {
+    Database {
+        users: vec![
+            User {
+                id: 1,
+                name: "Alice".to_owned(),
+            },
+            User {
+                id: 2,
+                name: "Bob".to_owned(),
+            },
+            User {
+                id: 3,
+                name: "Carol".to_owned(),
+            },
+        ],
+    }
+}
+
+#[cfg(test)]
+fn run_query(doc: &str) -> (Value, Vec<Value>) {
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+
+    let (result, errs) =
+        execute(doc, None, &schema, &Variables::new(), &database()).expect("Execution failed");
+
+    (
+        result,
+        errs.iter()
+            .map(|e| Value::string(e.error().message()))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_fields_from_methods() {
+    assert_eq!(
+        run_query(
+            r#"{
+                apiVersion
+                users { name }
+                user(id: 3) { id }
+                nameOf(userId: 2)
+                renamed
+            }"#
+        ),
+        (
+            graphql_value!({
+                "apiVersion": "1.0",
+                "users": [{ "name": "Alice" }, { "name": "Bob" }],
+                "user": { "id": 3 },
+                "nameOf": "Bob",
+                "renamed": true,
+            }),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn test_field_errors() {
+    assert_eq!(
+        run_query("{ user(id: 4) { id } }"),
+        (
+            Value::null(),
+            vec![Value::string("User 4 not found")]
+        )
+    );
+}
+
+#[test]
+fn test_async_resolution() {
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    let doc = "{ users(first: 3) { id } firstUser { name } }";
+    let db = database();
+
+    assert_eq!(
+        execute_async(doc, None, &schema, &Variables::new(), &db)
+            .expect("Execution failed")
+            .wait(),
+        Ok(execute(doc, None, &schema, &Variables::new(), &db).expect("Execution failed"))
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_cannot_query_skipped_method() {
+    let schema = RootNode::new(Query, EmptyMutation::<Database>::new());
+    execute("{ helper }", None, &schema, &Variables::new(), &database()).unwrap();
+}
+
+#[test]
+fn test_introspection() {
+    let (result, errs) = run_query(
+        r#"{
+            __type(name: "Query") {
+                description
+                fields(includeDeprecated: true) {
+                    name
+                    description
+                    isDeprecated
+                    deprecationReason
+                    args { name description defaultValue }
+                }
+            }
+        }"#,
+    );
+
+    assert_eq!(errs, []);
+
+    let query_type = result
+        .as_object_value()
+        .and_then(|r| r.get_field_value("__type"))
+        .and_then(|t| t.as_object_value())
+        .expect("__type field missing");
+    assert_eq!(
+        query_type.get_field_value("description"),
+        Some(&Value::string("The root query."))
+    );
+
+    let fields = query_type
+        .get_field_value("fields")
+        .and_then(|f| f.as_list_value())
+        .expect("fields missing");
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|f| {
+                f.as_object_value()
+                    .and_then(|f| f.get_field_value("name"))
+                    == Some(&Value::string(name))
+            })
+            .unwrap_or_else(|| panic!("Field {} missing", name))
+            .clone()
+    };
+
+    assert_eq!(fields.len(), 6);
+    assert_eq!(
+        field("apiVersion"),
+        graphql_value!({
+            "name": "apiVersion",
+            "description": "The API version.",
+            "isDeprecated": false,
+            "deprecationReason": None,
+            "args": [],
+        })
+    );
+    assert_eq!(
+        field("users"),
+        graphql_value!({
+            "name": "users",
+            "description": None,
+            "isDeprecated": false,
+            "deprecationReason": None,
+            "args": [{
+                "name": "first",
+                "description": "Number of users to return",
+                "defaultValue": "2",
+            }],
+        })
+    );
+    assert_eq!(
+        field("firstUser"),
+        graphql_value!({
+            "name": "firstUser",
+            "description": None,
+            "isDeprecated": true,
+            "deprecationReason": "Use users",
+            "args": [],
+        })
+    );
+    assert_eq!(
+        field("renamed"),
+        graphql_value!({
+            "name": "renamed",
+            "description": "descr",
+            "isDeprecated": false,
+            "deprecationReason": None,
+            "args": [],
+        })
+    );
+}
@@ -1,3 +1,7 @@
 mod derive_enum;
 mod derive_input_object;
 mod derive_object;
+mod derive_scalar;
+mod derive_union;
+mod impl_interface;
+mod impl_object;
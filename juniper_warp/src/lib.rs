@@ -39,71 +39,24 @@ Check the LICENSE file for details.
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+#[cfg(test)]
 #[macro_use]
-extern crate failure;
-extern crate futures;
-extern crate futures_cpupool;
 extern crate juniper;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde;
-extern crate serde_json;
-extern crate warp;
-
-#[cfg(test)]
-extern crate percent_encoding;
 
-use futures::Future;
-use futures_cpupool::CpuPool;
+use std::collections::HashMap;
+use std::io;
 use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Buf;
+use futures::{future, stream, FutureExt, Stream, StreamExt, TryStreamExt};
+use juniper::http::multipart::{self, MultipartBody, MultipartError, MultipartOptions};
+use juniper::http::{GraphQLBatchRequest, GraphQLRequest};
+use serde_json::json;
+use tokio::time::{self, Instant};
 use warp::{filters::BoxedFilter, Filter};
 
-#[derive(Debug, Deserialize, PartialEq)]
-#[serde(untagged)]
-enum GraphQLBatchRequest {
-    Single(juniper::http::GraphQLRequest),
-    Batch(Vec<juniper::http::GraphQLRequest>),
-}
-
-impl GraphQLBatchRequest {
-    pub fn execute<'a, CtxT, QueryT, MutationT>(
-        &'a self,
-        root_node: &juniper::RootNode<QueryT, MutationT>,
-        context: &CtxT,
-    ) -> GraphQLBatchResponse<'a>
-    where
-        QueryT: juniper::GraphQLType<Context = CtxT>,
-        MutationT: juniper::GraphQLType<Context = CtxT>,
-    {
-        match self {
-            &GraphQLBatchRequest::Single(ref request) => {
-                GraphQLBatchResponse::Single(request.execute(root_node, context))
-            }
-            &GraphQLBatchRequest::Batch(ref requests) => GraphQLBatchResponse::Batch(
-                requests
-                    .iter()
-                    .map(|request| request.execute(root_node, context))
-                    .collect(),
-            ),
-        }
-    }
-}
-
-#[derive(Serialize)]
-#[serde(untagged)]
-enum GraphQLBatchResponse<'a> {
-    Single(juniper::http::GraphQLResponse<'a>),
-    Batch(Vec<juniper::http::GraphQLResponse<'a>>),
-}
-
-impl<'a> GraphQLBatchResponse<'a> {
-    fn is_ok(&self) -> bool {
-        match self {
-            GraphQLBatchResponse::Single(res) => res.is_ok(),
-            GraphQLBatchResponse::Batch(reses) => reses.iter().all(|res| res.is_ok()),
-        }
-    }
-}
+pub mod subscriptions;
 
 /// Make a filter for graphql endpoint.
 ///
@@ -111,17 +64,15 @@ impl<'a> GraphQLBatchResponse<'a> {
 ///
 /// The `context_extractor` argument should be a filter that provides the GraphQL context required by the schema.
 ///
-/// In order to avoid blocking, this helper will create a [CpuPool](../futures_cpupool/struct.CpuPool.html) to resolve GraphQL requests.
+/// Multipart requests carrying file uploads are accepted within the default limits of [MultipartOptions](../juniper/http/multipart/struct.MultipartOptions.html).
 ///
-/// If you want to pass your own threadpool, use [make_graphql_filter_with_thread_pool](fn.make_graphql_filter_with_thread_pool.html) instead.
+/// In order to avoid blocking, this helper executes GraphQL requests with [spawn_blocking](../tokio/task/fn.spawn_blocking.html), on the thread pool Tokio keeps for blocking work.
 ///
 /// Example:
 ///
 /// ```
-/// # extern crate juniper_warp;
 /// # #[macro_use]
 /// # extern crate juniper;
-/// # extern crate warp;
 /// #
 /// # use std::sync::Arc;
 /// # use warp::Filter;
@@ -161,7 +112,7 @@ impl<'a> GraphQLBatchResponse<'a> {
 /// let graphql_filter = make_graphql_filter(schema, context_extractor);
 ///
 /// let graphql_endpoint = warp::path("graphql")
-///     .and(warp::post2())
+///     .and(warp::post())
 ///     .and(graphql_filter);
 /// # }
 /// ```
@@ -170,110 +121,324 @@ pub fn make_graphql_filter<Query, Mutation, Context>(
     context_extractor: BoxedFilter<(Context,)>,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
-    Context: Send + 'static,
+    Context: Send + Sync + 'static,
     Query: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
     Mutation: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
 {
-    let pool = CpuPool::new_num_cpus();
-    make_graphql_filter_with_thread_pool(schema, context_extractor, pool)
+    make_graphql_filter_with_upload_options(schema, context_extractor, MultipartOptions::new())
 }
 
-type Response =
-    Box<Future<Item = warp::http::Response<Vec<u8>>, Error = warp::reject::Rejection> + Send>;
-
-/// Same as [make_graphql_filter](./fn.make_graphql_filter.html), but use the provided [CpuPool](../futures_cpupool/struct.CpuPool.html) instead.
-pub fn make_graphql_filter_with_thread_pool<Query, Mutation, Context>(
+/// Same as [make_graphql_filter](./fn.make_graphql_filter.html), but limit and store the files of multipart requests according to the provided [MultipartOptions](../juniper/http/multipart/struct.MultipartOptions.html).
+pub fn make_graphql_filter_with_upload_options<Query, Mutation, Context>(
     schema: juniper::RootNode<'static, Query, Mutation>,
     context_extractor: BoxedFilter<(Context,)>,
-    thread_pool: futures_cpupool::CpuPool,
+    upload_options: MultipartOptions,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
-    Context: Send + 'static,
+    Context: Send + Sync + 'static,
     Query: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
     Mutation: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
 {
     let schema = Arc::new(schema);
     let post_schema = schema.clone();
-    let pool_filter = warp::any().map(move || thread_pool.clone());
-
-    let handle_post_request =
-        move |context: Context, request: GraphQLBatchRequest, pool: CpuPool| -> Response {
-            let schema = post_schema.clone();
-            Box::new(
-                pool.spawn_fn(move || {
-                    let response = request.execute(&schema, &context);
-                    Ok((serde_json::to_vec(&response)?, response.is_ok()))
-                }).then(|result| ::futures::future::done(Ok(build_response(result))))
-                .map_err(|_: failure::Error| warp::reject::server_error()),
-            )
-        };
+    let multipart_schema = schema.clone();
+
+    let handle_post_request = move |context: Context, request: GraphQLBatchRequest| {
+        let schema = post_schema.clone();
+        execute_blocking(move || {
+            let response = request.execute(&schema, &context);
+            Ok((serde_json::to_vec(&response)?, response.is_ok()))
+        })
+    };
 
-    let post_filter = warp::post2()
+    let post_filter = warp::post()
         .and(context_extractor.clone())
         .and(warp::body::json())
-        .and(pool_filter.clone())
         .and_then(handle_post_request);
 
-    let handle_get_request = move |context: Context,
-                                   mut request: std::collections::HashMap<String, String>,
-                                   pool: CpuPool|
-          -> Response {
-        let schema = schema.clone();
-        Box::new(
-            pool.spawn_fn(move || {
-                let variables = match request.remove("variables") {
-                    None => None,
-                    Some(vs) => serde_json::from_str(&vs)?,
+    let upload_options = Arc::new(upload_options);
+    let handle_multipart_request = move |content_type: String, context: Context, body| {
+        let schema = multipart_schema.clone();
+        let upload_options = upload_options.clone();
+
+        read_multipart_body(body, upload_options.clone()).then(move |body| {
+            execute_blocking(move || {
+                let request = match body.and_then(|body| {
+                    multipart::parse_multipart_request(&content_type, body.bytes(), &upload_options)
+                }) {
+                    Ok(request) => request,
+                    Err(err) => return error_response(&err.to_string()),
                 };
 
-                let graphql_request = juniper::http::GraphQLRequest::new(
-                    request.remove("query").ok_or_else(|| {
-                        format_err!("Missing GraphQL query string in query parameters")
-                    })?,
-                    request.get("operation_name").map(|s| s.to_owned()),
-                    variables,
-                );
-
-                let response = graphql_request.execute(&schema, &context);
+                let response = request.execute(&schema, &context);
                 Ok((serde_json::to_vec(&response)?, response.is_ok()))
-            }).then(|result| ::futures::future::done(Ok(build_response(result))))
-            .map_err(|_: failure::Error| warp::reject::server_error()),
-        )
+            })
+        })
     };
 
-    let get_filter = warp::get2()
+    // The content type is checked first, so that the context of requests
+    // handled by the other filters is not extracted in vain
+    let multipart_filter = warp::post()
+        .and(
+            warp::header::<String>("content-type").and_then(|content_type: String| {
+                future::ready(if multipart::is_multipart_request(&content_type) {
+                    Ok(content_type)
+                } else {
+                    Err(warp::reject::not_found())
+                })
+            }),
+        )
         .and(context_extractor.clone())
-        .and(warp::filters::query::query())
-        .and(pool_filter)
+        .and(warp::body::stream())
+        .and_then(handle_multipart_request);
+
+    let handle_get_request = move |context: Context, parameters: HashMap<String, String>| {
+        let schema = schema.clone();
+        execute_blocking(move || {
+            let request = match GraphQLRequest::from_get_parameters(parameters) {
+                Ok(request) => request,
+                Err(err) => return error_response(&err.to_string()),
+            };
+
+            let response = request.execute(&schema, &context);
+            Ok((serde_json::to_vec(&response)?, response.is_ok()))
+        })
+    };
+
+    let get_filter = warp::get()
+        .and(context_extractor)
+        .and(warp::query())
         .and_then(handle_get_request);
 
-    get_filter.or(post_filter).unify().boxed()
+    // Multipart requests are matched first, since the JSON body of other
+    // POST requests can only be read once
+    get_filter
+        .or(multipart_filter)
+        .unify()
+        .or(post_filter)
+        .unify()
+        .boxed()
+}
+
+// The size limit of the request is checked while the body is read
+async fn read_multipart_body<S, B>(
+    body: S,
+    upload_options: Arc<MultipartOptions>,
+) -> Result<MultipartBody, MultipartError>
+where
+    S: Stream<Item = Result<B, warp::Error>>,
+    B: Buf,
+{
+    body.map_err(|err| MultipartError::Io(io::Error::other(err.to_string())))
+        .try_fold(MultipartBody::new(&upload_options), |mut body, chunk| {
+            future::ready(body.push(chunk.chunk()).map(|()| body))
+        })
+        .await
+}
+
+fn error_response(message: &str) -> Result<(Vec<u8>, bool), serde_json::Error> {
+    let errors = json!({ "errors": [{ "message": message }] });
+    Ok((serde_json::to_vec(&errors)?, false))
+}
+
+// Runs `execute` on the thread pool of Tokio for blocking work
+async fn execute_blocking<F>(execute: F) -> Result<warp::http::Response<Vec<u8>>, warp::Rejection>
+where
+    F: FnOnce() -> Result<(Vec<u8>, bool), serde_json::Error> + Send + 'static,
+{
+    let response = tokio::task::spawn_blocking(execute).await;
+    Ok(build_response(response.ok().and_then(Result::ok)))
 }
 
-fn build_response(
-    response: Result<(Vec<u8>, bool), failure::Error>,
-) -> warp::http::Response<Vec<u8>> {
+fn build_response(response: Option<(Vec<u8>, bool)>) -> warp::http::Response<Vec<u8>> {
     match response {
-        Ok((body, is_ok)) => warp::http::Response::builder()
+        Some((body, is_ok)) => warp::http::Response::builder()
             .status(if is_ok { 200 } else { 400 })
             .header("content-type", "application/json")
             .body(body)
             .expect("response is valid"),
-        Err(_) => warp::http::Response::builder()
+        None => warp::http::Response::builder()
             .status(warp::http::StatusCode::INTERNAL_SERVER_ERROR)
             .body(Vec::new())
             .expect("status code is valid"),
     }
 }
 
+/// Make a filter serving GraphQL subscriptions over WebSockets.
+///
+/// The connections speak the `graphql-ws` protocol of [subscriptions-transport-ws](https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md), as implemented in the [subscriptions](subscriptions/index.html) module. Queries and mutations sent over a connection are executed with the same `schema` as subscriptions.
+///
+/// The `context_factory` argument receives the payload of the `connection_init` message, and returns either the context of the connection, or the error message that closes it.
+///
+/// The `graphql-ws` protocol is only confirmed to clients offering it in their `Sec-WebSocket-Protocol` header.
+///
+/// A keepalive message is sent every 15 seconds. Use [make_graphql_subscriptions_filter_with_keep_alive](fn.make_graphql_subscriptions_filter_with_keep_alive.html) to change this.
+///
+/// Every operation runs in a task of its own on the Tokio runtime. At most [MAX_OPERATIONS](subscriptions/constant.MAX_OPERATIONS.html) operations run at once on a connection, and [MAX_TOTAL_OPERATIONS](subscriptions/constant.MAX_TOTAL_OPERATIONS.html) across all connections of the filter. Use [make_graphql_subscriptions_filter_with_limits](fn.make_graphql_subscriptions_filter_with_limits.html) to change this.
+///
+/// Example:
+///
+/// ```
+/// # use warp::Filter;
+/// # use juniper::tests::model::Database;
+/// # use juniper::{EmptyMutation, RootNode};
+/// # use juniper_warp::make_graphql_subscriptions_filter;
+/// #
+/// # fn main() {
+/// let schema = RootNode::new(Database::new(), EmptyMutation::<Database>::new());
+///
+/// let subscriptions_filter = make_graphql_subscriptions_filter(
+///     schema,
+///     |_payload: serde_json::Value| Ok(Database::new()),
+/// );
+///
+/// let subscriptions_endpoint = warp::path("subscriptions").and(subscriptions_filter);
+/// # }
+/// ```
+pub fn make_graphql_subscriptions_filter<Query, Mutation, Subscription, Context, F>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription>,
+    context_factory: F,
+) -> BoxedFilter<(impl warp::Reply,)>
+where
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription:
+        juniper::GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(serde_json::Value) -> Result<Context, String> + Send + Sync + 'static,
+{
+    make_graphql_subscriptions_filter_with_keep_alive(
+        schema,
+        context_factory,
+        Some(Duration::from_secs(15)),
+    )
+}
+
+/// Same as [make_graphql_subscriptions_filter](./fn.make_graphql_subscriptions_filter.html), but send keepalive messages at the provided interval, or not at all.
+pub fn make_graphql_subscriptions_filter_with_keep_alive<
+    Query,
+    Mutation,
+    Subscription,
+    Context,
+    F,
+>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription>,
+    context_factory: F,
+    keep_alive: Option<Duration>,
+) -> BoxedFilter<(impl warp::Reply,)>
+where
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription:
+        juniper::GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(serde_json::Value) -> Result<Context, String> + Send + Sync + 'static,
+{
+    make_graphql_subscriptions_filter_with_limits(
+        schema,
+        context_factory,
+        keep_alive,
+        subscriptions::OperationLimits::default(),
+    )
+}
+
+/// Same as [make_graphql_subscriptions_filter_with_keep_alive](./fn.make_graphql_subscriptions_filter_with_keep_alive.html), but limit the number of operations running at once according to the provided [OperationLimits](subscriptions/struct.OperationLimits.html).
+///
+/// The total limit is shared by all connections of the filter, and by any other filter made with a clone of `limits`.
+pub fn make_graphql_subscriptions_filter_with_limits<Query, Mutation, Subscription, Context, F>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription>,
+    context_factory: F,
+    keep_alive: Option<Duration>,
+    limits: subscriptions::OperationLimits,
+) -> BoxedFilter<(impl warp::Reply,)>
+where
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription:
+        juniper::GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(serde_json::Value) -> Result<Context, String> + Send + Sync + 'static,
+{
+    let schema = Arc::new(schema);
+    let context_factory = Arc::new(context_factory);
+
+    let upgrade = move |ws: warp::ws::Ws| {
+        let schema = schema.clone();
+        let context_factory = context_factory.clone();
+        let limits = limits.clone();
+
+        ws.on_upgrade(move |socket| {
+            let (sink, stream) = socket.split();
+
+            let incoming = stream
+                .take_while(|message| {
+                    future::ready(message.as_ref().is_ok_and(|message| !message.is_close()))
+                })
+                .filter_map(|message| {
+                    future::ready(
+                        message
+                            .ok()
+                            .and_then(|message| message.to_str().ok().map(|text| text.to_owned())),
+                    )
+                });
+
+            let keep_alive = keep_alive.map(|interval| {
+                let ticks = time::interval_at(Instant::now() + interval, interval);
+                stream::unfold(ticks, |mut ticks| async move {
+                    ticks.tick().await;
+                    Some(((), ticks))
+                })
+                .boxed()
+            });
+
+            subscriptions::serve_connection(
+                schema,
+                context_factory,
+                incoming.boxed(),
+                keep_alive,
+                limits,
+            )
+            .map(|message| Ok(warp::ws::Message::text(message)))
+            .forward(sink)
+            .map(|_| ())
+        })
+    };
+
+    // The protocol is only confirmed to clients asking for it, the others
+    // are upgraded without a protocol
+    let protocol_offered =
+        warp::header::<String>("sec-websocket-protocol").and_then(|protocols: String| {
+            future::ready(
+                if protocols
+                    .split(',')
+                    .any(|protocol| protocol.trim() == subscriptions::PROTOCOL)
+                {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                },
+            )
+        });
+    let upgrade_with_protocol = upgrade.clone();
+
+    protocol_offered
+        .and(warp::ws())
+        .map(move |(), ws: warp::ws::Ws| {
+            warp::reply::with_header(
+                upgrade_with_protocol(ws),
+                "sec-websocket-protocol",
+                subscriptions::PROTOCOL,
+            )
+        })
+        .or(warp::ws().map(upgrade))
+        .boxed()
+}
+
 /// Create a filter that replies with an HTML page containing GraphiQL. This does not handle routing, so you can mount it on any endpoint.
 ///
 /// For example:
 ///
 /// ```
-/// # extern crate warp;
-/// # extern crate juniper_warp;
-/// #
 /// # use warp::Filter;
 /// # use juniper_warp::graphiql_handler;
 /// #
@@ -307,23 +472,24 @@ mod tests {
         graphiql_response("/abcd");
     }
 
-    #[test]
-    fn graphiql_endpoint_matches() {
-        let filter = warp::get2()
+    #[tokio::test]
+    async fn graphiql_endpoint_matches() {
+        let filter = warp::get()
             .and(warp::path("graphiql"))
             .and(graphiql_handler("/graphql"));
         let result = request()
             .method("GET")
             .path("/graphiql")
             .header("accept", "text/html")
-            .filter(&filter);
+            .filter(&filter)
+            .await;
 
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn graphiql_endpoint_returns_graphiql_source() {
-        let filter = warp::get2()
+    #[tokio::test]
+    async fn graphiql_endpoint_returns_graphiql_source() {
+        let filter = warp::get()
             .and(warp::path("dogs-api"))
             .and(warp::path("graphiql"))
             .and(graphiql_handler("/dogs-api/graphql"));
@@ -331,7 +497,8 @@ mod tests {
             .method("GET")
             .path("/dogs-api/graphiql")
             .header("accept", "text/html")
-            .reply(&filter);
+            .reply(&filter)
+            .await;
 
         assert_eq!(response.status(), http::StatusCode::OK);
         assert_eq!(
@@ -343,8 +510,8 @@ mod tests {
         assert!(body.contains("<script>var GRAPHQL_URL = '/dogs-api/graphql';</script>"));
     }
 
-    #[test]
-    fn graphql_handler_works_json_post() {
+    #[tokio::test]
+    async fn graphql_handler_works_json_post() {
         use juniper::tests::model::Database;
         use juniper::{EmptyMutation, RootNode};
 
@@ -352,7 +519,7 @@ mod tests {
 
         let schema: Schema = RootNode::new(Database::new(), EmptyMutation::<Database>::new());
 
-        let state = warp::any().map(move || Database::new());
+        let state = warp::any().map(Database::new);
         let filter = warp::path("graphql2").and(make_graphql_filter(schema, state.boxed()));
 
         let response = request()
@@ -361,7 +528,8 @@ mod tests {
             .header("accept", "application/json")
             .header("content-type", "application/json")
             .body(r##"{ "variables": null, "query": "{ hero(episode: NEW_HOPE) { name } }" }"##)
-            .reply(&filter);
+            .reply(&filter)
+            .await;
 
         assert_eq!(response.status(), http::StatusCode::OK);
         assert_eq!(
@@ -374,8 +542,8 @@ mod tests {
         );
     }
 
-    #[test]
-    fn batch_requests_work() {
+    #[tokio::test]
+    async fn batch_requests_work() {
         use juniper::tests::model::Database;
         use juniper::{EmptyMutation, RootNode};
 
@@ -383,7 +551,7 @@ mod tests {
 
         let schema: Schema = RootNode::new(Database::new(), EmptyMutation::<Database>::new());
 
-        let state = warp::any().map(move || Database::new());
+        let state = warp::any().map(Database::new);
         let filter = warp::path("graphql2").and(make_graphql_filter(schema, state.boxed()));
 
         let response = request()
@@ -396,7 +564,9 @@ mod tests {
                      { "variables": null, "query": "{ hero(episode: NEW_HOPE) { name } }" },
                      { "variables": null, "query": "{ hero(episode: EMPIRE) { id name } }" }
                  ]"##,
-            ).reply(&filter);
+            )
+            .reply(&filter)
+            .await;
 
         assert_eq!(response.status(), http::StatusCode::OK);
         assert_eq!(
@@ -409,6 +579,52 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn subscriptions_filter_answers_operations() {
+        use juniper::tests::model::Database;
+        use juniper::{EmptyMutation, RootNode};
+
+        let schema = RootNode::new(Database::new(), EmptyMutation::<Database>::new());
+        let filter = make_graphql_subscriptions_filter(schema, |_| Ok(Database::new()));
+
+        let mut client = warp::test::ws()
+            .header("sec-websocket-protocol", subscriptions::PROTOCOL)
+            .handshake(filter)
+            .await
+            .expect("handshake failed");
+
+        let mut messages = Vec::new();
+        client
+            .send_text(json!({ "type": "connection_init" }).to_string())
+            .await;
+        client
+            .send_text(
+                json!({
+                    "type": "start",
+                    "id": "1",
+                    "payload": { "query": "{ hero(episode: NEW_HOPE) { name } }" },
+                })
+                .to_string(),
+            )
+            .await;
+        for _ in 0..4 {
+            let message = client.recv().await.expect("connection closed");
+            messages.push(
+                serde_json::from_str::<serde_json::Value>(message.to_str().unwrap()).unwrap(),
+            );
+        }
+
+        assert_eq!(
+            messages,
+            vec![
+                json!({ "type": "connection_ack" }),
+                json!({ "type": "ka" }),
+                json!({ "type": "data", "id": "1", "payload": { "data": { "hero": { "name": "R2-D2" } } } }),
+                json!({ "type": "complete", "id": "1" }),
+            ]
+        );
+    }
+
     #[test]
     fn batch_request_deserialization_can_fail() {
         let json = r#"blah"#;
@@ -421,11 +637,14 @@ mod tests {
 #[cfg(test)]
 mod tests_http_harness {
     use super::*;
-    use juniper::http::tests::{run_http_test_suite, HTTPIntegration, TestResponse};
+    use juniper::http::tests::{
+        run_http_test_suite, run_http_upload_test_suite, upload_schema, HTTPIntegration,
+        HTTPUploadIntegration, TestResponse,
+    };
     use juniper::tests::model::Database;
     use juniper::EmptyMutation;
     use juniper::RootNode;
-    use warp;
+    use tokio::runtime::Runtime;
     use warp::Filter;
 
     type Schema = juniper::RootNode<'static, Database, EmptyMutation<Database>>;
@@ -433,27 +652,45 @@ mod tests_http_harness {
     fn warp_server() -> warp::filters::BoxedFilter<(warp::http::Response<Vec<u8>>,)> {
         let schema: Schema = RootNode::new(Database::new(), EmptyMutation::<Database>::new());
 
-        let state = warp::any().map(move || Database::new());
-        let filter = warp::filters::path::index().and(make_graphql_filter(schema, state.boxed()));
+        let state = warp::any().map(Database::new);
+        let filter = warp::path::end().and(make_graphql_filter(schema, state.boxed()));
 
         filter.boxed()
     }
 
     struct TestWarpIntegration {
+        runtime: Runtime,
         filter: warp::filters::BoxedFilter<(warp::http::Response<Vec<u8>>,)>,
     }
 
+    impl TestWarpIntegration {
+        fn new(filter: warp::filters::BoxedFilter<(warp::http::Response<Vec<u8>>,)>) -> Self {
+            TestWarpIntegration {
+                runtime: Runtime::new().unwrap(),
+                filter,
+            }
+        }
+
+        // Rejections are turned into responses with the status they carry
+        fn reply(&self, request: warp::test::RequestBuilder) -> TestResponse {
+            let response = self.runtime.block_on(request.reply(&self.filter));
+            test_response_from_http_response(response)
+        }
+    }
+
     // This can't be implemented with the From trait since TestResponse is not defined in this crate.
-    fn test_response_from_http_response(response: warp::http::Response<Vec<u8>>) -> TestResponse {
+    fn test_response_from_http_response(
+        response: warp::http::Response<bytes::Bytes>,
+    ) -> TestResponse {
         TestResponse {
             status_code: response.status().as_u16() as i32,
-            body: Some(String::from_utf8(response.body().to_owned()).unwrap()),
+            body: Some(String::from_utf8(response.body().to_vec()).unwrap()),
             content_type: response
                 .headers()
                 .get("content-type")
-                .expect("missing content-type header in warp response")
-                .to_str()
-                .expect("invalid content-type string")
+                .map_or("", |content_type| {
+                    content_type.to_str().expect("invalid content-type string")
+                })
                 .to_owned(),
         }
     }
@@ -461,49 +698,54 @@ mod tests_http_harness {
     impl HTTPIntegration for TestWarpIntegration {
         fn get(&self, url: &str) -> TestResponse {
             use percent_encoding::{percent_encode, DEFAULT_ENCODE_SET};
-            let url: String = percent_encode(url.replace("/?", "").as_bytes(), DEFAULT_ENCODE_SET)
-                .into_iter()
-                .collect::<Vec<_>>()
-                .join("");
-
-            let response = warp::test::request()
-                .method("GET")
-                .path(&format!("/?{}", url))
-                .filter(&self.filter)
-                .unwrap_or_else(|rejection| {
-                    warp::http::Response::builder()
-                        .status(rejection.status())
-                        .header("content-type", "application/json")
-                        .body(Vec::new())
-                        .unwrap()
-                });
-            test_response_from_http_response(response)
+            let url: String =
+                percent_encode(url.replace("/?", "").as_bytes(), DEFAULT_ENCODE_SET).collect();
+
+            self.reply(
+                warp::test::request()
+                    .method("GET")
+                    .path(&format!("/?{}", url)),
+            )
         }
 
         fn post(&self, url: &str, body: &str) -> TestResponse {
-            let response = warp::test::request()
-                .method("POST")
-                .header("content-type", "application/json")
-                .path(url)
-                .body(body)
-                .filter(&self.filter)
-                .unwrap_or_else(|rejection| {
-                    warp::http::Response::builder()
-                        .status(rejection.status())
-                        .header("content-type", "application/json")
-                        .body(Vec::new())
-                        .unwrap()
-                });
-            test_response_from_http_response(response)
+            self.reply(
+                warp::test::request()
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .path(url)
+                    .body(body),
+            )
+        }
+    }
+
+    impl HTTPUploadIntegration for TestWarpIntegration {
+        fn post_multipart(&self, url: &str, content_type: &str, body: &[u8]) -> TestResponse {
+            self.reply(
+                warp::test::request()
+                    .method("POST")
+                    .header("content-type", content_type)
+                    .path(url)
+                    .body(body),
+            )
         }
     }
 
     #[test]
     fn test_warp_integration() {
-        let integration = TestWarpIntegration {
-            filter: warp_server(),
-        };
+        let integration = TestWarpIntegration::new(warp_server());
 
         run_http_test_suite(&integration);
     }
+
+    #[test]
+    fn test_warp_upload_integration() {
+        let state = warp::any().map(Database::new);
+        let filter = warp::path::end()
+            .and(make_graphql_filter(upload_schema(), state.boxed()))
+            .boxed();
+        let integration = TestWarpIntegration::new(filter);
+
+        run_http_upload_test_suite(&integration);
+    }
 }
@@ -0,0 +1,888 @@
+//! GraphQL subscriptions over WebSockets
+//!
+//! This module implements the `graphql-ws` protocol of
+//! [subscriptions-transport-ws](https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md)
+//! independently of the socket it runs on. The warp filter serving it is
+//! [make_graphql_subscriptions_filter](../fn.make_graphql_subscriptions_filter.html),
+//! [serve_connection](fn.serve_connection.html) can be used to run the
+//! protocol on any stream of text messages.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{self, Poll};
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::channel::oneshot;
+use futures::compat::Stream01CompatExt;
+use futures::future::{self, Either};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use juniper::http::{GraphQLRequest, GraphQLResponse};
+use juniper::{GraphQLSubscriptionType, GraphQLType, RootNode};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{self, json, Value as Json};
+use tokio::sync::Semaphore;
+
+/// The name of the WebSocket subprotocol served by this module
+pub const PROTOCOL: &str = "graphql-ws";
+
+/// The default number of operations that may run at once on a connection
+pub const MAX_OPERATIONS: usize = 16;
+
+/// The default number of operations that may run at once across all
+/// connections sharing the same `OperationLimits`
+pub const MAX_TOTAL_OPERATIONS: usize = 1024;
+
+/// A stream of text messages received from a client
+pub type IncomingMessages = BoxStream<'static, String>;
+
+/// A stream ticking whenever a keepalive message should be sent
+pub type KeepAlive = BoxStream<'static, ()>;
+
+/// Limits on the number of operations running at once
+///
+/// Every running operation counts towards the limit of its connection, and
+/// towards the total limit shared by all clones of the same limits.
+/// Operations started while either limit is reached are answered with an
+/// `error` message.
+#[derive(Clone, Debug)]
+pub struct OperationLimits {
+    total: Arc<Semaphore>,
+    per_connection: usize,
+}
+
+impl OperationLimits {
+    /// Allow `total` operations to run at once overall, and
+    /// `per_connection` of them on a single connection
+    pub fn new(total: usize, per_connection: usize) -> OperationLimits {
+        OperationLimits {
+            total: Arc::new(Semaphore::new(total)),
+            per_connection,
+        }
+    }
+}
+
+impl Default for OperationLimits {
+    fn default() -> OperationLimits {
+        OperationLimits::new(MAX_TOTAL_OPERATIONS, MAX_OPERATIONS)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    ConnectionInit {
+        #[serde(default)]
+        payload: Json,
+    },
+    Start {
+        id: String,
+        payload: GraphQLRequest,
+    },
+    Stop {
+        id: String,
+    },
+    ConnectionTerminate,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    ConnectionAck,
+    ConnectionError {
+        payload: ErrorPayload,
+    },
+    #[serde(rename = "ka")]
+    KeepAlive,
+    Data {
+        id: &'a str,
+        payload: &'a GraphQLResponse<'a>,
+    },
+    Error {
+        id: &'a str,
+        payload: Json,
+    },
+    Complete {
+        id: &'a str,
+    },
+}
+
+#[derive(Serialize)]
+struct ErrorPayload {
+    message: String,
+}
+
+impl<'a> ServerMessage<'a> {
+    fn error(id: &'a str, message: &str) -> ServerMessage<'a> {
+        ServerMessage::Error {
+            id,
+            payload: json!([{ "message": message }]),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("messages are serializable")
+    }
+}
+
+// Running operations by id, along with the serial number telling a replaced
+// operation apart from the one replacing it. Dropping the sender stops the
+// operation.
+type Operations = Arc<Mutex<HashMap<String, (usize, oneshot::Sender<()>)>>>;
+
+/// Serve a single `graphql-ws` connection
+///
+/// The connection reads the messages of the client from `incoming`, and
+/// returns the stream of messages to send back. It ends when the client
+/// terminates the connection, when `incoming` ends, or when the context
+/// factory rejects the payload of the `connection_init` message.
+///
+/// The context factory receives that payload, and returns either the
+/// context shared by all operations of the connection, or the message of
+/// the `connection_error` sent to the client.
+///
+/// Every started operation is executed with `GraphQLRequest::subscribe` in a
+/// task of its own, spawned with `tokio::spawn`, so the connection must be
+/// polled within a Tokio runtime. Queries and mutations sent over the socket
+/// are answered with a single `data` message. At most as many operations as
+/// allowed by `limits` run at once, further ones are answered with an
+/// `error` message.
+///
+/// Stopping an operation drops its source event stream right away if the
+/// stream is waiting for an event, and before it is polled again otherwise.
+///
+/// If `keep_alive` is provided, a `ka` message is sent right after the
+/// connection is acknowledged and on every tick of the stream afterwards.
+pub fn serve_connection<Query, Mutation, Subscription, Context, F>(
+    schema: Arc<RootNode<'static, Query, Mutation, Subscription>>,
+    context_factory: Arc<F>,
+    incoming: IncomingMessages,
+    keep_alive: Option<KeepAlive>,
+    limits: OperationLimits,
+) -> Connection<Query, Mutation, Subscription, Context, F>
+where
+    Context: Send + Sync + 'static,
+    Query: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(Json) -> Result<Context, String> + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::unbounded();
+
+    Connection {
+        schema,
+        context_factory,
+        context: None,
+        incoming,
+        keep_alive,
+        sender,
+        receiver,
+        limits,
+        operations: Arc::new(Mutex::new(HashMap::new())),
+        next_operation: 0,
+        terminated: false,
+    }
+}
+
+/// The stream of messages sent to the client of a connection
+///
+/// Created by [serve_connection](fn.serve_connection.html).
+pub struct Connection<Query, Mutation, Subscription, Context, F>
+where
+    Query: GraphQLType<Context = Context, TypeInfo = ()>,
+    Mutation: GraphQLType<Context = Context, TypeInfo = ()>,
+    Subscription: GraphQLSubscriptionType<Context = Context, TypeInfo = ()>,
+{
+    schema: Arc<RootNode<'static, Query, Mutation, Subscription>>,
+    context_factory: Arc<F>,
+    context: Option<Arc<Context>>,
+    incoming: IncomingMessages,
+    keep_alive: Option<KeepAlive>,
+    sender: UnboundedSender<String>,
+    receiver: UnboundedReceiver<String>,
+    limits: OperationLimits,
+    operations: Operations,
+    next_operation: usize,
+    terminated: bool,
+}
+
+impl<Query, Mutation, Subscription, Context, F>
+    Connection<Query, Mutation, Subscription, Context, F>
+where
+    Context: Send + Sync + 'static,
+    Query: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(Json) -> Result<Context, String> + Send + Sync + 'static,
+{
+    fn send(&self, message: &ServerMessage) {
+        // The receiving end is owned by the connection itself
+        let _ = self.sender.unbounded_send(message.to_json());
+    }
+
+    fn handle_message(&mut self, text: &str) {
+        let message = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(err) => {
+                self.send(&ServerMessage::ConnectionError {
+                    payload: ErrorPayload {
+                        message: format!("Invalid message: {}", err),
+                    },
+                });
+                return;
+            }
+        };
+
+        match message {
+            ClientMessage::ConnectionInit { payload } => self.init(payload),
+            ClientMessage::Start { id, payload } => self.start(id, payload),
+            ClientMessage::Stop { id } => self.stop(&id),
+            ClientMessage::ConnectionTerminate => self.terminate(),
+        }
+    }
+
+    fn init(&mut self, payload: Json) {
+        match (self.context_factory)(payload) {
+            Ok(context) => {
+                self.context = Some(Arc::new(context));
+                self.send(&ServerMessage::ConnectionAck);
+                if self.keep_alive.is_some() {
+                    self.send(&ServerMessage::KeepAlive);
+                }
+            }
+            Err(message) => {
+                self.send(&ServerMessage::ConnectionError {
+                    payload: ErrorPayload { message },
+                });
+                self.terminate();
+            }
+        }
+    }
+
+    fn start(&mut self, id: String, request: GraphQLRequest) {
+        let context = match self.context {
+            Some(ref context) => context.clone(),
+            None => {
+                self.send(&ServerMessage::error(
+                    &id,
+                    "The connection has not been initialized",
+                ));
+                return;
+            }
+        };
+
+        let (stop_sender, stop) = oneshot::channel();
+        let serial = self.next_operation;
+        self.next_operation += 1;
+
+        let permit = {
+            let mut operations = self.operations.lock().unwrap();
+
+            // Starting an operation with the id of a running one replaces it
+            operations.remove(&id);
+
+            let permit = if operations.len() >= self.limits.per_connection {
+                Err("Too many operations are running on this connection")
+            } else {
+                self.limits
+                    .total
+                    .clone()
+                    .try_acquire_owned()
+                    .map_err(|_| "Too many operations are running on the server")
+            };
+
+            if permit.is_ok() {
+                operations.insert(id.clone(), (serial, stop_sender));
+            }
+            permit
+        };
+
+        let permit = match permit {
+            Ok(permit) => permit,
+            Err(message) => {
+                self.send(&ServerMessage::error(&id, message));
+                return;
+            }
+        };
+
+        let schema = self.schema.clone();
+        let sender = self.sender.clone();
+        let operations = self.operations.clone();
+
+        tokio::spawn(async move {
+            let ended = run_operation(&id, &request, &schema, &context, stop, &sender).await;
+
+            // A stopped operation has already been removed, and must not be
+            // reported as complete
+            let mut operations = operations.lock().unwrap();
+            if operations
+                .get(&id)
+                .is_some_and(|&(current, _)| current == serial)
+            {
+                operations.remove(&id);
+                if ended {
+                    let message = ServerMessage::Complete { id: &id };
+                    let _ = sender.unbounded_send(message.to_json());
+                }
+            }
+
+            drop(permit);
+        });
+    }
+
+    fn stop(&mut self, id: &str) {
+        self.operations.lock().unwrap().remove(id);
+    }
+
+    fn terminate(&mut self) {
+        self.terminated = true;
+        self.operations.lock().unwrap().clear();
+    }
+}
+
+// Runs an operation until its responses end, returning whether they did
+async fn run_operation<Query, Mutation, Subscription, Context>(
+    id: &str,
+    request: &GraphQLRequest,
+    schema: &RootNode<'static, Query, Mutation, Subscription>,
+    context: &Context,
+    mut stop: oneshot::Receiver<()>,
+    sender: &UnboundedSender<String>,
+) -> bool
+where
+    Context: Sync,
+    Query: GraphQLType<Context = Context, TypeInfo = ()> + Sync,
+    Mutation: GraphQLType<Context = Context, TypeInfo = ()> + Sync,
+    Subscription: GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Sync,
+{
+    let mut responses = match request.subscribe(schema, context) {
+        Ok(responses) => responses.compat(),
+        Err(response) => {
+            let mut response = serde_json::to_value(&response).expect("responses are serializable");
+            let message = ServerMessage::Error {
+                id,
+                payload: response["errors"].take(),
+            };
+            let _ = sender.unbounded_send(message.to_json());
+            return false;
+        }
+    };
+
+    // The stop signal is checked before every poll of the responses, and
+    // wakes the operation up while it waits for the next event
+    loop {
+        let response = match future::select(&mut stop, responses.next()).await {
+            Either::Right((Some(Ok(response)), _)) => response,
+            Either::Right((Some(Err(())), _)) | Either::Left(_) => return false,
+            Either::Right((None, _)) => return true,
+        };
+
+        let message = ServerMessage::Data {
+            id,
+            payload: &response,
+        };
+        if sender.unbounded_send(message.to_json()).is_err() {
+            return false;
+        }
+    }
+}
+
+impl<Query, Mutation, Subscription, Context, F> Stream
+    for Connection<Query, Mutation, Subscription, Context, F>
+where
+    Context: Send + Sync + 'static,
+    Query: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: GraphQLType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: GraphQLSubscriptionType<Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: Fn(Json) -> Result<Context, String> + Send + Sync + 'static,
+{
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Option<String>> {
+        let connection = &mut *self;
+
+        while !connection.terminated {
+            match connection.incoming.poll_next_unpin(cx) {
+                Poll::Ready(Some(text)) => connection.handle_message(&text),
+                Poll::Ready(None) => connection.terminate(),
+                Poll::Pending => break,
+            }
+        }
+
+        if let Poll::Ready(Some(message)) = connection.receiver.poll_next_unpin(cx) {
+            return Poll::Ready(Some(message));
+        }
+
+        if connection.terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut keep_alive_ended = false;
+        if let Some(ref mut keep_alive) = connection.keep_alive {
+            loop {
+                match keep_alive.poll_next_unpin(cx) {
+                    Poll::Ready(Some(())) => {
+                        if connection.context.is_some() {
+                            return Poll::Ready(Some(ServerMessage::KeepAlive.to_json()));
+                        }
+                    }
+                    Poll::Ready(None) => {
+                        keep_alive_ended = true;
+                        break;
+                    }
+                    Poll::Pending => break,
+                }
+            }
+        }
+        if keep_alive_ended {
+            connection.keep_alive = None;
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+    use std::time::Duration;
+
+    use futures01::sync::mpsc as mpsc01;
+    use futures01::Stream as Stream01;
+    use juniper::meta::MetaType;
+    use juniper::{Arguments, EmptyMutation, FieldError, FieldResult, Registry, SourceEvents};
+    use tokio::runtime::Runtime;
+
+    struct Context {
+        user: String,
+    }
+
+    struct Query;
+
+    graphql_object!(Query: Context |&self| {
+        field user(&executor) -> &str {
+            &executor.context().user
+        }
+    });
+
+    struct Subscription {
+        events: Mutex<Option<mpsc01::UnboundedReceiver<i32>>>,
+    }
+
+    impl GraphQLType for Subscription {
+        type Context = Context;
+        type TypeInfo = ();
+
+        fn name(_: &()) -> Option<&'static str> {
+            Some("Subscription")
+        }
+
+        fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r> {
+            let fields = &[
+                registry
+                    .field::<i32>("counter", &())
+                    .argument(registry.arg::<i32>("count", &())),
+                registry.field::<i32>("endless", &()),
+                registry.field::<i32>("events", &()),
+            ];
+
+            registry
+                .build_object_type::<Subscription>(&(), fields)
+                .into_meta()
+        }
+    }
+
+    impl GraphQLSubscriptionType for Subscription {
+        fn resolve_field_into_stream<'a>(
+            &'a self,
+            info: &'a (),
+            field_name: &str,
+            args: &Arguments,
+            _: &'a Context,
+        ) -> FieldResult<SourceEvents<'a, Context>> {
+            match field_name {
+                "counter" => {
+                    let count: i32 = args.get("count").unwrap();
+                    Ok(SourceEvents::new(info, 1..count + 1))
+                }
+                "endless" => Ok(SourceEvents::new(
+                    info,
+                    (1..).inspect(|_| thread::sleep(Duration::from_millis(10))),
+                )),
+                "events" => {
+                    let events = self.events.lock().unwrap().take().unwrap();
+                    Ok(SourceEvents::from_stream(
+                        info,
+                        events.map_err(|()| FieldError::from("Closed")),
+                    ))
+                }
+                _ => Err(FieldError::from("Unknown field")),
+            }
+        }
+    }
+
+    struct Client {
+        runtime: Runtime,
+        sender: UnboundedSender<String>,
+        events: mpsc01::UnboundedSender<i32>,
+        messages: Connection<Query, EmptyMutation<Context>, Subscription, Context, Factory>,
+    }
+
+    type Factory = fn(Json) -> Result<Context, String>;
+
+    fn context_factory(payload: Json) -> Result<Context, String> {
+        match payload["user"].as_str() {
+            Some(user) => Ok(Context {
+                user: user.to_owned(),
+            }),
+            None => Err("Missing user".to_owned()),
+        }
+    }
+
+    impl Client {
+        fn connect(keep_alive: Option<KeepAlive>) -> Client {
+            Client::connect_with_limits(keep_alive, OperationLimits::default())
+        }
+
+        fn connect_with_limits(keep_alive: Option<KeepAlive>, limits: OperationLimits) -> Client {
+            let (events, receiver) = mpsc01::unbounded();
+            let subscription = Subscription {
+                events: Mutex::new(Some(receiver)),
+            };
+            let schema = RootNode::new_with_subscription(Query, EmptyMutation::new(), subscription);
+            let (sender, incoming) = mpsc::unbounded();
+            let connection = serve_connection(
+                Arc::new(schema),
+                Arc::new(context_factory as Factory),
+                incoming.boxed(),
+                keep_alive,
+                limits,
+            );
+
+            Client {
+                runtime: Runtime::new().unwrap(),
+                sender,
+                events,
+                messages: connection,
+            }
+        }
+
+        fn send(&self, message: Json) {
+            self.sender.unbounded_send(message.to_string()).unwrap();
+        }
+
+        // Operations are spawned while the connection is polled, which
+        // happens within the runtime
+        fn receive(&mut self) -> Option<Json> {
+            let messages = &mut self.messages;
+            self.runtime
+                .block_on(messages.next())
+                .map(|message| serde_json::from_str(&message).unwrap())
+        }
+
+        fn init(&mut self) {
+            self.send(json!({ "type": "connection_init", "payload": { "user": "Ann" } }));
+            assert_eq!(self.receive(), Some(json!({ "type": "connection_ack" })));
+        }
+    }
+
+    #[test]
+    fn acknowledges_connections() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({ "type": "connection_terminate" }));
+
+        assert_eq!(client.receive(), None);
+    }
+
+    #[test]
+    fn rejects_connections_refused_by_the_context_factory() {
+        let mut client = Client::connect(None);
+
+        client.send(json!({ "type": "connection_init", "payload": {} }));
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "connection_error", "payload": { "message": "Missing user" } }))
+        );
+        assert_eq!(client.receive(), None);
+    }
+
+    #[test]
+    fn reports_invalid_messages() {
+        let mut client = Client::connect(None);
+
+        client.send(json!({ "type": "unknown" }));
+
+        let message = client.receive().unwrap();
+        assert_eq!(message["type"], "connection_error");
+
+        client.init();
+    }
+
+    #[test]
+    fn streams_subscription_events() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { counter(count: 2) }" },
+        }));
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "counter": 1 } } }))
+        );
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "counter": 2 } } }))
+        );
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "complete", "id": "1" }))
+        );
+    }
+
+    #[test]
+    fn executes_queries_with_the_connection_context() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "query",
+            "payload": { "query": "{ user }" },
+        }));
+
+        assert_eq!(
+            client.receive(),
+            Some(
+                json!({ "type": "data", "id": "query", "payload": { "data": { "user": "Ann" } } })
+            )
+        );
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "complete", "id": "query" }))
+        );
+    }
+
+    #[test]
+    fn reports_errors_of_invalid_operations() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { unknown }" },
+        }));
+
+        let message = client.receive().unwrap();
+        assert_eq!(message["type"], "error");
+        assert_eq!(message["id"], "1");
+        assert!(message["payload"]
+            .as_array()
+            .is_some_and(|errors| !errors.is_empty()));
+    }
+
+    #[test]
+    fn refuses_operations_before_initialization() {
+        let mut client = Client::connect(None);
+
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "{ user }" },
+        }));
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({
+                "type": "error",
+                "id": "1",
+                "payload": [{ "message": "The connection has not been initialized" }],
+            }))
+        );
+    }
+
+    #[test]
+    fn stops_subscriptions() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { endless }" },
+        }));
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "endless": 1 } } }))
+        );
+
+        client.send(json!({ "type": "stop", "id": "1" }));
+        client.send(json!({
+            "type": "start",
+            "id": "2",
+            "payload": { "query": "{ user }" },
+        }));
+
+        // Events of the stopped subscription may still be in flight
+        loop {
+            let message = client.receive().unwrap();
+            if message["id"] == "2" {
+                assert_eq!(message["type"], "data");
+                break;
+            }
+            assert_eq!(message["type"], "data");
+        }
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "complete", "id": "2" }))
+        );
+        client.send(json!({ "type": "connection_terminate" }));
+        assert_eq!(client.receive(), None);
+    }
+
+    #[test]
+    fn drops_the_sources_of_stopped_subscriptions() {
+        let mut client = Client::connect(None);
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { events }" },
+        }));
+
+        client.events.unbounded_send(1).unwrap();
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "events": 1 } } }))
+        );
+
+        client.send(json!({ "type": "stop", "id": "1" }));
+        client.send(json!({
+            "type": "start",
+            "id": "2",
+            "payload": { "query": "{ user }" },
+        }));
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "2", "payload": { "data": { "user": "Ann" } } }))
+        );
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "complete", "id": "2" }))
+        );
+
+        // The source was waiting for its next event when it was stopped
+        let mut attempts = 0;
+        while !client.events.is_closed() {
+            assert!(
+                attempts < 500,
+                "the source of the subscription was not dropped"
+            );
+            attempts += 1;
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn limits_the_operations_of_a_connection() {
+        let mut client =
+            Client::connect_with_limits(None, OperationLimits::new(MAX_TOTAL_OPERATIONS, 1));
+
+        client.init();
+        client.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { events }" },
+        }));
+        client.send(json!({
+            "type": "start",
+            "id": "2",
+            "payload": { "query": "{ user }" },
+        }));
+
+        assert_eq!(
+            client.receive(),
+            Some(json!({
+                "type": "error",
+                "id": "2",
+                "payload": [{ "message": "Too many operations are running on this connection" }],
+            }))
+        );
+
+        client.events.unbounded_send(1).unwrap();
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "events": 1 } } }))
+        );
+
+        // Stopping the subscription makes room for another operation
+        client.send(json!({ "type": "stop", "id": "1" }));
+        client.send(json!({
+            "type": "start",
+            "id": "3",
+            "payload": { "query": "{ user }" },
+        }));
+        assert_eq!(
+            client.receive(),
+            Some(json!({ "type": "data", "id": "3", "payload": { "data": { "user": "Ann" } } }))
+        );
+    }
+
+    #[test]
+    fn limits_the_operations_of_all_connections() {
+        let limits = OperationLimits::new(1, MAX_OPERATIONS);
+        let mut first = Client::connect_with_limits(None, limits.clone());
+        let mut second = Client::connect_with_limits(None, limits);
+
+        first.init();
+        first.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "subscription { events }" },
+        }));
+        first.events.unbounded_send(1).unwrap();
+        assert_eq!(
+            first.receive(),
+            Some(json!({ "type": "data", "id": "1", "payload": { "data": { "events": 1 } } }))
+        );
+
+        second.init();
+        second.send(json!({
+            "type": "start",
+            "id": "1",
+            "payload": { "query": "{ user }" },
+        }));
+        assert_eq!(
+            second.receive(),
+            Some(json!({
+                "type": "error",
+                "id": "1",
+                "payload": [{ "message": "Too many operations are running on the server" }],
+            }))
+        );
+    }
+
+    #[test]
+    fn sends_keepalive_messages() {
+        let (ticks, keep_alive) = mpsc::unbounded();
+        let mut client = Client::connect(Some(keep_alive.boxed()));
+
+        client.init();
+        assert_eq!(client.receive(), Some(json!({ "type": "ka" })));
+
+        ticks.unbounded_send(()).unwrap();
+        assert_eq!(client.receive(), Some(json!({ "type": "ka" })));
+    }
+}